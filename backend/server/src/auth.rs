@@ -0,0 +1,190 @@
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpRequest, HttpResponse};
+use std::collections::HashMap;
+use subtle::ConstantTimeEq;
+use crate::handlers::AppState;
+
+/// Routes guarded by `API_TOKEN`; everything else (e.g. `/metrics`) stays open
+const API_PATH_PREFIX: &str = "/api/";
+
+/// Query parameter carrying the token on streaming endpoints
+pub const ACCESS_TOKEN_PARAM: &str = "access_token";
+
+/// Streaming (SSE and WebSocket) endpoints. Browsers' `EventSource` and `WebSocket` can't
+/// set headers, so these also accept the token as `?access_token=`
+pub fn is_streaming_path(path: &str) -> bool {
+    path.ends_with("/sse") || path.ends_with("/stream") || path.ends_with("/ws")
+}
+
+/// Token presented with a request: `Authorization: Bearer <token>`, or on streaming
+/// endpoints the `access_token` query parameter
+pub fn request_token(req: &HttpRequest) -> Option<String> {
+    let bearer = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if let Some(token) = bearer {
+        return Some(token.to_string());
+    }
+
+    if !is_streaming_path(req.path()) {
+        return None;
+    }
+    web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .ok()
+        .and_then(|query| query.into_inner().remove(ACCESS_TOKEN_PARAM))
+}
+
+/// Compare a presented token with a configured one in constant time, so response timing
+/// doesn't reveal how much of a guess was right
+pub fn token_matches(presented: &str, expected: &str) -> bool {
+    presented.as_bytes().ct_eq(expected.as_bytes()).into()
+}
+
+/// Reject `/api/*` requests without a valid token with 401 when `API_TOKEN` is set; the admin
+/// token is accepted too, so admin clients need only one. Passes everything through when unset
+pub async fn middleware<B: MessageBody>(
+    state: web::Data<AppState>,
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    let Some(api_token) = state.config.api_token.as_deref() else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+    if !req.path().starts_with(API_PATH_PREFIX) {
+        return Ok(next.call(req).await?.map_into_left_body());
+    }
+
+    let authorized = request_token(req.request()).is_some_and(|token| {
+        token_matches(&token, api_token)
+            || state.config.admin_token.as_deref().is_some_and(|admin_token| token_matches(&token, admin_token))
+    });
+    if authorized {
+        return Ok(next.call(req).await?.map_into_left_body());
+    }
+
+    let response = HttpResponse::Unauthorized().json(serde_json::json!({
+        "error": "Missing or invalid API token"
+    }));
+    Ok(req.into_response(response).map_into_right_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn access_token_accepted_on_stream_paths() {
+        for path in [
+            "/api/stats/total/sse",
+            "/api/stats/ws",
+            "/api/containers/abc/stats/stream",
+            "/api/containers/abc/logs/stream",
+        ] {
+            let req = TestRequest::get().uri(&format!("{}?access_token=secret", path)).to_http_request();
+            assert_eq!(request_token(&req).as_deref(), Some("secret"), "path {}", path);
+        }
+    }
+
+    #[test]
+    fn access_token_rejected_on_other_paths() {
+        for path in ["/api/containers", "/api/containers/abc/logs", "/api/admin/streams", "/metrics"] {
+            let req = TestRequest::get().uri(&format!("{}?access_token=secret", path)).to_http_request();
+            assert_eq!(request_token(&req), None, "path {}", path);
+        }
+    }
+
+    #[test]
+    fn bearer_header_accepted_everywhere_and_preferred() {
+        let req = TestRequest::get()
+            .uri("/api/containers")
+            .insert_header((AUTHORIZATION, "Bearer header-token"))
+            .to_http_request();
+        assert_eq!(request_token(&req).as_deref(), Some("header-token"));
+
+        let req = TestRequest::get()
+            .uri("/api/events/sse?access_token=query-token")
+            .insert_header((AUTHORIZATION, "Bearer header-token"))
+            .to_http_request();
+        assert_eq!(request_token(&req).as_deref(), Some("header-token"));
+    }
+
+    #[test]
+    fn non_bearer_authorization_is_ignored() {
+        let req = TestRequest::get()
+            .uri("/api/containers")
+            .insert_header((AUTHORIZATION, "Basic dXNlcjpwYXNz"))
+            .to_http_request();
+        assert_eq!(request_token(&req), None);
+    }
+
+    mod middleware {
+        use super::*;
+        use crate::config::Config;
+        use actix_web::http::StatusCode;
+        use actix_web::{test, App};
+
+        fn config(api_token: Option<&str>, admin_token: Option<&str>) -> Config {
+            Config {
+                api_token: api_token.map(str::to_string),
+                admin_token: admin_token.map(str::to_string),
+                ..Config::from_env()
+            }
+        }
+
+        async fn status(config: Config, uri: &str, token: Option<&str>) -> StatusCode {
+            let app = test::init_service(
+                App::new()
+                    .wrap(actix_web::middleware::from_fn(super::super::middleware))
+                    .app_data(web::Data::new(AppState::for_tests(config)))
+                    .route("/api/containers", web::get().to(HttpResponse::Ok))
+                    .route("/metrics", web::get().to(HttpResponse::Ok)),
+            )
+            .await;
+
+            let mut req = test::TestRequest::get().uri(uri);
+            if let Some(token) = token {
+                req = req.insert_header((AUTHORIZATION, format!("Bearer {}", token)));
+            }
+            test::call_service(&app, req.to_request()).await.status()
+        }
+
+        #[actix_web::test]
+        async fn missing_or_wrong_token_is_rejected() {
+            let config = config(Some("api-secret"), None);
+            assert_eq!(status(config.clone(), "/api/containers", None).await, StatusCode::UNAUTHORIZED);
+            assert_eq!(status(config.clone(), "/api/containers", Some("api-secreT")).await, StatusCode::UNAUTHORIZED);
+            assert_eq!(status(config, "/api/containers", Some("api")).await, StatusCode::UNAUTHORIZED);
+        }
+
+        #[actix_web::test]
+        async fn api_and_admin_tokens_are_accepted() {
+            let config = config(Some("api-secret"), Some("admin-secret"));
+            assert_eq!(status(config.clone(), "/api/containers", Some("api-secret")).await, StatusCode::OK);
+            assert_eq!(status(config, "/api/containers", Some("admin-secret")).await, StatusCode::OK);
+        }
+
+        #[actix_web::test]
+        async fn everything_passes_without_api_token() {
+            assert_eq!(status(config(None, None), "/api/containers", None).await, StatusCode::OK);
+        }
+
+        #[actix_web::test]
+        async fn metrics_stays_open() {
+            assert_eq!(status(config(Some("api-secret"), None), "/metrics", None).await, StatusCode::OK);
+        }
+    }
+
+    #[test]
+    fn token_comparison_needs_an_exact_match() {
+        assert!(token_matches("secret", "secret"));
+        assert!(!token_matches("secreT", "secret"));
+        assert!(!token_matches("secret-and-more", "secret"));
+        assert!(!token_matches("", "secret"));
+    }
+}
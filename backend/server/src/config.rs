@@ -1,3 +1,4 @@
+use eyes_devine_services::docker_service::DEFAULT_MAX_LOG_LINE_LENGTH;
 use std::env;
 use std::time::Duration;
 
@@ -8,6 +9,10 @@ pub struct Config {
     pub server_host: String,
     pub server_port: u16,
     
+    // TLS (HTTPS is served when both paths are set)
+    pub tls_cert_path: Option<String>,
+    pub tls_key_path: Option<String>,
+    
     // Cache TTLs
     pub cache_ttl_containers: Duration,
     pub cache_ttl_stats: Duration,
@@ -17,6 +22,39 @@ pub struct Config {
     // Query limits
     pub max_query_range_days: u32,
     pub max_results_per_query: usize,
+    
+    // Docker connection health probe
+    pub docker_health_probe_interval: Duration,
+    
+    // Logs
+    pub max_log_line_length: usize,
+    
+    // Memory pressure: containers at or above this memory percent count as under pressure
+    pub memory_pressure_threshold_percent: f64,
+    
+    // Minimum stats samples in the window before limit recommendations are made
+    pub recommendation_min_samples: u64,
+    
+    // Serve container lookups, logs and the service map from the database while Docker is unreachable
+    pub docker_fallback_to_db: bool,
+    
+    // Origins allowed by CORS (applies to streaming responses too); None allows any origin
+    pub cors_allowed_origins: Option<Vec<String>>,
+    
+    // Admin endpoints require `Authorization: Bearer <token>`; disabled when unset
+    pub admin_token: Option<String>,
+    
+    // Every `/api/*` request must present this token (or the admin token); open when unset
+    pub api_token: Option<String>,
+    
+    // Per-client request rate (and burst) on the expensive history/log/service-map routes; off when unset
+    pub rate_limit_rps: Option<f64>,
+    pub rate_limit_burst: u32,
+    // Key rate limits on X-Forwarded-For / Forwarded; only behind a proxy that sets them
+    pub rate_limit_trust_proxy: bool,
+    
+    // Expose the admin self-test (writes and deletes a synthetic stats row); off by default
+    pub self_test_enabled: bool,
 }
 
 impl Config {
@@ -52,6 +90,39 @@ impl Config {
             .ok()
             .and_then(|s| s.parse().ok())
             .unwrap_or(10000);
+        
+        // tokio's interval panics on a zero period, so 0 falls back to the default too
+        let docker_health_probe_interval_secs = env::var("DOCKER_HEALTH_PROBE_INTERVAL")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|secs: &u64| *secs > 0)
+            .unwrap_or(15);
+        
+        let max_log_line_length = env::var("MAX_LOG_LINE_LENGTH")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_MAX_LOG_LINE_LENGTH);
+        
+        let memory_pressure_threshold_percent = env::var("MEMORY_PRESSURE_THRESHOLD_PERCENT")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(90.0);
+
+        let recommendation_min_samples = env::var("RECOMMENDATION_MIN_SAMPLES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(100);
+        
+        let rate_limit_rps = env::var("RATE_LIMIT_RPS")
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|rps| rps.is_finite() && *rps > 0.0);
+        
+        // Defaults to one second's worth of requests
+        let rate_limit_burst = env::var("RATE_LIMIT_BURST")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| rate_limit_rps.map_or(1, |rps| rps.ceil() as u32));
 
         Self {
             database_url: env::var("DATABASE_URL")
@@ -62,12 +133,45 @@ impl Config {
                 .unwrap_or_else(|_| "8080".to_string())
                 .parse()
                 .unwrap_or(8080),
+            tls_cert_path: env::var("TLS_CERT_PATH").ok(),
+            tls_key_path: env::var("TLS_KEY_PATH").ok(),
             cache_ttl_containers: Duration::from_secs(cache_ttl_containers_secs),
             cache_ttl_stats: Duration::from_secs(cache_ttl_stats_secs),
             cache_ttl_images: Duration::from_secs(cache_ttl_images_secs),
             cache_ttl_history: Duration::from_secs(cache_ttl_history_secs),
             max_query_range_days,
             max_results_per_query,
+            docker_health_probe_interval: Duration::from_secs(docker_health_probe_interval_secs),
+            max_log_line_length,
+            memory_pressure_threshold_percent,
+            recommendation_min_samples,
+            docker_fallback_to_db: env::var("DOCKER_FALLBACK_TO_DB")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(true),
+            // Comma-separated, e.g. "https://monitor.example.com,http://localhost:5173"
+            cors_allowed_origins: env::var("CORS_ALLOWED_ORIGINS")
+                .ok()
+                .map(|origins| {
+                    origins
+                        .split(',')
+                        .map(|origin| origin.trim().to_string())
+                        .filter(|origin| !origin.is_empty())
+                        .collect::<Vec<_>>()
+                })
+                .filter(|origins| !origins.is_empty()),
+            admin_token: env::var("ADMIN_TOKEN").ok().filter(|s| !s.is_empty()),
+            api_token: env::var("API_TOKEN").ok().filter(|s| !s.is_empty()),
+            rate_limit_rps,
+            rate_limit_burst,
+            rate_limit_trust_proxy: env::var("RATE_LIMIT_TRUST_PROXY")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+            self_test_enabled: env::var("SELF_TEST_ENABLED")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
         }
     }
 }
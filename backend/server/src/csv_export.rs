@@ -0,0 +1,233 @@
+use actix_web::HttpResponse;
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
+use anyhow::Result;
+use eyes_devine_shared::{AggregatedContainerStats, ContainerStats, GapfilledContainerStats, HttpRequest};
+
+/// Response format picked with `?format=`; JSON when absent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn parse(format: Option<&str>) -> Result<Self, Box<HttpResponse>> {
+        match format {
+            None | Some("json") => Ok(Self::Json),
+            Some("csv") => Ok(Self::Csv),
+            Some(other) => Err(Box::new(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid 'format' value '{}' (expected 'json' or 'csv')", other)
+            })))),
+        }
+    }
+}
+
+/// One CSV row per value; timestamps are written as RFC 3339 so they parse back unchanged
+pub trait CsvRecord {
+    const HEADER: &'static [&'static str];
+
+    fn record(&self) -> Vec<String>;
+}
+
+impl CsvRecord for ContainerStats {
+    const HEADER: &'static [&'static str] = &[
+        "timestamp",
+        "container_id",
+        "container_name",
+        "cpu_usage_percent",
+        "memory_usage_bytes",
+        "memory_limit_bytes",
+        "memory_usage_percent",
+        "memory_cache_bytes",
+        "memory_swap_bytes",
+        "network_rx_bytes",
+        "network_tx_bytes",
+        "block_read_bytes",
+        "block_write_bytes",
+        "per_cpu_usage_percent",
+        "pids_current",
+        "pids_limit",
+    ];
+
+    fn record(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_rfc3339(),
+            self.container_id.clone(),
+            self.container_name.clone(),
+            self.cpu_usage_percent.to_string(),
+            self.memory_usage_bytes.to_string(),
+            self.memory_limit_bytes.to_string(),
+            self.memory_usage_percent.to_string(),
+            self.memory_cache_bytes.to_string(),
+            self.memory_swap_bytes.to_string(),
+            self.network_rx_bytes.to_string(),
+            self.network_tx_bytes.to_string(),
+            self.block_read_bytes.to_string(),
+            self.block_write_bytes.to_string(),
+            // Semicolon-separated, one value per core
+            self.per_cpu_usage_percent
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(";"),
+            self.pids_current.to_string(),
+            self.pids_limit.to_string(),
+        ]
+    }
+}
+
+impl CsvRecord for AggregatedContainerStats {
+    const HEADER: &'static [&'static str] = &[
+        "timestamp",
+        "avg_cpu_usage_percent",
+        "max_cpu_usage_percent",
+        "avg_memory_usage_bytes",
+        "max_memory_usage_bytes",
+        "avg_memory_usage_percent",
+        "max_memory_usage_percent",
+    ];
+
+    fn record(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_rfc3339(),
+            self.avg_cpu_usage_percent.to_string(),
+            self.max_cpu_usage_percent.to_string(),
+            self.avg_memory_usage_bytes.to_string(),
+            self.max_memory_usage_bytes.to_string(),
+            self.avg_memory_usage_percent.to_string(),
+            self.max_memory_usage_percent.to_string(),
+        ]
+    }
+}
+
+impl CsvRecord for GapfilledContainerStats {
+    const HEADER: &'static [&'static str] = AggregatedContainerStats::HEADER;
+
+    fn record(&self) -> Vec<String> {
+        // Buckets without samples are written as empty fields
+        fn field<T: ToString>(value: Option<T>) -> String {
+            value.map(|v| v.to_string()).unwrap_or_default()
+        }
+        vec![
+            self.timestamp.to_rfc3339(),
+            field(self.avg_cpu_usage_percent),
+            field(self.max_cpu_usage_percent),
+            field(self.avg_memory_usage_bytes),
+            field(self.max_memory_usage_bytes),
+            field(self.avg_memory_usage_percent),
+            field(self.max_memory_usage_percent),
+        ]
+    }
+}
+
+/// Captured traffic is attacker-controlled; text a spreadsheet would run as a formula (starting
+/// with `=`, `+`, `-` or `@`) gets a leading `'` so opening the export doesn't execute it
+fn text_cell(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+impl CsvRecord for HttpRequest {
+    const HEADER: &'static [&'static str] = &[
+        "timestamp",
+        "container_id",
+        "container_name",
+        "method",
+        "endpoint",
+        "http_status",
+        "response_time_ms",
+        "headers",
+    ];
+
+    fn record(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_rfc3339(),
+            self.container_id.clone(),
+            text_cell(&self.container_name),
+            text_cell(&self.method),
+            text_cell(&self.endpoint),
+            self.http_status.to_string(),
+            self.response_time_ms.to_string(),
+            // Captured headers as a JSON object; empty when none were captured
+            self.headers
+                .as_ref()
+                .and_then(|headers| serde_json::to_string(headers).ok())
+                .unwrap_or_default(),
+        ]
+    }
+}
+
+/// Header row followed by one row per item
+pub fn to_csv<T: CsvRecord>(rows: &[T]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(T::HEADER)?;
+    for row in rows {
+        writer.write_record(row.record())?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// `rows` as a CSV download named `filename`, or a 500 if they can't be written
+pub fn csv_response<T: CsvRecord>(rows: &[T], filename: &str) -> HttpResponse {
+    match to_csv(rows) {
+        Ok(body) => HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header(ContentDisposition {
+                disposition: DispositionType::Attachment,
+                parameters: vec![DispositionParam::Filename(filename.to_string())],
+            })
+            .body(body),
+        Err(e) => {
+            log::error!("Failed to write CSV export: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to write CSV: {}", e)
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn request(method: &str, endpoint: &str) -> HttpRequest {
+        HttpRequest {
+            container_id: "abc".to_string(),
+            container_name: "web".to_string(),
+            endpoint: endpoint.to_string(),
+            method: method.to_string(),
+            http_status: 200,
+            response_time_ms: 12.5,
+            timestamp: Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+            headers: None,
+            client_container_id: None,
+        }
+    }
+
+    #[test]
+    fn formula_like_cells_are_prefixed() {
+        for value in ["=1+1", "+SUM(A1)", "-2", "@cmd"] {
+            assert_eq!(text_cell(value), format!("'{}", value));
+        }
+        assert_eq!(text_cell("/api/users"), "/api/users");
+        assert_eq!(text_cell("GET"), "GET");
+    }
+
+    #[test]
+    fn http_request_rows_are_quoted_and_escaped() {
+        let csv = to_csv(&[request("=HYPERLINK(\"x\")", "/search,v2\n\"q\"")]).unwrap();
+        let mut lines = csv.splitn(2, '\n');
+        assert_eq!(
+            lines.next(),
+            Some("timestamp,container_id,container_name,method,endpoint,http_status,response_time_ms,headers")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("2023-11-14T22:13:20+00:00,abc,web,\"'=HYPERLINK(\"\"x\"\")\",\"/search,v2\n\"\"q\"\"\",200,12.5,\n")
+        );
+    }
+}
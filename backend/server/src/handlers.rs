@@ -1,20 +1,82 @@
-use eyes_devine_shared::{ContainerLog, LogFilter, HttpRequest};
-use eyes_devine_services::{CacheService, DockerService, CachedQueryService, ServiceMapService};
-use actix_web::{web, HttpResponse, Responder, Error};
+use eyes_devine_shared::{AlertRuleInput, ContainerInfo, ContainerLog, ContainerRunState, ContainerStats, LogFilter, MemoryPressure};
+use eyes_devine_services::{AlertService, CacheService, ContainerFilter, ContainerSelector, DockerService, CachedQueryService, KeysetCursor, QueryService, ServiceMapService, TopMetric};
+use eyes_devine_services::query_service::RAW_STATS_MAX_RANGE_HOURS;
+use eyes_devine_services::docker_service::{is_docker_not_found, is_docker_unreachable};
+use eyes_devine_services::readiness;
+use actix_web::{web, HttpRequest, HttpResponse, Responder, Error};
 use actix_web::web::Bytes;
+use actix_web::http::header::{ContentEncoding, HeaderName};
 use chrono::{Utc, DateTime};
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 use futures::stream::{self, StreamExt, once};
 use std::time::Duration;
-use crate::query_validation::HistoryQueryValidator;
+use crate::auth;
+use crate::config::Config;
+use crate::csv_export::{self, ExportFormat};
+use crate::query_validation::{HistoryQueryValidator, PaginatedResponse, PaginationParams};
+use crate::rate_limit::RateLimiter;
+use crate::self_test;
+use crate::stats_feed::{STATS_FEED_INTERVAL, StatsFeed};
+use crate::streams::StreamRegistry;
+
+/// Set (to `true`) on responses served from the database because Docker could not be reached
+pub const DOCKER_UNAVAILABLE_HEADER: HeaderName = HeaderName::from_static("x-docker-unavailable");
 
 pub struct AppState {
     pub docker_service: Arc<DockerService>, // Keep for logs (still need real-time)
     pub db: Option<DatabaseConnection>,
     pub query_service: Option<Arc<CachedQueryService>>,
+    /// Shared live stats snapshot behind the SSE and WebSocket streams; None without a database
+    pub stats_feed: Option<Arc<StatsFeed>>,
+    /// Alert rule CRUD and recorded alerts; None without a database (the worker evaluates the rules)
+    pub alert_service: Option<Arc<AlertService>>,
     pub cache_service: Arc<CacheService>,
     pub query_validator: HistoryQueryValidator,
+    pub config: Config,
+    pub streams: StreamRegistry,
+    /// Throttles the expensive routes per client; None when `RATE_LIMIT_RPS` is unset
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+#[cfg(test)]
+impl AppState {
+    /// State without a database, cache or reachable Docker daemon, for exercising routing and middleware
+    pub fn for_tests(config: Config) -> Self {
+        Self {
+            // Nothing listens on the discard port, so Docker calls fail fast
+            docker_service: Arc::new(DockerService::with_client(
+                bollard::Docker::connect_with_http("http://127.0.0.1:9", 1, bollard::API_DEFAULT_VERSION).unwrap(),
+            )),
+            db: None,
+            query_service: None,
+            stats_feed: None,
+            alert_service: None,
+            cache_service: Arc::new(CacheService::new(None).unwrap()),
+            query_validator: HistoryQueryValidator::new(config.max_query_range_days, config.max_results_per_query),
+            config,
+            streams: StreamRegistry::new(),
+            rate_limiter: None,
+        }
+    }
+}
+
+/// Liveness probe: answers as long as the server is running, whatever its dependencies
+pub async fn get_health() -> impl Responder {
+    "OK"
+}
+
+/// Readiness probe: 200 when Docker and the database (if `DATABASE_URL` is set) answer,
+/// otherwise 503; the body has each component's state either way
+pub async fn get_readiness(state: web::Data<AppState>) -> impl Responder {
+    let database_required = !state.config.database_url.is_empty();
+    let readiness = readiness::check(&state.docker_service, state.db.as_ref(), database_required).await;
+
+    if readiness.ready {
+        HttpResponse::Ok().json(readiness)
+    } else {
+        HttpResponse::ServiceUnavailable().json(readiness)
+    }
 }
 
 /// Get total stats aggregated from all containers (from database)
@@ -41,10 +103,13 @@ pub async fn get_total_stats(state: web::Data<AppState>) -> impl Responder {
     }
 }
 
-/// SSE endpoint for comprehensive stats - streams data from database
-pub async fn get_total_stats_sse(state: web::Data<AppState>) -> Result<HttpResponse, Error> {
-    let query_service = match &state.query_service {
-        Some(qs) => Arc::clone(qs),
+/// SSE endpoint for total stats - streams the shared snapshot (refreshed from the database)
+pub async fn get_total_stats_sse(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+) -> Result<HttpResponse, Error> {
+    let stats_feed = match &state.stats_feed {
+        Some(feed) => feed,
         None => {
             return Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
                 "error": "Database not available"
@@ -67,9 +132,58 @@ pub async fn get_total_stats_sse(state: web::Data<AppState>) -> Result<HttpRespo
         }
     };
 
-    // Get initial stats
-    let initial_stats = match query_service.get_total_stats().await {
-        Ok(stats) => stats,
+    // Subscribing keeps the shared update loop running for as long as the stream is open
+    let receiver = match stats_feed.subscribe().await {
+        Ok(receiver) => receiver,
+        Err(e) => {
+            log::error!("Failed to get total stats: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get total stats: {}", e)
+            })));
+        }
+    };
+
+    // Send first message immediately
+    let first_message = format_stats(&receiver.borrow().total_stats)?;
+
+    // Create stream that sends the latest snapshot every 2 seconds
+    let interval_stream = stream::unfold(receiver, move |receiver| async move {
+        tokio::time::sleep(STATS_FEED_INTERVAL).await;
+        let stats = receiver.borrow().total_stats.clone();
+        let result = format_stats(&stats);
+        Some((result, receiver))
+    });
+
+    let stream = once(async move { Ok::<Bytes, Error>(first_message) })
+        .chain(interval_stream);
+    let stream = state.streams.track("total_stats", client_addr(&req), stream);
+
+    Ok(event_stream_response(stream))
+}
+
+/// Bounds for the push interval a stats WebSocket client can request
+const MIN_STATS_WS_INTERVAL_MS: u64 = 500;
+const MAX_STATS_WS_INTERVAL_MS: u64 = 60_000;
+
+/// WebSocket alternative to the total stats SSE stream, for proxies that buffer SSE.
+/// Pushes `{"type": "comprehensive_stats", "data": ...}` (the totals plus every container's
+/// latest stats) every 2 seconds from the same snapshot as the SSE endpoint. Clients can send JSON commands: `{"interval_ms": 5000}` changes the
+/// push interval (500-60000), `{"container_id": "<id>"}` switches to that container's latest
+/// stats (`container_stats` messages) and `{"container_id": null}` back to the full snapshot.
+/// Problems are reported as `{"type": "error", "error": ...}`
+pub async fn get_stats_ws(
+    req: HttpRequest,
+    body: web::Payload,
+    state: web::Data<AppState>,
+) -> Result<HttpResponse, Error> {
+    let (Some(stats_feed), Some(query_service)) = (&state.stats_feed, &state.query_service) else {
+        return Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "error": "Database not available"
+        })));
+    };
+
+    let receiver = match stats_feed.subscribe().await {
+        Ok(receiver) => receiver,
         Err(e) => {
             log::error!("Failed to get total stats: {}", e);
             return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
@@ -78,55 +192,121 @@ pub async fn get_total_stats_sse(state: web::Data<AppState>) -> Result<HttpRespo
         }
     };
 
-    // Create shared cache for stats
-    use std::sync::Arc;
-    use tokio::sync::Mutex;
-    let cached_stats = Arc::new(Mutex::new(initial_stats));
-    let cached_stats_for_updater = Arc::clone(&cached_stats);
-    let query_service_for_updater = Arc::clone(&query_service);
+    let (response, session, messages) = actix_ws::handle(&req, body)?;
+    let task = state.streams.track_task(
+        "total_stats_ws".to_string(),
+        client_addr(&req),
+        stats_ws_session(session, messages, receiver, Arc::clone(query_service)),
+    );
+    actix_web::rt::spawn(task);
 
-    // Spawn background task to update stats continuously
-    tokio::spawn(async move {
-        let mut update_interval = tokio::time::interval(Duration::from_millis(2000)); // Update every 2 seconds
-        loop {
-            update_interval.tick().await;
-            match query_service_for_updater.get_total_stats().await {
-                Ok(stats) => {
-                    *cached_stats_for_updater.lock().await = stats;
+    Ok(response)
+}
+
+/// Serve one stats WebSocket until the client goes away or closes it
+async fn stats_ws_session(
+    mut session: actix_ws::Session,
+    mut messages: actix_ws::MessageStream,
+    receiver: tokio::sync::watch::Receiver<eyes_devine_shared::ComprehensiveStats>,
+    query_service: Arc<CachedQueryService>,
+) {
+    let mut interval = STATS_FEED_INTERVAL;
+    let mut container_id: Option<String> = None;
+    let mut ticker = stats_ws_ticker(interval);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let payload = match &container_id {
+                    None => serde_json::json!({ "type": "comprehensive_stats", "data": receiver.borrow().clone() }),
+                    Some(id) => match query_service.get_latest_container_stats(&ContainerSelector::Id(id.clone())).await {
+                        Ok(Some(stats)) => serde_json::json!({ "type": "container_stats", "data": stats }),
+                        Ok(None) => serde_json::json!({ "type": "error", "error": format!("No stats for container {}", id) }),
+                        Err(e) => serde_json::json!({ "type": "error", "error": format!("Failed to get container stats: {}", e) }),
+                    },
+                };
+                if session.text(payload.to_string()).await.is_err() {
+                    return;
                 }
-                Err(e) => {
-                    log::error!("Background stats update failed: {}", e);
+            }
+            message = messages.next() => match message {
+                Some(Ok(actix_ws::Message::Text(text))) => {
+                    match StatsWsCommand::parse(&text) {
+                        Ok(command) => {
+                            interval = command.interval.unwrap_or(interval);
+                            if let Some(container) = command.container {
+                                container_id = container;
+                            }
+                            // Restarting the ticker pushes the new selection right away
+                            ticker = stats_ws_ticker(interval);
+                        }
+                        Err(e) => {
+                            let error = serde_json::json!({ "type": "error", "error": e });
+                            if session.text(error.to_string()).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+                Some(Ok(actix_ws::Message::Ping(bytes))) => {
+                    if session.pong(&bytes).await.is_err() {
+                        return;
+                    }
                 }
+                Some(Ok(actix_ws::Message::Close(reason))) => {
+                    let _ = session.close(reason).await;
+                    return;
+                }
+                Some(Ok(_)) => {}
+                Some(Err(_)) | None => return,
             }
         }
-    });
+    }
+}
 
-    // Send first message immediately
-    let first_message = format_stats(&*cached_stats.lock().await)?;
+fn stats_ws_ticker(interval: Duration) -> tokio::time::Interval {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    ticker
+}
 
-    // Create stream that sends cached stats every 2 seconds
-    let interval_stream = stream::unfold(cached_stats, move |cached_stats| async move {
-        tokio::time::sleep(Duration::from_millis(2000)).await;
-        let stats = cached_stats.lock().await.clone();
-        let result = format_stats(&stats);
-        Some((result, cached_stats))
-    });
+/// A client message on the stats WebSocket; None fields leave the setting unchanged
+struct StatsWsCommand {
+    interval: Option<Duration>,
+    /// `Some(None)` switches back to the full snapshot
+    container: Option<Option<String>>,
+}
 
-    let stream = once(async move { Ok::<Bytes, Error>(first_message) })
-        .chain(interval_stream);
+impl StatsWsCommand {
+    fn parse(text: &str) -> Result<Self, String> {
+        let command: serde_json::Value = serde_json::from_str(text)
+            .map_err(|e| format!("Invalid command: {}", e))?;
 
-    Ok(HttpResponse::Ok()
-        .content_type("text/event-stream")
-        .append_header(("Cache-Control", "no-cache"))
-        .append_header(("Connection", "keep-alive"))
-        .append_header(("X-Accel-Buffering", "no"))
-        .append_header(("Access-Control-Allow-Origin", "*"))
-        .append_header(("Access-Control-Allow-Headers", "Cache-Control"))
-        .streaming(stream))
+        let interval = match command.get("interval_ms") {
+            None => None,
+            Some(value) => {
+                let millis = value
+                    .as_u64()
+                    .filter(|ms| (MIN_STATS_WS_INTERVAL_MS..=MAX_STATS_WS_INTERVAL_MS).contains(ms))
+                    .ok_or_else(|| format!("'interval_ms' must be between {} and {}",
+                        MIN_STATS_WS_INTERVAL_MS, MAX_STATS_WS_INTERVAL_MS))?;
+                Some(Duration::from_millis(millis))
+            }
+        };
+
+        let container = match command.get("container_id") {
+            None => None,
+            Some(serde_json::Value::Null) => Some(None),
+            Some(serde_json::Value::String(id)) if !id.is_empty() => Some(Some(id.clone())),
+            Some(_) => return Err("'container_id' must be a container id or null".to_string()),
+        };
+
+        Ok(Self { interval, container })
+    }
 }
 
-/// List all containers (from database)
-pub async fn get_all_containers(state: web::Data<AppState>) -> impl Responder {
+/// Prometheus scrape endpoint: latest per-container stats as gauges, plus container counts
+pub async fn get_prometheus_metrics(state: web::Data<AppState>) -> impl Responder {
     let query_service = match &state.query_service {
         Some(qs) => qs,
         None => {
@@ -136,23 +316,90 @@ pub async fn get_all_containers(state: web::Data<AppState>) -> impl Responder {
         }
     };
 
-    match query_service.get_all_containers().await {
-        Ok(containers) => HttpResponse::Ok().json(containers),
+    let stats = match query_service.get_latest_all_container_stats().await {
+        Ok(stats) => stats,
         Err(e) => {
-            log::error!("Failed to list containers: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
+            log::error!("Failed to get container stats for metrics: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get container stats: {}", e)
+            }));
+        }
+    };
+
+    // Same up/down split as the comprehensive stats, without fetching stats for every container
+    let (containers_up, containers_down) = match state.docker_service.list_containers().await {
+        Ok(containers) => {
+            let up = containers.iter().filter(|c| c.is_running()).count();
+            (up, containers.len() - up)
+        }
+        Err(e) => {
+            log::error!("Failed to list containers for metrics: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
                 "error": format!("Failed to list containers: {}", e)
-            }))
+            }));
+        }
+    };
+
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(render_prometheus_metrics(&stats, containers_up, containers_down))
+}
+
+/// Extracts one gauge value from a stats sample
+type StatValue = fn(&ContainerStats) -> f64;
+
+/// Render stats in the Prometheus text exposition format
+fn render_prometheus_metrics(stats: &[ContainerStats], containers_up: usize, containers_down: usize) -> String {
+    use std::fmt::Write;
+
+    let gauges: [(&str, &str, StatValue); 11] = [
+        ("container_cpu_usage_percent", "CPU usage in percent", |s| s.cpu_usage_percent),
+        ("container_memory_usage_bytes", "Memory usage in bytes", |s| s.memory_usage_bytes as f64),
+        ("container_memory_limit_bytes", "Memory limit in bytes", |s| s.memory_limit_bytes as f64),
+        ("container_memory_usage_percent", "Memory usage in percent of the limit", |s| s.memory_usage_percent),
+        ("container_memory_cache_bytes", "Page cache in bytes", |s| s.memory_cache_bytes as f64),
+        ("container_memory_swap_bytes", "Swap usage in bytes", |s| s.memory_swap_bytes as f64),
+        ("container_network_rx_bytes", "Network bytes received", |s| s.network_rx_bytes as f64),
+        ("container_network_tx_bytes", "Network bytes transmitted", |s| s.network_tx_bytes as f64),
+        ("container_block_read_bytes", "Block device bytes read", |s| s.block_read_bytes as f64),
+        ("container_block_write_bytes", "Block device bytes written", |s| s.block_write_bytes as f64),
+        ("container_pids", "Processes and threads in the container", |s| s.pids_current as f64),
+    ];
+
+    let mut out = String::new();
+    for (name, help, value) in gauges {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} gauge", name);
+        for stat in stats {
+            let _ = writeln!(
+                out,
+                "{}{{container=\"{}\",id=\"{}\"}} {}",
+                name,
+                escape_label_value(&stat.container_name),
+                escape_label_value(&stat.container_id),
+                value(stat)
+            );
         }
     }
+
+    let _ = writeln!(out, "# HELP devine_eyes_containers_total Number of containers by state");
+    let _ = writeln!(out, "# TYPE devine_eyes_containers_total gauge");
+    let _ = writeln!(out, "devine_eyes_containers_total{{state=\"up\"}} {}", containers_up);
+    let _ = writeln!(out, "devine_eyes_containers_total{{state=\"down\"}} {}", containers_down);
+
+    out
 }
 
-/// Get latest stats for a specific container (from database)
-pub async fn get_container_stats(
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Get the fleet-wide CPU/memory series over time (for wall charts)
+/// Query parameters: `from`, `to` (validated like other history queries), `bucket` (seconds, optional)
+pub async fn get_total_stats_history(
     state: web::Data<AppState>,
-    path: web::Path<String>,
+    query: web::Query<FleetHistoryQuery>,
 ) -> impl Responder {
-    let container_id = path.into_inner();
     let query_service = match &state.query_service {
         Some(qs) => qs,
         None => {
@@ -162,27 +409,76 @@ pub async fn get_container_stats(
         }
     };
 
-    match query_service.get_latest_container_stats(&container_id).await {
-        Ok(Some(stats)) => HttpResponse::Ok().json(stats),
-        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": format!("No stats found for container: {}", container_id)
-        })),
+    let (from, to) = match state.query_validator.validate(query.from, query.to, None) {
+        // The validator always fills in the time range
+        Ok((from, to, _)) => (from.unwrap_or_default(), to.unwrap_or_else(Utc::now)),
         Err(e) => {
-            log::error!("Failed to get container stats: {}", e);
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid query parameters: {}", e)
+            }));
+        }
+    };
+
+    if query.bucket.is_some_and(|b| b <= 0) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "'bucket' must be a positive number of seconds"
+        }));
+    }
+
+    let bucket_seconds = history_bucket_seconds(&state, from, to, query.bucket);
+
+    match query_service.get_fleet_stats_history(from, to, bucket_seconds).await {
+        Ok(history) => HttpResponse::Ok().json(history),
+        Err(e) => {
+            log::error!("Failed to get fleet stats history: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to get container stats: {}", e)
+                "error": format!("Failed to get fleet stats history: {}", e)
             }))
         }
     }
 }
 
-/// Get historical stats for a container (from database)
-pub async fn get_container_stats_history(
+/// The `before` cursor of a paginated listing, or a 400 if it is neither a timestamp nor a
+/// cursor returned by a previous page
+fn parse_cursor(before: Option<&str>) -> Result<Option<KeysetCursor>, Box<HttpResponse>> {
+    before
+        .map(|value| {
+            KeysetCursor::parse(value).ok_or_else(|| {
+                Box::new(HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("Invalid 'before' cursor '{}'", value)
+                })))
+            })
+        })
+        .transpose()
+}
+
+/// Points returned by bucketed history endpoints when no bucket is given
+const DEFAULT_HISTORY_POINTS: i64 = 120;
+const MIN_HISTORY_BUCKET_SECONDS: i64 = 10;
+
+/// Bucket size for a bucketed history query: the requested one (or one giving
+/// `DEFAULT_HISTORY_POINTS`), but never so small that more points than a regular history
+/// query's row limit would be returned
+fn history_bucket_seconds(state: &AppState, from: DateTime<Utc>, to: DateTime<Utc>, requested: Option<i64>) -> i64 {
+    let range_seconds = (to - from).num_seconds().max(1);
+    let max_points = state.config.max_results_per_query.max(1) as i64;
+    let min_bucket = (range_seconds + max_points - 1) / max_points;
+    requested
+        .unwrap_or(range_seconds / DEFAULT_HISTORY_POINTS)
+        .max(min_bucket)
+        .max(MIN_HISTORY_BUCKET_SECONDS)
+}
+
+/// Containers returned by the top-usage endpoint when `n` is not given
+const DEFAULT_TOP_CONTAINERS: u64 = 10;
+
+/// The containers using the most CPU, memory (latest sample of each) or network (throughput
+/// over the last few minutes) right now, for spotting noisy neighbours. Query parameters: `metric` (`cpu`, the default, `memory`
+/// or `network`) and `n` (default 10)
+pub async fn get_top_containers(
     state: web::Data<AppState>,
-    path: web::Path<String>,
-    query: web::Query<HistoryQuery>,
+    query: web::Query<TopContainersQuery>,
 ) -> impl Responder {
-    let container_id = path.into_inner();
     let query_service = match &state.query_service {
         Some(qs) => qs,
         None => {
@@ -192,37 +488,30 @@ pub async fn get_container_stats_history(
         }
     };
 
-    // Validate query parameters
-    let (from, to, limit) = match state.query_validator.validate(query.from, query.to, query.limit) {
-        Ok(params) => params,
-        Err(e) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": format!("Invalid query parameters: {}", e)
-            }));
-        }
+    let metric_name = query.metric.as_deref().unwrap_or("cpu");
+    let Some(metric) = TopMetric::parse(metric_name) else {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Invalid metric '{}' (expected cpu, memory or network)", metric_name)
+        }));
     };
+    let n = query
+        .n
+        .unwrap_or(DEFAULT_TOP_CONTAINERS)
+        .clamp(1, state.config.max_results_per_query.max(1) as u64);
 
-    match query_service
-        .get_container_stats_history(
-            &container_id,
-            from,
-            to,
-            limit,
-        )
-        .await
-    {
+    match query_service.get_top_containers(metric, n).await {
         Ok(stats) => HttpResponse::Ok().json(stats),
         Err(e) => {
-            log::error!("Failed to get container stats history: {}", e);
+            log::error!("Failed to get top containers: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to get container stats history: {}", e)
+                "error": format!("Failed to get top containers: {}", e)
             }))
         }
     }
 }
 
-/// Get latest stats for all containers (from database)
-pub async fn get_all_container_stats(state: web::Data<AppState>) -> impl Responder {
+/// Get fleet-wide memory pressure: usage vs. configured limits and vs. host memory
+pub async fn get_memory_pressure(state: web::Data<AppState>) -> impl Responder {
     let query_service = match &state.query_service {
         Some(qs) => qs,
         None => {
@@ -232,76 +521,193 @@ pub async fn get_all_container_stats(state: web::Data<AppState>) -> impl Respond
         }
     };
 
-    match query_service.get_latest_all_container_stats().await {
-        Ok(stats) => HttpResponse::Ok().json(stats),
+    let stats = match query_service.get_latest_all_container_stats().await {
+        Ok(stats) => stats,
         Err(e) => {
-            log::error!("Failed to get all container stats: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to get all container stats: {}", e)
-            }))
+            log::error!("Failed to get latest container stats: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get latest container stats: {}", e)
+            }));
+        }
+    };
+
+    // Host memory is optional - the limit-based figures are still useful without it
+    let host_memory_bytes = match state.docker_service.get_host_memory_bytes().await {
+        Ok(bytes) => Some(bytes),
+        Err(e) => {
+            log::warn!("Failed to get host memory: {}", e);
+            None
         }
+    };
+
+    HttpResponse::Ok().json(compute_memory_pressure(
+        &stats,
+        host_memory_bytes,
+        state.config.memory_pressure_threshold_percent,
+    ))
+}
+
+/// Aggregate the latest per-container stats into a memory pressure summary.
+/// Containers without a memory limit report the host total (or 0) as their limit;
+/// those only count toward the host-relative figures. Containers at or over the threshold
+/// are listed fullest first
+fn compute_memory_pressure(
+    stats: &[ContainerStats],
+    host_memory_bytes: Option<u64>,
+    threshold_percent: f64,
+) -> MemoryPressure {
+    let is_unlimited = |s: &ContainerStats| {
+        s.memory_limit_bytes == 0 || host_memory_bytes.is_some_and(|host| s.memory_limit_bytes >= host)
+    };
+
+    let total_memory_usage_bytes: u64 = stats.iter().map(|s| s.memory_usage_bytes).sum();
+    let (limited_usage, total_memory_limit_bytes) = stats
+        .iter()
+        .filter(|s| !is_unlimited(s))
+        .fold((0u64, 0u64), |(usage, limit), s| {
+            (usage + s.memory_usage_bytes, limit + s.memory_limit_bytes)
+        });
+    let unlimited_containers = stats.iter().filter(|s| is_unlimited(s)).count();
+
+    let mut over_threshold: Vec<&ContainerStats> = stats
+        .iter()
+        .filter(|s| s.memory_usage_percent >= threshold_percent)
+        .collect();
+    over_threshold.sort_by(|a, b| {
+        b.memory_usage_percent
+            .total_cmp(&a.memory_usage_percent)
+            .then_with(|| a.container_name.cmp(&b.container_name))
+    });
+    let over_threshold: Vec<String> = over_threshold.into_iter().map(|s| s.container_name.clone()).collect();
+
+    let percent = |part: u64, whole: u64| {
+        if whole > 0 { part as f64 / whole as f64 * 100.0 } else { 0.0 }
+    };
+
+    MemoryPressure {
+        total_containers: stats.len(),
+        total_memory_usage_bytes,
+        total_memory_limit_bytes,
+        usage_percent_of_limits: percent(limited_usage, total_memory_limit_bytes),
+        host_memory_bytes,
+        usage_percent_of_host: host_memory_bytes.map(|host| percent(total_memory_usage_bytes, host)),
+        unlimited_containers,
+        threshold_percent,
+        containers_over_threshold: over_threshold.len(),
+        containers_over_threshold_names: over_threshold,
+        timestamp: Utc::now(),
     }
 }
 
-/// Get logs for a specific container (still from Docker - logs not stored in DB yet)
-pub async fn get_container_logs(
+const DEFAULT_CONTAINER_PAGE_SIZE: u64 = 50;
+const MAX_CONTAINER_PAGE_SIZE: u64 = 500;
+
+/// List all containers (from database)
+/// Query parameters: `by` (optional) - `name` keeps only the most recent container per name.
+/// Filters `name`, `image` (case-insensitive substrings), `status` (run state, e.g. `running`)
+/// and `label` (`key=value` or `key`, repeatable), or `page`/`page_size`, switch the response
+/// to `{data, pagination}` with the matches filtered and paged in the database
+pub async fn get_all_containers(
     state: web::Data<AppState>,
-    path: web::Path<String>,
-    query: web::Query<LogFilter>,
+    query: web::Query<Vec<(String, String)>>,
 ) -> impl Responder {
-    let container_id = path.into_inner();
-
-    let since = query.since.map(|dt| dt.timestamp());
-    let until = query.until.map(|dt| dt.timestamp());
-    let tail = query.limit;
+    let mut filter = ContainerFilter::default();
+    let mut pagination = PaginationParams { page: None, page_size: None };
+    let mut paginated = false;
+    for (key, value) in query.into_inner() {
+        let parsed: Result<(), String> = match key.as_str() {
+            "by" => match value.as_str() {
+                "id" => Ok(()),
+                "name" => {
+                    filter.latest_per_name = true;
+                    Ok(())
+                }
+                other => Err(format!("Invalid 'by' value '{}' (expected 'id' or 'name')", other)),
+            },
+            "name" => {
+                filter.name = Some(value);
+                Ok(())
+            }
+            "image" => {
+                filter.image = Some(value);
+                Ok(())
+            }
+            "status" => ContainerRunState::parse(&value.to_lowercase())
+                .map(|state| filter.state = Some(state))
+                .ok_or_else(|| format!("Invalid 'status' value '{}' (expected a container state such as 'running' or 'exited')", value)),
+            "label" => match value.split_once('=') {
+                Some((label, expected)) if !label.is_empty() => {
+                    filter.labels.push((label.to_string(), Some(expected.to_string())));
+                    Ok(())
+                }
+                None if !value.is_empty() => {
+                    filter.labels.push((value, None));
+                    Ok(())
+                }
+                _ => Err("'label' must be 'key=value' or 'key'".to_string()),
+            },
+            "page" => value
+                .parse()
+                .map(|page| pagination.page = Some(page))
+                .map_err(|_| "'page' must be a positive integer".to_string()),
+            "page_size" => value
+                .parse()
+                .map(|page_size| pagination.page_size = Some(page_size))
+                .map_err(|_| "'page_size' must be a positive integer".to_string()),
+            _ => Ok(()),
+        };
+        if let Err(e) = parsed {
+            return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
+        }
+        // Other parameters (e.g. a `_=<timestamp>` cache-buster) keep the bare array
+        paginated |= matches!(key.as_str(), "name" | "image" | "status" | "label" | "page" | "page_size");
+    }
 
-    match state
-        .docker_service
-        .get_container_logs(&container_id, since, until, tail)
-        .await
-    {
-        Ok(logs) => {
-            let container_name = state
-                .docker_service
-                .list_containers()
-                .await
-                .ok()
-                .and_then(|containers| {
-                    containers
-                        .iter()
-                        .find(|c| c.id == container_id)
-                        .map(|c| c.name.clone())
-                })
-                .unwrap_or_else(|| container_id.clone());
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
 
-            let log_entries: Vec<ContainerLog> = logs
-                .into_iter()
-                .enumerate()
-                .map(|(idx, line)| {
-                    let stream = if line.contains("stderr") { "stderr" } else { "stdout" };
-                    ContainerLog {
-                        container_id: container_id.clone(),
-                        container_name: container_name.clone(),
-                        log_line: line,
-                        timestamp: Utc::now() - chrono::Duration::seconds(idx as i64),
-                        stream: stream.to_string(),
-                    }
-                })
-                .collect();
+    if paginated {
+        let (page, page_size) = pagination.normalize(DEFAULT_CONTAINER_PAGE_SIZE, MAX_CONTAINER_PAGE_SIZE);
+        let offset = pagination.offset(DEFAULT_CONTAINER_PAGE_SIZE, MAX_CONTAINER_PAGE_SIZE);
+        return match query_service.find_containers(&filter, offset, page_size).await {
+            Ok((containers, total)) => HttpResponse::Ok().json(PaginatedResponse::new(containers, page, page_size, total)),
+            Err(e) => {
+                log::error!("Failed to find containers: {}", e);
+                HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Failed to find containers: {}", e)
+                }))
+            }
+        };
+    }
 
-            HttpResponse::Ok().json(log_entries)
+    let by_name = filter.latest_per_name;
+    match query_service.get_all_containers().await {
+        Ok(mut containers) => {
+            if by_name {
+                // Most recently collected first, so the first container per name is the latest run
+                let mut seen = std::collections::HashSet::new();
+                containers.retain(|container| seen.insert(container.name.clone()));
+            }
+            HttpResponse::Ok().json(containers)
         }
         Err(e) => {
-            log::error!("Failed to get container logs: {}", e);
+            log::error!("Failed to list containers: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to get container logs: {}", e)
+                "error": format!("Failed to list containers: {}", e)
             }))
         }
     }
 }
 
-/// Get all images (from database)
-pub async fn get_all_images(state: web::Data<AppState>) -> impl Responder {
+/// Containers grouped by Docker Compose project; standalone containers are in the
+/// group whose `project` is null (from database)
+pub async fn get_projects(state: web::Data<AppState>) -> impl Responder {
     let query_service = match &state.query_service {
         Some(qs) => qs,
         None => {
@@ -311,23 +717,29 @@ pub async fn get_all_images(state: web::Data<AppState>) -> impl Responder {
         }
     };
 
-    match query_service.get_all_images().await {
-        Ok(images) => HttpResponse::Ok().json(images),
+    match query_service.get_containers_grouped_by_project().await {
+        Ok(projects) => HttpResponse::Ok().json(projects),
         Err(e) => {
-            log::error!("Failed to get images: {}", e);
+            log::error!("Failed to get projects: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to get images: {}", e)
+                "error": format!("Failed to get projects: {}", e)
             }))
         }
     }
 }
 
-/// Get image by ID (from database)
-pub async fn get_image(
+/// Get latest stats for a specific container (from database)
+/// Query parameter: `by` (optional) - `id` (default) or `name` for the latest sample of any container run under that name
+pub async fn get_container_stats(
     state: web::Data<AppState>,
     path: web::Path<String>,
+    query: web::Query<ContainerByQuery>,
 ) -> impl Responder {
-    let image_id = path.into_inner();
+    let container_id = path.into_inner();
+    let selector = match container_selector(&container_id, query.by.as_deref()) {
+        Ok(selector) => selector,
+        Err(response) => return *response,
+    };
     let query_service = match &state.query_service {
         Some(qs) => qs,
         None => {
@@ -337,27 +749,137 @@ pub async fn get_image(
         }
     };
 
-    match query_service.get_image(&image_id).await {
-        Ok(Some(image)) => HttpResponse::Ok().json(image),
+    match query_service.get_latest_container_stats(&selector).await {
+        Ok(Some(stats)) => HttpResponse::Ok().json(stats),
         Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": format!("Image not found: {}", image_id)
+            "error": format!("No stats found for container: {}", container_id)
         })),
         Err(e) => {
-            log::error!("Failed to get image: {}", e);
+            log::error!("Failed to get container stats: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to get image: {}", e)
+                "error": format!("Failed to get container stats: {}", e)
             }))
         }
     }
 }
 
-/// Get image version history (from database)
-pub async fn get_image_history(
+/// Mounts, labels, restart policy, networks (with aliases) and the rest of a container's
+/// configuration, read live from Docker
+pub async fn get_container_details(
     state: web::Data<AppState>,
     path: web::Path<String>,
-    query: web::Query<HistoryQuery>,
 ) -> impl Responder {
-    let image_id = path.into_inner();
+    let container_identifier = path.into_inner();
+    let container_id = match resolve_container_id(&state, &container_identifier).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match state.docker_service.get_container_details(&container_id).await {
+        Ok(details) => HttpResponse::Ok().json(details),
+        Err(e) => {
+            log::error!("Failed to get details for {}: {}", container_id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get container details: {}", e)
+            }))
+        }
+    }
+}
+
+/// Processes running in a container (`docker top`, straight from Docker); empty for stopped containers
+pub async fn get_container_processes(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let container_identifier = path.into_inner();
+    let container_id = match resolve_container_id(&state, &container_identifier).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match state.docker_service.get_container_processes(&container_id).await {
+        Ok(processes) => HttpResponse::Ok().json(processes),
+        Err(e) => {
+            log::error!("Failed to get processes for {}: {}", container_id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get container processes: {}", e)
+            }))
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct DiskUsageQuery {
+    #[serde(default)]
+    pub by: Option<String>,
+    /// Ask Docker now instead of returning the worker's latest sample (slow on large containers)
+    #[serde(default)]
+    pub live: bool,
+}
+
+/// Writable-layer and root filesystem size of a container: the latest sample the worker
+/// stored (every `DISK_USAGE_INTERVAL`), or with `live=true` straight from Docker
+pub async fn get_container_disk_usage(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<DiskUsageQuery>,
+) -> impl Responder {
+    let container_identifier = path.into_inner();
+
+    if query.live {
+        let container_id = match resolve_container_id(&state, &container_identifier).await {
+            Ok(id) => id,
+            Err(response) => return response,
+        };
+        return match state.docker_service.get_container_disk_usage(&container_id).await {
+            Ok(usage) => HttpResponse::Ok().json(usage),
+            Err(e) => {
+                log::error!("Failed to get disk usage for {}: {}", container_id, e);
+                HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Failed to get disk usage: {}", e)
+                }))
+            }
+        };
+    }
+
+    let selector = match container_selector(&container_identifier, query.by.as_deref()) {
+        Ok(selector) => selector,
+        Err(response) => return *response,
+    };
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    match query_service.get_latest_disk_usage(&selector).await {
+        Ok(Some(usage)) => HttpResponse::Ok().json(usage),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("No disk usage recorded for container: {}", container_identifier)
+        })),
+        Err(e) => {
+            log::error!("Failed to get disk usage: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get disk usage: {}", e)
+            }))
+        }
+    }
+}
+
+/// State changes the worker recorded for a container (run state, exit code, health), newest first
+pub async fn get_container_status_history(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    let container_identifier = path.into_inner();
+    let selector = match container_selector(&container_identifier, query.by.as_deref()) {
+        Ok(selector) => selector,
+        Err(response) => return *response,
+    };
     let query_service = match &state.query_service {
         Some(qs) => qs,
         None => {
@@ -367,7 +889,6 @@ pub async fn get_image_history(
         }
     };
 
-    // Validate query parameters
     let (from, to, limit) = match state.query_validator.validate(query.from, query.to, query.limit) {
         Ok(params) => params,
         Err(e) => {
@@ -377,56 +898,307 @@ pub async fn get_image_history(
         }
     };
 
-    match query_service
-        .get_image_history(&image_id, from, to, limit)
-        .await
-    {
+    match query_service.get_container_status_history(&selector, from, to, limit).await {
         Ok(history) => HttpResponse::Ok().json(history),
         Err(e) => {
-            log::error!("Failed to get image history: {}", e);
+            log::error!("Failed to get container status history: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to get image history: {}", e)
+                "error": format!("Failed to get container status history: {}", e)
             }))
         }
     }
 }
 
-/// Get service communication map
-/// Query parameter: `service_id` (optional) - filter to show only connections for a specific service
-pub async fn get_service_map(
+/// SSE endpoint streaming live stats for one container directly from Docker (bypasses the database)
+/// Intended for focused debugging; the stream ends when the container stops or the client disconnects
+pub async fn get_container_stats_stream(
+    req: HttpRequest,
     state: web::Data<AppState>,
-    query: web::Query<std::collections::HashMap<String, String>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+    let container_identifier = path.into_inner();
+
+    let container_id = match resolve_container_id(&state, &container_identifier).await {
+        Ok(id) => id,
+        Err(response) => return Ok(response),
+    };
+
+    // Dropping the response body (client disconnect) drops the Docker stats stream with it
+    let stream = state
+        .docker_service
+        .stream_container_stats(&container_id)
+        .scan(false, |failed, sample| {
+            if *failed {
+                return futures::future::ready(None);
+            }
+            let data = match sample.and_then(|stats| Ok(serde_json::to_string(&stats)?)) {
+                Ok(json) => format!("data: {}\n\n", json),
+                Err(e) => {
+                    log::warn!("Container stats stream ended with error: {}", e);
+                    *failed = true;
+                    format!("data: {}\n\n", serde_json::json!({ "error": e.to_string() }))
+                }
+            };
+            futures::future::ready(Some(Ok::<Bytes, Error>(Bytes::from(data))))
+        });
+    let stream = state.streams.track(
+        format!("container_stats:{}", container_id),
+        client_addr(&req),
+        stream,
+    );
+
+    Ok(event_stream_response(stream))
+}
+
+/// SSE endpoint following a container's logs (`docker logs -f`), one `ContainerLog` message per
+/// line with the stream taken from Docker's frame header. Starts with the last `tail` lines
+/// (default none); ends when the container stops or the client disconnects
+pub async fn get_container_logs_stream(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<LogStreamQuery>,
+) -> Result<HttpResponse, Error> {
+    let container_identifier = path.into_inner();
+
+    let container_id = match resolve_container_id(&state, &container_identifier).await {
+        Ok(id) => id,
+        Err(response) => return Ok(response),
+    };
+    let container_name = state
+        .docker_service
+        .list_containers()
+        .await
+        .ok()
+        .and_then(|containers| containers.into_iter().find(|c| c.id == container_id))
+        .map(|c| c.name)
+        .unwrap_or_else(|| container_id.clone());
+
+    // Dropping the response body (client disconnect) drops the Docker logs stream with it
+    let log_container_id = container_id.clone();
+    let stream = state
+        .docker_service
+        .stream_container_logs(&container_id, query.tail)
+        .scan(false, move |failed, line| {
+            if *failed {
+                return futures::future::ready(None);
+            }
+            let entry = line.map(|(stream, line)| {
+                let (timestamp, log_line) = split_log_timestamp(&line);
+                ContainerLog {
+                    container_id: log_container_id.clone(),
+                    container_name: container_name.clone(),
+                    log_line: log_line.to_string(),
+                    timestamp: timestamp.unwrap_or_else(Utc::now),
+                    stream: stream.to_string(),
+                }
+            });
+            let data = match entry.and_then(|entry| Ok(serde_json::to_string(&entry)?)) {
+                Ok(json) => format!("data: {}\n\n", json),
+                Err(e) => {
+                    log::warn!("Container logs stream ended with error: {}", e);
+                    *failed = true;
+                    format!("data: {}\n\n", serde_json::json!({ "error": e.to_string() }))
+                }
+            };
+            futures::future::ready(Some(Ok::<Bytes, Error>(Bytes::from(data))))
+        });
+    let stream = state.streams.track(
+        format!("container_logs:{}", container_id),
+        client_addr(&req),
+        stream,
+    );
+
+    Ok(event_stream_response(stream))
+}
+
+/// Split the RFC 3339 timestamp Docker puts in front of each line (`timestamps: true`) off a log line
+fn split_log_timestamp(line: &str) -> (Option<DateTime<Utc>>, &str) {
+    line.split_once(' ')
+        .and_then(|(timestamp, rest)| {
+            DateTime::parse_from_rfc3339(timestamp)
+                .ok()
+                .map(|timestamp| (Some(timestamp.with_timezone(&Utc)), rest))
+        })
+        .unwrap_or((None, line))
+}
+
+/// SSE endpoint forwarding container create/start/stop/die events from Docker as they happen,
+/// each as an `event: container` message. Survives Docker restarts (the events connection is re-opened)
+pub async fn get_docker_events_sse(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+) -> Result<HttpResponse, Error> {
+    let stream = state.docker_service.subscribe_events().map(|event| {
+        let data = match serde_json::to_string(&event) {
+            Ok(json) => format!("event: container\ndata: {}\n\n", json),
+            Err(e) => {
+                log::error!("Failed to serialize container event: {}", e);
+                format!("data: {}\n\n", serde_json::json!({ "error": e.to_string() }))
+            }
+        };
+        Ok::<Bytes, Error>(Bytes::from(data))
+    });
+    let stream = state.streams.track("docker_events", client_addr(&req), stream);
+
+    Ok(event_stream_response(stream))
+}
+
+/// Get historical stats for a container (from database)
+/// Ranges over `RAW_STATS_MAX_RANGE_HOURS`, or requests with `bucket`, return per-bucket aggregates.
+/// With a `before` cursor the response is a page of raw samples plus `next_cursor`
+pub async fn get_container_stats_history(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
 ) -> impl Responder {
-    let service_map_service = ServiceMapService::new(Arc::clone(&state.docker_service));
+    let container_id = path.into_inner();
+    let selector = match container_selector(&container_id, query.by.as_deref()) {
+        Ok(selector) => selector,
+        Err(response) => return *response,
+    };
+    let format = match ExportFormat::parse(query.format.as_deref()) {
+        Ok(format) => format,
+        Err(response) => return *response,
+    };
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+    let csv_filename = format!("{}-stats.csv", selector.value());
+    let before = match parse_cursor(query.before.as_deref()) {
+        Ok(before) => before,
+        Err(response) => return *response,
+    };
 
-    // Get optional service_id from query parameters
-    let service_id = query.get("service_id").map(|s| s.as_str());
+    // Validate query parameters
+    let (from, to, limit) = match state.query_validator.validate(query.from, query.to, query.limit) {
+        Ok(params) => params,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid query parameters: {}", e)
+            }));
+        }
+    };
 
-    match service_map_service.generate_service_map_for_service(service_id).await {
-        Ok(service_map) => HttpResponse::Ok().json(service_map),
+    if query.bucket.is_some_and(|b| b <= 0) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "'bucket' must be a positive number of seconds"
+        }));
+    }
+
+    let carry_forward = match query.gapfill.as_deref() {
+        None | Some("false") => None,
+        Some("true") => Some(false),
+        Some("locf") => Some(true),
+        Some(other) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid 'gapfill' value '{}' (expected true, locf or false)", other)
+            }));
+        }
+    };
+
+    // Every bucket of the range, with nulls (or carried-forward values) where samples are missing
+    if let Some(carry_forward) = carry_forward {
+        let (Some(from_dt), Some(to_dt)) = (query.from, query.to) else {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "'gapfill' requires both 'from' and 'to'"
+            }));
+        };
+        if query.before.is_some() {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "'gapfill' cannot be combined with 'before'"
+            }));
+        }
+        let mut bucket_seconds = history_bucket_seconds(&state, from_dt, to_dt, query.bucket);
+        if query.bucket.is_none() && to_dt - from_dt > chrono::Duration::hours(RAW_STATS_MAX_RANGE_HOURS) {
+            bucket_seconds = bucket_seconds.max(3600);
+        }
+
+        return match query_service
+            .get_container_stats_history_gapfilled(&selector, from_dt, to_dt, bucket_seconds, carry_forward)
+            .await
+        {
+            Ok(stats) if format == ExportFormat::Csv => csv_export::csv_response(&stats, &csv_filename),
+            Ok(stats) => HttpResponse::Ok().json(stats),
+            Err(e) => {
+                log::error!("Failed to get gap-filled container stats history: {}", e);
+                HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Failed to get container stats history: {}", e)
+                }))
+            }
+        };
+    }
+
+    // Long ranges (or an explicit bucket) return per-bucket aggregates instead of raw samples,
+    // unless the client is paging back through raw samples with a cursor
+    let (from_dt, to_dt) = (from.unwrap_or_default(), to.unwrap_or_else(Utc::now));
+    let long_range = to_dt - from_dt > chrono::Duration::hours(RAW_STATS_MAX_RANGE_HOURS);
+    if (long_range && query.before.is_none()) || query.bucket.is_some() {
+        let mut bucket_seconds = history_bucket_seconds(&state, from_dt, to_dt, query.bucket);
+        if query.bucket.is_none() {
+            // Read the hourly aggregate rather than bucketing days of raw samples
+            bucket_seconds = bucket_seconds.max(3600);
+        }
+
+        return match query_service
+            .get_container_stats_history_bucketed(&selector, from_dt, to_dt, bucket_seconds)
+            .await
+        {
+            Ok(stats) if format == ExportFormat::Csv => csv_export::csv_response(&stats, &csv_filename),
+            Ok(stats) => HttpResponse::Ok().json(stats),
+            Err(e) => {
+                log::error!("Failed to get bucketed container stats history: {}", e);
+                HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Failed to get container stats history: {}", e)
+                }))
+            }
+        };
+    }
+
+    match query_service
+        .get_container_stats_history(
+            &selector,
+            from,
+            to,
+            before,
+            limit,
+        )
+        .await
+    {
+        // CSV has no envelope for a cursor; the client takes the last row's timestamp instead
+        Ok((stats, _)) if format == ExportFormat::Csv => csv_export::csv_response(&stats, &csv_filename),
+        // With a cursor, wrap the page so the client gets the next cursor
+        Ok((stats, last)) if before.is_some() => {
+            let page_size = limit.unwrap_or(stats.len() as u64);
+            HttpResponse::Ok().json(PaginatedResponse::with_cursor(stats, page_size, last, before))
+        }
+        Ok((stats, _)) => HttpResponse::Ok().json(stats),
         Err(e) => {
-            log::error!("Failed to generate service map: {}", e);
+            log::error!("Failed to get container stats history: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to generate service map: {}", e)
+                "error": format!("Failed to get container stats history: {}", e)
             }))
         }
     }
 }
 
-/// Get HTTP requests for a specific container/service
-/// Queries from database (collected by worker service)
-/// Supports both container ID and container name in the path
-pub async fn get_container_http_requests(
+/// Network throughput (bytes/sec received and sent) per bucket, derived from the cumulative
+/// counters. Query parameters: `from`, `to`, `bucket` (seconds, optional) and `by`
+pub async fn get_container_network_rate(
     state: web::Data<AppState>,
     path: web::Path<String>,
-    query: web::Query<std::collections::HashMap<String, String>>,
+    query: web::Query<HistoryQuery>,
 ) -> impl Responder {
-    let container_identifier = path.into_inner();
-    let limit = query
-        .get("limit")
-        .and_then(|s| s.parse::<u64>().ok())
-        .unwrap_or(100);
-
+    let container_id = path.into_inner();
+    let selector = match container_selector(&container_id, query.by.as_deref()) {
+        Ok(selector) => selector,
+        Err(response) => return *response,
+    };
     let query_service = match &state.query_service {
         Some(qs) => qs,
         None => {
@@ -436,68 +1208,1628 @@ pub async fn get_container_http_requests(
         }
     };
 
-    // Find container by ID or name
-    let container_id = match state.docker_service.list_containers().await {
-        Ok(containers) => {
-            // Try to find by ID first
-            if let Some(container) = containers.iter().find(|c| c.id == container_identifier) {
-                container.id.clone()
-            } else if let Some(container) = containers.iter().find(|c| 
-                c.name == container_identifier || 
-                c.name.trim_start_matches('/') == container_identifier ||
-                c.name.ends_with(&container_identifier)
-            ) {
-                container.id.clone()
-            } else {
-                log::warn!("Container not found: {} (tried as ID and name)", container_identifier);
-                return HttpResponse::NotFound().json(serde_json::json!({
-                    "error": format!("Container not found: {}", container_identifier)
-                }));
-            }
-        }
+    let (from, to) = match state.query_validator.validate(query.from, query.to, None) {
+        // The validator always fills in the time range
+        Ok((from, to, _)) => (from.unwrap_or_default(), to.unwrap_or_else(Utc::now)),
         Err(e) => {
-            log::error!("Failed to list containers: {}", e);
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to list containers: {}", e)
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid query parameters: {}", e)
             }));
         }
     };
 
-    log::debug!("Getting HTTP requests for container: {} from database", container_id);
+    if query.bucket.is_some_and(|b| b <= 0) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "'bucket' must be a positive number of seconds"
+        }));
+    }
 
-    // Parse optional time range query parameters
-    let from = query
-        .get("from")
-        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-        .map(|dt| dt.with_timezone(&chrono::Utc));
-    
-    let to = query
-        .get("to")
-        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-        .map(|dt| dt.with_timezone(&chrono::Utc));
+    let bucket_seconds = history_bucket_seconds(&state, from, to, query.bucket);
 
-    // Query database for HTTP requests
-    match query_service.get_container_http_requests(&container_id, from, to, Some(limit)).await {
-        Ok(requests) => {
-            log::info!("Retrieved {} HTTP requests from database for container {}", requests.len(), container_id);
-            HttpResponse::Ok().json(requests)
+    match query_service.get_container_network_rate(&selector, from, to, bucket_seconds).await {
+        Ok(rates) => HttpResponse::Ok().json(rates),
+        Err(e) => {
+            log::error!("Failed to get container network rate: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get container network rate: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get latest stats for all containers (from database)
+pub async fn get_all_container_stats(state: web::Data<AppState>) -> impl Responder {
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
         }
+    };
+
+    match query_service.get_latest_all_container_stats().await {
+        Ok(stats) => HttpResponse::Ok().json(stats),
         Err(e) => {
-            log::error!("Failed to get HTTP requests from database: {}", e);
+            log::error!("Failed to get all container stats: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to get HTTP requests: {}", e)
+                "error": format!("Failed to get all container stats: {}", e)
             }))
         }
     }
 }
 
-/// Query parameters for history endpoints
-#[derive(serde::Deserialize)]
-pub struct HistoryQuery {
-    #[serde(default)]
-    pub from: Option<DateTime<Utc>>,
-    #[serde(default)]
-    pub to: Option<DateTime<Utc>>,
-    #[serde(default)]
-    pub limit: Option<u64>,
+/// Get logs for a specific container (still from Docker - logs not stored in DB yet)
+pub async fn get_container_logs(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<LogFilter>,
+) -> impl Responder {
+    let container_id = path.into_inner();
+
+    let since = query.since.map(|dt| dt.timestamp());
+    let until = query.until.map(|dt| dt.timestamp());
+    let tail = query.limit;
+
+    match state
+        .docker_service
+        .get_container_logs(&container_id, since, until, tail)
+        .await
+    {
+        Ok(logs) => {
+            let container_name = state
+                .docker_service
+                .list_containers()
+                .await
+                .ok()
+                .and_then(|containers| {
+                    containers
+                        .iter()
+                        .find(|c| c.id == container_id)
+                        .map(|c| c.name.clone())
+                })
+                .unwrap_or_else(|| container_id.clone());
+
+            let log_entries: Vec<ContainerLog> = logs
+                .into_iter()
+                .map(|(stream, line)| {
+                    let (timestamp, log_line) = split_log_timestamp(&line);
+                    ContainerLog {
+                        container_id: container_id.clone(),
+                        container_name: container_name.clone(),
+                        log_line: log_line.to_string(),
+                        timestamp: timestamp.unwrap_or_else(Utc::now),
+                        stream: stream.to_string(),
+                    }
+                })
+                .collect();
+
+            HttpResponse::Ok().json(log_entries)
+        }
+        Err(e) if is_docker_not_found(&e) => {
+            HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("Container not found: {}", container_id)
+            }))
+        }
+        Err(e) => {
+            if let Some(query_service) = docker_fallback(&state, &e) {
+                log::warn!("Failed to get container logs from Docker, serving stored logs: {}", e);
+                let limit = query
+                    .limit
+                    .unwrap_or(state.config.max_results_per_query as u64)
+                    .min(state.config.max_results_per_query as u64);
+                // The path may hold a name; stored logs are keyed by ID
+                let container_id = match resolve_container_id(&state, &container_id).await {
+                    Ok(id) => id,
+                    Err(response) => return response,
+                };
+                return match query_service
+                    .get_container_logs(&container_id, query.since, query.until, limit)
+                    .await
+                {
+                    Ok(logs) => HttpResponse::Ok()
+                        .insert_header((DOCKER_UNAVAILABLE_HEADER, "true"))
+                        .json(logs),
+                    Err(db_err) => {
+                        log::error!("Failed to get stored container logs: {}", db_err);
+                        HttpResponse::InternalServerError().json(serde_json::json!({
+                            "error": format!("Failed to get container logs: {}", e)
+                        }))
+                    }
+                };
+            }
+            log::error!("Failed to get container logs: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get container logs: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get the published-port mapping for a container (from Docker)
+/// Returns every exposed port with its host binding; unpublished ports have `published: false`
+pub async fn get_container_ports(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let container_id = path.into_inner();
+
+    match state.docker_service.get_container_network_info(&container_id).await {
+        Ok(network_info) => HttpResponse::Ok().json(serde_json::json!({
+            "container_id": network_info.container_id,
+            "container_name": network_info.container_name,
+            "network_mode": network_info.network_mode,
+            "ports": network_info.ports,
+        })),
+        Err(e) => {
+            log::error!("Failed to get container ports: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get container ports: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get a single environment variable of a container by key
+/// Values of sensitive-looking keys (passwords, tokens, secrets, ...) are redacted
+pub async fn get_container_env_var(
+    state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (container_id, key) = path.into_inner();
+
+    match state.docker_service.get_container_environment(&container_id).await {
+        Ok(env_vars) => match env_vars.into_iter().find(|(k, _)| *k == key) {
+            Some((key, value)) => {
+                let redacted = is_sensitive_env_key(&key);
+                HttpResponse::Ok().json(serde_json::json!({
+                    "container_id": container_id,
+                    "key": key,
+                    "value": if redacted { REDACTED_VALUE.to_string() } else { value },
+                    "redacted": redacted,
+                }))
+            }
+            None => HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("Environment variable not set: {}", key)
+            })),
+        },
+        Err(e) => {
+            log::error!("Failed to get container environment: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get container environment: {}", e)
+            }))
+        }
+    }
+}
+
+const REDACTED_VALUE: &str = "[redacted]";
+
+/// Key fragments that mark an environment variable as sensitive wherever they appear
+const SENSITIVE_ENV_KEY_FRAGMENTS: [&str; 8] = [
+    "PASSWORD", "PASSWD", "SECRET", "TOKEN", "API_KEY", "APIKEY", "PRIVATE_KEY", "CREDENTIAL",
+];
+
+/// Abbreviations that mark a variable as sensitive only as a whole `_`-separated key segment
+/// (`DB_PWD`, `REDIS_PASS`), since as substrings they also hit harmless keys like `PASSTHROUGH`
+const SENSITIVE_ENV_KEY_SEGMENTS: [&str; 2] = ["PWD", "PASS"];
+
+/// Set by shells to the working directory, not a password
+const SHELL_DIRECTORY_ENV_KEYS: [&str; 2] = ["PWD", "OLDPWD"];
+
+fn is_sensitive_env_key(key: &str) -> bool {
+    let key = key.to_uppercase();
+    if SHELL_DIRECTORY_ENV_KEYS.contains(&key.as_str()) {
+        return false;
+    }
+    SENSITIVE_ENV_KEY_FRAGMENTS.iter().any(|fragment| key.contains(fragment))
+        || key
+            .split(['_', '-', '.'])
+            .any(|segment| SENSITIVE_ENV_KEY_SEGMENTS.contains(&segment))
+}
+
+/// Get all images (from database)
+pub async fn get_all_images(state: web::Data<AppState>) -> impl Responder {
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    match query_service.get_all_images().await {
+        Ok(images) => HttpResponse::Ok().json(images),
+        Err(e) => {
+            log::error!("Failed to get images: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get images: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get image by ID (from database)
+pub async fn get_image(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let image_id = path.into_inner();
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    match query_service.get_image(&image_id).await {
+        Ok(Some(image)) => HttpResponse::Ok().json(image),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Image not found: {}", image_id)
+        })),
+        Err(e) => {
+            log::error!("Failed to get image: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get image: {}", e)
+            }))
+        }
+    }
+}
+
+/// Staleness threshold when `older_than_days` is not given
+const DEFAULT_STALE_IMAGE_DAYS: u32 = 90;
+
+/// Images built more than `older_than_days` ago (default 90), oldest first, with the running
+/// containers using each; images with an unknown build date are listed under `unknown_created`.
+/// `in_use=true` drops images no running container uses
+pub async fn get_stale_images(
+    state: web::Data<AppState>,
+    query: web::Query<StaleImagesQuery>,
+) -> impl Responder {
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    let older_than_days = query.older_than_days.unwrap_or(DEFAULT_STALE_IMAGE_DAYS);
+    match query_service.get_stale_images(older_than_days).await {
+        Ok(mut report) => {
+            if query.in_use {
+                report.images.retain(|entry| !entry.containers.is_empty());
+                report.unknown_created.retain(|entry| !entry.containers.is_empty());
+            }
+            HttpResponse::Ok().json(report)
+        }
+        Err(e) => {
+            log::error!("Failed to get stale images: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get stale images: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get image version history (from database)
+pub async fn get_image_history(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    let image_id = path.into_inner();
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    // Validate query parameters
+    let (from, to, limit) = match state.query_validator.validate(query.from, query.to, query.limit) {
+        Ok(params) => params,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid query parameters: {}", e)
+            }));
+        }
+    };
+
+    match query_service
+        .get_image_history(&image_id, from, to, limit)
+        .await
+    {
+        Ok(history) => HttpResponse::Ok().json(history),
+        Err(e) => {
+            log::error!("Failed to get image history: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get image history: {}", e)
+            }))
+        }
+    }
+}
+
+/// Size of an image over `from`..`to`, following rebuilds under its current tags, with the
+/// change between the first and last point
+pub async fn get_image_size_trend(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    let image_id = path.into_inner();
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    let (from, to) = match state.query_validator.validate(query.from, query.to, None) {
+        // The validator always fills in the time range
+        Ok((from, to, _)) => (from.unwrap_or_default(), to.unwrap_or_else(Utc::now)),
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid query parameters: {}", e)
+            }));
+        }
+    };
+
+    match query_service.get_image_size_trend(&image_id, from, to).await {
+        // Neither a known tag nor any snapshot of the id in range
+        Ok(trend) if trend.points.is_empty() && trend.repo_tags.is_empty() => {
+            HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("No snapshots found for image: {}", image_id)
+            }))
+        }
+        Ok(trend) => HttpResponse::Ok().json(trend),
+        Err(e) => {
+            log::error!("Failed to get image size trend: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get image size trend: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get service communication map
+/// Query parameters: `service_id` (optional) - filter to show only connections for a specific service,
+/// `min_confidence` (optional, 0.0-1.0) - drop edges (and, when focused, the services they pulled in) below it
+pub async fn get_service_map(
+    state: web::Data<AppState>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    let service_map_service = ServiceMapService::new(
+        Arc::clone(&state.docker_service),
+        state.query_service.as_ref().map(|qs| qs.query_service()),
+    );
+
+    // Get optional service_id from query parameters
+    let service_id = query.get("service_id").map(|s| s.as_str());
+
+    let min_confidence = match query.get("min_confidence").map(|s| s.parse::<f64>()) {
+        None => 0.0,
+        Some(Ok(value)) if (0.0..=1.0).contains(&value) => value,
+        Some(_) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "'min_confidence' must be a number between 0 and 1"
+            }));
+        }
+    };
+
+    match service_map_service.generate_service_map_for_service(service_id, min_confidence).await {
+        Ok(service_map) => HttpResponse::Ok().json(service_map),
+        Err(e) => {
+            if let Some(query_service) = docker_fallback(&state, &e) {
+                log::warn!("Failed to generate service map, serving latest snapshot: {}", e);
+                match query_service.get_latest_service_map_snapshot().await {
+                    Ok(Some(snapshot)) => {
+                        let service_map = ServiceMapService::filter_service_map(snapshot, service_id, min_confidence);
+                        return HttpResponse::Ok()
+                            .insert_header((DOCKER_UNAVAILABLE_HEADER, "true"))
+                            .json(service_map);
+                    }
+                    Ok(None) => {}
+                    Err(db_err) => log::error!("Failed to load service map snapshot: {}", db_err),
+                }
+            }
+            log::error!("Failed to generate service map: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to generate service map: {}", e)
+            }))
+        }
+    }
+}
+
+/// Diff the stored service-map snapshots nearest to `from` and `to`
+/// Query parameters: `from`, `to` (RFC 3339 timestamps, both required)
+pub async fn get_service_map_diff(
+    state: web::Data<AppState>,
+    query: web::Query<ServiceMapDiffQuery>,
+) -> impl Responder {
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    let (from, to) = match (query.from, query.to) {
+        (Some(from), Some(to)) if from <= to => (from, to),
+        (Some(_), Some(_)) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "'from' must be before 'to'"
+            }));
+        }
+        _ => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Both 'from' and 'to' query parameters are required"
+            }));
+        }
+    };
+
+    match query_service.get_service_map_diff(from, to).await {
+        Ok(Some(diff)) => HttpResponse::Ok().json(diff),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "No service map snapshots available"
+        })),
+        Err(e) => {
+            log::error!("Failed to diff service map snapshots: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to diff service map snapshots: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get HTTP requests for a specific container/service
+/// Queries from database (collected by worker service)
+/// Supports both container ID and container name in the path
+pub async fn get_container_http_requests(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    let container_identifier = path.into_inner();
+    let limit = query
+        .get("limit")
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(100);
+    let format = match ExportFormat::parse(query.get("format").map(|s| s.as_str())) {
+        Ok(format) => format,
+        Err(response) => return *response,
+    };
+
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    // Find container by ID or name (or every container run under a name with `by=name`)
+    let selector = match resolve_container_selector(&state, &container_identifier, query.get("by").map(|s| s.as_str())).await {
+        Ok(selector) => selector,
+        Err(response) => return response,
+    };
+    let container_id = selector.value();
+
+    log::debug!("Getting HTTP requests for container: {} from database", container_id);
+
+    // Parse optional time range query parameters
+    let from = query
+        .get("from")
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.with_timezone(&chrono::Utc));
+    
+    let to = query
+        .get("to")
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.with_timezone(&chrono::Utc));
+
+    // Optional filters: one method, and/or endpoints starting with a prefix
+    let method = query.get("method").map(|s| s.trim()).filter(|s| !s.is_empty());
+    let endpoint = query.get("endpoint").map(|s| s.trim()).filter(|s| !s.is_empty());
+
+    // Query database for HTTP requests
+    match query_service
+        .get_container_http_requests(&selector, from, to, method, endpoint, Some(limit))
+        .await
+    {
+        Ok(requests) => {
+            log::info!("Retrieved {} HTTP requests from database for container {}", requests.len(), container_id);
+            match format {
+                ExportFormat::Csv => csv_export::csv_response(&requests, &format!("{}-requests.csv", container_id)),
+                ExportFormat::Json => HttpResponse::Ok().json(requests),
+            }
+        }
+        Err(e) => {
+            log::error!("Failed to get HTTP requests from database: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get HTTP requests: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get the most recent requests to one endpoint of a container, newest first (from database)
+/// `path` is the endpoint template as stored after normalization, e.g. `/api/users/:id`
+pub async fn get_container_endpoint_requests(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<EndpointRequestsQuery>,
+) -> impl Responder {
+    let container_identifier = path.into_inner();
+
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    if query.path.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Query parameter 'path' is required"
+        }));
+    }
+
+    let (from, to, limit) = match state.query_validator.validate(query.from, query.to, Some(query.limit.unwrap_or(100))) {
+        // The validator always fills in the time range and limit
+        Ok((from, to, limit)) => (from.unwrap_or_default(), to.unwrap_or_else(Utc::now), limit.unwrap_or(100)),
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid query parameters: {}", e)
+            }));
+        }
+    };
+
+    let selector = match resolve_container_selector(&state, &container_identifier, query.by.as_deref()).await {
+        Ok(selector) => selector,
+        Err(response) => return response,
+    };
+
+    match query_service.get_endpoint_http_requests(&selector, &query.path, from, to, limit).await {
+        Ok(requests) => HttpResponse::Ok().json(requests),
+        Err(e) => {
+            log::error!("Failed to get requests for endpoint {}: {}", query.path, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get endpoint requests: {}", e)
+            }))
+        }
+    }
+}
+
+/// Full-text search of stored log lines across all containers (lines containing every word of `q`)
+/// Query parameters: `q` (required), `from`, `to`, `container_id`, `stream`, `sort`, `limit`, `before`.
+/// Results are newest first and paginated by the `before` cursor (`sort=time`, the default) or
+/// ranked by relevance (`sort=relevance`, one page only); the time range is capped by the
+/// history query validator so a search never scans all stored logs
+pub async fn search_logs(
+    state: web::Data<AppState>,
+    query: web::Query<LogSearchQuery>,
+) -> impl Responder {
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    let text = query.q.trim();
+    if text.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Query parameter 'q' is required"
+        }));
+    }
+
+    if let Some(stream) = query.stream.as_deref()
+        && stream != "stdout"
+        && stream != "stderr"
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Invalid stream '{}' (expected stdout or stderr)", stream)
+        }));
+    }
+
+    let before = match parse_cursor(query.before.as_deref()) {
+        Ok(before) => before,
+        Err(response) => return *response,
+    };
+
+    let by_relevance = match query.sort.as_deref() {
+        None | Some("time") => false,
+        Some("relevance") => true,
+        Some(other) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid sort '{}' (expected time or relevance)", other)
+            }));
+        }
+    };
+
+    let (from, to, limit) = match state.query_validator.validate(query.from, query.to, Some(query.limit.unwrap_or(100))) {
+        // The validator always fills in the time range and limit
+        Ok((from, to, limit)) => (from.unwrap_or_default(), to.unwrap_or_else(Utc::now), limit.unwrap_or(100)),
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid query parameters: {}", e)
+            }));
+        }
+    };
+
+    match query_service
+        .search_logs(
+            text,
+            from,
+            to,
+            query.container_id.as_deref(),
+            query.stream.as_deref(),
+            before,
+            by_relevance,
+            limit,
+        )
+        .await
+    {
+        // The `before` cursor only pages through time-ordered results
+        Ok((logs, _)) if by_relevance => HttpResponse::Ok().json(PaginatedResponse::with_cursor(logs, limit, None, None)),
+        Ok((logs, last)) => HttpResponse::Ok().json(PaginatedResponse::with_cursor(logs, limit, last, before)),
+        Err(e) => {
+            log::error!("Failed to search logs: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to search logs: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get HTTP response counts per status class for a container (from database)
+/// Always returns every class, with zero counts when there is no data
+pub async fn get_container_http_status_breakdown(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    let container_identifier = path.into_inner();
+
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    let (from, to) = match state.query_validator.validate(query.from, query.to, None) {
+        // The validator always fills in the time range
+        Ok((from, to, _)) => (from.unwrap_or_default(), to.unwrap_or_else(Utc::now)),
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid query parameters: {}", e)
+            }));
+        }
+    };
+
+    let selector = match resolve_container_selector(&state, &container_identifier, query.by.as_deref()).await {
+        Ok(selector) => selector,
+        Err(response) => return response,
+    };
+
+    match query_service.get_http_status_breakdown(&selector, from, to).await {
+        Ok(breakdown) => HttpResponse::Ok().json(breakdown),
+        Err(e) => {
+            log::error!("Failed to get HTTP status breakdown: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get HTTP status breakdown: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get total requests, status-class counts, error rate and requests per minute for a container
+pub async fn get_container_http_summary(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    let container_identifier = path.into_inner();
+
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    let (from, to) = match state.query_validator.validate(query.from, query.to, None) {
+        // The validator always fills in the time range
+        Ok((from, to, _)) => (from.unwrap_or_default(), to.unwrap_or_else(Utc::now)),
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid query parameters: {}", e)
+            }));
+        }
+    };
+
+    let selector = match resolve_container_selector(&state, &container_identifier, query.by.as_deref()).await {
+        Ok(selector) => selector,
+        Err(response) => return response,
+    };
+
+    match query_service.get_http_request_summary(&selector, from, to).await {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(e) => {
+            log::error!("Failed to get HTTP request summary: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get HTTP request summary: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get p50/p95/p99 and average HTTP response time for a container (from database)
+/// Returns zeros when there are no requests in the range
+pub async fn get_container_http_latency(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    let container_identifier = path.into_inner();
+
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    let (from, to) = match state.query_validator.validate(query.from, query.to, None) {
+        // The validator always fills in the time range
+        Ok((from, to, _)) => (from.unwrap_or_default(), to.unwrap_or_else(Utc::now)),
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid query parameters: {}", e)
+            }));
+        }
+    };
+
+    let selector = match resolve_container_selector(&state, &container_identifier, query.by.as_deref()).await {
+        Ok(selector) => selector,
+        Err(response) => return response,
+    };
+
+    match query_service.get_http_latency_percentiles(&selector, from, to).await {
+        Ok(latency) => HttpResponse::Ok().json(latency),
+        Err(e) => {
+            log::error!("Failed to get HTTP latency percentiles: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get HTTP latency percentiles: {}", e)
+            }))
+        }
+    }
+}
+
+/// Endpoints returned by the endpoint inventory when `limit` is not given
+const DEFAULT_ENDPOINT_LIMIT: u64 = 100;
+
+/// Get the distinct (method, endpoint) pairs a container served, with call count, average
+/// latency and 5xx count, most called first (from database). At most `limit` endpoints
+pub async fn get_container_http_endpoints(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    let container_identifier = path.into_inner();
+
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    let (from, to, limit) = match state.query_validator.validate(query.from, query.to, Some(query.limit.unwrap_or(DEFAULT_ENDPOINT_LIMIT))) {
+        // The validator always fills in the time range and limit
+        Ok((from, to, limit)) => (from.unwrap_or_default(), to.unwrap_or_else(Utc::now), limit.unwrap_or(DEFAULT_ENDPOINT_LIMIT)),
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid query parameters: {}", e)
+            }));
+        }
+    };
+
+    let selector = match resolve_container_selector(&state, &container_identifier, query.by.as_deref()).await {
+        Ok(selector) => selector,
+        Err(response) => return response,
+    };
+
+    match query_service.get_container_endpoints(&selector, from, to, limit).await {
+        Ok(endpoints) => HttpResponse::Ok().json(endpoints),
+        Err(e) => {
+            log::error!("Failed to get HTTP endpoints: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get HTTP endpoints: {}", e)
+            }))
+        }
+    }
+}
+
+/// List active streaming (SSE) sessions
+/// Requires the `ADMIN_TOKEN` bearer token
+pub async fn get_admin_streams(req: HttpRequest, state: web::Data<AppState>) -> impl Responder {
+    if let Err(response) = require_admin(&req, &state) {
+        return *response;
+    }
+
+    HttpResponse::Ok().json(state.streams.list())
+}
+
+/// Forcibly close a streaming session, ending the client's response
+/// Requires the `ADMIN_TOKEN` bearer token
+pub async fn close_admin_stream(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<u64>,
+) -> impl Responder {
+    if let Err(response) = require_admin(&req, &state) {
+        return *response;
+    }
+
+    let session_id = path.into_inner();
+    if state.streams.close(session_id) {
+        log::info!("Closed streaming session {} on admin request", session_id);
+        HttpResponse::NoContent().finish()
+    } else {
+        HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("No active stream with id {}", session_id)
+        }))
+    }
+}
+
+/// End-to-end check of a deployment: write a synthetic stats row, read it back from the
+/// database and through the cache, then delete it. Reports each layer's outcome and timing
+/// (200 when all pass, 503 otherwise). Requires the `ADMIN_TOKEN` bearer token and `SELF_TEST_ENABLED`
+pub async fn run_self_test(req: HttpRequest, state: web::Data<AppState>) -> impl Responder {
+    if let Err(response) = require_admin(&req, &state) {
+        return *response;
+    }
+    if !state.config.self_test_enabled {
+        return HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Self-test is disabled; set SELF_TEST_ENABLED=true to enable it"
+        }));
+    }
+
+    let (Some(db), Some(query_service)) = (&state.db, &state.query_service) else {
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "error": "Database not available"
+        }));
+    };
+
+    let report = self_test::run(db, query_service, &state.cache_service).await;
+    if report.success {
+        HttpResponse::Ok().json(report)
+    } else {
+        log::warn!("Self-test failed: {:?}", report.steps);
+        HttpResponse::ServiceUnavailable().json(report)
+    }
+}
+
+/// Change how long a hypertable keeps data: `{"table": "container_stats", "interval": "14 days"}`,
+/// or `"interval": null` to keep it forever. Requires the `ADMIN_TOKEN` bearer token
+pub async fn set_retention_policy(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<RetentionPolicyRequest>,
+) -> impl Responder {
+    if let Err(response) = require_admin(&req, &state) {
+        return *response;
+    }
+
+    let interval = body.interval.as_deref().map(str::trim);
+    if let Err(message) = QueryService::validate_retention(&body.table, interval) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": message }));
+    }
+
+    let Some(query_service) = &state.query_service else {
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "error": "Database not available"
+        }));
+    };
+
+    match query_service.set_retention_policy(&body.table, interval).await {
+        Ok(()) => {
+            log::info!("Retention policy of {} set to {:?} on admin request", body.table, interval);
+            HttpResponse::Ok().json(serde_json::json!({
+                "table": body.table,
+                "interval": interval,
+            }))
+        }
+        Err(e) => {
+            log::error!("Failed to set retention policy of {}: {}", body.table, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to set retention policy: {}", e)
+            }))
+        }
+    }
+}
+
+/// Check the request's token (see [`auth::request_token`]) against the configured admin token
+fn require_admin(req: &HttpRequest, state: &AppState) -> Result<(), Box<HttpResponse>> {
+    let Some(expected) = state.config.admin_token.as_deref() else {
+        return Err(Box::new(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Admin endpoints are disabled; set ADMIN_TOKEN to enable them"
+        }))));
+    };
+
+    match auth::request_token(req) {
+        Some(token) if auth::token_matches(&token, expected) => Ok(()),
+        _ => Err(Box::new(HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "Missing or invalid admin token"
+        })))),
+    }
+}
+
+/// Server-sent events response. Marked `Content-Encoding: identity` so the `Compress`
+/// middleware leaves it alone: a compressor buffers output, which would hold events back
+fn event_stream_response<S>(stream: S) -> HttpResponse
+where
+    S: futures::Stream<Item = Result<Bytes, Error>> + 'static,
+{
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(ContentEncoding::Identity)
+        .append_header(("Cache-Control", "no-cache"))
+        .append_header(("Connection", "keep-alive"))
+        .append_header(("X-Accel-Buffering", "no"))
+        .streaming(stream)
+}
+
+/// Client address for session bookkeeping (honours `X-Forwarded-For` / `Forwarded`)
+fn client_addr(req: &HttpRequest) -> String {
+    req.connection_info()
+        .realip_remote_addr()
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Suggest CPU/memory limits for a container from the p95 of its stored stats
+/// Query parameters: `from`, `to` (optional, RFC3339) - window, validated like other history queries
+pub async fn get_container_recommendations(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    let container_identifier = path.into_inner();
+
+    let query_service = match &state.query_service {
+        Some(qs) => qs,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Database not available"
+            }));
+        }
+    };
+
+    let (from, to) = match state.query_validator.validate(query.from, query.to, None) {
+        // The validator always fills in the time range
+        Ok((from, to, _)) => (from.unwrap_or_default(), to.unwrap_or_else(Utc::now)),
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid query parameters: {}", e)
+            }));
+        }
+    };
+
+    let selector = match resolve_container_selector(&state, &container_identifier, query.by.as_deref()).await {
+        Ok(selector) => selector,
+        Err(response) => return response,
+    };
+
+    match query_service
+        .get_resource_recommendation(&selector, from, to, state.config.recommendation_min_samples)
+        .await
+    {
+        Ok(recommendation) => HttpResponse::Ok().json(recommendation),
+        Err(e) => {
+            log::error!("Failed to compute resource recommendation: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to compute resource recommendation: {}", e)
+            }))
+        }
+    }
+}
+
+/// Interpret a container path segment per the `by` query parameter without resolving it:
+/// `id` (default) selects that container id, `name` every container that ran under the name
+fn container_selector(identifier: &str, by: Option<&str>) -> Result<ContainerSelector, Box<HttpResponse>> {
+    match by {
+        None | Some("id") => Ok(ContainerSelector::Id(identifier.to_string())),
+        Some("name") => Ok(ContainerSelector::by_name(identifier)),
+        Some(other) => Err(Box::new(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Invalid 'by' value '{}' (expected 'id' or 'name')", other)
+        })))),
+    }
+}
+
+/// Like [`container_selector`], but with `by=id` the path may be an ID or a name of a
+/// current container, resolved to its full ID
+async fn resolve_container_selector(
+    state: &AppState,
+    identifier: &str,
+    by: Option<&str>,
+) -> Result<ContainerSelector, HttpResponse> {
+    match container_selector(identifier, by).map_err(|response| *response)? {
+        ContainerSelector::Id(_) => Ok(ContainerSelector::Id(resolve_container_id(state, identifier).await?)),
+        by_name => Ok(by_name),
+    }
+}
+
+/// Resolve a container ID or name (as used in request paths) to a full container ID.
+/// Falls back to the containers stored in the database when Docker can't be reached
+async fn resolve_container_id(state: &AppState, container_identifier: &str) -> Result<String, HttpResponse> {
+    let containers = match state.docker_service.list_containers().await {
+        Ok(containers) => containers,
+        Err(e) => match docker_fallback(state, &e) {
+            Some(query_service) => {
+                log::warn!("Failed to list containers from Docker, resolving from database: {}", e);
+                query_service.get_all_containers().await.map_err(|db_err| {
+                    log::error!("Failed to list stored containers: {}", db_err);
+                    HttpResponse::InternalServerError().json(serde_json::json!({
+                        "error": format!("Failed to list containers: {}", e)
+                    }))
+                })?
+            }
+            None => {
+                log::error!("Failed to list containers: {}", e);
+                return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Failed to list containers: {}", e)
+                })));
+            }
+        },
+    };
+
+    match find_container_id(&containers, container_identifier) {
+        Some(id) => Ok(id),
+        None => {
+            log::warn!("Container not found: {} (tried as ID and name)", container_identifier);
+            Err(HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("Container not found: {}", container_identifier)
+            })))
+        }
+    }
+}
+
+/// Match by ID first, then by name (with or without the leading `/`, or as a name suffix)
+fn find_container_id(containers: &[ContainerInfo], container_identifier: &str) -> Option<String> {
+    containers
+        .iter()
+        .find(|c| c.id == container_identifier)
+        .or_else(|| containers.iter().find(|c|
+            c.name == container_identifier ||
+            c.name.trim_start_matches('/') == container_identifier ||
+            c.name.ends_with(container_identifier)
+        ))
+        .map(|c| c.id.clone())
+}
+
+/// Query service to fall back to when `error` means Docker can't be reached; `None` for
+/// other Docker errors (e.g. an unknown container), if the fallback is disabled
+/// (`DOCKER_FALLBACK_TO_DB=false`) or there is no database
+fn docker_fallback<'a>(state: &'a AppState, error: &anyhow::Error) -> Option<&'a Arc<CachedQueryService>> {
+    if state.config.docker_fallback_to_db && is_docker_unreachable(error) {
+        state.query_service.as_ref()
+    } else {
+        None
+    }
+}
+
+/// Query parameters for history endpoints
+#[derive(serde::Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub limit: Option<u64>,
+    /// Bucket size in seconds; stats history returns per-bucket aggregates when set
+    #[serde(default)]
+    pub bucket: Option<i64>,
+    /// Cursor: only rows older than this (a timestamp, or `pagination.next_cursor` of the
+    /// previous page); the response is then a `PaginatedResponse`
+    #[serde(default)]
+    pub before: Option<String>,
+    /// `true` (nulls) or `locf` (last value carried forward) to return every bucket of the
+    /// `from`..`to` range, including those without samples
+    #[serde(default)]
+    pub gapfill: Option<String>,
+    /// `id` (default) or `name` - see [`container_selector`]
+    #[serde(default)]
+    pub by: Option<String>,
+    /// `json` (default) or `csv`, where the endpoint supports it
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// Body of [`set_retention_policy`]
+#[derive(serde::Deserialize)]
+pub struct RetentionPolicyRequest {
+    pub table: String,
+    /// e.g. `14 days`; null or absent removes the policy
+    #[serde(default)]
+    pub interval: Option<String>,
+}
+
+/// Query parameters for the live log stream
+#[derive(serde::Deserialize)]
+pub struct LogStreamQuery {
+    /// Lines of existing output to send before following; only new output when absent
+    #[serde(default)]
+    pub tail: Option<u64>,
+}
+
+/// Query parameters for the fleet-wide log search
+#[derive(serde::Deserialize)]
+pub struct LogSearchQuery {
+    #[serde(default)]
+    pub q: String,
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub container_id: Option<String>,
+    /// `stdout` or `stderr`
+    #[serde(default)]
+    pub stream: Option<String>,
+    /// `time` (default, newest first, pageable with `before`) or `relevance` (by `ts_rank`)
+    #[serde(default)]
+    pub sort: Option<String>,
+    #[serde(default)]
+    pub limit: Option<u64>,
+    /// Cursor: only lines older than this (a timestamp, or `pagination.next_cursor` of the previous page)
+    #[serde(default)]
+    pub before: Option<String>,
+}
+
+/// Query parameters for the per-endpoint request drill-down
+#[derive(serde::Deserialize)]
+pub struct EndpointRequestsQuery {
+    /// Endpoint template to match, e.g. `/api/users/:id`
+    #[serde(default)]
+    pub path: String,
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub limit: Option<u64>,
+    #[serde(default)]
+    pub by: Option<String>,
+}
+
+/// Query parameters for per-container endpoints without a time range
+#[derive(serde::Deserialize)]
+pub struct ContainerByQuery {
+    #[serde(default)]
+    pub by: Option<String>,
+}
+
+/// Query parameters for the stale images report
+#[derive(serde::Deserialize)]
+pub struct StaleImagesQuery {
+    #[serde(default, alias = "days")]
+    pub older_than_days: Option<u32>,
+    /// Only images at least one running container uses
+    #[serde(default)]
+    pub in_use: bool,
+}
+
+/// Query parameters for the service map diff endpoint
+#[derive(serde::Deserialize)]
+pub struct ServiceMapDiffQuery {
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for the fleet stats history endpoint
+#[derive(serde::Deserialize)]
+pub struct FleetHistoryQuery {
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub bucket: Option<i64>,
+}
+
+/// Query parameters for the top containers by usage
+#[derive(serde::Deserialize)]
+pub struct TopContainersQuery {
+    #[serde(default)]
+    pub metric: Option<String>,
+    #[serde(default)]
+    pub n: Option<u64>,
+}
+
+/// Alerts returned when `limit` is not given, and the most that can be requested
+const DEFAULT_ALERT_LIMIT: u64 = 100;
+const MAX_ALERT_LIMIT: u64 = 1000;
+
+#[derive(serde::Deserialize)]
+pub struct AlertsQuery {
+    /// Only alerts that are currently firing (latest transition per rule and container)
+    pub firing: Option<bool>,
+    pub limit: Option<u64>,
+}
+
+/// Recorded alert transitions, newest first, or with `firing=true` the alerts that are firing now
+pub async fn get_alerts(
+    state: web::Data<AppState>,
+    query: web::Query<AlertsQuery>,
+) -> impl Responder {
+    let Some(alert_service) = &state.alert_service else {
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "error": "Database not available"
+        }));
+    };
+
+    let result = if query.firing.unwrap_or(false) {
+        alert_service.firing_alerts().await
+    } else {
+        let limit = query.limit.unwrap_or(DEFAULT_ALERT_LIMIT).clamp(1, MAX_ALERT_LIMIT);
+        alert_service.list_alerts(limit).await
+    };
+
+    match result {
+        Ok(alerts) => HttpResponse::Ok().json(alerts),
+        Err(e) => {
+            log::error!("Failed to get alerts: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get alerts: {}", e)
+            }))
+        }
+    }
+}
+
+pub async fn get_alert_rules(state: web::Data<AppState>) -> impl Responder {
+    let Some(alert_service) = &state.alert_service else {
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "error": "Database not available"
+        }));
+    };
+
+    match alert_service.list_rules().await {
+        Ok(rules) => HttpResponse::Ok().json(rules),
+        Err(e) => {
+            log::error!("Failed to get alert rules: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get alert rules: {}", e)
+            }))
+        }
+    }
+}
+
+pub async fn get_alert_rule(
+    state: web::Data<AppState>,
+    path: web::Path<i64>,
+) -> impl Responder {
+    let Some(alert_service) = &state.alert_service else {
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "error": "Database not available"
+        }));
+    };
+
+    let id = path.into_inner();
+    match alert_service.get_rule(id).await {
+        Ok(Some(rule)) => HttpResponse::Ok().json(rule),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Alert rule {} not found", id)
+        })),
+        Err(e) => {
+            log::error!("Failed to get alert rule {}: {}", id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get alert rule: {}", e)
+            }))
+        }
+    }
+}
+
+/// Create an alert rule; responds 201 with the stored rule
+pub async fn create_alert_rule(
+    state: web::Data<AppState>,
+    body: web::Json<AlertRuleInput>,
+) -> impl Responder {
+    let Some(alert_service) = &state.alert_service else {
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "error": "Database not available"
+        }));
+    };
+    if let Err(message) = AlertService::validate_rule(&body) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": message }));
+    }
+
+    match alert_service.create_rule(&body).await {
+        Ok(rule) => {
+            log::info!("Created alert rule {} '{}'", rule.id, rule.name);
+            HttpResponse::Created().json(rule)
+        }
+        Err(e) => {
+            log::error!("Failed to create alert rule: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to create alert rule: {}", e)
+            }))
+        }
+    }
+}
+
+/// Replace an alert rule's settings
+pub async fn update_alert_rule(
+    state: web::Data<AppState>,
+    path: web::Path<i64>,
+    body: web::Json<AlertRuleInput>,
+) -> impl Responder {
+    let Some(alert_service) = &state.alert_service else {
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "error": "Database not available"
+        }));
+    };
+    if let Err(message) = AlertService::validate_rule(&body) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": message }));
+    }
+
+    let id = path.into_inner();
+    match alert_service.update_rule(id, &body).await {
+        Ok(Some(rule)) => HttpResponse::Ok().json(rule),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Alert rule {} not found", id)
+        })),
+        Err(e) => {
+            log::error!("Failed to update alert rule {}: {}", id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to update alert rule: {}", e)
+            }))
+        }
+    }
+}
+
+/// Delete an alert rule; alerts it already recorded are kept
+pub async fn delete_alert_rule(
+    state: web::Data<AppState>,
+    path: web::Path<i64>,
+) -> impl Responder {
+    let Some(alert_service) = &state.alert_service else {
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "error": "Database not available"
+        }));
+    };
+
+    let id = path.into_inner();
+    match alert_service.delete_rule(id).await {
+        Ok(true) => {
+            log::info!("Deleted alert rule {}", id);
+            HttpResponse::NoContent().finish()
+        }
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Alert rule {} not found", id)
+        })),
+        Err(e) => {
+            log::error!("Failed to delete alert rule {}: {}", id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to delete alert rule: {}", e)
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE};
+    use actix_web::test;
+
+    async fn events() -> HttpResponse {
+        let event = Bytes::from_static(b"data: {\"containers_up\":1}\n\n");
+        event_stream_response(futures::stream::once(async move { Ok::<_, Error>(event) }))
+    }
+
+    #[actix_web::test]
+    async fn compresses_json_but_not_event_streams() {
+        let state = web::Data::new(AppState::for_tests(Config { api_token: None, ..Config::from_env() }));
+        let app = test::init_service(crate::app(state).route("/sse", web::get().to(events))).await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/containers")
+            .insert_header((ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        // No Docker daemon answers here, so this is the JSON 503 body; any JSON goes through the same middleware
+        assert_eq!(res.status(), actix_web::http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "application/json");
+
+        let req = test::TestRequest::get().uri("/sse").insert_header((ACCEPT_ENCODING, "gzip")).to_request();
+        let res = test::call_service(&app, req).await;
+        let encoding = res.headers().get(CONTENT_ENCODING).map(|value| value.to_str().unwrap().to_string());
+        assert_ne!(encoding.as_deref(), Some("gzip"));
+        assert_eq!(test::read_body(res).await, Bytes::from_static(b"data: {\"containers_up\":1}\n\n"));
+    }
+
+    #[actix_web::test]
+    async fn container_event_stream_follows_the_cors_policy() {
+        use actix_web::http::header::{ACCESS_CONTROL_ALLOW_ORIGIN, ORIGIN};
+
+        let config = Config {
+            api_token: None,
+            cors_allowed_origins: Some(vec!["https://dashboard.example".to_string()]),
+            ..Config::from_env()
+        };
+        let app = test::init_service(crate::app(web::Data::new(AppState::for_tests(config)))).await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/events/sse")
+            .insert_header((ORIGIN, "https://dashboard.example"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/event-stream");
+        assert_eq!(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(), "https://dashboard.example");
+
+        let req = test::TestRequest::get()
+            .uri("/api/events/sse")
+            .insert_header((ORIGIN, "https://elsewhere.example"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
+
+    #[actix_web::test]
+    async fn admin_routes_need_the_admin_token() {
+        use actix_web::http::StatusCode;
+        use actix_web::http::header::AUTHORIZATION;
+
+        let state = AppState::for_tests(Config { admin_token: Some("admin-secret".to_string()), ..Config::from_env() });
+        let check = |token: Option<&str>| {
+            let mut req = test::TestRequest::get().uri("/api/admin/streams");
+            if let Some(token) = token {
+                req = req.insert_header((AUTHORIZATION, format!("Bearer {}", token)));
+            }
+            require_admin(&req.to_http_request(), &state).err().map(|response| response.status())
+        };
+        assert_eq!(check(None), Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(check(Some("admin-secreT")), Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(check(Some("admin-secret")), None);
+
+        let state = AppState::for_tests(Config { admin_token: None, ..Config::from_env() });
+        let req = test::TestRequest::get().insert_header((AUTHORIZATION, "Bearer admin-secret")).to_http_request();
+        assert_eq!(require_admin(&req, &state).err().map(|response| response.status()), Some(StatusCode::FORBIDDEN));
+    }
+
+    #[actix_web::test]
+    async fn sensitive_env_keys_are_redacted() {
+        for key in [
+            "POSTGRES_PASSWORD",
+            "mysql_root_passwd",
+            "DB_PWD",
+            "REDIS_PASS",
+            "GITHUB_TOKEN",
+            "AWS_SECRET_ACCESS_KEY",
+            "STRIPE_API_KEY",
+            "GOOGLE_APPLICATION_CREDENTIALS",
+        ] {
+            assert!(is_sensitive_env_key(key), "{}", key);
+        }
+    }
+
+    #[actix_web::test]
+    async fn harmless_env_keys_are_shown() {
+        for key in [
+            "PWD",
+            "OLDPWD",
+            "PATH",
+            "HOME",
+            "PASSTHROUGH_MODE",
+            "NODE_ENV",
+            "COMPASS_URL",
+        ] {
+            assert!(!is_sensitive_env_key(key), "{}", key);
+        }
+    }
+
+    #[actix_web::test]
+    async fn log_timestamps_are_split_off() {
+        let (timestamp, rest) = split_log_timestamp("2024-12-01T10:00:00.123456789Z listening on :8080");
+        assert_eq!(timestamp.map(|t| t.to_rfc3339()), Some("2024-12-01T10:00:00.123456789+00:00".to_string()));
+        assert_eq!(rest, "listening on :8080");
+
+        // Offsets are converted to UTC; an empty message stays empty
+        let (timestamp, rest) = split_log_timestamp("2024-12-01T12:00:00+02:00 ");
+        assert_eq!(timestamp.map(|t| t.to_rfc3339()), Some("2024-12-01T10:00:00+00:00".to_string()));
+        assert_eq!(rest, "");
+    }
+
+    #[actix_web::test]
+    async fn lines_without_a_valid_timestamp_are_kept_whole() {
+        for line in [
+            "listening on :8080",
+            "",
+            "2024-12-01T10:00:00.123Z",
+            "2024-12-01 10:00:00 started",
+            "2024-13-01T10:00:00Z month out of range",
+            "2024-12-01T10:00:00 no offset",
+            "[2024-12-01T10:00:00Z] bracketed",
+        ] {
+            assert_eq!(split_log_timestamp(line), (None, line), "{}", line);
+        }
+    }
+
+    fn memory_stats(name: &str, usage: u64, limit: u64, percent: f64) -> ContainerStats {
+        ContainerStats {
+            memory_usage_bytes: usage,
+            memory_limit_bytes: limit,
+            memory_usage_percent: percent,
+            ..ContainerStats::empty(name.to_string(), name.to_string(), Utc::now())
+        }
+    }
+
+    #[actix_web::test]
+    async fn memory_pressure_lists_containers_at_the_threshold_fullest_first() {
+        let stats = [
+            memory_stats("api", 80, 100, 80.0),
+            memory_stats("db", 950, 1000, 95.0),
+            memory_stats("web", 10, 100, 79.9),
+            memory_stats("cache", 90, 100, 90.0),
+            memory_stats("worker", 400, 1000, 95.0),
+        ];
+
+        let pressure = compute_memory_pressure(&stats, None, 80.0);
+        assert_eq!(pressure.containers_over_threshold, 4);
+        assert_eq!(pressure.containers_over_threshold_names, ["db", "worker", "cache", "api"]);
+        assert_eq!(pressure.total_memory_usage_bytes, 1530);
+        assert_eq!(pressure.total_memory_limit_bytes, 2300);
+        assert_eq!(pressure.usage_percent_of_host, None);
+    }
+
+    #[actix_web::test]
+    async fn unlimited_containers_count_only_toward_the_host() {
+        let host = 4000;
+        let stats = [
+            memory_stats("api", 50, 100, 50.0),
+            // No limit: Docker reports the host total, or 0
+            memory_stats("batch", 1000, host, 25.0),
+            memory_stats("sidecar", 950, 0, 0.0),
+        ];
+
+        let pressure = compute_memory_pressure(&stats, Some(host), 90.0);
+        assert_eq!(pressure.unlimited_containers, 2);
+        assert_eq!(pressure.total_memory_limit_bytes, 100);
+        assert_eq!(pressure.usage_percent_of_limits, 50.0);
+        assert_eq!(pressure.usage_percent_of_host, Some(50.0));
+        assert_eq!(pressure.containers_over_threshold, 0);
+
+        let empty = compute_memory_pressure(&[], Some(host), 90.0);
+        assert_eq!((empty.usage_percent_of_limits, empty.usage_percent_of_host), (0.0, Some(0.0)));
+    }
 }
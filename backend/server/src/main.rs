@@ -1,12 +1,22 @@
+mod auth;
 mod config;
+mod csv_export;
 mod handlers;
+mod request_id;
 mod routes;
+mod self_test;
+mod stats_feed;
+mod streams;
 mod query_validation;
+mod rate_limit;
+mod tls;
 
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
 use actix_web::{web, App, HttpServer};
 use config::Config;
 use handlers::AppState;
-use eyes_devine_services::{CacheService, DockerService, QueryService, CachedQueryService, create_connection};
+use eyes_devine_services::{AlertService, CacheService, DockerService, QueryService, CachedQueryService, create_connection};
 use std::sync::Arc;
 use actix_cors::Cors;
 use crate::query_validation::HistoryQueryValidator;
@@ -14,7 +24,19 @@ use crate::query_validation::HistoryQueryValidator;
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv::dotenv().ok();
-    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
+    // Log lines emitted while handling a request are tagged with its X-Request-Id
+    env_logger::Builder::from_env(env_logger::Env::new().default_filter_or("info"))
+        .format(|buf, record| {
+            use std::io::Write;
+            let timestamp = buf.timestamp();
+            match request_id::current() {
+                Some(id) => writeln!(buf, "[{} {} {}] [req={}] {}",
+                    timestamp, record.level(), record.target(), id, record.args()),
+                None => writeln!(buf, "[{} {} {}] {}",
+                    timestamp, record.level(), record.target(), record.args()),
+            }
+        })
+        .init();
 
     let config = Config::from_env();
     
@@ -24,9 +46,13 @@ async fn main() -> std::io::Result<()> {
     let docker_service = Arc::new(
         DockerService::new()
             .await
-            .expect("Failed to initialize Docker service"),
+            .expect("Failed to initialize Docker service")
+            .with_max_log_line_length(config.max_log_line_length),
     );
 
+    // Re-create the Docker client if the daemon goes away (e.g. during a Docker upgrade)
+    docker_service.start_health_probe(config.docker_health_probe_interval);
+
     // Initialize Redis cache if URL is provided
     let cache_service = Arc::new(
         CacheService::new(config.redis_url.clone())
@@ -72,27 +98,86 @@ async fn main() -> std::io::Result<()> {
         config.max_results_per_query,
     );
 
+    let stats_feed = query_service
+        .as_ref()
+        .map(|qs| Arc::new(stats_feed::StatsFeed::new(Arc::clone(qs))));
+
+    let alert_service = db.as_ref().map(|db| Arc::new(AlertService::new(db.clone())));
+
+    let rate_limiter = config.rate_limit_rps.map(|rps| {
+        log::info!("Rate limiting expensive routes to {} req/s per client (burst {})", rps, config.rate_limit_burst);
+        Arc::new(
+            rate_limit::RateLimiter::new(rps, config.rate_limit_burst)
+                .with_trusted_proxy(config.rate_limit_trust_proxy),
+        )
+    });
+
     let app_state = web::Data::new(AppState {
         docker_service,
         db,
         query_service,
+        stats_feed,
+        alert_service,
         cache_service,
         query_validator,
+        config: config.clone(),
+        streams: streams::StreamRegistry::new(),
+        rate_limiter,
     });
 
-    HttpServer::new(move || {
-        let cors = Cors::default()
-            .allow_any_method()
-            .allow_any_origin()
-            .allow_any_header();
-
-        App::new()
-            .wrap(cors)
-            .app_data(app_state.clone())
-            .configure(routes::configure)
-    })
-    .bind(format!("{}:{}", config.server_host, config.server_port))?
-    .run()
-    .await
+    let server = HttpServer::new(move || app(app_state.clone()));
+
+    let bind_addr = format!("{}:{}", config.server_host, config.server_port);
+    let server = match (&config.tls_cert_path, &config.tls_key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            let tls_config = tls::load_rustls_config(cert_path, key_path)
+                .unwrap_or_else(|e| panic!("Failed to load TLS configuration: {:#}", e));
+            log::info!("Serving HTTPS on {}", bind_addr);
+            server.bind_rustls_0_23(bind_addr, tls_config)?
+        }
+        (None, None) => server.bind(bind_addr)?,
+        _ => panic!("TLS_CERT_PATH and TLS_KEY_PATH must be set together"),
+    };
+
+    server.run().await
+}
+
+/// The API routes behind their middleware, as served; the tests build the same app
+fn app(
+    app_state: web::Data<AppState>,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    let cors = cors(&app_state.config);
+
+    App::new()
+        // gzip/brotli/zstd as the client accepts; event streams opt out (see handlers)
+        .wrap(actix_web::middleware::Compress::default())
+        .wrap(actix_web::middleware::from_fn(auth::middleware))
+        .wrap(actix_web::middleware::from_fn(request_id::middleware))
+        .wrap(cors)
+        .app_data(app_state)
+        .configure(routes::configure)
 }
 
+/// CORS policy for the API and its streaming responses: the configured origins, or any
+/// origin when `CORS_ALLOWED_ORIGINS` is unset
+fn cors(config: &Config) -> Cors {
+    let cors = Cors::default()
+        .allow_any_method()
+        .allow_any_header()
+        .expose_headers([request_id::REQUEST_ID_HEADER, handlers::DOCKER_UNAVAILABLE_HEADER]);
+
+    match &config.cors_allowed_origins {
+        Some(origins) => origins
+            .iter()
+            .fold(cors, |cors, origin| cors.allowed_origin(origin)),
+        None => cors.allow_any_origin(),
+    }
+}
@@ -1,5 +1,9 @@
 use chrono::{DateTime, Utc, Duration as ChronoDuration};
 use anyhow::{Result, anyhow};
+use eyes_devine_services::KeysetCursor;
+
+/// Normalized `(from, to, limit)` triple produced by [`HistoryQueryValidator::validate`]
+pub type HistoryQueryParams = (Option<DateTime<Utc>>, Option<DateTime<Utc>>, Option<u64>);
 
 /// Validate and normalize history query parameters
 pub struct HistoryQueryValidator {
@@ -21,7 +25,7 @@ impl HistoryQueryValidator {
         from: Option<DateTime<Utc>>,
         to: Option<DateTime<Utc>>,
         limit: Option<u64>,
-    ) -> Result<(Option<DateTime<Utc>>, Option<DateTime<Utc>>, Option<u64>)> {
+    ) -> Result<HistoryQueryParams> {
         let now = Utc::now();
         let max_range = ChronoDuration::days(self.max_range_days as i64);
 
@@ -96,17 +100,54 @@ pub struct PaginatedResponse<T> {
     pub pagination: PaginationInfo,
 }
 
+/// Page-number pagination fills `page`/`total`/`total_pages`; cursor pagination
+/// fills `next_cursor` instead (no total is counted)
 #[derive(serde::Serialize)]
 pub struct PaginationInfo {
-    pub page: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u64>,
     pub page_size: u64,
-    pub total: u64,
-    pub total_pages: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_pages: Option<u64>,
     pub has_next: bool,
     pub has_prev: bool,
+    /// Pass as `before` to get the next (older) page (see [`KeysetCursor`])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 impl<T> PaginatedResponse<T> {
+    /// Page of results ordered newest first, continuing from `last`, the cursor of the oldest
+    /// returned row. A full page means there may be more. `before` is the cursor the page was
+    /// requested with: only one returned by a previous page (with an id) means there is a
+    /// newer page; a bare timestamp such as now may start the listing
+    pub fn with_cursor(
+        data: Vec<T>,
+        page_size: u64,
+        last: Option<KeysetCursor>,
+        before: Option<KeysetCursor>,
+    ) -> Self {
+        let next_cursor = last
+            .filter(|_| data.len() as u64 >= page_size)
+            .map(|cursor| cursor.to_string());
+        let has_prev = before.is_some_and(|cursor| cursor.id.is_some());
+
+        Self {
+            data,
+            pagination: PaginationInfo {
+                page: None,
+                page_size,
+                total: None,
+                total_pages: None,
+                has_next: next_cursor.is_some(),
+                has_prev,
+                next_cursor,
+            },
+        }
+    }
+
     pub fn new(data: Vec<T>, page: u64, page_size: u64, total: u64) -> Self {
         let total_pages = (total as f64 / page_size as f64).ceil() as u64;
         let has_next = page < total_pages;
@@ -115,14 +156,48 @@ impl<T> PaginatedResponse<T> {
         Self {
             data,
             pagination: PaginationInfo {
-                page,
+                page: Some(page),
                 page_size,
-                total,
-                total_pages,
+                total: Some(total),
+                total_pages: Some(total_pages),
                 has_next,
                 has_prev,
+                next_cursor: None,
             },
         }
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor(value: &str) -> KeysetCursor {
+        KeysetCursor::parse(value).unwrap()
+    }
+
+    #[test]
+    fn full_page_gets_a_next_cursor() {
+        let last = cursor("2026-01-01T00:00:00Z_5");
+        let page = PaginatedResponse::with_cursor(vec![1, 2], 2, Some(last), None);
+        assert_eq!(page.pagination.next_cursor.as_deref(), Some("2026-01-01T00:00:00.000000Z_5"));
+        assert!(page.pagination.has_next);
+
+        let page = PaginatedResponse::with_cursor(vec![1], 2, Some(last), None);
+        assert_eq!(page.pagination.next_cursor, None);
+        assert!(!page.pagination.has_next);
+    }
+
+    #[test]
+    fn only_a_returned_cursor_means_a_previous_page() {
+        let first = PaginatedResponse::with_cursor(vec![1], 2, None, Some(cursor("2026-01-01T00:00:00Z")));
+        assert!(!first.pagination.has_prev);
+
+        let first = PaginatedResponse::<i32>::with_cursor(vec![], 2, None, None);
+        assert!(!first.pagination.has_prev);
+
+        let later = PaginatedResponse::with_cursor(vec![1], 2, None, Some(cursor("2026-01-01T00:00:00Z_5")));
+        assert!(later.pagination.has_prev);
+    }
+}
@@ -0,0 +1,217 @@
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::RETRY_AFTER;
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpResponse};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::handlers::AppState;
+
+/// Above this many tracked clients, buckets that have refilled completely are dropped
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// A full table is swept at most this often, so a flood of new clients can't make every
+/// request pay for a scan of the whole table
+const EVICTION_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Shared bucket for new clients that arrive while the table is still full after a sweep
+const OVERFLOW_CLIENT: &str = "<overflow>";
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+struct Buckets {
+    by_client: HashMap<String, Bucket>,
+    last_eviction: Option<Instant>,
+}
+
+/// Token bucket per client: `rate` requests per second on average, bursts of up to `burst`
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    max_clients: usize,
+    trust_proxy: bool,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub fn new(rate: f64, burst: u32) -> Self {
+        Self {
+            rate,
+            burst: burst.max(1) as f64,
+            max_clients: MAX_TRACKED_CLIENTS,
+            trust_proxy: false,
+            buckets: Mutex::new(Buckets {
+                by_client: HashMap::new(),
+                last_eviction: None,
+            }),
+        }
+    }
+
+    /// Key clients by `X-Forwarded-For` / `Forwarded` instead of the socket peer. Only safe
+    /// behind a proxy that overwrites those headers, since clients can set them freely
+    pub fn with_trusted_proxy(mut self, trust_proxy: bool) -> Self {
+        self.trust_proxy = trust_proxy;
+        self
+    }
+
+    /// Address the request is limited under
+    pub fn client_key(&self, req: &ServiceRequest) -> String {
+        let addr = if self.trust_proxy {
+            req.connection_info().realip_remote_addr().map(str::to_string)
+        } else {
+            req.peer_addr().map(|addr| addr.ip().to_string())
+        };
+        addr.unwrap_or_else(|| "unknown".to_string())
+    }
+
+    /// Take a token for `client`, or return how long until one is available
+    pub fn check(&self, client: &str) -> Result<(), Duration> {
+        self.check_at(client, Instant::now())
+    }
+
+    fn check_at(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+
+        let mut client = client;
+        if buckets.by_client.len() >= self.max_clients && !buckets.by_client.contains_key(client) {
+            let sweep_due = buckets
+                .last_eviction
+                .is_none_or(|at| now.duration_since(at) >= EVICTION_INTERVAL);
+            if sweep_due {
+                buckets.by_client.retain(|_, bucket| self.refilled(bucket, now) < self.burst);
+                buckets.last_eviction = Some(now);
+            }
+            if buckets.by_client.len() >= self.max_clients {
+                client = OVERFLOW_CLIENT;
+            }
+        }
+
+        let bucket = buckets.by_client.entry(client.to_string()).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        bucket.tokens = self.refilled(bucket, now);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        (bucket.tokens + elapsed * self.rate).min(self.burst)
+    }
+}
+
+/// Throttle the wrapped routes per client address (the socket peer, or the forwarded client
+/// with `RATE_LIMIT_TRUST_PROXY`), answering 429 with `Retry-After` when a client's bucket
+/// is empty. Passes everything through when `RATE_LIMIT_RPS` is unset
+pub async fn middleware<B: MessageBody>(
+    state: web::Data<AppState>,
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    let Some(limiter) = &state.rate_limiter else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+
+    let client = limiter.client_key(&req);
+
+    match limiter.check(&client) {
+        Ok(()) => Ok(next.call(req).await?.map_into_left_body()),
+        Err(retry_after) => {
+            log::debug!("Rate limited {} on {}", client, req.path());
+            let response = HttpResponse::TooManyRequests()
+                .insert_header((RETRY_AFTER, retry_after.as_secs_f64().ceil().max(1.0).to_string()))
+                .json(serde_json::json!({
+                    "error": "Too many requests; retry later"
+                }));
+            Ok(req.into_response(response).map_into_right_body())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn request_from(peer: &str, forwarded_for: &str) -> ServiceRequest {
+        TestRequest::default()
+            .peer_addr(peer.parse().unwrap())
+            .insert_header(("X-Forwarded-For", forwarded_for))
+            .to_srv_request()
+    }
+
+    #[test]
+    fn spoofed_forwarded_header_does_not_change_the_key() {
+        let limiter = RateLimiter::new(1.0, 1);
+        let first = limiter.client_key(&request_from("10.0.0.7:40000", "1.1.1.1"));
+        let second = limiter.client_key(&request_from("10.0.0.7:40001", "2.2.2.2"));
+
+        assert_eq!(first, "10.0.0.7");
+        assert_eq!(first, second);
+        assert!(limiter.check(&first).is_ok());
+        assert!(limiter.check(&second).is_err());
+    }
+
+    #[test]
+    fn trusted_proxy_keys_on_forwarded_client() {
+        let limiter = RateLimiter::new(1.0, 1).with_trusted_proxy(true);
+        let key = limiter.client_key(&request_from("10.0.0.7:40000", "1.1.1.1"));
+
+        assert_eq!(key, "1.1.1.1");
+    }
+
+    #[test]
+    fn full_table_evicts_refilled_buckets() {
+        let mut limiter = RateLimiter::new(1.0, 1);
+        limiter.max_clients = 2;
+        let start = Instant::now();
+
+        assert!(limiter.check_at("a", start).is_ok());
+        assert!(limiter.check_at("b", start).is_ok());
+
+        // Both buckets have refilled by now, so the sweep makes room for "c"
+        let later = start + Duration::from_secs(5);
+        assert!(limiter.check_at("c", later).is_ok());
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.by_client.len(), 1);
+        assert!(buckets.by_client.contains_key("c"));
+    }
+
+    #[test]
+    fn full_table_is_swept_at_most_once_per_interval() {
+        let mut limiter = RateLimiter::new(1.0, 1);
+        limiter.max_clients = 2;
+        let start = Instant::now();
+
+        assert!(limiter.check_at("a", start).is_ok());
+        assert!(limiter.check_at("b", start).is_ok());
+
+        // Nothing has refilled, so the sweep frees nothing and new clients share the overflow bucket
+        assert!(limiter.check_at("c", start).is_ok());
+        assert!(limiter.check_at("d", start).is_err());
+        let last_eviction = limiter.buckets.lock().unwrap().last_eviction;
+        assert_eq!(last_eviction, Some(start));
+
+        // Within the interval the table is not swept again, even though "a" has refilled
+        let soon = start + Duration::from_secs(2);
+        assert!(limiter.check_at("e", soon).is_ok());
+        assert_eq!(limiter.buckets.lock().unwrap().last_eviction, Some(start));
+        assert!(!limiter.buckets.lock().unwrap().by_client.contains_key("e"));
+
+        let after_interval = start + EVICTION_INTERVAL;
+        assert!(limiter.check_at("f", after_interval).is_ok());
+        assert_eq!(limiter.buckets.lock().unwrap().last_eviction, Some(after_interval));
+        assert!(limiter.buckets.lock().unwrap().by_client.contains_key("f"));
+    }
+}
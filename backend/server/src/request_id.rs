@@ -0,0 +1,72 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::Error;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied request id that is reused as-is
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Process start (seconds since epoch) keeps generated ids unique across restarts
+static ID_PREFIX: LazyLock<u64> = LazyLock::new(|| {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+});
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Request id of the request currently being handled, if any
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Assign each request an id (reusing a sane incoming `X-Request-Id`), run the handler with
+/// it in scope so every log line it emits carries it, and echo it in the response
+pub async fn middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let request_id = req
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_valid_request_id(id))
+        .map(str::to_string)
+        .unwrap_or_else(generate_request_id);
+
+    let method = req.method().clone();
+    let path = req.path().to_string();
+    let started = Instant::now();
+
+    let result = REQUEST_ID.scope(request_id.clone(), next.call(req)).await;
+
+    let mut response = result.inspect_err(|e| {
+        log::warn!("[req={}] {} {} failed: {}", request_id, method, path, e);
+    })?;
+    log::info!("[req={}] {} {} -> {} ({} ms)",
+        request_id, method, path, response.status().as_u16(), started.elapsed().as_millis());
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    Ok(response)
+}
+
+fn generate_request_id() -> String {
+    format!("{:x}-{:06x}", *ID_PREFIX, NEXT_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Client ids are only reused if they are short, visible ASCII (safe to log and echo back)
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic())
+}
@@ -1,49 +1,187 @@
 use crate::handlers;
+use crate::rate_limit;
+use actix_web::middleware::from_fn;
 use actix_web::web;
 
+/// History, log and service-map routes (the ones that hit TimescaleDB or Docker hard) are
+/// wrapped in the per-client rate limiter; everything else, `/metrics` included, is not
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg
+        // Liveness and readiness probes
+        .route("/health", web::get().to(handlers::get_health))
+        .route("/ready", web::get().to(handlers::get_readiness))
+
+        // Prometheus scrape endpoint
+        .route("/metrics", web::get().to(handlers::get_prometheus_metrics))
+        
         // Stats endpoints
         .route("/api/stats/total", web::get().to(handlers::get_total_stats))
         .route("/api/stats/total/sse", web::get().to(handlers::get_total_stats_sse))
+        .route("/api/stats/ws", web::get().to(handlers::get_stats_ws))
+        .service(
+            web::resource("/api/stats/total/history")
+                .wrap(from_fn(rate_limit::middleware))
+                .route(web::get().to(handlers::get_total_stats_history)),
+        )
+        .route("/api/stats/memory-pressure", web::get().to(handlers::get_memory_pressure))
+        .route("/api/stats/top", web::get().to(handlers::get_top_containers))
         
         // Container endpoints
         .route("/api/containers", web::get().to(handlers::get_all_containers))
+        .route("/api/projects", web::get().to(handlers::get_projects))
         .route(
             "/api/containers/{id}/stats",
             web::get().to(handlers::get_container_stats),
         )
         .route(
-            "/api/containers/{id}/stats/history",
-            web::get().to(handlers::get_container_stats_history),
+            "/api/containers/{id}/stats/stream",
+            web::get().to(handlers::get_container_stats_stream),
+        )
+        .service(
+            web::resource("/api/containers/{id}/stats/history")
+                .wrap(from_fn(rate_limit::middleware))
+                .route(web::get().to(handlers::get_container_stats_history)),
+        )
+        .service(
+            web::resource("/api/containers/{id}/stats/network-rate")
+                .wrap(from_fn(rate_limit::middleware))
+                .route(web::get().to(handlers::get_container_network_rate)),
+        )
+        .service(
+            web::resource("/api/containers/{id}/status-history")
+                .wrap(from_fn(rate_limit::middleware))
+                .route(web::get().to(handlers::get_container_status_history)),
+        )
+        .route(
+            "/api/containers/{id}/disk",
+            web::get().to(handlers::get_container_disk_usage),
+        )
+        .route(
+            "/api/containers/{id}/details",
+            web::get().to(handlers::get_container_details),
+        )
+        .route(
+            "/api/containers/{id}/top",
+            web::get().to(handlers::get_container_processes),
         )
         .route(
             "/api/containers/stats",
             web::get().to(handlers::get_all_container_stats),
         )
+        .service(
+            web::resource("/api/logs/search")
+                .wrap(from_fn(rate_limit::middleware))
+                .route(web::get().to(handlers::search_logs)),
+        )
+        .route("/api/events/sse", web::get().to(handlers::get_docker_events_sse))
         .route(
-            "/api/containers/{id}/logs",
-            web::get().to(handlers::get_container_logs),
+            "/api/containers/{id}/logs/stream",
+            web::get().to(handlers::get_container_logs_stream),
+        )
+        .service(
+            web::resource("/api/containers/{id}/logs")
+                .wrap(from_fn(rate_limit::middleware))
+                .route(web::get().to(handlers::get_container_logs)),
+        )
+        .route(
+            "/api/containers/{id}/ports",
+            web::get().to(handlers::get_container_ports),
+        )
+        .route(
+            "/api/containers/{id}/env/{key}",
+            web::get().to(handlers::get_container_env_var),
+        )
+        .route(
+            "/api/containers/{id}/recommendations",
+            web::get().to(handlers::get_container_recommendations),
         )
         
         // Image endpoints
         .route("/api/images", web::get().to(handlers::get_all_images))
+        .route("/api/images/stale", web::get().to(handlers::get_stale_images))
         .route(
             "/api/images/{id}",
             web::get().to(handlers::get_image),
         )
-        .route(
-            "/api/images/{id}/history",
-            web::get().to(handlers::get_image_history),
+        .service(
+            web::resource("/api/images/{id}/history")
+                .wrap(from_fn(rate_limit::middleware))
+                .route(web::get().to(handlers::get_image_history)),
+        )
+        .service(
+            web::resource("/api/images/{id}/size-trend")
+                .wrap(from_fn(rate_limit::middleware))
+                .route(web::get().to(handlers::get_image_size_trend)),
         )
         
         // Service map endpoint
-        .route("/api/services/map", web::get().to(handlers::get_service_map))
+        .service(
+            web::resource("/api/services/map")
+                .wrap(from_fn(rate_limit::middleware))
+                .route(web::get().to(handlers::get_service_map)),
+        )
+        .service(
+            web::resource("/api/service-map/diff")
+                .wrap(from_fn(rate_limit::middleware))
+                .route(web::get().to(handlers::get_service_map_diff)),
+        )
         
         // HTTP requests endpoint
         .route(
             "/api/containers/{id}/requests",
             web::get().to(handlers::get_container_http_requests),
-        );
+        )
+        .route(
+            "/api/containers/{id}/http/status-breakdown",
+            web::get().to(handlers::get_container_http_status_breakdown),
+        )
+        .route(
+            "/api/containers/{id}/http/endpoint",
+            web::get().to(handlers::get_container_endpoint_requests),
+        )
+        .route(
+            "/api/containers/{id}/http/summary",
+            web::get().to(handlers::get_container_http_summary),
+        )
+        .route(
+            "/api/containers/{id}/http/latency",
+            web::get().to(handlers::get_container_http_latency),
+        )
+        .route(
+            "/api/containers/{id}/http/endpoints",
+            web::get().to(handlers::get_container_http_endpoints),
+        )
+        
+        // Alerts
+        .route("/api/alerts", web::get().to(handlers::get_alerts))
+        .route(
+            "/api/alerts/rules",
+            web::get().to(handlers::get_alert_rules),
+        )
+        .route(
+            "/api/alerts/rules",
+            web::post().to(handlers::create_alert_rule),
+        )
+        .route(
+            "/api/alerts/rules/{id}",
+            web::get().to(handlers::get_alert_rule),
+        )
+        .route(
+            "/api/alerts/rules/{id}",
+            web::put().to(handlers::update_alert_rule),
+        )
+        .route(
+            "/api/alerts/rules/{id}",
+            web::delete().to(handlers::delete_alert_rule),
+        )
+
+        // Admin endpoints (require ADMIN_TOKEN)
+        .route("/api/admin/streams", web::get().to(handlers::get_admin_streams))
+        .route(
+            "/api/admin/streams/{id}",
+            web::delete().to(handlers::close_admin_stream),
+        )
+        .route("/api/admin/self-test", web::post().to(handlers::run_self_test))
+        .route("/api/admin/retention", web::post().to(handlers::set_retention_policy));
 }
 
@@ -0,0 +1,141 @@
+use anyhow::{Result, anyhow};
+use chrono::{FixedOffset, Utc};
+use eyes_devine_services::{CacheService, CachedQueryService, ContainerSelector, container_stats};
+use sea_orm::{ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::Serialize;
+use std::time::Instant;
+
+/// Container id prefix of the synthetic rows, so leftovers are easy to spot and delete
+const SELF_TEST_CONTAINER_PREFIX: &str = "__self_test__";
+
+/// Outcome of one layer of the self-test
+#[derive(Debug, Serialize)]
+pub struct SelfTestStep {
+    pub name: &'static str,
+    pub success: bool,
+    /// The step did not apply (cache read with Redis disabled)
+    pub skipped: bool,
+    pub duration_ms: f64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SelfTestReport {
+    pub success: bool,
+    pub container_id: String,
+    pub steps: Vec<SelfTestStep>,
+}
+
+/// Write a synthetic stats row, read it back from the database and through the cache,
+/// then delete it again. Each layer is timed; layers after a failed one are not run
+pub async fn run(
+    db: &DatabaseConnection,
+    query_service: &CachedQueryService,
+    cache_service: &CacheService,
+) -> SelfTestReport {
+    let container_id = format!("{}{}", SELF_TEST_CONTAINER_PREFIX, Utc::now().timestamp_nanos_opt().unwrap_or_default());
+    let selector = ContainerSelector::Id(container_id.clone());
+    let mut steps = Vec::new();
+
+    let written = step(&mut steps, "db_write", write_row(db, &container_id)).await;
+
+    let read = written
+        && step(&mut steps, "db_read", async {
+            match query_service.query_service().get_latest_container_stats(&selector).await? {
+                Some(_) => Ok(()),
+                None => Err(anyhow!("Synthetic row not found in the database")),
+            }
+        })
+        .await;
+
+    // Populate the cache before the row is deleted, so the read afterwards can only be served from it
+    let cached = read
+        && cache_service.is_enabled()
+        && query_service.get_latest_container_stats(&selector).await.is_ok_and(|s| s.is_some());
+
+    if written {
+        step(&mut steps, "cleanup", async {
+            container_stats::Entity::delete_many()
+                .filter(container_stats::Column::ContainerId.eq(container_id.as_str()))
+                .exec(db)
+                .await?;
+            Ok(())
+        })
+        .await;
+    }
+
+    if !cache_service.is_enabled() {
+        steps.push(SelfTestStep {
+            name: "cache_read",
+            success: true,
+            skipped: true,
+            duration_ms: 0.0,
+            error: None,
+        });
+    } else if read {
+        step(&mut steps, "cache_read", async {
+            if !cached {
+                return Err(anyhow!("Synthetic row was not cached"));
+            }
+            match query_service.get_latest_container_stats(&selector).await? {
+                Some(_) => Ok(()),
+                None => Err(anyhow!("Synthetic row not served from the cache")),
+            }
+        })
+        .await;
+        let _ = query_service.invalidate_container_cache(&container_id).await;
+    }
+
+    SelfTestReport {
+        success: steps.iter().all(|s| s.success),
+        container_id,
+        steps,
+    }
+}
+
+async fn write_row(db: &DatabaseConnection, container_id: &str) -> Result<()> {
+    let timestamp = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+    let row = container_stats::ActiveModel {
+        container_id: Set(container_id.to_string()),
+        container_name: Set(container_id.to_string()),
+        cpu_usage_percent: Set(0.0),
+        memory_usage_bytes: Set(0),
+        memory_limit_bytes: Set(0),
+        memory_usage_percent: Set(0.0),
+        memory_cache_bytes: Set(Some(0)),
+        memory_swap_bytes: Set(Some(0)),
+        network_rx_bytes: Set(0),
+        network_tx_bytes: Set(0),
+        block_read_bytes: Set(0),
+        block_write_bytes: Set(0),
+        per_cpu_usage_percent: Set(None),
+        pids_current: Set(Some(0)),
+        pids_limit: Set(Some(0)),
+        timestamp: Set(timestamp),
+        ..Default::default()
+    };
+    container_stats::Entity::insert(row).exec(db).await?;
+    Ok(())
+}
+
+/// Time one step and record its outcome; returns whether it succeeded
+async fn step(
+    steps: &mut Vec<SelfTestStep>,
+    name: &'static str,
+    action: impl Future<Output = Result<()>>,
+) -> bool {
+    let started = Instant::now();
+    let result = action.await;
+    let duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+    if let Err(e) = &result {
+        log::warn!("Self-test step {} failed: {}", name, e);
+    }
+    steps.push(SelfTestStep {
+        name,
+        success: result.is_ok(),
+        skipped: false,
+        duration_ms,
+        error: result.err().map(|e| e.to_string()),
+    });
+    steps.last().is_some_and(|s| s.success)
+}
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use eyes_devine_services::CachedQueryService;
+use eyes_devine_shared::ComprehensiveStats;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, watch};
+
+/// How often the shared snapshot is refreshed while anyone is subscribed
+pub const STATS_FEED_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Latest fleet snapshot (totals plus each container's latest stats) shared by every live
+/// transport (SSE and WebSocket), refreshed by a single background loop that runs only while
+/// there are subscribers
+pub struct StatsFeed {
+    query_service: Arc<CachedQueryService>,
+    sender: watch::Sender<ComprehensiveStats>,
+    /// Whether the update loop is running; held while starting or stopping it
+    running: Mutex<bool>,
+}
+
+impl StatsFeed {
+    pub fn new(query_service: Arc<CachedQueryService>) -> Self {
+        let (sender, _) = watch::channel(ComprehensiveStats::default());
+        Self {
+            query_service,
+            sender,
+            running: Mutex::new(false),
+        }
+    }
+
+    /// Receiver of the shared snapshot. Starts the update loop (after loading a fresh
+    /// snapshot, whose error is returned) if no one else is subscribed
+    pub async fn subscribe(self: &Arc<Self>) -> Result<watch::Receiver<ComprehensiveStats>> {
+        let receiver = self.sender.subscribe();
+
+        let mut running = self.running.lock().await;
+        if !*running {
+            let stats = self.query_service.get_comprehensive_stats().await?;
+            self.sender.send_replace(stats);
+            *running = true;
+
+            let feed = Arc::clone(self);
+            tokio::spawn(async move { feed.run().await });
+        }
+
+        Ok(receiver)
+    }
+
+    async fn run(&self) {
+        let mut ticker = tokio::time::interval(STATS_FEED_INTERVAL);
+        ticker.tick().await; // The first tick is immediate; subscribe just loaded a snapshot
+
+        loop {
+            ticker.tick().await;
+            {
+                // Checked under the lock so a concurrent subscribe either keeps this loop
+                // alive or starts a new one
+                let mut running = self.running.lock().await;
+                if self.sender.receiver_count() == 0 {
+                    *running = false;
+                    return;
+                }
+            }
+
+            match self.query_service.get_comprehensive_stats().await {
+                Ok(stats) => {
+                    self.sender.send_replace(stats);
+                }
+                Err(e) => {
+                    log::error!("Background stats update failed: {}", e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use actix_web::Error;
+use actix_web::web::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::{AbortHandle, AbortRegistration, Abortable, LocalBoxStream, Stream, StreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// An active streaming (SSE) response, as listed by the admin endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamSession {
+    pub id: u64,
+    /// Which endpoint the stream was opened on, e.g. `total_stats` or `container_stats:<id>`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Remote address of the client (honours `X-Forwarded-For` / `Forwarded`)
+    pub client: String,
+    pub started_at: DateTime<Utc>,
+}
+
+struct SessionEntry {
+    session: StreamSession,
+    abort_handle: AbortHandle,
+}
+
+type Sessions = Arc<Mutex<HashMap<u64, SessionEntry>>>;
+
+/// Registry of active streaming sessions so operators can list them and close misbehaving ones
+#[derive(Default)]
+pub struct StreamRegistry {
+    sessions: Sessions,
+    next_id: AtomicU64,
+}
+
+impl StreamRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a streaming body; the session is removed again when the returned stream is dropped
+    pub fn track<S>(&self, kind: impl Into<String>, client: impl Into<String>, stream: S) -> TrackedStream
+    where
+        S: Stream<Item = Result<Bytes, Error>> + 'static,
+    {
+        let (guard, abort_registration) = self.register(kind.into(), client.into());
+
+        TrackedStream {
+            inner: Abortable::new(stream.boxed_local(), abort_registration),
+            _guard: guard,
+        }
+    }
+
+    /// Register a session served by a task rather than a response body (e.g. a WebSocket);
+    /// closing the session aborts the task, and the session ends when the task does
+    pub fn track_task<F>(&self, kind: String, client: String, task: F) -> impl Future<Output = ()> + use<F>
+    where
+        F: Future<Output = ()>,
+    {
+        let (guard, abort_registration) = self.register(kind, client);
+
+        async move {
+            let _guard = guard;
+            let _ = Abortable::new(task, abort_registration).await;
+        }
+    }
+
+    fn register(&self, kind: String, client: String) -> (SessionGuard, AbortRegistration) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let session = StreamSession {
+            id,
+            kind,
+            client,
+            started_at: Utc::now(),
+        };
+
+        self.lock().insert(id, SessionEntry { session, abort_handle });
+
+        let guard = SessionGuard {
+            sessions: Arc::clone(&self.sessions),
+            id,
+        };
+        (guard, abort_registration)
+    }
+
+    /// Active sessions, oldest first
+    pub fn list(&self) -> Vec<StreamSession> {
+        let mut sessions: Vec<StreamSession> = self
+            .lock()
+            .values()
+            .map(|entry| entry.session.clone())
+            .collect();
+        sessions.sort_by_key(|session| session.id);
+        sessions
+    }
+
+    /// Forcibly end a session; returns false if no such session is active
+    pub fn close(&self, id: u64) -> bool {
+        match self.lock().remove(&id) {
+            Some(entry) => {
+                entry.abort_handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, SessionEntry>> {
+        self.sessions.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Streaming body registered in a [`StreamRegistry`]; ends early if the session is closed
+pub struct TrackedStream {
+    inner: Abortable<LocalBoxStream<'static, Result<Bytes, Error>>>,
+    _guard: SessionGuard,
+}
+
+impl Stream for TrackedStream {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+/// Deregisters a session when dropped
+struct SessionGuard {
+    sessions: Sessions,
+    id: u64,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        // Client disconnects drop the body (or end the task), which deregisters the session
+        let mut sessions = self.sessions.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        sessions.remove(&self.id);
+    }
+}
@@ -0,0 +1,25 @@
+use anyhow::{Context, Result};
+use rustls::ServerConfig;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject};
+
+/// Build a rustls server config from PEM-encoded certificate chain and private key files
+pub fn load_rustls_config(cert_path: &str, key_path: &str) -> Result<ServerConfig> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .with_context(|| format!("Failed to open TLS certificate: {}", cert_path))?
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to parse TLS certificate: {}", cert_path))?;
+
+    if certs.is_empty() {
+        return Err(anyhow::anyhow!("No certificates found in {}", cert_path));
+    }
+
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .with_context(|| format!("Failed to load TLS private key: {}", key_path))?;
+
+    ServerConfig::builder_with_provider(rustls::crypto::ring::default_provider().into())
+        .with_safe_default_protocol_versions()
+        .context("Failed to configure TLS protocol versions")?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("TLS certificate and private key do not match")
+}
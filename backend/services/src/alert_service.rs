@@ -0,0 +1,487 @@
+use crate::NotificationService;
+use crate::entity::{alert_rules, alerts};
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset, Utc};
+use eyes_devine_shared::{Alert, AlertComparator, AlertMetric, AlertRule, AlertRuleInput, AlertState, ContainerStats};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, DatabaseConnection, DbBackend, EntityTrait,
+    QueryOrder, QuerySelect, Statement,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Breach and firing state of one rule for one container, kept between stats ticks
+#[derive(Debug, Default)]
+struct RuleTracking {
+    /// Start of the current run of breaching samples
+    breached_since: Option<DateTime<Utc>>,
+    firing: bool,
+    /// Latest sample's container name and metric value, recorded if the container goes away
+    container_name: String,
+    value: f64,
+}
+
+/// A rule starting or stopping to fire for a container
+#[derive(Debug)]
+struct Transition<'a> {
+    rule: &'a AlertRule,
+    container_id: String,
+    container_name: String,
+    value: f64,
+    state: AlertState,
+    timestamp: DateTime<Utc>,
+}
+
+/// Alert rules (CRUD) and their evaluation against container stats. The worker calls
+/// [`AlertService::evaluate`] every stats tick; a rule fires for a container once its
+/// threshold has been breached for the rule's duration and resolves on the first sample
+/// that no longer breaches it, or once the container is no longer running. Each transition is stored in the `alerts` table, and firing
+/// ones are passed on to the [`NotificationService`] if one is set
+pub struct AlertService {
+    db: DatabaseConnection,
+    /// Keyed by (rule id, container id); None until the currently firing alerts are loaded
+    tracked: Mutex<Option<HashMap<(i64, String), RuleTracking>>>,
+    /// Told about every newly firing alert; None when nothing should be notified
+    notifications: Option<Arc<NotificationService>>,
+}
+
+impl AlertService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            tracked: Mutex::new(None),
+            notifications: None,
+        }
+    }
+
+    /// Send newly firing alerts to the configured webhooks
+    pub fn with_notifications(mut self, notifications: Arc<NotificationService>) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Reject rules that could never be evaluated sensibly; the message is meant for the client
+    pub fn validate_rule(input: &AlertRuleInput) -> std::result::Result<(), String> {
+        if input.name.trim().is_empty() {
+            return Err("Rule name must not be empty".to_string());
+        }
+        if input.container_pattern.is_empty() {
+            return Err("container_pattern must not be empty (use \"*\" for all containers)".to_string());
+        }
+        if !input.threshold.is_finite() {
+            return Err("threshold must be a finite number".to_string());
+        }
+        if input.duration_secs > i32::MAX as u32 {
+            return Err("duration_secs is too large".to_string());
+        }
+        Ok(())
+    }
+
+    pub async fn list_rules(&self) -> Result<Vec<AlertRule>> {
+        let rules = alert_rules::Entity::find()
+            .order_by_asc(alert_rules::Column::Id)
+            .all(&self.db)
+            .await?;
+        Ok(rules.into_iter().filter_map(Self::entity_to_rule).collect())
+    }
+
+    pub async fn get_rule(&self, id: i64) -> Result<Option<AlertRule>> {
+        let rule = alert_rules::Entity::find_by_id(id).one(&self.db).await?;
+        Ok(rule.and_then(Self::entity_to_rule))
+    }
+
+    pub async fn create_rule(&self, input: &AlertRuleInput) -> Result<AlertRule> {
+        let now = Self::now();
+        let rule = alert_rules::ActiveModel {
+            name: Set(input.name.clone()),
+            container_pattern: Set(input.container_pattern.clone()),
+            metric: Set(input.metric.as_str().to_string()),
+            comparator: Set(input.comparator.as_str().to_string()),
+            threshold: Set(input.threshold),
+            duration_secs: Set(input.duration_secs as i32),
+            enabled: Set(input.enabled),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await?;
+        Self::entity_to_rule(rule).ok_or_else(|| anyhow::anyhow!("Stored rule could not be read back"))
+    }
+
+    /// Replace a rule's settings; None if it does not exist
+    pub async fn update_rule(&self, id: i64, input: &AlertRuleInput) -> Result<Option<AlertRule>> {
+        let Some(existing) = alert_rules::Entity::find_by_id(id).one(&self.db).await? else {
+            return Ok(None);
+        };
+        let mut rule: alert_rules::ActiveModel = existing.into();
+        rule.name = Set(input.name.clone());
+        rule.container_pattern = Set(input.container_pattern.clone());
+        rule.metric = Set(input.metric.as_str().to_string());
+        rule.comparator = Set(input.comparator.as_str().to_string());
+        rule.threshold = Set(input.threshold);
+        rule.duration_secs = Set(input.duration_secs as i32);
+        rule.enabled = Set(input.enabled);
+        rule.updated_at = Set(Self::now());
+        let rule = rule.update(&self.db).await?;
+        Ok(Self::entity_to_rule(rule))
+    }
+
+    /// Delete a rule; its recorded alerts are kept. Returns whether it existed
+    pub async fn delete_rule(&self, id: i64) -> Result<bool> {
+        let result = alert_rules::Entity::delete_by_id(id).exec(&self.db).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Most recent firing/resolved transitions, newest first
+    pub async fn list_alerts(&self, limit: u64) -> Result<Vec<Alert>> {
+        let rows = alerts::Entity::find()
+            .order_by_desc(alerts::Column::Timestamp)
+            .limit(limit)
+            .all(&self.db)
+            .await?;
+        Ok(rows.into_iter().filter_map(Self::entity_to_alert).collect())
+    }
+
+    /// Alerts whose latest transition is `firing`, for rules that still exist and are enabled
+    pub async fn firing_alerts(&self) -> Result<Vec<Alert>> {
+        let sql = "SELECT * FROM (\
+                SELECT DISTINCT ON (rule_id, container_id) * FROM alerts \
+                WHERE rule_id IN (SELECT id FROM alert_rules WHERE enabled) \
+                ORDER BY rule_id, container_id, timestamp DESC\
+            ) latest WHERE state = $1 ORDER BY timestamp DESC";
+        let rows = alerts::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                sql,
+                [AlertState::Firing.as_str().into()],
+            ))
+            .all(&self.db)
+            .await?;
+        Ok(rows.into_iter().filter_map(Self::entity_to_alert).collect())
+    }
+
+    /// Check the enabled rules against one tick of stats and record any firing/resolved
+    /// transitions. `running` holds every running container, including those whose stats
+    /// were not sampled this tick; alerts of any other container resolve. Returns the number
+    /// of transitions recorded
+    pub async fn evaluate(&self, stats: &[ContainerStats], running: &HashSet<String>) -> Result<usize> {
+        let rules: Vec<AlertRule> = self.list_rules().await?.into_iter().filter(|r| r.enabled).collect();
+
+        let mut tracked = self.tracked.lock().await;
+        if tracked.is_none() {
+            // Pick up alerts that were firing before a restart so they are not recorded twice
+            let firing = self.firing_alerts().await?;
+            *tracked = Some(
+                firing
+                    .into_iter()
+                    .map(|alert| {
+                        let tracking = RuleTracking {
+                            breached_since: Some(alert.timestamp),
+                            firing: true,
+                            container_name: alert.container_name,
+                            value: alert.value,
+                        };
+                        ((alert.rule_id, alert.container_id), tracking)
+                    })
+                    .collect(),
+            );
+        }
+        let Some(tracked) = tracked.as_mut() else {
+            return Ok(0);
+        };
+
+        let transitions = evaluate_tick(tracked, &rules, stats, running, Utc::now());
+        let transitions: Vec<alerts::ActiveModel> = transitions
+            .into_iter()
+            .map(|transition| {
+                let rule = transition.rule;
+                log::info!("Alert rule '{}' {} for container {} ({} {} {}, value {:.2})",
+                    rule.name, transition.state.as_str(), transition.container_name,
+                    rule.metric.as_str(), rule.comparator.as_str(), rule.threshold, transition.value);
+                alerts::ActiveModel {
+                    rule_id: Set(rule.id),
+                    rule_name: Set(rule.name.clone()),
+                    container_id: Set(transition.container_id),
+                    container_name: Set(transition.container_name),
+                    metric: Set(rule.metric.as_str().to_string()),
+                    value: Set(transition.value),
+                    threshold: Set(rule.threshold),
+                    state: Set(transition.state.as_str().to_string()),
+                    timestamp: Set(transition.timestamp.with_timezone(&FixedOffset::east_opt(0).unwrap())),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        // Transitions are rare; inserting them one by one gives back the stored rows to notify about
+        let count = transitions.len();
+        for transition in transitions {
+            let stored = transition.insert(&self.db).await?;
+            if let Some(notifications) = &self.notifications
+                && let Some(alert) = Self::entity_to_alert(stored)
+                && alert.state == AlertState::Firing
+            {
+                notifications.notify(&alert);
+            }
+        }
+        Ok(count)
+    }
+
+    fn now() -> DateTime<FixedOffset> {
+        Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap())
+    }
+
+    /// None (and skipped) if the row holds a metric or comparator this build does not know
+    fn entity_to_rule(rule: alert_rules::Model) -> Option<AlertRule> {
+        Some(AlertRule {
+            id: rule.id,
+            name: rule.name,
+            container_pattern: rule.container_pattern,
+            metric: AlertMetric::parse(&rule.metric)?,
+            comparator: AlertComparator::parse(&rule.comparator)?,
+            threshold: rule.threshold,
+            duration_secs: rule.duration_secs.max(0) as u32,
+            enabled: rule.enabled,
+            created_at: rule.created_at.with_timezone(&Utc),
+            updated_at: rule.updated_at.with_timezone(&Utc),
+        })
+    }
+
+    fn entity_to_alert(alert: alerts::Model) -> Option<Alert> {
+        Some(Alert {
+            id: alert.id,
+            rule_id: alert.rule_id,
+            rule_name: alert.rule_name,
+            container_id: alert.container_id,
+            container_name: alert.container_name,
+            metric: AlertMetric::parse(&alert.metric)?,
+            value: alert.value,
+            threshold: alert.threshold,
+            state: AlertState::parse(&alert.state)?,
+            timestamp: alert.timestamp.with_timezone(&Utc),
+        })
+    }
+}
+
+/// Advance the per-(rule, container) state by one tick and return the transitions. Tracking
+/// of disabled or deleted rules is dropped; that of containers missing from `running` is
+/// dropped too, resolving their firing alerts at `now`
+fn evaluate_tick<'a>(
+    tracked: &mut HashMap<(i64, String), RuleTracking>,
+    rules: &'a [AlertRule],
+    stats: &[ContainerStats],
+    running: &HashSet<String>,
+    now: DateTime<Utc>,
+) -> Vec<Transition<'a>> {
+    let rule_ids: HashSet<i64> = rules.iter().map(|r| r.id).collect();
+    tracked.retain(|(rule_id, _), _| rule_ids.contains(rule_id));
+
+    let mut transitions = Vec::new();
+    let gone: Vec<(i64, String)> = tracked
+        .keys()
+        .filter(|(_, container_id)| !running.contains(container_id))
+        .cloned()
+        .collect();
+    for key in gone {
+        let Some(tracking) = tracked.remove(&key) else {
+            continue;
+        };
+        if let Some(rule) = rules.iter().find(|r| r.id == key.0)
+            && tracking.firing
+        {
+            transitions.push(Transition {
+                rule,
+                container_id: key.1,
+                container_name: tracking.container_name,
+                value: tracking.value,
+                state: AlertState::Resolved,
+                timestamp: now,
+            });
+        }
+    }
+
+    for rule in rules {
+        for sample in stats {
+            if !pattern_matches(&rule.container_pattern, &sample.container_id)
+                && !pattern_matches(&rule.container_pattern, &sample.container_name)
+            {
+                continue;
+            }
+
+            let value = rule.metric.value(sample);
+            let tracking = tracked.entry((rule.id, sample.container_id.clone())).or_default();
+            tracking.container_name = sample.container_name.clone();
+            tracking.value = value;
+
+            let state = if rule.comparator.breached(value, rule.threshold) {
+                let since = *tracking.breached_since.get_or_insert(sample.timestamp);
+                let held = (sample.timestamp - since).num_seconds() >= rule.duration_secs as i64;
+                (held && !tracking.firing).then_some(AlertState::Firing)
+            } else {
+                tracking.breached_since = None;
+                tracking.firing.then_some(AlertState::Resolved)
+            };
+
+            if let Some(state) = state {
+                tracking.firing = state == AlertState::Firing;
+                transitions.push(Transition {
+                    rule,
+                    container_id: sample.container_id.clone(),
+                    container_name: sample.container_name.clone(),
+                    value,
+                    state,
+                    timestamp: sample.timestamp,
+                });
+            }
+        }
+    }
+    transitions
+}
+
+/// Glob match where `*` stands for any run of characters (including none)
+fn pattern_matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return text.is_empty();
+    };
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*`: the whole text must equal the pattern
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(duration_secs: u32) -> AlertRule {
+        AlertRule {
+            id: 1,
+            name: "high cpu".to_string(),
+            container_pattern: "api-*".to_string(),
+            metric: AlertMetric::CpuPercent,
+            comparator: AlertComparator::Gt,
+            threshold: 80.0,
+            duration_secs,
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn sample(container_id: &str, container_name: &str, cpu: f64, at: DateTime<Utc>) -> ContainerStats {
+        ContainerStats {
+            cpu_usage_percent: cpu,
+            ..ContainerStats::empty(container_id.to_string(), container_name.to_string(), at)
+        }
+    }
+
+    fn running(ids: &[&str]) -> HashSet<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn states(transitions: &[Transition]) -> Vec<(String, AlertState)> {
+        transitions.iter().map(|t| (t.container_id.clone(), t.state)).collect()
+    }
+
+    #[test]
+    fn glob_patterns() {
+        assert!(pattern_matches("*", ""));
+        assert!(pattern_matches("*", "anything"));
+        assert!(pattern_matches("api", "api"));
+        assert!(!pattern_matches("api", "api-1"));
+        assert!(pattern_matches("api-*", "api-1"));
+        assert!(pattern_matches("*-worker", "billing-worker"));
+        assert!(pattern_matches("api*db*", "api-main-db-1"));
+        assert!(!pattern_matches("api*db", "api-db-1"));
+        // Overlapping prefix and suffix can't share characters
+        assert!(!pattern_matches("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn fires_after_the_duration_and_resolves_on_recovery() {
+        let rules = [rule(30)];
+        let mut tracked = HashMap::new();
+        let start = Utc::now();
+        let at = |secs| start + chrono::Duration::seconds(secs);
+        let up = running(&["a1"]);
+        let mut tick = |cpu, secs| {
+            let stats = [sample("a1", "api-1", cpu, at(secs))];
+            states(&evaluate_tick(&mut tracked, &rules, &stats, &up, at(secs)))
+        };
+
+        assert!(tick(90.0, 0).is_empty());
+        // A dip restarts the breach window
+        assert!(tick(50.0, 10).is_empty());
+        assert!(tick(90.0, 20).is_empty());
+        assert!(tick(90.0, 40).is_empty());
+        assert_eq!(tick(95.0, 50), [("a1".to_string(), AlertState::Firing)]);
+        // Fires once while the breach lasts
+        assert!(tick(95.0, 60).is_empty());
+        assert_eq!(tick(10.0, 70), [("a1".to_string(), AlertState::Resolved)]);
+        assert!(tick(10.0, 80).is_empty());
+    }
+
+    #[test]
+    fn non_matching_containers_are_ignored() {
+        let rules = [rule(0)];
+        let mut tracked = HashMap::new();
+        let stats = [sample("w1", "web-1", 99.0, Utc::now())];
+        assert!(evaluate_tick(&mut tracked, &rules, &stats, &running(&["w1"]), Utc::now()).is_empty());
+        assert!(tracked.is_empty());
+    }
+
+    #[test]
+    fn container_that_went_away_resolves_and_is_forgotten() {
+        let rules = [rule(0)];
+        let mut tracked = HashMap::new();
+        let now = Utc::now();
+        let stats = [sample("a1", "api-1", 99.0, now), sample("a2", "api-2", 10.0, now)];
+        let fired = evaluate_tick(&mut tracked, &rules, &stats, &running(&["a1", "a2"]), now);
+        assert_eq!(states(&fired), [("a1".to_string(), AlertState::Firing)]);
+
+        // Both stop: the firing one resolves with its last value, the other just drops out
+        let later = now + chrono::Duration::seconds(10);
+        let resolved = evaluate_tick(&mut tracked, &rules, &[], &running(&[]), later);
+        assert_eq!(states(&resolved), [("a1".to_string(), AlertState::Resolved)]);
+        assert_eq!(resolved[0].container_name, "api-1");
+        assert_eq!(resolved[0].value, 99.0);
+        assert_eq!(resolved[0].timestamp, later);
+        assert!(tracked.is_empty());
+    }
+
+    #[test]
+    fn running_container_without_a_sample_keeps_firing() {
+        let rules = [rule(0)];
+        let mut tracked = HashMap::new();
+        let now = Utc::now();
+        evaluate_tick(&mut tracked, &rules, &[sample("a1", "api-1", 99.0, now)], &running(&["a1"]), now);
+
+        // Skipped by stats sampling this tick, but still running
+        assert!(evaluate_tick(&mut tracked, &rules, &[], &running(&["a1"]), now).is_empty());
+        assert!(tracked[&(1, "a1".to_string())].firing);
+    }
+
+    #[test]
+    fn deleted_rules_stop_being_tracked() {
+        let mut tracked = HashMap::new();
+        let now = Utc::now();
+        evaluate_tick(&mut tracked, &[rule(0)], &[sample("a1", "api-1", 99.0, now)], &running(&["a1"]), now);
+        assert!(evaluate_tick(&mut tracked, &[], &[], &running(&["a1"]), now).is_empty());
+        assert!(tracked.is_empty());
+    }
+}
@@ -3,6 +3,9 @@ use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+/// Keys examined per `SCAN` step; small enough that Redis never blocks long on one call
+const SCAN_COUNT: usize = 500;
+
 pub struct CacheService {
     client: Option<redis::Client>,
 }
@@ -59,8 +62,152 @@ impl CacheService {
         Ok(())
     }
 
+    /// Delete every key matching a glob-style `pattern` (e.g. `stats:history:abc:*`).
+    /// Walks the keyspace with `SCAN` rather than `KEYS` so Redis keeps serving other
+    /// clients, deleting each batch as it is found. Returns the number of keys deleted
+    pub async fn delete_pattern(&self, pattern: &str) -> Result<usize> {
+        let Some(ref client) = self.client else {
+            return Ok(0);
+        };
+        let mut conn = client.get_multiplexed_async_connection().await?;
+        Self::scan_and_delete(&mut conn, pattern).await
+    }
+
+    /// The `SCAN` / `DEL` loop of [`Self::delete_pattern`], following the cursor until Redis
+    /// returns 0
+    async fn scan_and_delete<C>(conn: &mut C, pattern: &str) -> Result<usize>
+    where
+        C: redis::aio::ConnectionLike + Send + Sync,
+    {
+        let mut cursor: u64 = 0;
+        let mut deleted = 0;
+        loop {
+            let (next_cursor, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(pattern)
+                .arg("COUNT")
+                .arg(SCAN_COUNT)
+                .query_async(conn)
+                .await?;
+            if !keys.is_empty() {
+                let removed: usize = conn.del(&keys).await?;
+                deleted += removed;
+            }
+            if next_cursor == 0 {
+                return Ok(deleted);
+            }
+            cursor = next_cursor;
+        }
+    }
+
+    /// Escape glob metacharacters so `value` only matches itself in a `delete_pattern` pattern
+    pub fn escape_pattern(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
     pub fn is_enabled(&self) -> bool {
         self.client.is_some()
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::{Cmd, ErrorKind, Pipeline, RedisError, RedisFuture, Value};
+    use std::collections::VecDeque;
+
+    /// Answers commands from a script and records them as text
+    struct ScriptedConnection {
+        replies: VecDeque<Value>,
+        commands: Vec<String>,
+    }
+
+    impl redis::aio::ConnectionLike for ScriptedConnection {
+        fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+            let args: Vec<String> = cmd
+                .args_iter()
+                .map(|arg| match arg {
+                    redis::Arg::Simple(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+                    _ => "<cursor>".to_string(),
+                })
+                .collect();
+            self.commands.push(args.join(" "));
+            let reply = self
+                .replies
+                .pop_front()
+                .ok_or_else(|| RedisError::from((ErrorKind::Client, "unexpected command: the script is exhausted")));
+            Box::pin(std::future::ready(reply))
+        }
+
+        // Pipelines are not used by delete_pattern; fail the call rather than the test process
+        fn req_packed_commands<'a>(&'a mut self, _cmd: &'a Pipeline, _offset: usize, _count: usize) -> RedisFuture<'a, Vec<Value>> {
+            Box::pin(std::future::ready(Err(RedisError::from((ErrorKind::Client, "pipelines are not scripted")))))
+        }
+
+        fn get_db(&self) -> i64 {
+            0
+        }
+    }
+
+    fn scan_page(cursor: &str, keys: &[&str]) -> Value {
+        Value::Array(vec![
+            Value::BulkString(cursor.as_bytes().to_vec()),
+            Value::Array(keys.iter().map(|key| Value::BulkString(key.as_bytes().to_vec())).collect()),
+        ])
+    }
+
+    #[tokio::test]
+    async fn delete_pattern_follows_scan_cursor_across_pages() {
+        let mut conn = ScriptedConnection {
+            replies: VecDeque::from([
+                scan_page("17", &["stats:history:abc:1", "stats:history:abc:2"]),
+                Value::Int(2),
+                // A page can come back empty while the cursor is still non-zero
+                scan_page("42", &[]),
+                scan_page("0", &["stats:history:abc:3"]),
+                Value::Int(1),
+            ]),
+            commands: Vec::new(),
+        };
+
+        let deleted = CacheService::scan_and_delete(&mut conn, "stats:history:abc:*").await.unwrap();
+
+        assert_eq!(deleted, 3);
+        assert_eq!(
+            conn.commands,
+            [
+                "SCAN 0 MATCH stats:history:abc:* COUNT 500",
+                "DEL stats:history:abc:1 stats:history:abc:2",
+                "SCAN 17 MATCH stats:history:abc:* COUNT 500",
+                "SCAN 42 MATCH stats:history:abc:* COUNT 500",
+                "DEL stats:history:abc:3",
+            ]
+        );
+        assert!(conn.replies.is_empty());
+    }
+
+    #[test]
+    fn escape_pattern_escapes_glob_metacharacters() {
+        assert_eq!(CacheService::escape_pattern("web[1]*?\\x"), "web\\[1\\]\\*\\?\\\\x");
+    }
+
+    #[tokio::test]
+    async fn delete_pattern_reports_connection_errors() {
+        // The script ends after the first page, so the DEL fails like a dropped connection would
+        let mut conn = ScriptedConnection {
+            replies: VecDeque::from([scan_page("0", &["stats:history:abc:1"])]),
+            commands: Vec::new(),
+        };
+
+        assert!(CacheService::scan_and_delete(&mut conn, "stats:history:abc:*").await.is_err());
+    }
+}
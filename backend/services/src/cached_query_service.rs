@@ -1,9 +1,10 @@
-use crate::{QueryService, CacheService};
+use crate::{QueryService, CacheService, ServiceMapService};
+use crate::query_service::{ContainerFilter, ContainerSelector, KeysetCursor, TopMetric};
 use std::sync::Arc;
 use std::time::Duration;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use eyes_devine_shared::{ContainerStats, ContainerInfo, ImageInfo, HttpRequest};
+use eyes_devine_shared::{ComprehensiveStats, ContainerDetails, FleetStatsHistory, AggregatedContainerStats, GapfilledContainerStats, NetworkRate, ContainerLog, ContainerStats, ContainerInfo, ComposeProject, ContainerStatusChange, DiskUsage, ImageInfo, ImageSizeTrend, StaleImageReport, HttpRequest, HttpStatusBreakdown, HttpRequestSummary, HttpLatencyPercentiles, HttpEndpointStats, ResourceRecommendation, ServiceMap, ServiceMapDiff};
 
 /// Wrapper around QueryService that adds Redis caching
 pub struct CachedQueryService {
@@ -34,12 +35,17 @@ impl CachedQueryService {
         }
     }
 
+    /// The uncached query service, for callers that run their own queries
+    pub fn query_service(&self) -> Arc<QueryService> {
+        Arc::clone(&self.query_service)
+    }
+
     /// Get latest stats for a specific container (cached)
     pub async fn get_latest_container_stats(
         &self,
-        container_id: &str,
+        selector: &ContainerSelector,
     ) -> Result<Option<ContainerStats>> {
-        let cache_key = format!("stats:container:{}:latest", container_id);
+        let cache_key = format!("stats:container:{}:latest", selector.cache_key());
 
         // Try cache first
         if let Some(cached) = self.cache_service.get::<ContainerStats>(&cache_key).await? {
@@ -47,7 +53,7 @@ impl CachedQueryService {
         }
 
         // Cache miss - query database
-        let result = self.query_service.get_latest_container_stats(container_id).await?;
+        let result = self.query_service.get_latest_container_stats(selector).await?;
 
         // Store in cache if found
         if let Some(ref stats) = result {
@@ -57,12 +63,29 @@ impl CachedQueryService {
         Ok(result)
     }
 
+    /// Most recent disk usage sample of a container (cached)
+    pub async fn get_latest_disk_usage(&self, selector: &ContainerSelector) -> Result<Option<DiskUsage>> {
+        let cache_key = format!("disk:container:{}:latest", selector.cache_key());
+
+        if let Some(cached) = self.cache_service.get::<DiskUsage>(&cache_key).await? {
+            return Ok(Some(cached));
+        }
+
+        let result = self.query_service.get_latest_disk_usage(selector).await?;
+
+        if let Some(ref usage) = result {
+            let _ = self.cache_service.set(&cache_key, usage, Some(self.cache_ttl_stats)).await;
+        }
+
+        Ok(result)
+    }
+
     /// Get latest stats for all containers (cached)
     pub async fn get_latest_all_container_stats(&self) -> Result<Vec<ContainerStats>> {
         let cache_key = "stats:containers:all:latest";
 
         // Try cache first
-        if let Some(cached) = self.cache_service.get::<Vec<ContainerStats>>(&cache_key).await? {
+        if let Some(cached) = self.cache_service.get::<Vec<ContainerStats>>(cache_key).await? {
             return Ok(cached);
         }
 
@@ -70,6 +93,21 @@ impl CachedQueryService {
         let result = self.query_service.get_latest_all_container_stats().await?;
 
         // Store in cache
+        let _ = self.cache_service.set(cache_key, &result, Some(self.cache_ttl_stats)).await;
+
+        Ok(result)
+    }
+
+    /// Get the containers with the highest latest usage of a metric (cached like latest stats)
+    pub async fn get_top_containers(&self, metric: TopMetric, n: u64) -> Result<Vec<ContainerStats>> {
+        let cache_key = format!("stats:containers:top:{:?}:{}", metric, n);
+
+        if let Some(cached) = self.cache_service.get::<Vec<ContainerStats>>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self.query_service.get_top_containers(metric, n).await?;
+
         let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_stats)).await;
 
         Ok(result)
@@ -80,7 +118,7 @@ impl CachedQueryService {
         let cache_key = "stats:total:latest";
 
         // Try cache first
-        if let Some(cached) = self.cache_service.get::<eyes_devine_shared::TotalStats>(&cache_key).await? {
+        if let Some(cached) = self.cache_service.get::<eyes_devine_shared::TotalStats>(cache_key).await? {
             return Ok(cached);
         }
 
@@ -88,17 +126,69 @@ impl CachedQueryService {
         let result = self.query_service.get_total_stats().await?;
 
         // Store in cache
-        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_stats)).await;
+        let _ = self.cache_service.set(cache_key, &result, Some(self.cache_ttl_stats)).await;
 
         Ok(result)
     }
 
+    /// Every container with its latest stats and image, plus the totals, built from the cached
+    /// container, stats and image lists. Environments aren't stored, so they are left empty
+    pub async fn get_comprehensive_stats(&self) -> Result<ComprehensiveStats> {
+        let total_stats = self.get_total_stats().await?;
+        let containers = self.get_all_containers().await?;
+        let mut latest_stats: std::collections::HashMap<String, ContainerStats> = self
+            .get_latest_all_container_stats()
+            .await?
+            .into_iter()
+            .map(|stats| (stats.container_id.clone(), stats))
+            .collect();
+        let images = self.get_all_images().await?;
+
+        let timestamp = total_stats.timestamp;
+        let containers_up = containers.iter().filter(|c| c.is_running()).count();
+        let details: Vec<ContainerDetails> = containers
+            .into_iter()
+            .map(|container| {
+                let is_running = container.is_running();
+                // Stopped containers report zeros rather than their last sample
+                let stats = latest_stats
+                    .remove(&container.id)
+                    .filter(|_| is_running)
+                    .unwrap_or_else(|| ContainerStats::empty(container.id.clone(), container.name.clone(), timestamp));
+                let image_info = images
+                    .iter()
+                    .find(|image| image.id == container.image || image.repo_tags.contains(&container.image))
+                    .cloned();
+                ContainerDetails {
+                    container_id: container.id,
+                    container_name: container.name,
+                    image: container.image,
+                    status: container.status,
+                    is_running,
+                    environment: Vec::new(),
+                    image_info,
+                    stats,
+                    created: container.created,
+                }
+            })
+            .collect();
+
+        Ok(ComprehensiveStats {
+            total_containers: details.len(),
+            containers_up,
+            containers_down: details.len() - containers_up,
+            total_stats,
+            containers: details,
+            timestamp,
+        })
+    }
+
     /// Get all containers (cached)
     pub async fn get_all_containers(&self) -> Result<Vec<ContainerInfo>> {
         let cache_key = "containers:list";
 
         // Try cache first
-        if let Some(cached) = self.cache_service.get::<Vec<ContainerInfo>>(&cache_key).await? {
+        if let Some(cached) = self.cache_service.get::<Vec<ContainerInfo>>(cache_key).await? {
             return Ok(cached);
         }
 
@@ -106,17 +196,37 @@ impl CachedQueryService {
         let result = self.query_service.get_all_containers().await?;
 
         // Store in cache
-        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_containers)).await;
+        let _ = self.cache_service.set(cache_key, &result, Some(self.cache_ttl_containers)).await;
 
         Ok(result)
     }
 
+    /// Containers grouped by Docker Compose project (built from the cached container list)
+    pub async fn get_containers_grouped_by_project(&self) -> Result<Vec<ComposeProject>> {
+        Ok(ComposeProject::group(self.get_all_containers().await?))
+    }
+
+    /// Filtered, paged container list (not cached; filters are ad hoc)
+    pub async fn find_containers(
+        &self,
+        filter: &ContainerFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<ContainerInfo>, u64)> {
+        self.query_service.find_containers(filter, offset, limit).await
+    }
+
+    /// Replace a hypertable's retention policy (not cached)
+    pub async fn set_retention_policy(&self, table: &str, interval: Option<&str>) -> Result<()> {
+        self.query_service.set_retention_policy(table, interval).await
+    }
+
     /// Get all images (cached)
     pub async fn get_all_images(&self) -> Result<Vec<ImageInfo>> {
         let cache_key = "images:list";
 
         // Try cache first
-        if let Some(cached) = self.cache_service.get::<Vec<ImageInfo>>(&cache_key).await? {
+        if let Some(cached) = self.cache_service.get::<Vec<ImageInfo>>(cache_key).await? {
             return Ok(cached);
         }
 
@@ -124,7 +234,7 @@ impl CachedQueryService {
         let result = self.query_service.get_all_images().await?;
 
         // Store in cache
-        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_images)).await;
+        let _ = self.cache_service.set(cache_key, &result, Some(self.cache_ttl_images)).await;
 
         Ok(result)
     }
@@ -152,27 +262,29 @@ impl CachedQueryService {
     /// Get historical stats (cached with query-specific key)
     pub async fn get_container_stats_history(
         &self,
-        container_id: &str,
+        selector: &ContainerSelector,
         from: Option<DateTime<Utc>>,
         to: Option<DateTime<Utc>>,
+        before: Option<KeysetCursor>,
         limit: Option<u64>,
-    ) -> Result<Vec<ContainerStats>> {
+    ) -> Result<(Vec<ContainerStats>, Option<KeysetCursor>)> {
         // Create cache key from query parameters
         let cache_key = format!(
-            "stats:history:{}:{}:{}:{}",
-            container_id,
+            "stats:history:{}:{}:{}:{}:{}",
+            selector.cache_key(),
             from.map(|d| d.timestamp().to_string()).unwrap_or_else(|| "none".to_string()),
             to.map(|d| d.timestamp().to_string()).unwrap_or_else(|| "none".to_string()),
+            before.map(|cursor| cursor.to_string()).unwrap_or_else(|| "none".to_string()),
             limit.unwrap_or(0)
         );
 
         // Try cache first
-        if let Some(cached) = self.cache_service.get::<Vec<ContainerStats>>(&cache_key).await? {
+        if let Some(cached) = self.cache_service.get::<(Vec<ContainerStats>, Option<KeysetCursor>)>(&cache_key).await? {
             return Ok(cached);
         }
 
         // Cache miss - query database
-        let result = self.query_service.get_container_stats_history(container_id, from, to, limit).await?;
+        let result = self.query_service.get_container_stats_history(selector, from, to, before, limit).await?;
 
         // Store in cache (shorter TTL for historical queries)
         let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
@@ -180,6 +292,137 @@ impl CachedQueryService {
         Ok(result)
     }
 
+    /// Get bucketed stats history for a container (cached)
+    pub async fn get_container_stats_history_bucketed(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket_seconds: i64,
+    ) -> Result<Vec<AggregatedContainerStats>> {
+        let cache_key = format!(
+            "stats:history_bucketed:{}:{}:{}:{}",
+            selector.cache_key(),
+            from.timestamp(),
+            to.timestamp(),
+            bucket_seconds
+        );
+
+        if let Some(cached) = self.cache_service.get::<Vec<AggregatedContainerStats>>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self.query_service.get_container_stats_history_bucketed(selector, from, to, bucket_seconds).await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(result)
+    }
+
+    /// Get gap-filled stats history for a container (cached)
+    pub async fn get_container_stats_history_gapfilled(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket_seconds: i64,
+        carry_forward: bool,
+    ) -> Result<Vec<GapfilledContainerStats>> {
+        let cache_key = format!(
+            "stats:history_gapfilled:{}:{}:{}:{}:{}",
+            selector.cache_key(),
+            from.timestamp(),
+            to.timestamp(),
+            bucket_seconds,
+            carry_forward
+        );
+
+        if let Some(cached) = self.cache_service.get::<Vec<GapfilledContainerStats>>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self
+            .query_service
+            .get_container_stats_history_gapfilled(selector, from, to, bucket_seconds, carry_forward)
+            .await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(result)
+    }
+
+    /// Get a container's network throughput per bucket (cached)
+    pub async fn get_container_network_rate(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket_seconds: i64,
+    ) -> Result<Vec<NetworkRate>> {
+        let cache_key = format!(
+            "stats:network_rate:{}:{}:{}:{}",
+            selector.cache_key(),
+            from.timestamp(),
+            to.timestamp(),
+            bucket_seconds
+        );
+
+        if let Some(cached) = self.cache_service.get::<Vec<NetworkRate>>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self
+            .query_service
+            .get_container_network_rate(selector, from, to, bucket_seconds)
+            .await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(result)
+    }
+
+    /// Get the image staleness report (cached like the image list)
+    pub async fn get_stale_images(&self, older_than_days: u32) -> Result<StaleImageReport> {
+        let cache_key = format!("images:stale:{}", older_than_days);
+
+        if let Some(cached) = self.cache_service.get::<StaleImageReport>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self.query_service.get_stale_images(older_than_days).await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_images)).await;
+
+        Ok(result)
+    }
+
+    /// Recorded state changes of a container (cached)
+    pub async fn get_container_status_history(
+        &self,
+        selector: &ContainerSelector,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: Option<u64>,
+    ) -> Result<Vec<ContainerStatusChange>> {
+        let cache_key = format!(
+            "container:status_history:{}:{}:{}:{}",
+            selector.cache_key(),
+            from.map(|d| d.timestamp().to_string()).unwrap_or_else(|| "none".to_string()),
+            to.map(|d| d.timestamp().to_string()).unwrap_or_else(|| "none".to_string()),
+            limit.unwrap_or(0)
+        );
+
+        if let Some(cached) = self.cache_service.get::<Vec<ContainerStatusChange>>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self.query_service.get_container_status_history(selector, from, to, limit).await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(result)
+    }
+
     /// Get image history (cached)
     pub async fn get_image_history(
         &self,
@@ -211,8 +454,28 @@ impl CachedQueryService {
         Ok(result)
     }
 
-    /// Invalidate cache for a container (call when container data changes)
-    /// Note: This invalidates specific keys. Wildcard deletion would require Redis SCAN.
+    /// Get an image's size trend across rebuilds (cached)
+    pub async fn get_image_size_trend(
+        &self,
+        image_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<ImageSizeTrend> {
+        let cache_key = format!("image:size_trend:{}:{}:{}", image_id, from.timestamp(), to.timestamp());
+
+        if let Some(cached) = self.cache_service.get::<ImageSizeTrend>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self.query_service.get_image_size_trend(image_id, from, to).await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(result)
+    }
+
+    /// Invalidate cache for a container (call when container data changes).
+    /// History and HTTP request keys carry query parameters, so they are matched by prefix
     pub async fn invalidate_container_cache(&self, container_id: &str) -> Result<()> {
         let keys = vec![
             format!("stats:container:{}:latest", container_id),
@@ -225,6 +488,20 @@ impl CachedQueryService {
             let _ = self.cache_service.delete(&key).await;
         }
 
+        let escaped_id = CacheService::escape_pattern(container_id);
+        let patterns = [
+            format!("stats:history:{}:*", escaped_id),
+            format!("stats:history_bucketed:{}:*", escaped_id),
+            format!("stats:network_rate:{}:*", escaped_id),
+            format!("http_requests:{}:*", escaped_id),
+        ];
+
+        for pattern in patterns {
+            if let Err(e) = self.cache_service.delete_pattern(&pattern).await {
+                log::warn!("Failed to invalidate cache keys matching {}: {}", pattern, e);
+            }
+        }
+
         Ok(())
     }
 
@@ -239,23 +516,32 @@ impl CachedQueryService {
             let _ = self.cache_service.delete(&key).await;
         }
 
+        if let Err(e) = self.cache_service.delete_pattern("images:stale:*").await {
+            log::warn!("Failed to invalidate stale image reports: {}", e);
+        }
+
         Ok(())
     }
 
     /// Get HTTP requests for a container (cached)
     pub async fn get_container_http_requests(
         &self,
-        container_id: &str,
+        selector: &ContainerSelector,
         from: Option<DateTime<Utc>>,
         to: Option<DateTime<Utc>>,
+        method: Option<&str>,
+        endpoint: Option<&str>,
         limit: Option<u64>,
     ) -> Result<Vec<HttpRequest>> {
-        // Create cache key from query parameters
+        // Create cache key from query parameters; the filters are debug-formatted so an
+        // absent filter can't collide with a given one
         let cache_key = format!(
-            "http_requests:{}:{}:{}:{}",
-            container_id,
+            "http_requests:{}:{}:{}:{:?}:{:?}:{}",
+            selector.cache_key(),
             from.map(|d| d.timestamp().to_string()).unwrap_or_else(|| "none".to_string()),
             to.map(|d| d.timestamp().to_string()).unwrap_or_else(|| "none".to_string()),
+            method.map(|m| m.to_uppercase()),
+            endpoint.map(|e| e.to_lowercase()),
             limit.unwrap_or(0)
         );
 
@@ -265,12 +551,255 @@ impl CachedQueryService {
         }
 
         // Cache miss - query database
-        let result = self.query_service.get_container_http_requests(container_id, from, to, limit).await?;
+        let result = self
+            .query_service
+            .get_container_http_requests(selector, from, to, method, endpoint, limit)
+            .await?;
 
         // Store in cache (shorter TTL for request queries)
         let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
 
         Ok(result)
     }
-}
 
+    /// Get a container's recent requests to one endpoint template (cached)
+    pub async fn get_endpoint_http_requests(
+        &self,
+        selector: &ContainerSelector,
+        endpoint: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: u64,
+    ) -> Result<Vec<HttpRequest>> {
+        let cache_key = format!(
+            "http_endpoint_requests:{}:{}:{}:{}:{}",
+            selector.cache_key(),
+            endpoint,
+            from.timestamp(),
+            to.timestamp(),
+            limit
+        );
+
+        if let Some(cached) = self.cache_service.get::<Vec<HttpRequest>>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self.query_service.get_endpoint_http_requests(selector, endpoint, from, to, limit).await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(result)
+    }
+
+    pub async fn get_http_status_breakdown(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<HttpStatusBreakdown> {
+        let cache_key = format!(
+            "http_status_breakdown:{}:{}:{}",
+            selector.cache_key(),
+            from.timestamp(),
+            to.timestamp()
+        );
+
+        if let Some(cached) = self.cache_service.get::<HttpStatusBreakdown>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self.query_service.get_http_status_breakdown(selector, from, to).await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(result)
+    }
+
+    /// Get request volume and error rate for a container (cached)
+    pub async fn get_http_request_summary(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<HttpRequestSummary> {
+        let cache_key = format!(
+            "http_summary:{}:{}:{}",
+            selector.cache_key(),
+            from.timestamp(),
+            to.timestamp()
+        );
+
+        if let Some(cached) = self.cache_service.get::<HttpRequestSummary>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self.query_service.get_http_request_summary(selector, from, to).await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(result)
+    }
+
+    /// Get HTTP response-time percentiles for a container (cached)
+    pub async fn get_http_latency_percentiles(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<HttpLatencyPercentiles> {
+        let cache_key = format!(
+            "http_latency:{}:{}:{}",
+            selector.cache_key(),
+            from.timestamp(),
+            to.timestamp()
+        );
+
+        if let Some(cached) = self.cache_service.get::<HttpLatencyPercentiles>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self.query_service.get_http_latency_percentiles(selector, from, to).await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(result)
+    }
+
+    /// Get the endpoint inventory of a container (cached)
+    pub async fn get_container_endpoints(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: u64,
+    ) -> Result<Vec<HttpEndpointStats>> {
+        let cache_key = format!(
+            "http_endpoints:{}:{}:{}:{}",
+            selector.cache_key(),
+            from.timestamp(),
+            to.timestamp(),
+            limit
+        );
+
+        if let Some(cached) = self.cache_service.get::<Vec<HttpEndpointStats>>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self.query_service.get_container_endpoints(selector, from, to, limit).await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(result)
+    }
+
+    /// Get CPU/memory limit recommendations for a container (cached)
+    pub async fn get_resource_recommendation(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        min_samples: u64,
+    ) -> Result<ResourceRecommendation> {
+        let cache_key = format!(
+            "resource_recommendation:{}:{}:{}:{}",
+            selector.cache_key(),
+            from.timestamp(),
+            to.timestamp(),
+            min_samples
+        );
+
+        if let Some(cached) = self.cache_service.get::<ResourceRecommendation>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self
+            .query_service
+            .get_resource_recommendation(selector, from, to, min_samples)
+            .await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(result)
+    }
+
+    /// Get the fleet-wide stats series (cached)
+    pub async fn get_fleet_stats_history(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket_seconds: i64,
+    ) -> Result<FleetStatsHistory> {
+        let cache_key = format!("fleet_stats_history:{}:{}:{}", from.timestamp(), to.timestamp(), bucket_seconds);
+
+        if let Some(cached) = self.cache_service.get::<FleetStatsHistory>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let result = self.query_service.get_fleet_stats_history(from, to, bucket_seconds).await?;
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(result)
+    }
+
+    /// Stored log lines of one container (not cached: only read while Docker is unreachable)
+    pub async fn get_container_logs(
+        &self,
+        container_id: &str,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        limit: u64,
+    ) -> Result<Vec<ContainerLog>> {
+        self.query_service.get_container_logs(container_id, since, until, limit).await
+    }
+
+    /// Search stored log lines across containers (not cached: searches are ad hoc)
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search_logs(
+        &self,
+        text: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        container_id: Option<&str>,
+        stream: Option<&str>,
+        before: Option<KeysetCursor>,
+        by_relevance: bool,
+        limit: u64,
+    ) -> Result<(Vec<ContainerLog>, Option<KeysetCursor>)> {
+        self.query_service
+            .search_logs(text, from, to, container_id, stream, before, by_relevance, limit)
+            .await
+    }
+
+    /// Most recently stored service-map snapshot (not cached: only read while Docker is unreachable)
+    pub async fn get_latest_service_map_snapshot(&self) -> Result<Option<ServiceMap>> {
+        self.query_service.get_nearest_service_map_snapshot(Utc::now()).await
+    }
+
+    /// Diff the service-map snapshots nearest to `from` and `to` (cached).
+    /// Returns `None` when no snapshots have been stored yet
+    pub async fn get_service_map_diff(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<ServiceMapDiff>> {
+        let cache_key = format!("service_map_diff:{}:{}", from.timestamp(), to.timestamp());
+
+        if let Some(cached) = self.cache_service.get::<ServiceMapDiff>(&cache_key).await? {
+            return Ok(Some(cached));
+        }
+
+        let from_snapshot = self.query_service.get_nearest_service_map_snapshot(from).await?;
+        let to_snapshot = self.query_service.get_nearest_service_map_snapshot(to).await?;
+
+        let (Some(from_snapshot), Some(to_snapshot)) = (from_snapshot, to_snapshot) else {
+            return Ok(None);
+        };
+
+        let result = ServiceMapService::diff_service_maps(&from_snapshot, &to_snapshot);
+
+        let _ = self.cache_service.set(&cache_key, &result, Some(self.cache_ttl_history)).await;
+
+        Ok(Some(result))
+    }
+}
@@ -0,0 +1,159 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Ports captured when no rules file overrides them
+pub const DEFAULT_CAPTURE_PORTS: [u16; 8] = [80, 443, 8080, 8443, 8000, 3000, 5000, 9000];
+
+/// Serializable form of the capture rules, as read from `CAPTURE_RULES_FILE`
+///
+/// ```json
+/// {
+///   "capture_ports": [80, 8080, 4000],
+///   "endpoint_rules": [{ "pattern": "^[0-9]+$", "replacement": "{id}" }]
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureRulesConfig {
+    #[serde(default = "default_capture_ports")]
+    pub capture_ports: Vec<u16>,
+    #[serde(default = "default_endpoint_rules")]
+    pub endpoint_rules: Vec<EndpointRuleConfig>,
+}
+
+/// Path segments fully matching `pattern` are replaced with `replacement`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointRuleConfig {
+    pub pattern: String,
+    pub replacement: String,
+}
+
+fn default_capture_ports() -> Vec<u16> {
+    DEFAULT_CAPTURE_PORTS.to_vec()
+}
+
+fn default_endpoint_rules() -> Vec<EndpointRuleConfig> {
+    vec![
+        EndpointRuleConfig {
+            pattern: "^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$".to_string(),
+            replacement: "{uuid}".to_string(),
+        },
+        EndpointRuleConfig {
+            pattern: "^[0-9]+$".to_string(),
+            replacement: "{id}".to_string(),
+        },
+    ]
+}
+
+impl Default for CaptureRulesConfig {
+    fn default() -> Self {
+        Self {
+            capture_ports: default_capture_ports(),
+            endpoint_rules: default_endpoint_rules(),
+        }
+    }
+}
+
+/// Validated capture rules: which ports to capture and how to template captured endpoints
+#[derive(Debug)]
+pub struct CaptureRules {
+    config: CaptureRulesConfig,
+    endpoint_rules: Vec<(Regex, String)>,
+}
+
+impl CaptureRules {
+    /// Compile and validate a rules config. Fails on invalid regexes or an unusable port list
+    pub fn from_config(config: CaptureRulesConfig) -> Result<Self> {
+        if config.capture_ports.is_empty() {
+            return Err(anyhow::anyhow!("capture_ports must not be empty"));
+        }
+        if config.capture_ports.contains(&0) {
+            return Err(anyhow::anyhow!("capture_ports must not contain port 0"));
+        }
+
+        let endpoint_rules = config
+            .endpoint_rules
+            .iter()
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .map(|regex| (regex, rule.replacement.clone()))
+                    .with_context(|| format!("Invalid endpoint rule pattern: {}", rule.pattern))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            config,
+            endpoint_rules,
+        })
+    }
+
+    /// Load rules from a JSON file, or use the defaults when no path is given.
+    /// `default_ports` are captured when no file is given or the file has no `capture_ports`
+    pub fn load(path: Option<&str>, default_ports: &[u16]) -> Result<Self> {
+        let config = match path {
+            Some(path) => {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read capture rules file: {}", path))?;
+                let mut value: serde_json::Value = serde_json::from_str(&content)
+                    .with_context(|| format!("Failed to parse capture rules file: {}", path))?;
+                if let Some(fields) = value.as_object_mut() {
+                    fields
+                        .entry("capture_ports")
+                        .or_insert_with(|| serde_json::json!(default_ports));
+                }
+                serde_json::from_value(value)
+                    .with_context(|| format!("Failed to parse capture rules file: {}", path))?
+            }
+            None => CaptureRulesConfig {
+                capture_ports: default_ports.to_vec(),
+                ..CaptureRulesConfig::default()
+            },
+        };
+
+        Self::from_config(config)
+    }
+
+    pub fn config(&self) -> &CaptureRulesConfig {
+        &self.config
+    }
+
+    pub fn capture_ports(&self) -> &[u16] {
+        &self.config.capture_ports
+    }
+
+    pub fn is_capture_port(&self, port: u16) -> bool {
+        self.config.capture_ports.contains(&port)
+    }
+
+    /// BPF filter matching TCP traffic on the capture ports
+    pub fn bpf_filter(&self) -> String {
+        self.config
+            .capture_ports
+            .iter()
+            .map(|port| format!("tcp port {}", port))
+            .collect::<Vec<_>>()
+            .join(" or ")
+    }
+
+    /// Template an endpoint path, e.g. `/users/42/orders?page=2` -> `/users/{id}/orders`
+    pub fn normalize_endpoint(&self, path: &str) -> String {
+        let path = path.split(['?', '#']).next().unwrap_or(path);
+
+        path.split('/')
+            .map(|segment| {
+                self.endpoint_rules
+                    .iter()
+                    .find(|(regex, _)| !segment.is_empty() && regex.is_match(segment))
+                    .map(|(_, replacement)| replacement.as_str())
+                    .unwrap_or(segment)
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+impl Default for CaptureRules {
+    fn default() -> Self {
+        Self::from_config(CaptureRulesConfig::default()).expect("Default capture rules must be valid")
+    }
+}
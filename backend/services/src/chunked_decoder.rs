@@ -0,0 +1,170 @@
+use anyhow::{Result, bail};
+
+/// Incremental decoder for a `Transfer-Encoding: chunked` body that only tracks framing.
+/// It is fed the body bytes as they arrive (possibly split across TCP segments at any
+/// point, including inside a chunk-size line) and reports when the terminating
+/// zero-length chunk and trailer section have been consumed.
+#[derive(Debug, Clone)]
+pub struct ChunkedDecoder {
+    state: ChunkState,
+    /// Total chunk payload bytes seen so far
+    body_len: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChunkState {
+    /// Reading the hex chunk size; `in_extension` once a `;` chunk extension started
+    Size { value: u64, digits: u8, in_extension: bool },
+    /// Inside chunk data with this many bytes left
+    Data(u64),
+    /// Expecting the CRLF that ends a chunk's data
+    DataEnd,
+    /// After the zero-length chunk: trailer fields until an empty line
+    Trailer { line_empty: bool },
+    Done,
+}
+
+const INITIAL_SIZE_STATE: ChunkState = ChunkState::Size { value: 0, digits: 0, in_extension: false };
+
+impl Default for ChunkedDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChunkedDecoder {
+    pub fn new() -> Self {
+        Self {
+            state: INITIAL_SIZE_STATE,
+            body_len: 0,
+        }
+    }
+
+    /// True once the terminating chunk (and any trailers) have been consumed
+    pub fn is_done(&self) -> bool {
+        self.state == ChunkState::Done
+    }
+
+    /// Total decoded body length so far
+    pub fn body_len(&self) -> u64 {
+        self.body_len
+    }
+
+    /// Consume the next piece of the body. Returns whether the body is now complete;
+    /// bytes after the end of the body (e.g. a pipelined response) are ignored
+    pub fn feed(&mut self, mut data: &[u8]) -> Result<bool> {
+        while !data.is_empty() && !self.is_done() {
+            match self.state {
+                ChunkState::Data(remaining) => {
+                    let take = remaining.min(data.len() as u64) as usize;
+                    self.body_len += take as u64;
+                    data = &data[take..];
+                    let remaining = remaining - take as u64;
+                    self.state = if remaining == 0 { ChunkState::DataEnd } else { ChunkState::Data(remaining) };
+                }
+                state => {
+                    self.state = Self::step(state, data[0])?;
+                    data = &data[1..];
+                }
+            }
+        }
+        Ok(self.is_done())
+    }
+
+    /// Advance the framing states that consume one byte at a time
+    fn step(state: ChunkState, byte: u8) -> Result<ChunkState> {
+        Ok(match state {
+            ChunkState::Size { value, digits, in_extension } => match byte {
+                b'\n' if digits == 0 => bail!("Chunk size line without a size"),
+                b'\n' if value == 0 => ChunkState::Trailer { line_empty: true },
+                b'\n' => ChunkState::Data(value),
+                b'\r' => state,
+                _ if in_extension => state,
+                b';' | b' ' | b'\t' => ChunkState::Size { value, digits, in_extension: true },
+                _ => {
+                    let digit = (byte as char)
+                        .to_digit(16)
+                        .ok_or_else(|| anyhow::anyhow!("Invalid chunk size byte 0x{:02x}", byte))?;
+                    // 16 hex digits fill a u64
+                    if digits >= 16 {
+                        bail!("Chunk size too large");
+                    }
+                    ChunkState::Size { value: (value << 4) | digit as u64, digits: digits + 1, in_extension }
+                }
+            },
+            ChunkState::DataEnd => match byte {
+                b'\r' => state,
+                b'\n' => INITIAL_SIZE_STATE,
+                _ => bail!("Missing CRLF after chunk data"),
+            },
+            ChunkState::Trailer { line_empty } => match byte {
+                b'\r' => state,
+                b'\n' if line_empty => ChunkState::Done,
+                b'\n' => ChunkState::Trailer { line_empty: true },
+                _ => ChunkState::Trailer { line_empty: false },
+            },
+            ChunkState::Data(_) | ChunkState::Done => state,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_body_with_zero_length_terminator() {
+        let mut decoder = ChunkedDecoder::new();
+        assert!(decoder.feed(b"5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n").unwrap());
+        assert_eq!(decoder.body_len(), 11);
+    }
+
+    #[test]
+    fn terminator_alone_is_an_empty_body() {
+        let mut decoder = ChunkedDecoder::new();
+        assert!(decoder.feed(b"0\r\n\r\n").unwrap());
+        assert_eq!(decoder.body_len(), 0);
+    }
+
+    #[test]
+    fn zero_length_chunk_needs_the_final_empty_line() {
+        let mut decoder = ChunkedDecoder::new();
+        assert!(!decoder.feed(b"3\r\nabc\r\n0\r\n").unwrap());
+        assert!(decoder.feed(b"\r\n").unwrap());
+    }
+
+    #[test]
+    fn skips_chunk_extensions() {
+        let mut decoder = ChunkedDecoder::new();
+        assert!(decoder.feed(b"4;name=value\r\nwiki\r\na ; ext\r\npedia in\r\n\r\n0;last\r\n\r\n").unwrap());
+        assert_eq!(decoder.body_len(), 4 + 10);
+    }
+
+    #[test]
+    fn chunk_size_split_across_packets() {
+        let mut decoder = ChunkedDecoder::new();
+        assert!(!decoder.feed(b"1").unwrap());
+        assert!(!decoder.feed(b"a\r").unwrap());
+        assert!(!decoder.feed(b"\n0123456789").unwrap());
+        assert!(!decoder.feed(b"abcdefghijklmnop\r\n0\r").unwrap());
+        assert!(decoder.feed(b"\n\r\n").unwrap());
+        assert_eq!(decoder.body_len(), 0x1a);
+    }
+
+    #[test]
+    fn consumes_trailers() {
+        let mut decoder = ChunkedDecoder::new();
+        assert!(!decoder.feed(b"2\r\nok\r\n0\r\nExpires: never\r\n").unwrap());
+        assert!(!decoder.feed(b"X-Checksum: abc\r\n").unwrap());
+        assert!(decoder.feed(b"\r\nHTTP/1.1 200 OK\r\n").unwrap());
+        assert_eq!(decoder.body_len(), 2);
+    }
+
+    #[test]
+    fn rejects_malformed_framing() {
+        assert!(ChunkedDecoder::new().feed(b"zz\r\n").is_err());
+        assert!(ChunkedDecoder::new().feed(b"\r\n").is_err());
+        assert!(ChunkedDecoder::new().feed(b"2\r\nokX").is_err());
+        assert!(ChunkedDecoder::new().feed(b"11111111111111111\r\n").is_err());
+    }
+}
@@ -1,31 +1,141 @@
-use eyes_devine_shared::{ContainerInfo, ContainerStats, TotalStats};
+use eyes_devine_shared::{
+    ContainerFullDetails, ContainerHealth, ContainerInfo, ContainerMount, ContainerRestartPolicy, ContainerProcess, ContainerRunState, ContainerState, ContainerStats, DiskUsage, DockerConnectionHealth,
+    DockerContainerEvent, TotalStats,
+};
 use anyhow::{Context, Result};
-use bollard::query_parameters::{ListContainersOptions, LogsOptions, StatsOptions};
+use bollard::query_parameters::{EventsOptions, ListContainersOptions, LogsOptions, StatsOptions};
 use bollard::Docker;
+use bollard::container::LogOutput;
 use chrono::Utc;
 use futures::StreamExt;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Default maximum length (in characters) of a returned log line
+pub const DEFAULT_MAX_LOG_LINE_LENGTH: usize = 16 * 1024;
+
+/// Whether `error` means the Docker daemon couldn't be reached (socket missing, connection
+/// refused or reset, timeout), as opposed to Docker answering the request with an error
+pub fn is_docker_unreachable(error: &anyhow::Error) -> bool {
+    use bollard::errors::Error as DockerError;
+    error.chain().any(|cause| match cause.downcast_ref::<DockerError>() {
+        Some(docker_error) => matches!(
+            docker_error,
+            DockerError::RequestTimeoutError
+                | DockerError::IOError { .. }
+                | DockerError::HyperResponseError { .. }
+                | DockerError::HyperLegacyError { .. }
+                | DockerError::SocketNotFoundError(_)
+        ),
+        None => cause.is::<std::io::Error>(),
+    })
+}
+
+/// Whether Docker answered with 404, e.g. "No such container"
+pub fn is_docker_not_found(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<bollard::errors::Error>(),
+            Some(bollard::errors::Error::DockerResponseServerError { status_code: 404, .. })
+        )
+    })
+}
+
+/// Container actions forwarded by [`DockerService::subscribe_events`]
+const CONTAINER_EVENT_ACTIONS: [&str; 4] = ["create", "start", "stop", "die"];
+
+/// Wait before re-opening a dropped Docker events connection
+const EVENTS_RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// What the service map reads from a container's inspect response
+pub struct ContainerWiring {
+    pub network_info: eyes_devine_shared::ContainerNetworkInfo,
+    pub env_vars: Vec<(String, String)>,
+    /// Process command line (`Path` followed by `Args`)
+    pub command: Vec<String>,
+}
 
 pub struct DockerService {
-    docker: Docker,
+    docker: RwLock<Docker>,
+    health: RwLock<DockerConnectionHealth>,
+    max_log_line_length: usize,
 }
 
 impl DockerService {
     pub async fn new() -> Result<Self> {
+        let service = Self::with_client(Self::connect()?);
+        
+        // Test the connection by listing containers
+        let test_options = ListContainersOptions {
+            all: true,
+            ..Default::default()
+        };
+        let test_containers = service.client().list_containers(Some(test_options)).await;
+        match test_containers {
+            Ok(containers) => {
+                log::info!("Docker connection successful. Found {} containers on initial connection test", containers.len());
+            }
+            Err(e) => {
+                return Err(anyhow::anyhow!("Failed to connect to Docker daemon: {}. Make sure Docker is running and accessible.", e));
+            }
+        }
+        
+        {
+            let mut health = service.health.write().unwrap();
+            health.connected = true;
+            health.last_checked = Some(Utc::now());
+        }
+        Ok(service)
+    }
+
+    /// Service on an existing client, without checking that the daemon answers; calls fail
+    /// until it does, and `health()` reports it unchecked until the first probe
+    pub fn with_client(docker: Docker) -> Self {
+        Self {
+            docker: RwLock::new(docker),
+            health: RwLock::new(DockerConnectionHealth {
+                connected: false,
+                last_checked: None,
+                last_error: None,
+                reconnect_count: 0,
+            }),
+            max_log_line_length: DEFAULT_MAX_LOG_LINE_LENGTH,
+        }
+    }
+
+    /// Truncate log lines longer than `max_len` characters
+    pub fn with_max_log_line_length(mut self, max_len: usize) -> Self {
+        self.max_log_line_length = max_len;
+        self
+    }
+
+    /// Build a bollard client from the environment (DOCKER_HOST, Docker Desktop socket, or local defaults)
+    fn connect() -> Result<Docker> {
         // Try to detect Docker socket path
         // Docker Desktop uses ~/.docker/desktop/docker.sock
         // Standard Docker uses /var/run/docker.sock
         // Also check DOCKER_HOST environment variable
+        let tls_verify = std::env::var("DOCKER_TLS_VERIFY").is_ok_and(|v| !v.is_empty() && v != "0");
         let docker = if let Ok(docker_host) = std::env::var("DOCKER_HOST") {
             log::info!("Using DOCKER_HOST: {}", docker_host);
             // DOCKER_HOST can be unix:///path or tcp://host:port
             if docker_host.starts_with("unix://") {
+                if tls_verify {
+                    log::warn!("DOCKER_TLS_VERIFY is ignored for the unix socket {}", docker_host);
+                }
                 let socket_path = docker_host.strip_prefix("unix://").unwrap_or(&docker_host);
                 Docker::connect_with_socket(socket_path, 120, bollard::API_DEFAULT_VERSION)
                     .context(format!("Failed to connect to Docker socket: {}", socket_path))?
+            } else if tls_verify {
+                Self::connect_with_tls(&docker_host)?
             } else {
                 Docker::connect_with_http(&docker_host, 120, bollard::API_DEFAULT_VERSION)
                     .context(format!("Failed to connect to Docker host: {}", docker_host))?
             }
+        } else if tls_verify {
+            anyhow::bail!("DOCKER_TLS_VERIFY is set but DOCKER_HOST is not; set DOCKER_HOST=tcp://<host>:2376");
         } else if let Ok(home) = std::env::var("HOME") {
             let desktop_path = format!("{}/.docker/desktop/docker.sock", home);
             if std::path::Path::new(&desktop_path).exists() {
@@ -48,23 +158,95 @@ impl DockerService {
             Docker::connect_with_local_defaults()
                 .context("Failed to connect to Docker daemon")?
         };
-        
-        // Test the connection by listing containers
-        let test_options = ListContainersOptions {
-            all: true,
-            ..Default::default()
+
+        Ok(docker)
+    }
+
+    /// Mutual TLS as the Docker CLI does it: `ca.pem`, `cert.pem` and `key.pem` from
+    /// `DOCKER_CERT_PATH` (default `~/.docker`)
+    fn connect_with_tls(docker_host: &str) -> Result<Docker> {
+        let cert_dir = docker_cert_dir(std::env::var("DOCKER_CERT_PATH").ok(), std::env::var("HOME").ok())?;
+        let [ca, cert, key] = docker_tls_files(&cert_dir)?;
+
+        log::info!("Connecting to {} with TLS (certificates from {})", docker_host, cert_dir.display());
+        Docker::connect_with_ssl(docker_host, &key, &cert, &ca, 120, bollard::API_DEFAULT_VERSION)
+            .with_context(|| format!("Failed to set up TLS connection to Docker host: {}", docker_host))
+    }
+
+    /// Current bollard client. Cloning is cheap (the client is reference counted internally),
+    /// and lets the health probe swap in a fresh client without blocking in-flight calls.
+    fn client(&self) -> Docker {
+        self.docker.read().unwrap().clone()
+    }
+
+    /// Snapshot of the Docker connection health as seen by the last probe
+    pub fn health(&self) -> DockerConnectionHealth {
+        self.health.read().unwrap().clone()
+    }
+
+    /// Periodically ping the Docker daemon and re-create the client when the ping fails,
+    /// so a daemon restart doesn't leave the service stuck on a dead connection
+    pub fn start_health_probe(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let service = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately and the connection was just tested in new()
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                service.probe().await;
+            }
+        })
+    }
+
+    async fn probe(&self) {
+        let ping_error = match self.client().ping().await {
+            Ok(_) => {
+                let mut health = self.health.write().unwrap();
+                if !health.connected {
+                    log::info!("Docker connection restored");
+                }
+                health.connected = true;
+                health.last_checked = Some(Utc::now());
+                health.last_error = None;
+                return;
+            }
+            Err(e) => e.to_string(),
         };
-        let test_containers = docker.list_containers(Some(test_options)).await;
-        match test_containers {
-            Ok(containers) => {
-                log::info!("Docker connection successful. Found {} containers on initial connection test", containers.len());
+
+        log::warn!("Docker health probe failed: {}. Reconnecting...", ping_error);
+        {
+            let mut health = self.health.write().unwrap();
+            health.connected = false;
+            health.last_checked = Some(Utc::now());
+            health.last_error = Some(ping_error);
+        }
+
+        let docker = match Self::connect() {
+            Ok(docker) => docker,
+            Err(e) => {
+                log::warn!("Failed to re-create Docker client: {}", e);
+                self.health.write().unwrap().last_error = Some(e.to_string());
+                return;
+            }
+        };
+
+        match docker.ping().await {
+            Ok(_) => {
+                *self.docker.write().unwrap() = docker;
+                let mut health = self.health.write().unwrap();
+                health.connected = true;
+                health.last_error = None;
+                health.reconnect_count += 1;
+                log::info!("Reconnected to Docker daemon (reconnect #{})", health.reconnect_count);
             }
             Err(e) => {
-                return Err(anyhow::anyhow!("Failed to connect to Docker daemon: {}. Make sure Docker is running and accessible.", e));
+                log::warn!("Docker daemon still unreachable after reconnect: {}", e);
+                self.health.write().unwrap().last_error = Some(e.to_string());
             }
         }
-        
-        Ok(Self { docker })
     }
 
     pub async fn list_containers(&self) -> Result<Vec<ContainerInfo>> {
@@ -74,12 +256,13 @@ impl DockerService {
         };
 
         let containers = self
-            .docker
+            .client()
             .list_containers(Some(options))
             .await
             .map_err(|e| {
                 log::error!("Docker API error: {:?}", e);
-                anyhow::anyhow!("Failed to list containers: {}", e)
+                // Keep the Docker error itself so callers can tell an unreachable daemon apart
+                anyhow::Error::from(e)
             })?;
 
         log::info!("Docker API returned {} containers", containers.len());
@@ -87,6 +270,7 @@ impl DockerService {
             log::warn!("No containers returned from Docker API, but containers are running. This might indicate a connection or permissions issue.");
         }
 
+        let collected_at = Utc::now();
         let mut result = Vec::new();
         for (idx, container) in containers.iter().enumerate() {
             log::debug!("Container {}: id={:?}, names={:?}, image={:?}, status={:?}", 
@@ -121,6 +305,22 @@ impl DockerService {
                 continue;
             }
             
+            // Health and exit code only appear in the status text, e.g. "Up 2 hours (healthy)"
+            let health = parse_health_from_status(&status);
+            let state = container
+                .state
+                .as_ref()
+                .and_then(|state| ContainerRunState::parse(state.as_ref()))
+                .map(|run_state| ContainerState {
+                    state: run_state,
+                    exit_code: parse_exit_code_from_status(&status).map(|code| code as i32),
+                    health,
+                });
+
+            let labels: BTreeMap<String, String> =
+                container.labels.clone().unwrap_or_default().into_iter().collect();
+            let (compose_project, compose_service) = ContainerInfo::compose_labels(&labels);
+
             result.push(ContainerInfo {
                 id: container_id,
                 name,
@@ -130,6 +330,15 @@ impl DockerService {
                     chrono::DateTime::from_timestamp(ts, 0)
                         .unwrap_or_else(Utc::now)
                 }),
+                // Not part of the list response; filled in by `fill_inspect_state`
+                started_at: None,
+                restart_count: None,
+                state,
+                health: health.map(|health| health.as_str().to_string()),
+                labels,
+                compose_project,
+                compose_service,
+                collected_at: Some(collected_at),
             });
         }
 
@@ -137,6 +346,114 @@ impl DockerService {
         Ok(result)
     }
 
+    /// Fill in the fields that only inspect provides: `started_at`, `restart_count` and the structured
+    /// `state` (with `health`)
+    pub async fn fill_inspect_state(&self, container: &mut ContainerInfo) -> Result<()> {
+        use bollard::query_parameters::InspectContainerOptions;
+        let inspect = self
+            .client()
+            .inspect_container(&container.id, None::<InspectContainerOptions>)
+            .await
+            .context("Failed to inspect container")?;
+
+        container.restart_count = inspect.restart_count;
+
+        let Some(state) = inspect.state else {
+            return Ok(());
+        };
+
+        container.started_at = state
+            .started_at
+            .as_deref()
+            .and_then(|started_at| chrono::DateTime::parse_from_rfc3339(started_at).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+            // Docker reports the zero time for never-started containers
+            .filter(|dt| dt.timestamp() > 0);
+        container.state = to_container_state(&state);
+        container.health = container.state.and_then(|state| state.health).map(|health| health.as_str().to_string());
+
+        Ok(())
+    }
+
+    /// Disk usage of every container from one `docker system df` call. Docker walks each
+    /// container's writable layer for this, so it can take seconds on busy hosts
+    pub async fn get_disk_usage(&self) -> Result<Vec<DiskUsage>> {
+        use bollard::query_parameters::DataUsageOptions;
+        let options = DataUsageOptions {
+            _type: Some(vec!["container".to_string()]),
+        };
+        let usage = self
+            .client()
+            .df(Some(options))
+            .await
+            .context("Failed to get disk usage")?;
+
+        let timestamp = Utc::now();
+        Ok(usage
+            .containers
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|container| {
+                let container_id = container.id?;
+                let container_name = container
+                    .names
+                    .and_then(|names| names.into_iter().next())
+                    .map(|name| name.trim_start_matches('/').to_string())
+                    .unwrap_or_else(|| container_id.clone());
+                Some(DiskUsage {
+                    container_id,
+                    container_name,
+                    size_rw_bytes: container.size_rw.map(|size| size.max(0) as u64),
+                    size_root_fs_bytes: container.size_root_fs.map(|size| size.max(0) as u64),
+                    timestamp,
+                })
+            })
+            .collect())
+    }
+
+    /// Disk usage of one container (inspect with `size=true`)
+    pub async fn get_container_disk_usage(&self, container_id: &str) -> Result<DiskUsage> {
+        use bollard::query_parameters::InspectContainerOptions;
+        let inspect = self
+            .client()
+            .inspect_container(container_id, Some(InspectContainerOptions { size: true }))
+            .await
+            .context("Failed to inspect container")?;
+
+        Ok(DiskUsage {
+            container_id: inspect.id.unwrap_or_else(|| container_id.to_string()),
+            container_name: inspect
+                .name
+                .map(|name| name.trim_start_matches('/').to_string())
+                .unwrap_or_else(|| container_id.to_string()),
+            size_rw_bytes: inspect.size_rw.map(|size| size.max(0) as u64),
+            size_root_fs_bytes: inspect.size_root_fs.map(|size| size.max(0) as u64),
+            timestamp: Utc::now(),
+        })
+    }
+
+    /// Processes running in a container, like `docker top` (`ps aux` columns).
+    /// A container that is not running has none, so that is an empty list rather than an error
+    pub async fn get_container_processes(&self, container_id: &str) -> Result<Vec<ContainerProcess>> {
+        use bollard::query_parameters::TopOptions;
+        let options = TopOptions {
+            ps_args: "aux".to_string(),
+        };
+        let top = match self.client().top_processes(container_id, Some(options)).await {
+            Ok(top) => top,
+            // Docker answers 409 Conflict for stopped containers
+            Err(bollard::errors::Error::DockerResponseServerError { status_code: 409, .. }) => {
+                return Ok(Vec::new());
+            }
+            Err(e) => return Err(e).context("Failed to list container processes"),
+        };
+
+        Ok(processes_from_top(
+            &top.titles.unwrap_or_default(),
+            top.processes.unwrap_or_default(),
+        ))
+    }
+
     pub async fn get_container_stats(&self, container_id: &str) -> Result<ContainerStats> {
         let options = StatsOptions {
             stream: false,
@@ -144,7 +461,7 @@ impl DockerService {
         };
 
         let mut stats_stream = self
-            .docker
+            .client()
             .stats(container_id, Some(options));
 
         let stats = stats_stream
@@ -153,6 +470,146 @@ impl DockerService {
             .ok_or_else(|| anyhow::anyhow!("No stats available"))?
             .context("Failed to read stats")?;
 
+        Self::to_container_stats(container_id, &stats)
+    }
+
+    /// Stream live stats samples for a container straight from Docker (`stream: true`).
+    /// The stream ends when the container stops or the Docker stream fails; dropping it
+    /// closes the underlying Docker connection.
+    pub fn stream_container_stats(
+        &self,
+        container_id: &str,
+    ) -> impl futures::Stream<Item = Result<ContainerStats>> + use<> {
+        let options = StatsOptions {
+            stream: true,
+            ..Default::default()
+        };
+        let container_id = container_id.to_string();
+
+        self.client()
+            .stats(&container_id, Some(options))
+            .take_while(|sample| {
+                // Stopped containers report samples without CPUs or memory usage
+                let running = match sample {
+                    Ok(stats) => {
+                        stats.cpu_stats.as_ref().and_then(|c| c.online_cpus).unwrap_or(0) > 0
+                            && stats.memory_stats.as_ref().and_then(|m| m.usage).is_some()
+                    }
+                    Err(_) => true,
+                };
+                futures::future::ready(running)
+            })
+            .map(move |sample| {
+                let stats = sample.context("Failed to read stats")?;
+                Self::to_container_stats(&container_id, &stats)
+            })
+    }
+
+    /// Follow container create/start/stop/die events from the Docker events API. The stream
+    /// never ends on its own: when the events connection drops it is re-opened (on the current
+    /// client, so it survives a health-probe reconnect) and replays events since the last one
+    /// seen, so nothing in between is lost or delivered twice
+    pub fn subscribe_events(self: &Arc<Self>) -> impl futures::Stream<Item = DockerContainerEvent> + use<> {
+        type EventStream = futures::stream::BoxStream<
+            'static,
+            std::result::Result<bollard::models::EventMessage, bollard::errors::Error>,
+        >;
+
+        struct Subscription {
+            service: Arc<DockerService>,
+            events: Option<EventStream>,
+            // `timeNano` of the last forwarded event
+            last_seen: Option<i64>,
+        }
+
+        let subscription = Subscription {
+            service: Arc::clone(self),
+            events: None,
+            last_seen: None,
+        };
+
+        futures::stream::unfold(subscription, |mut sub| async move {
+            loop {
+                let events = match &mut sub.events {
+                    Some(events) => events,
+                    None => {
+                        let options = EventsOptions {
+                            since: sub.last_seen.map(|nanos| (nanos / 1_000_000_000).to_string()),
+                            until: None,
+                            filters: Some(
+                                [
+                                    ("type".to_string(), vec!["container".to_string()]),
+                                    (
+                                        "event".to_string(),
+                                        CONTAINER_EVENT_ACTIONS.iter().map(|a| a.to_string()).collect(),
+                                    ),
+                                ]
+                                .into_iter()
+                                .collect(),
+                            ),
+                        };
+                        sub.events.insert(sub.service.client().events(Some(options)).boxed())
+                    }
+                };
+
+                match events.next().await {
+                    Some(Ok(message)) => {
+                        let time_nano = message.time_nano.unwrap_or_default();
+                        // `since` has second precision, so a replay repeats the last second
+                        if sub.last_seen.is_some_and(|last| time_nano <= last) {
+                            continue;
+                        }
+                        if let Some(event) = Self::to_container_event(message) {
+                            sub.last_seen = Some(time_nano);
+                            return Some((event, sub));
+                        }
+                    }
+                    Some(Err(e)) => {
+                        log::warn!("Docker events stream failed: {}. Reconnecting...", e);
+                        sub.events = None;
+                        tokio::time::sleep(EVENTS_RECONNECT_DELAY).await;
+                    }
+                    None => {
+                        log::warn!("Docker events stream ended. Reconnecting...");
+                        sub.events = None;
+                        tokio::time::sleep(EVENTS_RECONNECT_DELAY).await;
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_container_event(message: bollard::models::EventMessage) -> Option<DockerContainerEvent> {
+        let action = message.action?;
+        if !CONTAINER_EVENT_ACTIONS.contains(&action.as_str()) {
+            return None;
+        }
+        let actor = message.actor?;
+        let container_id = actor.id?;
+        let attributes = actor.attributes.unwrap_or_default();
+
+        let timestamp = message
+            .time_nano
+            .map(chrono::DateTime::from_timestamp_nanos)
+            .or_else(|| message.time.and_then(|secs| chrono::DateTime::from_timestamp(secs, 0)))
+            .unwrap_or_else(Utc::now);
+
+        Some(DockerContainerEvent {
+            container_name: attributes
+                .get("name")
+                .cloned()
+                .unwrap_or_else(|| container_id.clone()),
+            exit_code: attributes.get("exitCode").and_then(|code| code.parse().ok()),
+            action,
+            container_id,
+            timestamp,
+        })
+    }
+
+    fn to_container_stats(
+        container_id: &str,
+        stats: &bollard::models::ContainerStatsResponse,
+    ) -> Result<ContainerStats> {
         let cpu_stats = stats.cpu_stats.as_ref().ok_or_else(|| anyhow::anyhow!("No CPU stats"))?;
         let precpu_stats = stats.precpu_stats.as_ref();
         
@@ -175,10 +632,35 @@ impl DockerService {
             0.0
         };
 
+        // Per-core usage, scaled like the aggregate so the entries sum to `cpu_percent`
+        let online_cpus = cpu_stats.online_cpus.unwrap_or(1) as f64;
+        let per_cpu_percent: Vec<f64> = match (
+            cpu_stats.cpu_usage.as_ref().and_then(|cu| cu.percpu_usage.as_ref()),
+            precpu_stats.and_then(|pc| pc.cpu_usage.as_ref()).and_then(|pcu| pcu.percpu_usage.as_ref()),
+        ) {
+            (Some(current), Some(previous)) if system_delta > 0 => current
+                .iter()
+                .zip(previous.iter().chain(std::iter::repeat(&0)))
+                .map(|(cur, prev)| (cur.saturating_sub(*prev) as f64 / system_delta as f64) * 100.0 * online_cpus)
+                .collect(),
+            _ => Vec::new(),
+        };
+
         let memory_stats = stats.memory_stats.as_ref().ok_or_else(|| anyhow::anyhow!("No memory stats"))?;
         let memory_usage = memory_stats.usage.unwrap_or(0);
         let memory_limit = memory_stats.limit.unwrap_or(1);
         let memory_percent = (memory_usage as f64 / memory_limit as f64) * 100.0;
+        // The `stats` breakdown differs by cgroup version: v1 has `cache` (and `swap` with swap
+        // accounting), v2 calls the page cache `file` and reports no swap here
+        let memory_breakdown = |keys: &[&str]| {
+            memory_stats.stats.as_ref()
+                .and_then(|s| keys.iter().find_map(|key| s.get(*key).copied()))
+                .unwrap_or(0)
+        };
+        let memory_cache = memory_breakdown(&["cache", "file"]);
+        let memory_swap = memory_breakdown(&["swap"]);
+
+        let (pids_current, pids_limit) = pids_from_stats(stats.pids_stats.as_ref());
 
         let network_rx = stats.networks.as_ref()
             .map(|n| n.values().map(|net| net.rx_bytes.unwrap_or(0)).sum())
@@ -213,10 +695,15 @@ impl DockerService {
             memory_usage_bytes: memory_usage,
             memory_limit_bytes: memory_limit,
             memory_usage_percent: memory_percent,
+            memory_cache_bytes: memory_cache,
+            memory_swap_bytes: memory_swap,
             network_rx_bytes: network_rx,
             network_tx_bytes: network_tx,
             block_read_bytes: block_read,
             block_write_bytes: block_write,
+            per_cpu_usage_percent: per_cpu_percent,
+            pids_current,
+            pids_limit,
             timestamp: Utc::now(),
         })
     }
@@ -234,11 +721,21 @@ impl DockerService {
         Ok(all_stats)
     }
 
+    /// Fleet totals: resource usage is summed (CPU averaged) over running containers only,
+    /// `total_containers` also counts stopped ones
     pub async fn get_total_stats(&self) -> Result<TotalStats> {
-        let all_stats = self.get_all_container_stats().await?;
+        let containers = self.list_containers().await?;
+        let total_containers = containers.len();
 
-        let total_containers = all_stats.len();
-        let total_cpu = all_stats.iter().map(|s| s.cpu_usage_percent).sum::<f64>() / total_containers.max(1) as f64;
+        let mut all_stats = Vec::new();
+        for container in containers.iter().filter(|c| c.is_running()) {
+            if let Ok(stats) = self.get_container_stats(&container.id).await {
+                all_stats.push(stats);
+            }
+        }
+
+        let running_containers = all_stats.len();
+        let total_cpu = all_stats.iter().map(|s| s.cpu_usage_percent).sum::<f64>() / running_containers.max(1) as f64;
         let total_memory_usage = all_stats.iter().map(|s| s.memory_usage_bytes).sum();
         let total_memory_limit = all_stats.iter().map(|s| s.memory_limit_bytes).sum();
         let total_memory_percent = if total_memory_limit > 0 {
@@ -253,6 +750,7 @@ impl DockerService {
 
         Ok(TotalStats {
             total_containers,
+            running_containers,
             total_cpu_usage_percent: total_cpu,
             total_memory_usage_bytes: total_memory_usage,
             total_memory_limit_bytes: total_memory_limit,
@@ -265,13 +763,29 @@ impl DockerService {
         })
     }
 
+    /// Total memory of the Docker host in bytes
+    pub async fn get_host_memory_bytes(&self) -> Result<u64> {
+        let info = self
+            .client()
+            .info()
+            .await
+            .context("Failed to get Docker system info")?;
+
+        info.mem_total
+            .map(|bytes| bytes as u64)
+            .ok_or_else(|| anyhow::anyhow!("Docker did not report host memory"))
+    }
+
+    /// Existing output of a container as (stream, line) pairs, oldest first. The stream
+    /// ("stdout" or "stderr") comes from Docker's frame header; each line is prefixed with
+    /// Docker's RFC 3339 timestamp
     pub async fn get_container_logs(
         &self,
         container_id: &str,
         since: Option<i64>,
         until: Option<i64>,
         tail: Option<u64>,
-    ) -> Result<Vec<String>> {
+    ) -> Result<Vec<(&'static str, String)>> {
         let mut options = LogsOptions {
             stdout: true,
             stderr: true,
@@ -291,141 +805,148 @@ impl DockerService {
         }
 
         let mut logs_stream = self
-            .docker
+            .client()
             .logs(container_id, Some(options));
 
         let mut logs = Vec::new();
 
+        // Errors (unknown container, daemon unreachable) arrive as stream items
         while let Some(log_result) = logs_stream.next().await {
-            match log_result {
-                Ok(log_chunk) => {
-                    let log_line = String::from_utf8_lossy(&log_chunk.into_bytes()).to_string();
-                    logs.push(log_line);
-                }
-                Err(e) => {
-                    log::warn!("Error reading log chunk: {}", e);
-                }
-            }
+            logs.push(log_output_line(log_result?, self.max_log_line_length));
         }
 
         Ok(logs)
     }
 
+    /// Follow a container's output like `docker logs -f`, starting with the last `tail` lines
+    /// (only new output when None). Each item is the stream the line was written to
+    /// ("stdout" or "stderr") and the sanitized line, prefixed with Docker's RFC 3339
+    /// timestamp. The stream ends when the container stops; dropping it closes the
+    /// underlying Docker connection.
+    pub fn stream_container_logs(
+        &self,
+        container_id: &str,
+        tail: Option<u64>,
+    ) -> impl futures::Stream<Item = Result<(&'static str, String)>> + use<> {
+        let options = LogsOptions {
+            stdout: true,
+            stderr: true,
+            follow: true,
+            timestamps: true,
+            tail: tail.unwrap_or(0).to_string(),
+            ..Default::default()
+        };
+        let max_len = self.max_log_line_length;
+
+        self.client()
+            .logs(container_id, Some(options))
+            .map(move |chunk| {
+                let output = chunk.context("Failed to read logs")?;
+                Ok(log_output_line(output, max_len))
+            })
+    }
+
     pub async fn get_container_environment(&self, container_id: &str) -> Result<Vec<(String, String)>> {
         use bollard::query_parameters::InspectContainerOptions;
         let inspect = self
-            .docker
+            .client()
             .inspect_container(container_id, None::<InspectContainerOptions>)
             .await
             .context("Failed to inspect container")?;
 
-        let env_vars = inspect
-            .config
-            .and_then(|config| config.env)
-            .unwrap_or_default()
-            .into_iter()
-            .filter_map(|env_str| {
-                let parts: Vec<&str> = env_str.splitn(2, '=').collect();
-                if parts.len() == 2 {
-                    Some((parts[0].to_string(), parts[1].to_string()))
-                } else {
-                    Some((env_str, String::new()))
-                }
-            })
-            .collect();
+        Ok(env_from_inspect(&inspect))
+    }
 
-        Ok(env_vars)
+    /// Network info, environment and command line of a container from one inspect call
+    pub async fn get_container_wiring(&self, container_id: &str) -> Result<ContainerWiring> {
+        use bollard::query_parameters::InspectContainerOptions;
+        let inspect = self
+            .client()
+            .inspect_container(container_id, None::<InspectContainerOptions>)
+            .await
+            .context("Failed to inspect container")?;
+
+        Ok(ContainerWiring {
+            network_info: network_info_from_inspect(container_id, &inspect),
+            env_vars: env_from_inspect(&inspect),
+            command: command_from_inspect(&inspect),
+        })
     }
 
     pub async fn get_container_network_info(&self, container_id: &str) -> Result<eyes_devine_shared::ContainerNetworkInfo> {
         use bollard::query_parameters::InspectContainerOptions;
         let inspect = self
-            .docker
+            .client()
             .inspect_container(container_id, None::<InspectContainerOptions>)
             .await
             .context("Failed to inspect container")?;
 
-        let container_name = inspect
-            .name
-            .as_ref()
-            .map(|n| n.trim_start_matches('/').to_string())
-            .unwrap_or_else(|| container_id.to_string());
+        Ok(network_info_from_inspect(container_id, &inspect))
+    }
 
-        // Extract network information and port mappings
-        let mut networks = Vec::new();
-        let mut ip_addresses = Vec::new();
-        let mut ports = Vec::new();
-
-        if let Some(network_settings) = &inspect.network_settings {
-            // Extract networks
-            if let Some(networks_map) = &network_settings.networks {
-                for (network_name, endpoint_settings) in networks_map {
-                    let network_id = endpoint_settings
-                        .network_id
-                        .as_ref()
-                        .map(|s| s.clone())
-                        .unwrap_or_else(|| "unknown".to_string());
-                    let ip_address = endpoint_settings
-                        .ip_address
-                        .as_ref()
-                        .map(|s| s.clone())
-                        .unwrap_or_else(|| "unknown".to_string());
-                    let aliases = endpoint_settings
-                        .aliases
-                        .as_ref()
-                        .cloned()
-                        .unwrap_or_default();
+    /// Configuration and runtime details of a container (mounts, labels, restart policy,
+    /// networks with their aliases, ...) from one inspect call
+    pub async fn get_container_details(&self, container_id: &str) -> Result<ContainerFullDetails> {
+        use bollard::query_parameters::InspectContainerOptions;
+        let inspect = self
+            .client()
+            .inspect_container(container_id, None::<InspectContainerOptions>)
+            .await
+            .context("Failed to inspect container")?;
 
-                    if ip_address != "unknown" {
-                        ip_addresses.push(ip_address.clone());
-                    }
+        Ok(details_from_inspect(container_id, inspect))
+    }
 
-                    networks.push(eyes_devine_shared::NetworkInfo {
-                        network_name: network_name.clone(),
-                        network_id,
-                        ip_address,
-                        aliases,
-                    });
-                }
-            }
+    /// Build a "died" event for a container that has stopped, using the exit code and
+    /// finish time from inspect. Falls back to parsing the status string ("Exited (137) ...")
+    /// when the container can no longer be inspected (e.g. it was removed)
+    pub async fn get_container_exit_event(
+        &self,
+        container_id: &str,
+        container_name: &str,
+        status: &str,
+    ) -> eyes_devine_shared::ContainerEvent {
+        use bollard::query_parameters::InspectContainerOptions;
 
-            // Extract port mappings
-            if let Some(ports_map) = &network_settings.ports {
-                for (container_port_str, host_bindings) in ports_map {
-                    // Parse container port (format: "80/tcp" or "80/udp")
-                    let parts: Vec<&str> = container_port_str.split('/').collect();
-                    if parts.len() == 2 {
-                        if let Ok(container_port) = parts[0].parse::<u16>() {
-                            let protocol = parts[1].to_string();
-                            let host_port = host_bindings
-                                .as_ref()
-                                .and_then(|bindings| bindings.first())
-                                .and_then(|binding| binding.host_port.as_ref())
-                                .and_then(|port_str| port_str.parse::<u16>().ok());
-
-                            ports.push(eyes_devine_shared::PortMapping {
-                                container_port,
-                                host_port,
-                                protocol,
-                            });
-                        }
-                    }
-                }
+        let state = match self
+            .client()
+            .inspect_container(container_id, None::<InspectContainerOptions>)
+            .await
+        {
+            Ok(inspect) => inspect.state,
+            Err(e) => {
+                log::debug!("Failed to inspect exited container {}: {}", container_id, e);
+                None
             }
-        }
+        };
 
-        Ok(eyes_devine_shared::ContainerNetworkInfo {
+        let exit_code = state
+            .as_ref()
+            .and_then(|s| s.exit_code)
+            .or_else(|| parse_exit_code_from_status(status));
+        let oom_killed = state.as_ref().and_then(|s| s.oom_killed);
+        // Docker reports "0001-01-01T00:00:00Z" for containers that never finished
+        let finished_at = state
+            .as_ref()
+            .and_then(|s| s.finished_at.as_deref())
+            .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+            .filter(|dt| dt.timestamp() > 0);
+
+        eyes_devine_shared::ContainerEvent {
             container_id: container_id.to_string(),
-            container_name,
-            networks,
-            ports,
-            ip_addresses,
-        })
+            container_name: container_name.to_string(),
+            event_type: "died".to_string(),
+            exit_code,
+            oom_killed,
+            finished_at,
+            timestamp: finished_at.unwrap_or_else(Utc::now),
+        }
     }
 
     pub async fn get_image_info(&self, image_id: &str) -> Result<Option<eyes_devine_shared::ImageInfo>> {
         let inspect = match self
-            .docker
+            .client()
             .inspect_image(image_id)
             .await
         {
@@ -477,8 +998,7 @@ impl DockerService {
         let mut container_details = Vec::new();
 
         for container in &containers {
-            let is_running = container.status.to_lowercase().contains("up") 
-                || container.status.to_lowercase().contains("running");
+            let is_running = container.is_running();
             
             if is_running {
                 containers_up += 1;
@@ -491,35 +1011,11 @@ impl DockerService {
                 self.get_container_stats(&container.id).await.unwrap_or_else(|e| {
                     log::warn!("Failed to get stats for container {}: {}", container.id, e);
                     // Return empty stats if we can't get them
-                    eyes_devine_shared::ContainerStats {
-                        container_id: container.id.clone(),
-                        container_name: container.name.clone(),
-                        cpu_usage_percent: 0.0,
-                        memory_usage_bytes: 0,
-                        memory_limit_bytes: 0,
-                        memory_usage_percent: 0.0,
-                        network_rx_bytes: 0,
-                        network_tx_bytes: 0,
-                        block_read_bytes: 0,
-                        block_write_bytes: 0,
-                        timestamp: chrono::Utc::now(),
-                    }
+                    eyes_devine_shared::ContainerStats::empty(container.id.clone(), container.name.clone(), chrono::Utc::now())
                 })
             } else {
                 // Return empty stats for stopped containers
-                eyes_devine_shared::ContainerStats {
-                    container_id: container.id.clone(),
-                    container_name: container.name.clone(),
-                    cpu_usage_percent: 0.0,
-                    memory_usage_bytes: 0,
-                    memory_limit_bytes: 0,
-                    memory_usage_percent: 0.0,
-                    network_rx_bytes: 0,
-                    network_tx_bytes: 0,
-                    block_read_bytes: 0,
-                    block_write_bytes: 0,
-                    timestamp: chrono::Utc::now(),
-                }
+                eyes_devine_shared::ContainerStats::empty(container.id.clone(), container.name.clone(), chrono::Utc::now())
             };
 
             // Get environment variables
@@ -559,3 +1055,649 @@ impl DockerService {
     }
 }
 
+/// Parse a Docker port spec such as "80/tcp" or "53/udp" into (port, protocol)
+fn parse_port_spec(spec: &str) -> Option<(u16, String)> {
+    let (port, protocol) = spec.split_once('/')?;
+    Some((port.parse().ok()?, protocol.to_string()))
+}
+
+/// Canonical text form of an IP address (e.g. "FD00:0:0::2" -> "fd00::2") so addresses
+/// reported by Docker compare equal to those parsed from packets; unparseable input is kept as-is
+fn normalize_ip(ip: &str) -> String {
+    ip.parse::<std::net::IpAddr>()
+        .map(|addr| addr.to_string())
+        .unwrap_or_else(|_| ip.to_string())
+}
+
+/// Parse the exit code out of a Docker status string such as "Exited (137) 5 minutes ago"
+fn parse_exit_code_from_status(status: &str) -> Option<i64> {
+    let rest = status.trim().strip_prefix("Exited (")?;
+    let (code, _) = rest.split_once(')')?;
+    code.parse().ok()
+}
+
+/// Parse the health check status out of a Docker status string such as "Up 2 hours (healthy)"
+/// or "Up 5 seconds (health: starting)"; None for containers without a health check
+fn parse_health_from_status(status: &str) -> Option<ContainerHealth> {
+    let (_, rest) = status.rsplit_once('(')?;
+    let health = rest.strip_suffix(')')?;
+    ContainerHealth::parse(health.strip_prefix("health: ").unwrap_or(health))
+}
+
+/// Make a raw log line safe to return: ANSI escapes (colors, cursor movement) are removed,
+/// binary / non-UTF-8 content is replaced with a placeholder and overly long lines are
+/// truncated with a marker
+fn sanitize_log_line(raw: &[u8], max_len: usize) -> String {
+    let line = match std::str::from_utf8(raw).map(strip_ansi_escapes) {
+        // Allow tabs and newlines; any other control character left once the escapes are
+        // gone means the container is writing binary to stdout/stderr
+        Ok(line) if !line.chars().any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r')) => line,
+        _ => return format!("[binary data: {} bytes]", raw.len()),
+    };
+
+    match line.char_indices().nth(max_len) {
+        Some((cut, _)) => format!("{}… [truncated {} bytes]", &line[..cut], line.len() - cut),
+        None => line.to_string(),
+    }
+}
+
+/// `line` without ANSI escape sequences: CSI (`ESC [ ... m` colors and the like), OSC
+/// (`ESC ] ...` up to BEL or `ESC \`) and two-character `ESC x` sequences
+fn strip_ansi_escapes(line: &str) -> String {
+    let mut stripped = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            stripped.push(c);
+            continue;
+        }
+        match chars.next() {
+            // Parameters and intermediates up to a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    stripped
+}
+
+/// Stream name and sanitized line of one log frame. The stream comes from the frame header
+/// Docker adds to non-TTY output; TTY containers have a single combined stream, reported as stdout
+fn log_output_line(output: LogOutput, max_len: usize) -> (&'static str, String) {
+    let stream = match output {
+        LogOutput::StdErr { .. } => "stderr",
+        _ => "stdout",
+    };
+    (stream, sanitize_log_line(&output.into_bytes(), max_len))
+}
+
+/// Mounts, labels, restart policy, command, networks and ports of a container from its inspect response
+fn details_from_inspect(container_id: &str, inspect: bollard::models::ContainerInspectResponse) -> ContainerFullDetails {
+    let network_info = network_info_from_inspect(container_id, &inspect);
+    let config = inspect.config.clone().unwrap_or_default();
+    let host_config = inspect.host_config.clone().unwrap_or_default();
+
+    let mounts = inspect
+        .mounts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|mount| ContainerMount {
+            mount_type: mount.typ.map(|t| t.to_string()).unwrap_or_default(),
+            name: mount.name.filter(|name| !name.is_empty()),
+            source: mount.source.unwrap_or_default(),
+            destination: mount.destination.unwrap_or_default(),
+            mode: mount.mode.unwrap_or_default(),
+            read_write: mount.rw.unwrap_or(true),
+        })
+        .collect();
+
+    let restart_policy = host_config.restart_policy.unwrap_or_default();
+    let restart_policy = ContainerRestartPolicy {
+        name: restart_policy
+            .name
+            .map(|name| name.to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "no".to_string()),
+        maximum_retry_count: restart_policy.maximum_retry_count,
+    };
+
+    ContainerFullDetails {
+        id: inspect.id.unwrap_or_else(|| container_id.to_string()),
+        name: network_info.container_name,
+        image: config.image.unwrap_or_default(),
+        image_id: inspect.image.unwrap_or_default(),
+        created: inspect
+            .created
+            .as_deref()
+            .and_then(|created| chrono::DateTime::parse_from_rfc3339(created).ok())
+            .map(|dt| dt.with_timezone(&Utc)),
+        state: inspect.state.as_ref().and_then(to_container_state),
+        restart_count: inspect.restart_count,
+        command: config.cmd.unwrap_or_default(),
+        entrypoint: config.entrypoint.unwrap_or_default(),
+        working_dir: config.working_dir.filter(|dir| !dir.is_empty()),
+        user: config.user.filter(|user| !user.is_empty()),
+        labels: config.labels.unwrap_or_default().into_iter().collect(),
+        mounts,
+        restart_policy,
+        network_mode: network_info.network_mode,
+        networks: network_info.networks,
+        ports: network_info.ports,
+    }
+}
+
+/// Rows of `docker top` output, located by column title: `ps aux` titles on Linux, with `-ef`
+/// style and Windows names as fallbacks. CPU and memory are only reported from the `%CPU` and
+/// `%MEM` columns; `-ef`'s `C` is a scheduling figure, not a percentage
+fn processes_from_top(titles: &[String], rows: Vec<Vec<String>>) -> Vec<ContainerProcess> {
+    let column = |names: &[&str]| titles.iter().position(|title| names.contains(&title.as_str()));
+    let pid_column = column(&["PID"]);
+    let user_column = column(&["USER", "UID"]);
+    let cpu_column = column(&["%CPU"]);
+    let memory_column = column(&["%MEM"]);
+    let command_column = column(&["COMMAND", "CMD", "Name"]);
+
+    rows.into_iter()
+        .map(|row| {
+            let field = |index: Option<usize>| index.and_then(|i| row.get(i)).map(|value| value.trim());
+            ContainerProcess {
+                pid: field(pid_column).and_then(|pid| pid.parse().ok()).unwrap_or(0),
+                user: field(user_column).unwrap_or_default().to_string(),
+                cpu_percent: field(cpu_column).and_then(|cpu| cpu.parse().ok()),
+                memory_percent: field(memory_column).and_then(|memory| memory.parse().ok()),
+                command: field(command_column).unwrap_or_default().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Current process count and limit (0 when unlimited or not reported). cgroup v2 reports an
+/// unlimited pids.max as the largest value rather than 0
+fn pids_from_stats(pids: Option<&bollard::models::ContainerPidsStats>) -> (u64, u64) {
+    let current = pids.and_then(|p| p.current).unwrap_or(0);
+    let limit = pids
+        .and_then(|p| p.limit)
+        .filter(|&limit| limit <= i64::MAX as u64)
+        .unwrap_or(0);
+    (current, limit)
+}
+
+/// `DOCKER_CERT_PATH` if set, else `$HOME/.docker`
+fn docker_cert_dir(cert_path: Option<String>, home: Option<String>) -> Result<PathBuf> {
+    match (cert_path.filter(|path| !path.is_empty()), home) {
+        (Some(path), _) => Ok(PathBuf::from(path)),
+        (None, Some(home)) => Ok(Path::new(&home).join(".docker")),
+        (None, None) => anyhow::bail!("DOCKER_TLS_VERIFY is set but neither DOCKER_CERT_PATH nor HOME is"),
+    }
+}
+
+/// `ca.pem`, `cert.pem` and `key.pem` in `cert_dir`, failing with the name of any unreadable file
+fn docker_tls_files(cert_dir: &Path) -> Result<[PathBuf; 3]> {
+    let files = ["ca.pem", "cert.pem", "key.pem"].map(|name| cert_dir.join(name));
+    for path in &files {
+        std::fs::File::open(path).with_context(|| format!("Cannot read Docker TLS file {}", path.display()))?;
+    }
+    Ok(files)
+}
+
+/// `KEY=value` environment entries from an inspect response, split into pairs
+fn env_from_inspect(inspect: &bollard::models::ContainerInspectResponse) -> Vec<(String, String)> {
+    inspect
+        .config
+        .as_ref()
+        .and_then(|config| config.env.as_ref())
+        .into_iter()
+        .flatten()
+        .map(|env_str| match env_str.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => (env_str.clone(), String::new()),
+        })
+        .collect()
+}
+
+/// Process command line (entrypoint/command as resolved by Docker: `Path` followed by `Args`)
+fn command_from_inspect(inspect: &bollard::models::ContainerInspectResponse) -> Vec<String> {
+    inspect
+        .path
+        .iter()
+        .filter(|path| !path.is_empty())
+        .chain(inspect.args.iter().flatten())
+        .cloned()
+        .collect()
+}
+
+/// Networks (with aliases), IP addresses, port mappings and network mode from an inspect response
+fn network_info_from_inspect(
+    container_id: &str,
+    inspect: &bollard::models::ContainerInspectResponse,
+) -> eyes_devine_shared::ContainerNetworkInfo {
+    let container_name = inspect
+        .name
+        .as_ref()
+        .map(|n| n.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| container_id.to_string());
+
+    // Extract network information and port mappings
+    let mut networks = Vec::new();
+    let mut ip_addresses = Vec::new();
+    let mut ports = Vec::new();
+
+    if let Some(network_settings) = &inspect.network_settings {
+        // Extract networks
+        if let Some(networks_map) = &network_settings.networks {
+            for (network_name, endpoint_settings) in networks_map {
+                let network_id = endpoint_settings
+                    .network_id
+                    .clone()
+                    .unwrap_or_else(|| "unknown".to_string());
+                let ip_address = endpoint_settings
+                    .ip_address
+                    .clone()
+                    .unwrap_or_else(|| "unknown".to_string());
+                let aliases = endpoint_settings
+                    .aliases
+                    .as_ref()
+                    .cloned()
+                    .unwrap_or_default();
+
+                if ip_address != "unknown" {
+                    ip_addresses.push(normalize_ip(&ip_address));
+                }
+                // Networks created with --ipv6 also assign a global IPv6 address
+                if let Some(ipv6_address) = endpoint_settings
+                    .global_ipv6_address
+                    .as_deref()
+                    .filter(|ip| !ip.is_empty())
+                {
+                    ip_addresses.push(normalize_ip(ipv6_address));
+                }
+
+                networks.push(eyes_devine_shared::NetworkInfo {
+                    network_name: network_name.clone(),
+                    network_id,
+                    ip_address,
+                    aliases,
+                });
+            }
+        }
+
+        // Extract port mappings
+        if let Some(ports_map) = &network_settings.ports {
+            for (container_port_str, host_bindings) in ports_map {
+                if let Some((container_port, protocol)) = parse_port_spec(container_port_str) {
+                    let binding = host_bindings
+                        .as_ref()
+                        .and_then(|bindings| bindings.first());
+                    let host_port = binding
+                        .and_then(|binding| binding.host_port.as_ref())
+                        .and_then(|port_str| port_str.parse::<u16>().ok());
+                    let host_ip = binding
+                        .and_then(|binding| binding.host_ip.clone())
+                        .filter(|ip| !ip.is_empty());
+
+                    ports.push(eyes_devine_shared::PortMapping {
+                        container_port,
+                        host_port,
+                        protocol,
+                        host_ip,
+                        published: host_port.is_some(),
+                    });
+                }
+            }
+        }
+    }
+
+    let network_mode = inspect
+        .host_config
+        .as_ref()
+        .and_then(|hc| hc.network_mode.clone())
+        .unwrap_or_else(|| "default".to_string());
+
+    // In host network mode Docker reports no port bindings; every exposed port
+    // is reachable directly on the host under the same number
+    if network_mode == "host"
+        && ports.is_empty()
+        && let Some(exposed) = inspect.config.as_ref().and_then(|c| c.exposed_ports.as_ref())
+    {
+        for container_port_str in exposed.keys() {
+            if let Some((container_port, protocol)) = parse_port_spec(container_port_str) {
+                ports.push(eyes_devine_shared::PortMapping {
+                    container_port,
+                    host_port: Some(container_port),
+                    protocol,
+                    host_ip: None,
+                    published: true,
+                });
+            }
+        }
+    }
+
+    ports.sort_by(|a, b| {
+        a.container_port
+            .cmp(&b.container_port)
+            .then_with(|| a.protocol.cmp(&b.protocol))
+    });
+
+    eyes_devine_shared::ContainerNetworkInfo {
+        container_id: container_id.to_string(),
+        container_name,
+        networks,
+        ports,
+        ip_addresses,
+        network_mode,
+    }
+}
+
+/// Convert inspect's `State` into the shared structured state
+fn to_container_state(state: &bollard::models::ContainerState) -> Option<ContainerState> {
+    let run_state = ContainerRunState::parse(state.status?.as_ref())?;
+
+    let exit_code = match run_state {
+        ContainerRunState::Exited | ContainerRunState::Dead => state.exit_code.map(|code| code as i32),
+        _ => None,
+    };
+
+    let health = state
+        .health
+        .as_ref()
+        .and_then(|health| health.status)
+        .and_then(|status| ContainerHealth::parse(status.as_ref()));
+
+    Some(ContainerState {
+        state: run_state,
+        exit_code,
+        health,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_lines_are_tagged_with_their_stream() {
+        let stdout = LogOutput::StdOut { message: "2024-12-01T10:00:00.000000000Z listening on :8080\n".into() };
+        let stderr = LogOutput::StdErr { message: "2024-12-01T10:00:01.000000000Z panic: stdout closed\n".into() };
+
+        assert_eq!(
+            log_output_line(stdout, 1000),
+            ("stdout", "2024-12-01T10:00:00.000000000Z listening on :8080\n".to_string())
+        );
+        // Mentioning another stream in the text doesn't change the tag
+        assert_eq!(
+            log_output_line(stderr, 1000),
+            ("stderr", "2024-12-01T10:00:01.000000000Z panic: stdout closed\n".to_string())
+        );
+    }
+
+    #[test]
+    fn tty_output_is_reported_as_stdout() {
+        let console = LogOutput::Console { message: "stderr text from a tty\n".into() };
+        assert_eq!(log_output_line(console, 1000).0, "stdout");
+    }
+
+    #[test]
+    fn classifies_docker_errors() {
+        use bollard::errors::Error as DockerError;
+
+        let not_found = anyhow::Error::from(DockerError::DockerResponseServerError {
+            status_code: 404,
+            message: "No such container: web".to_string(),
+        });
+        assert!(is_docker_not_found(&not_found));
+        assert!(!is_docker_unreachable(&not_found));
+
+        let server_error = anyhow::Error::from(DockerError::DockerResponseServerError {
+            status_code: 500,
+            message: "internal error".to_string(),
+        });
+        assert!(!is_docker_not_found(&server_error));
+        assert!(!is_docker_unreachable(&server_error));
+
+        let refused = anyhow::Error::from(DockerError::IOError {
+            err: std::io::Error::from(std::io::ErrorKind::ConnectionRefused),
+        })
+        .context("Failed to list containers");
+        assert!(is_docker_unreachable(&refused));
+        assert!(!is_docker_not_found(&refused));
+
+        assert!(is_docker_unreachable(&anyhow::Error::from(DockerError::RequestTimeoutError)));
+        assert!(is_docker_unreachable(&anyhow::Error::from(DockerError::SocketNotFoundError(
+            "/var/run/docker.sock".to_string()
+        ))));
+        assert!(!is_docker_unreachable(&anyhow::anyhow!("Container has no network settings")));
+    }
+
+    #[test]
+    fn tls_certificates_default_to_the_home_docker_dir() {
+        let dir = |cert_path: Option<&str>, home: Option<&str>| {
+            docker_cert_dir(cert_path.map(str::to_string), home.map(str::to_string))
+        };
+        assert_eq!(dir(Some("/certs"), Some("/root")).unwrap(), PathBuf::from("/certs"));
+        assert_eq!(dir(Some(""), Some("/root")).unwrap(), PathBuf::from("/root/.docker"));
+        assert_eq!(dir(None, Some("/root")).unwrap(), PathBuf::from("/root/.docker"));
+        assert!(dir(None, None).is_err());
+    }
+
+    #[test]
+    fn missing_tls_files_are_named() {
+        let cert_dir = std::env::temp_dir().join(format!("devine-eyes-docker-tls-{}", std::process::id()));
+        std::fs::create_dir_all(&cert_dir).unwrap();
+        for name in ["ca.pem", "cert.pem"] {
+            std::fs::write(cert_dir.join(name), "").unwrap();
+        }
+
+        let error = docker_tls_files(&cert_dir).unwrap_err().to_string();
+        assert!(error.contains("key.pem"), "{}", error);
+
+        std::fs::write(cert_dir.join("key.pem"), "").unwrap();
+        assert_eq!(
+            docker_tls_files(&cert_dir).unwrap(),
+            ["ca.pem", "cert.pem", "key.pem"].map(|name| cert_dir.join(name))
+        );
+
+        std::fs::remove_dir_all(&cert_dir).unwrap();
+    }
+
+    #[test]
+    fn details_come_from_inspect() {
+        use bollard::models::{
+            ContainerConfig, ContainerInspectResponse, HostConfig, MountPoint, MountPointTypeEnum, RestartPolicy,
+            RestartPolicyNameEnum,
+        };
+
+        let inspect = ContainerInspectResponse {
+            id: Some("abc123".to_string()),
+            name: Some("/web".to_string()),
+            image: Some("sha256:feed".to_string()),
+            created: Some("2024-12-01T10:00:00.5Z".to_string()),
+            restart_count: Some(2),
+            config: Some(ContainerConfig {
+                image: Some("nginx:latest".to_string()),
+                cmd: Some(vec!["nginx".to_string(), "-g".to_string(), "daemon off;".to_string()]),
+                working_dir: Some(String::new()),
+                user: Some("101".to_string()),
+                labels: Some([("com.docker.compose.service".to_string(), "web".to_string())].into()),
+                ..Default::default()
+            }),
+            host_config: Some(HostConfig {
+                restart_policy: Some(RestartPolicy {
+                    name: Some(RestartPolicyNameEnum::ON_FAILURE),
+                    maximum_retry_count: Some(3),
+                }),
+                ..Default::default()
+            }),
+            mounts: Some(vec![
+                MountPoint {
+                    typ: Some(MountPointTypeEnum::VOLUME),
+                    name: Some("web-cache".to_string()),
+                    source: Some("/var/lib/docker/volumes/web-cache/_data".to_string()),
+                    destination: Some("/cache".to_string()),
+                    rw: Some(false),
+                    ..Default::default()
+                },
+                MountPoint {
+                    typ: Some(MountPointTypeEnum::BIND),
+                    name: Some(String::new()),
+                    source: Some("/srv/www".to_string()),
+                    destination: Some("/usr/share/nginx/html".to_string()),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        };
+
+        let details = details_from_inspect("abc", inspect);
+        assert_eq!((details.id.as_str(), details.name.as_str()), ("abc123", "web"));
+        assert_eq!((details.image.as_str(), details.image_id.as_str()), ("nginx:latest", "sha256:feed"));
+        assert_eq!(details.created.map(|created| created.timestamp_millis()), Some(1_733_047_200_500));
+        assert_eq!(details.restart_count, Some(2));
+        assert_eq!(details.command, ["nginx", "-g", "daemon off;"]);
+        assert!(details.entrypoint.is_empty());
+        assert_eq!(details.working_dir, None);
+        assert_eq!(details.user.as_deref(), Some("101"));
+        assert_eq!(details.labels.get("com.docker.compose.service").map(String::as_str), Some("web"));
+        assert_eq!((details.restart_policy.name.as_str(), details.restart_policy.maximum_retry_count), ("on-failure", Some(3)));
+
+        let [volume, bind] = details.mounts.as_slice() else {
+            panic!("expected two mounts, got {}", details.mounts.len());
+        };
+        assert_eq!((volume.mount_type.as_str(), volume.name.as_deref(), volume.read_write), ("volume", Some("web-cache"), false));
+        assert_eq!((bind.mount_type.as_str(), bind.name.as_deref(), bind.read_write), ("bind", None, true));
+        assert_eq!(bind.destination, "/usr/share/nginx/html");
+    }
+
+    #[test]
+    fn details_without_host_config_have_no_restart_policy() {
+        let details = details_from_inspect("abc", bollard::models::ContainerInspectResponse::default());
+        assert_eq!((details.id.as_str(), details.name.as_str()), ("abc", "abc"));
+        assert_eq!(details.restart_policy.name, "no");
+        assert!(details.mounts.is_empty() && details.labels.is_empty());
+    }
+
+    #[test]
+    fn unlimited_pids_are_reported_as_no_limit() {
+        use bollard::models::ContainerPidsStats;
+
+        let pids = |current, limit| ContainerPidsStats { current, limit };
+        assert_eq!(pids_from_stats(Some(&pids(Some(12), Some(512)))), (12, 512));
+        assert_eq!(pids_from_stats(Some(&pids(Some(12), Some(u64::MAX)))), (12, 0));
+        assert_eq!(pids_from_stats(Some(&pids(Some(3), None))), (3, 0));
+        assert_eq!(pids_from_stats(None), (0, 0));
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn ps_aux_output_gives_cpu_and_memory_percentages() {
+        let titles = strings(&["USER", "PID", "%CPU", "%MEM", "VSZ", "RSS", "TTY", "STAT", "START", "TIME", "COMMAND"]);
+        let rows = vec![
+            strings(&["root", "1", "0.5", "1.2", "1000", "200", "?", "Ss", "10:00", "0:01", "nginx: master process"]),
+            strings(&["101", "29", " 12.0 ", "3.4", "1000", "200", "?", "S", "10:00", "0:09", "nginx: worker process"]),
+        ];
+
+        let processes = processes_from_top(&titles, rows);
+        let parsed: Vec<_> = processes
+            .iter()
+            .map(|p| (p.pid, p.user.as_str(), p.cpu_percent, p.memory_percent, p.command.as_str()))
+            .collect();
+        assert_eq!(parsed, [
+            (1, "root", Some(0.5), Some(1.2), "nginx: master process"),
+            (29, "101", Some(12.0), Some(3.4), "nginx: worker process"),
+        ]);
+    }
+
+    #[test]
+    fn ps_ef_output_has_no_cpu_percentage() {
+        let titles = strings(&["UID", "PID", "PPID", "C", "STIME", "TTY", "TIME", "CMD"]);
+        let rows = vec![strings(&["root", "7", "1", "3", "10:00", "?", "00:00:01", "redis-server *:6379"])];
+
+        let processes = processes_from_top(&titles, rows);
+        assert_eq!(processes.len(), 1);
+        let process = &processes[0];
+        assert_eq!((process.pid, process.user.as_str(), process.command.as_str()), (7, "root", "redis-server *:6379"));
+        assert_eq!((process.cpu_percent, process.memory_percent), (None, None));
+    }
+
+    #[test]
+    fn short_or_unparsable_ps_rows_keep_what_they_have() {
+        let titles = strings(&["PID", "%CPU", "COMMAND"]);
+        let processes = processes_from_top(&titles, vec![strings(&["x", "n/a"])]);
+        let process = &processes[0];
+        assert_eq!((process.pid, process.cpu_percent, process.command.as_str()), (0, None, ""));
+    }
+
+    #[test]
+    fn ansi_escapes_are_removed_from_log_lines() {
+        let colored = "\u{1b}[1;32mINFO\u{1b}[0m server started\u{1b}[K";
+        assert_eq!(sanitize_log_line(colored.as_bytes(), 1000), "INFO server started");
+
+        let title = "\u{1b}]0;build\u{7}step 1\u{1b}]8;;https://example.com\u{1b}\\link\u{1b}]8;;\u{1b}\\ done\u{1b}=";
+        assert_eq!(sanitize_log_line(title.as_bytes(), 1000), "step 1link done");
+
+        // Truncation counts the visible characters
+        assert_eq!(sanitize_log_line("\u{1b}[31mabcdef\u{1b}[0m".as_bytes(), 3), "abc… [truncated 3 bytes]");
+    }
+
+    #[test]
+    fn control_characters_mark_a_line_as_binary() {
+        assert_eq!(sanitize_log_line(b"col1\tcol2\r\n", 1000), "col1\tcol2\r\n");
+        assert_eq!(sanitize_log_line(b"\x00\x01ELF\x02", 1000), "[binary data: 6 bytes]");
+        assert_eq!(sanitize_log_line(b"bell\x07", 1000), "[binary data: 5 bytes]");
+        assert_eq!(sanitize_log_line(&[0xff, 0xfe, b'a'], 1000), "[binary data: 3 bytes]");
+    }
+
+    #[test]
+    fn exit_codes_come_from_exited_statuses() {
+        let cases = [
+            ("Exited (0) 5 minutes ago", Some(0)),
+            ("Exited (137) About an hour ago", Some(137)),
+            ("  Exited (1) 2 days ago", Some(1)),
+            ("Exited (-1) 3 seconds ago", Some(-1)),
+            ("Exited (255)", Some(255)),
+            ("Up 2 hours", None),
+            ("Up 5 seconds (health: starting)", None),
+            ("Restarting (1) 10 seconds ago", None),
+            ("Created", None),
+            ("Exited () 1 minute ago", None),
+            ("Exited (oom) 1 minute ago", None),
+            ("Exited (137", None),
+            ("", None),
+        ];
+        for (status, expected) in cases {
+            assert_eq!(parse_exit_code_from_status(status), expected, "{}", status);
+        }
+    }
+
+    #[test]
+    fn health_comes_from_the_status_suffix() {
+        let cases = [
+            ("Up 2 hours (healthy)", Some(ContainerHealth::Healthy)),
+            ("Up 3 minutes (unhealthy)", Some(ContainerHealth::Unhealthy)),
+            ("Up 5 seconds (health: starting)", Some(ContainerHealth::Starting)),
+            ("Up 2 hours", None),
+            ("Up 2 minutes (Paused)", None),
+            ("Exited (0) 5 minutes ago", None),
+            ("Restarting (1) 10 seconds ago", None),
+            ("Up 2 hours (healthy", None),
+            ("", None),
+        ];
+        for (status, expected) in cases {
+            assert_eq!(parse_health_from_status(status), expected, "{}", status);
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "alerts")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub rule_id: i64,
+    pub rule_name: String,
+    pub container_id: String,
+    pub container_name: String,
+    pub metric: String,
+    #[sea_orm(column_type = "Double")]
+    pub value: f64,
+    #[sea_orm(column_type = "Double")]
+    pub threshold: f64,
+    pub state: String,
+    pub timestamp: DateTimeWithTimeZone,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,20 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "container_disk_usage")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub container_id: String,
+    pub container_name: String,
+    pub size_rw_bytes: Option<i64>,
+    pub size_root_fs_bytes: Option<i64>,
+    pub timestamp: DateTimeWithTimeZone,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -11,6 +11,13 @@ pub struct Model {
     pub image: String,
     pub status: String,
     pub created: Option<DateTimeWithTimeZone>,
+    pub started_at: Option<DateTimeWithTimeZone>,
+    pub restart_count: Option<i64>,
+    pub state: Option<String>,
+    pub exit_code: Option<i32>,
+    pub health: Option<String>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub labels: Option<Json>,
     pub collected_at: DateTimeWithTimeZone,
 }
 
@@ -16,6 +16,10 @@ pub struct Model {
     pub memory_limit_bytes: i64,
     #[sea_orm(column_type = "Double")]
     pub memory_usage_percent: f64,
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub memory_cache_bytes: Option<i64>,
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub memory_swap_bytes: Option<i64>,
     #[sea_orm(column_type = "BigInteger")]
     pub network_rx_bytes: i64,
     #[sea_orm(column_type = "BigInteger")]
@@ -24,6 +28,12 @@ pub struct Model {
     pub block_read_bytes: i64,
     #[sea_orm(column_type = "BigInteger")]
     pub block_write_bytes: i64,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub per_cpu_usage_percent: Option<Json>,
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub pids_current: Option<i64>,
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub pids_limit: Option<i64>,
     pub timestamp: DateTimeWithTimeZone,
     pub created_at: DateTimeWithTimeZone,
 }
@@ -14,6 +14,8 @@ pub struct Model {
     pub http_status: i16,
     #[sea_orm(column_type = "Double")]
     pub response_time_ms: f64,
+    pub headers: Option<Json>,
+    pub client_container_id: Option<String>,
     pub timestamp: DateTimeWithTimeZone,
     pub created_at: DateTimeWithTimeZone,
 }
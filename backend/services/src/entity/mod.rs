@@ -5,3 +5,11 @@ pub mod docker_images;
 pub mod image_versions;
 pub mod http_requests;
 
+pub mod container_events;
+pub mod unmatched_http_requests;
+pub mod service_map_snapshots;
+pub mod total_stats;
+pub mod alert_rules;
+pub mod alerts;
+pub mod container_disk_usage;
+pub mod container_status_history;
@@ -0,0 +1,28 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "total_stats")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub total_containers: i32,
+    pub running_containers: i32,
+    #[sea_orm(column_type = "Double")]
+    pub total_cpu_usage_percent: f64,
+    pub total_memory_usage_bytes: i64,
+    pub total_memory_limit_bytes: i64,
+    #[sea_orm(column_type = "Double")]
+    pub total_memory_usage_percent: f64,
+    pub total_network_rx_bytes: i64,
+    pub total_network_tx_bytes: i64,
+    pub total_block_read_bytes: i64,
+    pub total_block_write_bytes: i64,
+    pub timestamp: DateTimeWithTimeZone,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
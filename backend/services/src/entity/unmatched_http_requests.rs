@@ -0,0 +1,27 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "unmatched_http_requests")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub src_ip: String,
+    pub src_port: i32,
+    pub dst_ip: String,
+    pub dst_port: i32,
+    pub method: String,
+    pub endpoint: String,
+    #[sea_orm(column_type = "SmallInteger")]
+    pub http_status: Option<i16>,
+    #[sea_orm(column_type = "Double")]
+    pub response_time_ms: Option<f64>,
+    pub reason: String,
+    pub timestamp: DateTimeWithTimeZone,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -6,14 +6,22 @@ pub mod query_service;
 pub mod cached_query_service;
 pub mod service_map_service;
 pub mod network_monitor_service;
+pub mod capture_rules;
+pub mod chunked_decoder;
+pub mod alert_service;
+pub mod notification_service;
+pub mod readiness;
 
 pub use docker_service::DockerService;
 pub use cache_service::CacheService;
 pub use database::create_connection;
-pub use query_service::QueryService;
+pub use query_service::{QueryService, ContainerFilter, ContainerSelector, KeysetCursor, ObservedHttpCall, TopMetric};
 pub use cached_query_service::CachedQueryService;
 pub use service_map_service::ServiceMapService;
 pub use network_monitor_service::NetworkMonitorService;
+pub use capture_rules::{CaptureRules, CaptureRulesConfig};
+pub use alert_service::AlertService;
+pub use notification_service::NotificationService;
 
 // Re-export entities for convenience
 pub use entity::container_stats;
@@ -22,4 +30,12 @@ pub use entity::container_info;
 pub use entity::docker_images;
 pub use entity::image_versions;
 pub use entity::http_requests;
+pub use entity::container_events;
+pub use entity::unmatched_http_requests;
+pub use entity::service_map_snapshots;
+pub use entity::total_stats;
+pub use entity::alert_rules;
+pub use entity::alerts;
+pub use entity::container_disk_usage;
+pub use entity::container_status_history;
 
@@ -1,10 +1,17 @@
+// Most of the packet handling below is only reachable from the pcap capture loop
+#![cfg_attr(not(feature = "network-capture"), allow(dead_code))]
+
 use anyhow::Result;
-use eyes_devine_shared::HttpRequest;
-use std::collections::HashMap;
+use eyes_devine_shared::{CaptureHealth, CaptureStats, HttpRequest};
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::RwLock;
 use chrono::{Utc, DateTime, FixedOffset};
 use sea_orm::{DatabaseConnection, ActiveValue::Set, ActiveModelTrait};
+use crate::capture_rules::CaptureRules;
+use crate::chunked_decoder::ChunkedDecoder;
 use crate::docker_service::DockerService;
 
 /// Network monitoring service that captures HTTP requests from network traffic
@@ -16,17 +23,300 @@ pub struct NetworkMonitorService {
     /// Key: container_id, Value: Vec<HttpRequest>
     captured_requests: Arc<RwLock<HashMap<String, Vec<HttpRequest>>>>,
     /// Track TCP connections to match requests with responses
-    /// Key: connection_id (format: "src_ip:src_port-dst_ip:dst_port"), Value: PendingRequest
-    pending_requests: Arc<RwLock<HashMap<String, PendingRequest>>>,
+    /// Key: connection_id (format: "src_ip:src_port-dst_ip:dst_port", IPv6 bracketed) plus request end sequence number
+    pending_requests: Arc<RwLock<HashMap<PendingKey, PendingRequest>>>,
+    /// Capture port list and endpoint normalization rules, swapped atomically on reload
+    capture_rules: Arc<std::sync::RwLock<Arc<CaptureRules>>>,
+    /// Persist requests that could not be attributed to a container (diagnostics)
+    store_unmatched: bool,
+    /// Follow `Transfer-Encoding: chunked` response bodies to their terminating chunk and
+    /// time the request to the end of the response instead of its first segment
+    track_chunked_responses: bool,
+    /// Chunked responses whose body is still arriving. Key: connection_id
+    in_flight_responses: Arc<RwLock<HashMap<String, InFlightResponse>>>,
+    /// HTTP messages whose headers span several TCP segments, per connection direction
+    reassembly_buffers: Arc<RwLock<HashMap<FlowKey, ReassemblyBuffer>>>,
+    /// Re-initialize a capture that saw traffic and then went silent this long (None = never)
+    capture_stall_timeout: Option<std::time::Duration>,
+    /// Interfaces being captured and watchdog restarts, reported on the worker status endpoint
+    capture_health: Arc<std::sync::RwLock<CaptureHealth>>,
+    /// Running totals behind [`NetworkMonitorService::get_capture_stats`]
+    capture_counters: Arc<CaptureCounters>,
+    /// Container IPs used to attribute packets, shared by all capture tasks
+    container_ip_cache: Arc<RwLock<ContainerIpCache>>,
+    /// Age after which the container IP cache is rebuilt from Docker
+    container_ip_cache_ttl: std::time::Duration,
+    /// Lowercase names of request/response headers stored with each captured request
+    captured_headers: Vec<String>,
+}
+
+/// Most header names accepted by [`NetworkMonitorService::with_captured_headers`]
+pub const MAX_CAPTURED_HEADER_NAMES: usize = 16;
+
+/// Credential-carrying headers that are never stored, even when allowlisted
+const NEVER_CAPTURED_HEADERS: [&str; 4] = ["authorization", "proxy-authorization", "cookie", "set-cookie"];
+
+/// Budget for captured header names plus values per request; headers past it are dropped
+const MAX_CAPTURED_HEADERS_BYTES: usize = 2048;
+
+/// Default age after which container IPs are re-read from Docker
+pub const DEFAULT_CONTAINER_IP_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Shortest accepted container IP cache TTL; a zero TTL would list containers on every packet
+pub const MIN_CONTAINER_IP_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Pause between a capture stopping and the watchdog re-initializing it
+const CAPTURE_RESTART_DELAY_SECS: u64 = 5;
+
+/// Largest HTTP header block buffered across segments before the message is given up on
+const MAX_REASSEMBLY_BYTES: usize = 64 * 1024;
+
+/// How long a partially received HTTP header block is kept waiting for its next segment
+const REASSEMBLY_TIMEOUT_SECS: i64 = 10;
+
+/// How long a chunked response may go without a new segment before it is recorded as-is
+const IN_FLIGHT_RESPONSE_TIMEOUT_SECS: i64 = 30;
+
+/// How long a request waits for its response before it is dropped
+const PENDING_REQUEST_TIMEOUT_SECS: i64 = 30;
+
+/// How often stalled chunked responses and unanswered requests are expired when no new
+/// traffic triggers it
+const EXPIRY_SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Largest gap between the end of a request's first segment and the ack of its response.
+/// Covers request bodies split across segments we didn't parse
+const MAX_REQUEST_SEQ_SPAN: u32 = 16 * 1024 * 1024;
+
+/// Key of a pending request. Docker NAT can reuse an IP:port tuple for a new connection,
+/// so the tuple alone could pair a response with a stale request from an earlier one; the
+/// TCP sequence number just past the request payload tells the two apart.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PendingKey {
+    connection_id: String,
+    request_end_seq: u32,
 }
 
 /// Pending HTTP request waiting for response
 struct PendingRequest {
     container_id: String,
     container_name: String,
+    /// Container that sent the request; None when the client isn't a container
+    client_container_id: Option<String>,
     method: String,
     endpoint: String,
     request_timestamp: DateTime<Utc>,
+    /// Addresses of the request packet (client -> server)
+    endpoints: PacketEndpoints,
+    /// Allowlisted request headers, later joined by those of the response
+    headers: BTreeMap<String, String>,
+}
+
+/// Body framing state of a chunked response, and where its next segment should come from
+struct ChunkedBody {
+    decoder: ChunkedDecoder,
+    /// TCP sequence number expected for the next body segment
+    next_seq: u32,
+    /// Server side of the connection (the response's source)
+    responder_ip: String,
+    responder_port: u16,
+}
+
+/// Matched request whose chunked response body is still arriving
+struct InFlightResponse {
+    pending: PendingRequest,
+    container_id: String,
+    container_name: String,
+    status_code: Option<u16>,
+    body: ChunkedBody,
+    last_segment_at: DateTime<Utc>,
+}
+
+/// One direction of a TCP connection
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FlowKey {
+    connection_id: String,
+    src_ip: String,
+    src_port: u16,
+}
+
+/// Capture counters shared by all capture tasks; see [`CaptureStats`] for their meaning
+#[derive(Default)]
+struct CaptureCounters {
+    packets_seen: AtomicU64,
+    tcp_packets: AtomicU64,
+    http_packets: AtomicU64,
+    http_messages_parsed: AtomicU64,
+    requests_matched: AtomicU64,
+    requests_unmatched: AtomicU64,
+    responses_without_request: AtomicU64,
+    requests_expired: AtomicU64,
+}
+
+impl CaptureCounters {
+    fn add(counter: &AtomicU64, n: u64) {
+        counter.fetch_add(n, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> CaptureStats {
+        CaptureStats {
+            packets_seen: self.packets_seen.load(Ordering::Relaxed),
+            tcp_packets: self.tcp_packets.load(Ordering::Relaxed),
+            http_packets: self.http_packets.load(Ordering::Relaxed),
+            http_messages_parsed: self.http_messages_parsed.load(Ordering::Relaxed),
+            requests_matched: self.requests_matched.load(Ordering::Relaxed),
+            requests_unmatched: self.requests_unmatched.load(Ordering::Relaxed),
+            responses_without_request: self.responses_without_request.load(Ordering::Relaxed),
+            requests_expired: self.requests_expired.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Container IP to container ID, rebuilt as a whole when it goes stale
+#[derive(Default)]
+struct ContainerIpCache {
+    container_by_ip: HashMap<String, String>,
+    /// Last refresh attempt (successful or not); None before the first one
+    refreshed_at: Option<std::time::Instant>,
+}
+
+impl ContainerIpCache {
+    fn is_fresh(&self, ttl: std::time::Duration) -> bool {
+        self.refreshed_at.is_some_and(|at| at.elapsed() < ttl)
+    }
+
+    fn lookup(&self, src_ip: &str, dst_ip: &str) -> PacketContainers {
+        PacketContainers {
+            src: self.container_by_ip.get(src_ip).cloned(),
+            dst: self.container_by_ip.get(dst_ip).cloned(),
+        }
+    }
+}
+
+/// Which way a packet travels, judged by container addresses alone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PacketDirection {
+    /// Sent to a container, i.e. a request toward it (or the response to its outbound call)
+    ToContainer,
+    /// Sent by a container to an address no container owns
+    FromContainer,
+    /// Neither address belongs to a container
+    Unknown,
+}
+
+/// Containers owning a packet's source and destination addresses
+#[derive(Debug, Default)]
+struct PacketContainers {
+    src: Option<String>,
+    dst: Option<String>,
+}
+
+impl PacketContainers {
+    fn direction(&self) -> PacketDirection {
+        match (&self.src, &self.dst) {
+            (_, Some(_)) => PacketDirection::ToContainer,
+            (Some(_), None) => PacketDirection::FromContainer,
+            (None, None) => PacketDirection::Unknown,
+        }
+    }
+
+    /// Container a request is recorded for: the one it is sent to, or the sender when
+    /// it calls out to something that isn't a container
+    fn request_owner(&self) -> Option<&String> {
+        self.dst.as_ref().or(self.src.as_ref())
+    }
+
+    /// Container that sent a request, stored alongside its owner so calls between two
+    /// containers keep both ends
+    fn request_client(&self) -> Option<&String> {
+        self.src.as_ref()
+    }
+
+    /// Container a response is recorded for: the one sending it, or the receiver when
+    /// it answers that container's outbound call
+    fn response_owner(&self) -> Option<&String> {
+        self.src.as_ref().or(self.dst.as_ref())
+    }
+}
+
+/// Segments of an HTTP message whose headers didn't fit in its first segment
+struct ReassemblyBuffer {
+    /// Sequence number of the message's first byte
+    start_seq: u32,
+    /// Segment payloads by offset from `start_seq` (may arrive out of order)
+    segments: BTreeMap<u32, Vec<u8>>,
+    buffered_bytes: usize,
+    last_update: DateTime<Utc>,
+}
+
+impl ReassemblyBuffer {
+    fn new(start_seq: u32, payload: &[u8]) -> Self {
+        let mut segments = BTreeMap::new();
+        segments.insert(0, payload.to_vec());
+        Self {
+            start_seq,
+            segments,
+            buffered_bytes: payload.len(),
+            last_update: Utc::now(),
+        }
+    }
+
+    /// Add a segment; retransmissions and data from before the message start are ignored
+    fn insert(&mut self, seq: u32, payload: &[u8]) {
+        let offset = seq.wrapping_sub(self.start_seq);
+        if (offset as i32) < 0 || self.segments.contains_key(&offset) {
+            return;
+        }
+        self.segments.insert(offset, payload.to_vec());
+        self.buffered_bytes += payload.len();
+        self.last_update = Utc::now();
+    }
+
+    /// Bytes received without gaps from the start of the message
+    fn contiguous(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.buffered_bytes);
+        for (&offset, payload) in &self.segments {
+            let offset = offset as usize;
+            if offset > data.len() {
+                break;
+            }
+            // Segments may overlap when a retransmission was re-segmented
+            let overlap = data.len() - offset;
+            if overlap < payload.len() {
+                data.extend_from_slice(&payload[overlap..]);
+            }
+        }
+        data
+    }
+}
+
+/// Outcome of feeding a segment through header reassembly
+enum Reassembled {
+    /// Handle the segment on its own (complete message, or not the start of one)
+    Single,
+    /// Segment was buffered; the message's headers are not complete yet
+    Pending,
+    /// Headers are now complete; `data` holds the message from its first byte
+    Complete { start_seq: u32, data: Vec<u8> },
+}
+
+/// Source and destination of a captured packet
+#[derive(Debug, Clone)]
+struct PacketEndpoints {
+    src_ip: String,
+    src_port: u16,
+    dst_ip: String,
+    dst_port: u16,
+}
+
+/// TCP connection details and payload extracted from a captured packet
+struct PacketConnection<'a> {
+    connection_id: String,
+    endpoints: PacketEndpoints,
+    payload: &'a [u8],
+    /// TCP sequence and acknowledgment numbers
+    seq: u32,
+    ack: u32,
 }
 
 impl NetworkMonitorService {
@@ -36,6 +326,17 @@ impl NetworkMonitorService {
             db: None,
             captured_requests: Arc::new(RwLock::new(HashMap::new())),
             pending_requests: Arc::new(RwLock::new(HashMap::new())),
+            capture_rules: Arc::new(std::sync::RwLock::new(Arc::new(CaptureRules::default()))),
+            store_unmatched: false,
+            track_chunked_responses: true,
+            in_flight_responses: Arc::new(RwLock::new(HashMap::new())),
+            reassembly_buffers: Arc::new(RwLock::new(HashMap::new())),
+            capture_stall_timeout: None,
+            capture_health: Arc::new(std::sync::RwLock::new(CaptureHealth::default())),
+            capture_counters: Arc::new(CaptureCounters::default()),
+            container_ip_cache: Arc::new(RwLock::new(ContainerIpCache::default())),
+            container_ip_cache_ttl: DEFAULT_CONTAINER_IP_CACHE_TTL,
+            captured_headers: Vec::new(),
         }
     }
 
@@ -46,6 +347,129 @@ impl NetworkMonitorService {
             db: Some(db),
             captured_requests: Arc::new(RwLock::new(HashMap::new())),
             pending_requests: Arc::new(RwLock::new(HashMap::new())),
+            capture_rules: Arc::new(std::sync::RwLock::new(Arc::new(CaptureRules::default()))),
+            store_unmatched: false,
+            track_chunked_responses: true,
+            in_flight_responses: Arc::new(RwLock::new(HashMap::new())),
+            reassembly_buffers: Arc::new(RwLock::new(HashMap::new())),
+            capture_stall_timeout: None,
+            capture_health: Arc::new(std::sync::RwLock::new(CaptureHealth::default())),
+            capture_counters: Arc::new(CaptureCounters::default()),
+            container_ip_cache: Arc::new(RwLock::new(ContainerIpCache::default())),
+            container_ip_cache_ttl: DEFAULT_CONTAINER_IP_CACHE_TTL,
+            captured_headers: Vec::new(),
+        }
+    }
+
+    /// Use the given capture rules instead of the defaults
+    pub fn with_capture_rules(self, rules: CaptureRules) -> Self {
+        self.set_capture_rules(rules);
+        self
+    }
+
+    /// Also store requests that could not be attributed to any container in
+    /// `unmatched_http_requests` (requires a database connection)
+    pub fn with_unmatched_request_storage(mut self, enabled: bool) -> Self {
+        self.store_unmatched = enabled;
+        self
+    }
+
+    /// Follow chunked response bodies to completion before recording the request (default on).
+    /// When disabled, every response is timed at its first segment
+    pub fn with_chunked_response_tracking(mut self, enabled: bool) -> Self {
+        self.track_chunked_responses = enabled;
+        self
+    }
+
+    /// Restart capture (re-running interface discovery) when an interface that had traffic
+    /// delivers no packets for `stall_timeout`, or when the capture fails. None disables it
+    pub fn with_capture_watchdog(mut self, stall_timeout: Option<std::time::Duration>) -> Self {
+        self.capture_stall_timeout = stall_timeout;
+        self.capture_health.write().unwrap().stall_timeout_secs = stall_timeout.map(|t| t.as_secs());
+        self
+    }
+
+    /// Re-read container IPs from Docker once the cached ones are older than `ttl`
+    /// (default [`DEFAULT_CONTAINER_IP_CACHE_TTL`]). Packets from a container started since
+    /// the last refresh stay unattributed until the next one. TTLs below
+    /// [`MIN_CONTAINER_IP_CACHE_TTL`] are raised to it
+    pub fn with_container_ip_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        if ttl < MIN_CONTAINER_IP_CACHE_TTL {
+            log::warn!(
+                "Container IP cache TTL of {:?} is too short; using {:?}",
+                ttl, MIN_CONTAINER_IP_CACHE_TTL
+            );
+        }
+        self.container_ip_cache_ttl = ttl.max(MIN_CONTAINER_IP_CACHE_TTL);
+        self
+    }
+
+    /// Store these request/response headers (matched case-insensitively) with each captured
+    /// request, e.g. `Host` or `User-Agent`. At most [`MAX_CAPTURED_HEADER_NAMES`] are kept;
+    /// credential headers such as `Authorization` and `Cookie` are always left out
+    pub fn with_captured_headers(mut self, names: Vec<String>) -> Self {
+        let mut captured_headers: Vec<String> = Vec::new();
+        for name in names {
+            let name = name.trim().to_ascii_lowercase();
+            if NEVER_CAPTURED_HEADERS.contains(&name.as_str()) {
+                log::warn!("Not capturing the {} header: it carries credentials", name);
+                continue;
+            }
+            if !name.is_empty() && !captured_headers.contains(&name) {
+                captured_headers.push(name);
+            }
+        }
+        if captured_headers.len() > MAX_CAPTURED_HEADER_NAMES {
+            log::warn!("Only the first {} captured headers are used (got {})",
+                MAX_CAPTURED_HEADER_NAMES, captured_headers.len());
+            captured_headers.truncate(MAX_CAPTURED_HEADER_NAMES);
+        }
+        self.captured_headers = captured_headers;
+        self
+    }
+
+    /// Interfaces being captured and how often the watchdog had to restart them
+    pub fn capture_health(&self) -> CaptureHealth {
+        self.capture_health.read().unwrap().clone()
+    }
+
+    /// Packet, parse and matching counters since startup, for telling whether capture works
+    /// and where requests get lost
+    pub fn get_capture_stats(&self) -> CaptureStats {
+        self.capture_counters.snapshot()
+    }
+
+    /// Current capture rules
+    pub fn capture_rules(&self) -> Arc<CaptureRules> {
+        self.capture_rules.read().unwrap().clone()
+    }
+
+    /// Atomically replace the capture rules. Running capture loops pick up the new
+    /// port filter on their next iteration without re-opening the capture
+    pub fn set_capture_rules(&self, rules: CaptureRules) {
+        log::info!("Applying capture rules: ports={:?}, {} endpoint rule(s)",
+            rules.capture_ports(), rules.config().endpoint_rules.len());
+        *self.capture_rules.write().unwrap() = Arc::new(rules);
+    }
+
+    /// Handle sharing all state with this monitor, for spawning per-interface capture tasks
+    fn shared_handle(&self) -> Self {
+        Self {
+            docker_service: self.docker_service.clone(),
+            db: self.db.clone(),
+            captured_requests: Arc::clone(&self.captured_requests),
+            pending_requests: Arc::clone(&self.pending_requests),
+            capture_rules: Arc::clone(&self.capture_rules),
+            store_unmatched: self.store_unmatched,
+            track_chunked_responses: self.track_chunked_responses,
+            in_flight_responses: Arc::clone(&self.in_flight_responses),
+            reassembly_buffers: Arc::clone(&self.reassembly_buffers),
+            capture_stall_timeout: self.capture_stall_timeout,
+            capture_health: Arc::clone(&self.capture_health),
+            capture_counters: Arc::clone(&self.capture_counters),
+            container_ip_cache: Arc::clone(&self.container_ip_cache),
+            container_ip_cache_ttl: self.container_ip_cache_ttl,
+            captured_headers: self.captured_headers.clone(),
         }
     }
 
@@ -54,16 +478,16 @@ impl NetworkMonitorService {
         #[cfg(not(feature = "network-capture"))]
         {
             log::debug!("Network capture feature not enabled - cannot get requests for container {}", container_id);
-            return Err(anyhow::anyhow!("Network capture feature not enabled. Rebuild with --features network-capture"));
+            Err(anyhow::anyhow!("Network capture feature not enabled. Rebuild with --features network-capture"))
         }
         
         #[cfg(feature = "network-capture")]
         {
-        let requests = self.captured_requests.read().await;
-        Ok(requests
-            .get(container_id)
-            .cloned()
-            .unwrap_or_default())
+            let requests = self.captured_requests.read().await;
+            Ok(requests
+                .get(container_id)
+                .cloned()
+                .unwrap_or_default())
         }
     }
 
@@ -79,6 +503,7 @@ impl NetworkMonitorService {
     /// Works both when running locally (monitoring Docker containers) and when running in Docker
     pub async fn start_monitoring(&self) -> Result<()> {
         log::info!("Starting network-level HTTP request monitoring");
+        self.spawn_expiry_sweep();
         
         // Detect if we're running inside Docker
         let is_running_in_docker = self.detect_if_in_docker().await;
@@ -107,18 +532,7 @@ impl NetworkMonitorService {
                     log::info!("Found {} Docker network interface(s) as fallback", network_interfaces.len());
                     for interface in network_interfaces {
                         log::info!("Attempting to monitor interface: {}", interface);
-                        let monitor_clone = Arc::new(NetworkMonitorService {
-                            docker_service: self.docker_service.clone(),
-                            db: self.db.clone(),
-                            captured_requests: Arc::clone(&self.captured_requests),
-                            pending_requests: Arc::clone(&self.pending_requests),
-                        });
-                        let interface_clone = interface.clone();
-                        tokio::spawn(async move {
-                            if let Err(e) = monitor_clone.capture_packets(&interface_clone).await {
-                                log::warn!("Failed to capture packets on {}: {}", interface_clone, e);
-                            }
-                        });
+                        self.spawn_capture(interface);
                     }
                 } else {
                     log::warn!("No Docker network interfaces found. Packet capture may not work.");
@@ -170,18 +584,7 @@ impl NetworkMonitorService {
                 // Capture on all Docker interfaces
         for interface in network_interfaces {
             log::info!("Attempting to monitor interface: {}", interface);
-            let monitor_clone = Arc::new(NetworkMonitorService {
-                docker_service: self.docker_service.clone(),
-                db: self.db.clone(),
-                captured_requests: Arc::clone(&self.captured_requests),
-                pending_requests: Arc::clone(&self.pending_requests),
-            });
-            let interface_clone = interface.clone();
-            tokio::spawn(async move {
-                if let Err(e) = monitor_clone.capture_packets(&interface_clone).await {
-                    log::warn!("Failed to capture packets on {}: {}", interface_clone, e);
-                }
-            });
+            self.spawn_capture(interface);
         }
             }
         }
@@ -207,10 +610,10 @@ impl NetworkMonitorService {
         // Check /proc/self/cgroup for Docker (Linux only)
         #[cfg(target_os = "linux")]
         {
-            if let Ok(cgroup_content) = std::fs::read_to_string("/proc/self/cgroup") {
-                if cgroup_content.contains("docker") || cgroup_content.contains("containerd") {
-                    return true;
-                }
+            if let Ok(cgroup_content) = std::fs::read_to_string("/proc/self/cgroup")
+                && (cgroup_content.contains("docker") || cgroup_content.contains("containerd"))
+            {
+                return true;
             }
         }
         
@@ -234,7 +637,7 @@ impl NetworkMonitorService {
                 }
             };
             
-            let possible_prefixes = vec![
+            let possible_prefixes = [
                 "docker",           // docker0, docker1, etc.
                 "br-",              // Bridge networks (br-xxx)
                 "veth",             // Virtual ethernet pairs
@@ -302,8 +705,7 @@ impl NetworkMonitorService {
                 Some(ParsedHttpRequest {
                     method: req.method?.to_string(),
                     path: req.path?.to_string(),
-                    status: None,
-                    response_time_ms: None,
+                    headers: self.extract_headers(req.headers),
                 })
             }
             Ok(Status::Partial) | Err(_) => None,
@@ -318,45 +720,154 @@ impl NetworkMonitorService {
         let mut resp = Response::new(&mut resp_headers);
         
         match resp.parse(packet_data) {
-            Ok(Status::Complete(_)) => {
+            Ok(Status::Complete(body_start)) => {
+                // Chunked is always the final transfer coding when present
+                let chunked = resp.headers.iter().any(|header| {
+                    header.name.eq_ignore_ascii_case("transfer-encoding")
+                        && std::str::from_utf8(header.value).is_ok_and(|value| {
+                            value
+                                .rsplit(',')
+                                .next()
+                                .is_some_and(|coding| coding.trim().eq_ignore_ascii_case("chunked"))
+                        })
+                });
                 Some(ParsedHttpResponse {
                     status: resp.code,
+                    chunked,
+                    body_start,
+                    headers: self.extract_headers(resp.headers),
                 })
             }
             Ok(Status::Partial) | Err(_) => None,
         }
     }
 
+    /// Allowlisted headers of one message, keyed by lowercase name. Repeated headers are
+    /// joined with ", "; non-UTF-8 values are skipped
+    fn extract_headers(&self, headers: &[httparse::Header<'_>]) -> BTreeMap<String, String> {
+        let mut extracted: BTreeMap<String, String> = BTreeMap::new();
+        if self.captured_headers.is_empty() {
+            return extracted;
+        }
+        for header in headers {
+            let name = header.name.to_ascii_lowercase();
+            if !self.captured_headers.contains(&name) {
+                continue;
+            }
+            let Ok(value) = std::str::from_utf8(header.value) else {
+                continue;
+            };
+            extracted
+                .entry(name)
+                .and_modify(|existing| {
+                    existing.push_str(", ");
+                    existing.push_str(value.trim());
+                })
+                .or_insert_with(|| value.trim().to_string());
+        }
+        extracted
+    }
+
+    /// Accumulate HTTP messages whose headers span several segments (large cookies, long
+    /// URLs). A buffer starts when a segment opens a message that doesn't parse completely
+    /// and is released once httparse sees the complete header block
+    async fn reassemble(&self, flow: FlowKey, seq: u32, payload: &[u8]) -> Reassembled {
+        let mut buffers = self.reassembly_buffers.write().await;
+        
+        if let Some(buffer) = buffers.get_mut(&flow) {
+            let expected_seq = buffer.start_seq.wrapping_add(buffer.contiguous().len() as u32);
+            if seq != expected_seq && looks_like_http_start(payload) {
+                // A new message past a gap: the rest of the buffered one was lost
+                log::debug!("Dropping incomplete HTTP headers on {} (new message started)", flow.connection_id);
+                buffers.remove(&flow);
+            } else {
+                buffer.insert(seq, payload);
+                let data = buffer.contiguous();
+                return match http_headers_status(&data) {
+                    Some(true) => {
+                        let start_seq = buffer.start_seq;
+                        buffers.remove(&flow);
+                        Reassembled::Complete { start_seq, data }
+                    }
+                    Some(false) if buffer.buffered_bytes <= MAX_REASSEMBLY_BYTES => Reassembled::Pending,
+                    _ => {
+                        log::debug!("Giving up on HTTP headers on {} ({} bytes buffered)",
+                            flow.connection_id, buffer.buffered_bytes);
+                        buffers.remove(&flow);
+                        Reassembled::Pending
+                    }
+                };
+            }
+        }
+        
+        if !looks_like_http_start(payload) || http_headers_status(payload) != Some(false) {
+            return Reassembled::Single;
+        }
+        
+        let cutoff = Utc::now() - chrono::Duration::seconds(REASSEMBLY_TIMEOUT_SECS);
+        buffers.retain(|_, buffer| buffer.last_update > cutoff);
+        log::debug!("HTTP headers on {} continue in a later segment; buffering", flow.connection_id);
+        buffers.insert(flow, ReassemblyBuffer::new(seq, payload));
+        Reassembled::Pending
+    }
+
+    /// Start following the body of a chunked response (None if tracking is off, the
+    /// response isn't chunked, or its framing is already broken in the first segment)
+    fn start_chunked_body(
+        &self,
+        parsed: &ParsedHttpResponse,
+        endpoints: &PacketEndpoints,
+        seq: u32,
+        payload: &[u8],
+    ) -> Option<ChunkedBody> {
+        if !self.track_chunked_responses || !parsed.chunked {
+            return None;
+        }
+        let mut decoder = ChunkedDecoder::new();
+        if let Err(e) = decoder.feed(&payload[parsed.body_start..]) {
+            log::debug!("Malformed chunked response body: {}", e);
+            return None;
+        }
+        Some(ChunkedBody {
+            decoder,
+            next_seq: seq.wrapping_add(payload.len() as u32),
+            responder_ip: endpoints.src_ip.clone(),
+            responder_port: endpoints.src_port,
+        })
+    }
+
     /// Handle HTTP request - store as pending and wait for response
     async fn handle_http_request(
         &self,
-        connection_id: &str,
+        key: PendingKey,
         container_id: &str,
         container_name: &str,
-        method: String,
-        path: String,
+        client_container_id: Option<String>,
+        request: ParsedHttpRequest,
+        endpoints: PacketEndpoints,
     ) {
+        let ParsedHttpRequest { method, path, headers } = request;
+        let endpoint = self.capture_rules().normalize_endpoint(&path);
+        let mut captured_headers = BTreeMap::new();
+        merge_captured_headers(&mut captured_headers, headers);
         let pending = PendingRequest {
             container_id: container_id.to_string(),
             container_name: container_name.to_string(),
+            client_container_id,
             method: method.clone(),
-            endpoint: path.clone(),
+            endpoint,
             request_timestamp: Utc::now(),
+            endpoints,
+            headers: captured_headers,
         };
 
         let mut pending_map = self.pending_requests.write().await;
         
-        // Clean up old pending requests (older than 30 seconds) before inserting
-        let cutoff = Utc::now() - chrono::Duration::seconds(30);
-        let before_cleanup = pending_map.len();
-        pending_map.retain(|_, req| req.request_timestamp > cutoff);
-        let after_cleanup = pending_map.len();
-        if before_cleanup != after_cleanup {
-            log::debug!("Cleaned up {} expired pending requests (kept {})", 
-                before_cleanup - after_cleanup, after_cleanup);
-        }
+        // Clean up old pending requests before inserting
+        self.expire_pending_requests(&mut pending_map, Utc::now());
         
-        pending_map.insert(connection_id.to_string(), pending);
+        let connection_id = key.connection_id.clone();
+        pending_map.insert(key, pending);
         
         if container_id == "unknown" {
             log::info!("📝 Stored pending HTTP request: {} {} (connection: {}, container: unknown - will try to match on response)", 
@@ -368,16 +879,24 @@ impl NetworkMonitorService {
     }
 
     /// Handle HTTP response - match with pending request and create complete HttpRequest
+    /// A chunked response whose body isn't complete yet is parked until its last chunk arrives
     async fn handle_http_response(
         &self,
         connection_id: &str,
+        ack: u32,
         container_id: &str,
         container_name: &str,
-        status_code: Option<u16>,
+        response: ParsedHttpResponse,
+        chunked_body: Option<ChunkedBody>,
     ) {
+        let status_code = response.status;
         let mut pending_map = self.pending_requests.write().await;
         
-        if let Some(mut pending) = pending_map.remove(connection_id) {
+        let pending = Self::find_pending_key(&pending_map, connection_id, ack)
+            .and_then(|key| pending_map.remove(&key));
+        if let Some(mut pending) = pending {
+            merge_captured_headers(&mut pending.headers, response.headers);
+
             // If container was unknown in the request, use the one from the response
             if pending.container_id == "unknown" && container_id != "unknown" {
                 log::info!("Updating container info for connection {}: {} -> {}", 
@@ -398,45 +917,31 @@ impl NetworkMonitorService {
             } else {
                 container_name.to_string()
             };
+            drop(pending_map);
             
-            let response_timestamp = Utc::now();
-            let latency_ms = (response_timestamp - pending.request_timestamp)
-                .num_milliseconds() as f64;
-
-            let request = HttpRequest {
-                container_id: final_container_id.clone(),
-                container_name: final_container_name.clone(),
-                endpoint: pending.endpoint.clone(),
-                method: pending.method.clone(),
-                http_status: status_code.unwrap_or(200),
-                response_time_ms: latency_ms.max(0.0),
-                timestamp: pending.request_timestamp,
-            };
-            
-            // Only store if we have a valid container ID
-            if final_container_id != "unknown" {
-                log::info!("✅ Captured HTTP request: {} {} {} {}ms from container {} ({})", 
-                    request.method, request.endpoint, request.http_status, 
-                    request.response_time_ms, &final_container_id[..12], final_container_name);
-                
-                // Try to insert directly into database if available
-                if let Some(db) = &self.db {
-                    if let Err(e) = self.insert_http_request_to_db(db, &request).await {
-                        log::warn!("Failed to insert HTTP request directly to database: {}. Storing in memory as fallback.", e);
-                        // Fallback to in-memory storage
-                        self.store_request(final_container_id, request).await;
-                    } else {
-                        log::info!("💾 Successfully inserted HTTP request directly into database: {} {} {} ({}ms)", 
-                            request.method, request.endpoint, request.http_status, request.response_time_ms);
-                    }
-                } else {
-                    // No database connection - store in memory for later collection
-                    self.store_request(final_container_id, request).await;
-                }
-            } else {
-                log::warn!("⚠️ HTTP request/response matched but container still unknown - skipping storage (connection: {})", connection_id);
+            if let Some(body) = chunked_body.filter(|body| !body.decoder.is_done()) {
+                log::debug!("Waiting for the rest of a chunked response (connection: {})", connection_id);
+                self.park_chunked_response(connection_id, InFlightResponse {
+                    pending,
+                    container_id: final_container_id,
+                    container_name: final_container_name,
+                    status_code,
+                    body,
+                    last_segment_at: Utc::now(),
+                }).await;
+                return;
             }
+            
+            self.record_http_request(
+                pending,
+                &final_container_id,
+                &final_container_name,
+                status_code,
+                Utc::now(),
+                connection_id,
+            ).await;
         } else {
+            CaptureCounters::add(&self.capture_counters.responses_without_request, 1);
             log::debug!("Received HTTP response for unknown connection: {} (no pending request found)", connection_id);
             // Log pending requests for debugging
             let pending_count = pending_map.len();
@@ -448,100 +953,203 @@ impl NetworkMonitorService {
         }
     }
 
-    /// Extract connection info and HTTP payload from packet
-    /// Returns: (connection_id, http_payload, is_response)
-    /// 
-    /// Note: On Linux "any" interface, packets use SLL (Socket Layer Link) header (16 bytes)
-    /// instead of Ethernet header (14 bytes). We need to detect this.
-    #[cfg(feature = "network-capture")]
-    async fn extract_connection_info<'a>(&self, packet: &'a pcap::Packet<'_>) -> Option<(String, &'a [u8], bool)> {
-        if packet.data.len() < 34 {
-            return None;
-        }
-
-        // Detect packet type: SLL (Linux "any" interface) or Ethernet
-        // SLL header starts with 0x00 0x00 (packet type field)
-        // Ethernet header starts with MAC addresses (not 0x00 0x00 typically)
-        let is_sll = packet.data[0] == 0x00 && packet.data[1] == 0x00;
-        
-        let (ip_header_start, ip_protocol_offset, src_ip_offset, dst_ip_offset) = if is_sll {
-            // SLL header: 16 bytes
-            // IP header starts at byte 16
-            // IP protocol is at byte 9 of IP header = byte 25 of packet
-            // Source IP: bytes 12-15 of IP header = bytes 28-31 of packet
-            // Dest IP: bytes 16-19 of IP header = bytes 32-35 of packet
-            (16, 25, 28, 32)
-        } else {
-            // Ethernet header: 14 bytes
-            // IP header starts at byte 14
-            // IP protocol is at byte 9 of IP header = byte 23 of packet
-            // Source IP: bytes 12-15 of IP header = bytes 26-29 of packet
-            // Dest IP: bytes 16-19 of IP header = bytes 30-33 of packet
-            (14, 23, 26, 30)
+    /// Hold a matched request until its chunked response completes. Responses that stalled
+    /// (or were superseded by a new response on the same connection) are recorded as they are
+    async fn park_chunked_response(&self, connection_id: &str, response: InFlightResponse) {
+        let finished = {
+            let mut in_flight = self.in_flight_responses.write().await;
+            let mut finished = take_stalled_responses(&mut in_flight, Utc::now());
+            if let Some(previous) = in_flight.insert(connection_id.to_string(), response) {
+                finished.push((connection_id.to_string(), previous));
+            }
+            finished
         };
+        self.record_unfinished_responses(finished).await;
+    }
 
-        if packet.data.len() < dst_ip_offset + 4 {
-            return None;
+    /// Record chunked responses that stopped before their last chunk, as far as they got
+    async fn record_unfinished_responses(&self, responses: Vec<(String, InFlightResponse)>) {
+        for (key, entry) in responses {
+            log::debug!("Chunked response on {} did not complete ({} body bytes seen); recording it as-is",
+                key, entry.body.decoder.body_len());
+            let completed_at = entry.last_segment_at;
+            self.record_finished_response(entry, completed_at, &key).await;
         }
+    }
 
-        // Check IP protocol
-        let ip_protocol = packet.data[ip_protocol_offset];
-        if ip_protocol != 6 {
-            // Not TCP (protocol 6)
-            return None;
+    /// Drop requests that have waited longer than `PENDING_REQUEST_TIMEOUT_SECS` for a response
+    fn expire_pending_requests(&self, pending_map: &mut HashMap<PendingKey, PendingRequest>, now: DateTime<Utc>) {
+        let cutoff = now - chrono::Duration::seconds(PENDING_REQUEST_TIMEOUT_SECS);
+        let before_cleanup = pending_map.len();
+        pending_map.retain(|_, req| req.request_timestamp > cutoff);
+        let after_cleanup = pending_map.len();
+        if before_cleanup != after_cleanup {
+            CaptureCounters::add(&self.capture_counters.requests_expired, (before_cleanup - after_cleanup) as u64);
+            log::debug!("Cleaned up {} expired pending requests (kept {})",
+                before_cleanup - after_cleanup, after_cleanup);
         }
+    }
 
-        // Extract IP addresses
-        let src_ip = format!("{}.{}.{}.{}", 
-            packet.data[src_ip_offset], packet.data[src_ip_offset + 1], 
-            packet.data[src_ip_offset + 2], packet.data[src_ip_offset + 3]);
-        let dst_ip = format!("{}.{}.{}.{}", 
-            packet.data[dst_ip_offset], packet.data[dst_ip_offset + 1], 
-            packet.data[dst_ip_offset + 2], packet.data[dst_ip_offset + 3]);
-
-        // Extract TCP ports
-        // TCP header starts after IP header (IP header is typically 20 bytes, but can have options)
-        // For simplicity, assume IP header is 20 bytes (no options)
-        let tcp_start = ip_header_start + 20;
-        if packet.data.len() < tcp_start + 4 {
-            return None;
+    /// Expire unanswered requests and stalled chunked responses every `EXPIRY_SWEEP_INTERVAL`.
+    /// New traffic expires them too, but a quiet network would otherwise keep them forever
+    fn spawn_expiry_sweep(&self) {
+        let monitor = self.shared_handle();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(EXPIRY_SWEEP_INTERVAL);
+            loop {
+                ticker.tick().await;
+                let now = Utc::now();
+                monitor.expire_pending_requests(&mut *monitor.pending_requests.write().await, now);
+                let stalled = take_stalled_responses(&mut *monitor.in_flight_responses.write().await, now);
+                monitor.record_unfinished_responses(stalled).await;
+            }
+        });
+    }
+
+    /// Feed a segment that is neither a request nor a response start to the chunked
+    /// response in flight on its connection, recording the request once the body ends
+    async fn handle_response_continuation(
+        &self,
+        connection_id: &str,
+        endpoints: &PacketEndpoints,
+        seq: u32,
+        payload: &[u8],
+    ) {
+        let mut in_flight = self.in_flight_responses.write().await;
+        let Some(entry) = in_flight.get_mut(connection_id) else {
+            return;
+        };
+        // Only the server side of the connection carries the response body
+        if entry.body.responder_ip != endpoints.src_ip || entry.body.responder_port != endpoints.src_port {
+            return;
         }
-        let src_port = ((packet.data[tcp_start] as u16) << 8) | (packet.data[tcp_start + 1] as u16);
-        let dst_port = ((packet.data[tcp_start + 2] as u16) << 8) | (packet.data[tcp_start + 3] as u16);
-
-        // Determine if this is a response (packet going TO a server port)
-        // This is a heuristic - we check if destination port is a common HTTP server port
-        // In practice, we should track which IP is the container to determine direction more accurately
-        let is_response = dst_port == 80 || dst_port == 443 || dst_port == 8080 || 
-                         dst_port == 8443 || dst_port == 8000 || dst_port == 3000 || 
-                         dst_port == 5000 || dst_port == 9000;
-
-        // Create connection ID (bidirectional - same connection regardless of direction)
-        let connection_id = if src_port < dst_port {
-            format!("{}:{}:{}:{}", src_ip, src_port, dst_ip, dst_port)
+        
+        let offset = seq.wrapping_sub(entry.body.next_seq) as i32;
+        if offset < 0 {
+            // Retransmission of a segment we already consumed
+            return;
+        }
+        
+        let now = Utc::now();
+        let complete = if offset > 0 {
+            log::debug!("Missed {} bytes of a chunked response on {}; recording it as-is", offset, connection_id);
+            true
         } else {
-            format!("{}:{}:{}:{}", dst_ip, dst_port, src_ip, src_port)
+            entry.body.next_seq = seq.wrapping_add(payload.len() as u32);
+            entry.last_segment_at = now;
+            entry.body.decoder.feed(payload).unwrap_or_else(|e| {
+                log::debug!("Malformed chunked response on {}: {}; recording it as-is", connection_id, e);
+                true
+            })
         };
-
-        // Extract HTTP payload (skip link layer + IP + TCP headers)
-        // TCP header length is in the 4-bit header length field (byte 12 of TCP header, bits 4-7)
-        let tcp_header_len_offset = tcp_start + 12;
-        if packet.data.len() < tcp_header_len_offset + 1 {
-            return None;
+        
+        if complete && let Some(entry) = in_flight.remove(connection_id) {
+            drop(in_flight);
+            self.record_finished_response(entry, now, connection_id).await;
         }
-        let tcp_header_len = ((packet.data[tcp_header_len_offset] & 0xF0) >> 4) * 4;
-        let http_start = tcp_start + tcp_header_len as usize;
+    }
+
+    async fn record_finished_response(&self, entry: InFlightResponse, completed_at: DateTime<Utc>, connection_id: &str) {
+        self.record_http_request(
+            entry.pending,
+            &entry.container_id,
+            &entry.container_name,
+            entry.status_code,
+            completed_at,
+            connection_id,
+        ).await;
+    }
+
+    /// Store a completed request/response pair (database, in-memory fallback, or unmatched diagnostics)
+    async fn record_http_request(
+        &self,
+        pending: PendingRequest,
+        final_container_id: &str,
+        final_container_name: &str,
+        status_code: Option<u16>,
+        response_timestamp: DateTime<Utc>,
+        connection_id: &str,
+    ) {
+        let latency_ms = (response_timestamp - pending.request_timestamp)
+            .num_milliseconds() as f64;
+
+        let request = HttpRequest {
+            container_id: final_container_id.to_string(),
+            container_name: final_container_name.to_string(),
+            endpoint: pending.endpoint.clone(),
+            method: pending.method.clone(),
+            http_status: status_code.unwrap_or(200),
+            response_time_ms: latency_ms.max(0.0),
+            timestamp: pending.request_timestamp,
+            headers: (!pending.headers.is_empty()).then(|| pending.headers.clone()),
+            client_container_id: pending.client_container_id.clone(),
+        };
         
-        if packet.data.len() > http_start {
-            Some((connection_id, &packet.data[http_start..], is_response))
+        // Only store if we have a valid container ID
+        if final_container_id != "unknown" {
+            CaptureCounters::add(&self.capture_counters.requests_matched, 1);
+            log::info!("✅ Captured HTTP request: {} {} {} {}ms from container {} ({})", 
+                request.method, request.endpoint, request.http_status, 
+                request.response_time_ms, &final_container_id[..12], final_container_name);
+            
+            // Try to insert directly into database if available
+            if let Some(db) = &self.db {
+                if let Err(e) = self.insert_http_request_to_db(db, &request).await {
+                    log::warn!("Failed to insert HTTP request directly to database: {}. Storing in memory as fallback.", e);
+                    // Fallback to in-memory storage
+                    self.store_request(final_container_id.to_string(), request).await;
+                } else {
+                    log::info!("💾 Successfully inserted HTTP request directly into database: {} {} {} ({}ms)", 
+                        request.method, request.endpoint, request.http_status, request.response_time_ms);
+                }
+            } else {
+                // No database connection - store in memory for later collection
+                self.store_request(final_container_id.to_string(), request).await;
+            }
         } else {
-            None
+            CaptureCounters::add(&self.capture_counters.requests_unmatched, 1);
+            log::warn!("⚠️ HTTP request/response matched but container still unknown - skipping storage (connection: {})", connection_id);
+            if self.store_unmatched
+                && let Some(db) = &self.db
+            {
+                let reason = format!("No container owns {} or {}",
+                    pending.endpoints.src_ip, pending.endpoints.dst_ip);
+                if let Err(e) = Self::insert_unmatched_request_to_db(db, &pending, &request, &reason).await {
+                    log::warn!("Failed to store unmatched HTTP request: {}", e);
+                }
+            }
         }
     }
 
+    /// Find the pending request a response belongs to: on the same connection, with the
+    /// response acknowledging the request's payload (closest preceding request wins)
+    fn find_pending_key(
+        pending_map: &HashMap<PendingKey, PendingRequest>,
+        connection_id: &str,
+        ack: u32,
+    ) -> Option<PendingKey> {
+        pending_map
+            .keys()
+            .filter(|key| key.connection_id == connection_id)
+            .map(|key| (ack.wrapping_sub(key.request_end_seq), key))
+            .filter(|(distance, _)| *distance <= MAX_REQUEST_SEQ_SPAN)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, key)| key.clone())
+    }
+
+    /// Extract connection info and HTTP payload from packet
+    /// 
+    /// Note: On Linux "any" interface, packets use SLL (Socket Layer Link) header (16 bytes)
+    /// instead of Ethernet header (14 bytes). We need to detect this.
+    /// Both IPv4 and IPv6 are handled; see [`parse_ip_layer`].
+    #[cfg(feature = "network-capture")]
+    async fn extract_connection_info<'a>(&self, packet: &'a pcap::Packet<'_>) -> Option<PacketConnection<'a>> {
+        parse_tcp_packet(packet.data)
+    }
+
     /// Extract connection info (fallback when network-capture feature is disabled)
     #[cfg(not(feature = "network-capture"))]
-    async fn extract_connection_info(&self, _packet: &[u8]) -> Option<(String, &[u8], bool)> {
+    async fn extract_connection_info<'a>(&self, _packet: &'a [u8]) -> Option<PacketConnection<'a>> {
         None
     }
 
@@ -563,6 +1171,8 @@ impl NetworkMonitorService {
             method: Set(request.method.clone()),
             http_status: Set(request.http_status as i16),
             response_time_ms: Set(request.response_time_ms),
+            headers: Set(request.headers.as_ref().map(|headers| serde_json::json!(headers))),
+            client_container_id: Set(request.client_container_id.clone()),
             timestamp: Set(timestamp),
             ..Default::default()
         };
@@ -575,6 +1185,39 @@ impl NetworkMonitorService {
         Ok(())
     }
 
+    /// Insert a request that could not be attributed to a container (diagnostics)
+    async fn insert_unmatched_request_to_db(
+        db: &DatabaseConnection,
+        pending: &PendingRequest,
+        request: &HttpRequest,
+        reason: &str,
+    ) -> Result<()> {
+        use crate::entity::unmatched_http_requests;
+
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        let active_model = unmatched_http_requests::ActiveModel {
+            src_ip: Set(pending.endpoints.src_ip.clone()),
+            src_port: Set(pending.endpoints.src_port as i32),
+            dst_ip: Set(pending.endpoints.dst_ip.clone()),
+            dst_port: Set(pending.endpoints.dst_port as i32),
+            method: Set(request.method.clone()),
+            endpoint: Set(request.endpoint.clone()),
+            http_status: Set(Some(request.http_status as i16)),
+            response_time_ms: Set(Some(request.response_time_ms)),
+            reason: Set(reason.to_string()),
+            timestamp: Set(request.timestamp.with_timezone(&fixed_offset)),
+            ..Default::default()
+        };
+
+        active_model
+            .insert(db)
+            .await
+            .map_err(|e| anyhow::anyhow!("Database insert failed: {}", e))?;
+
+        Ok(())
+    }
+
     /// Store a captured HTTP request in memory (fallback when database is not available)
     pub async fn store_request(&self, container_id: String, request: HttpRequest) {
         let mut requests = self.captured_requests.write().await;
@@ -605,7 +1248,8 @@ impl NetworkMonitorService {
     /// Capture packets from a network interface using pcap
     /// Cross-platform: Works on Linux, macOS, and Windows
     /// Uses existing pcap library - no need to implement packet capture from scratch
-    async fn capture_packets(&self, interface: &str) -> Result<()> {
+    /// Returns once capture has stalled or failed; errors before capture started are returned as Err
+    async fn capture_packets(&self, interface: &str) -> Result<CaptureEnd> {
         #[cfg(feature = "network-capture")]
         {
             use pcap::{Capture, Device};
@@ -634,7 +1278,8 @@ impl NetworkMonitorService {
             let mut cap = match Capture::from_device(device.name.as_str()) {
                 Ok(cap) => {
                     // Configure capture settings
-                    let capture = cap.promisc(true).snaplen(65535);
+                    // A read timeout lets the loop notice rule reloads even when the interface is idle
+                    let capture = cap.promisc(true).snaplen(65535).timeout(1000);
                     
                     // Open the capture
                     match capture.open() {
@@ -657,15 +1302,10 @@ impl NetworkMonitorService {
                 }
             };
             
-            // Filter for HTTP traffic (ports 80, 8080, 8000, 3000, etc.)
+            // Filter for HTTP traffic on the configured capture ports
             // BPF filter syntax works on all platforms
-            // Also include common application ports
-            let filter_str = "tcp port 80 or tcp port 443 or tcp port 8080 or tcp port 8443 or tcp port 8000 or tcp port 3000 or tcp port 5000 or tcp port 9000";
-            if let Err(e) = cap.filter(filter_str, true) {
-                log::warn!("Failed to set packet filter: {}. Capturing all TCP traffic.", e);
-            } else {
-                log::info!("Packet filter applied: {} (capturing HTTP/HTTPS traffic on common ports)", filter_str);
-            }
+            let mut applied_rules = self.capture_rules();
+            Self::apply_capture_filter(&mut cap, &applied_rules);
             
             log::info!("Started capturing packets on {} (cross-platform)", interface);
             
@@ -675,13 +1315,26 @@ impl NetworkMonitorService {
             let mut tcp_packet_count = 0u64;
             let mut http_packet_count = 0u64;
             let mut last_log_time = std::time::Instant::now();
+            // Watchdog: only an interface that has delivered packets is expected to keep doing so
+            let mut last_packet_time = std::time::Instant::now();
+            let mut saw_traffic = false;
             
             log::info!("Starting packet capture loop on interface: {}", interface);
             
             loop {
+                // Re-apply the BPF filter if the capture rules were reloaded
+                let current_rules = self.capture_rules();
+                if !Arc::ptr_eq(&current_rules, &applied_rules) {
+                    Self::apply_capture_filter(&mut cap, &current_rules);
+                    applied_rules = current_rules;
+                }
+                
                 let packet = match cap.next_packet() {
                     Ok(p) => {
                         packet_count += 1;
+                        CaptureCounters::add(&self.capture_counters.packets_seen, 1);
+                        last_packet_time = std::time::Instant::now();
+                        saw_traffic = true;
                         // Log packet capture stats every 10 seconds
                         if last_log_time.elapsed().as_secs() >= 10 {
                             log::info!("Packet capture stats (last 10s): Total={}, TCP={}, HTTP={} on {}", 
@@ -703,50 +1356,66 @@ impl NetworkMonitorService {
                             log::warn!("  3. Consider using host network mode or ensuring worker is on same network");
                             last_log_time = std::time::Instant::now();
                         }
+                        if let Some(stall_timeout) = self.capture_stall_timeout
+                            && saw_traffic
+                            && last_packet_time.elapsed() >= stall_timeout
+                        {
+                            return Ok(CaptureEnd::Stalled(last_packet_time.elapsed()));
+                        }
                         continue;
                     }
                     Err(e) => {
-                        log::error!("Error capturing packet: {}", e);
-                        break;
+                        log::error!("Error capturing packet on {}: {}", interface, e);
+                        return Ok(CaptureEnd::Failed(e.to_string()));
                     }
                 };
                 
                 // Check if it's TCP (increment counter)
-                // Detect SLL vs Ethernet header
-                if packet.data.len() >= 34 {
-                    let is_sll = packet.data[0] == 0x00 && packet.data[1] == 0x00;
-                    let ip_protocol_offset = if is_sll { 25 } else { 23 };
-                    if packet.data.len() > ip_protocol_offset {
-                        let ip_protocol = packet.data[ip_protocol_offset];
-                        if ip_protocol == 6 {
-                            tcp_packet_count += 1;
-                        }
-                    }
+                if parse_ip_layer(packet.data).is_some_and(|ip| ip.protocol == IP_PROTOCOL_TCP) {
+                    tcp_packet_count += 1;
+                    CaptureCounters::add(&self.capture_counters.tcp_packets, 1);
                 }
                 
                 // Extract connection info and HTTP data
-                if let Some((connection_id, http_data, is_response)) = self.extract_connection_info(&packet).await {
+                if let Some(PacketConnection { connection_id, endpoints, payload, seq, ack }) = self.extract_connection_info(&packet).await {
                     http_packet_count += 1;
+                    CaptureCounters::add(&self.capture_counters.http_packets, 1);
+                    
+                    // Headers split across segments are parsed once the last piece arrives
+                    let flow = FlowKey {
+                        connection_id: connection_id.clone(),
+                        src_ip: endpoints.src_ip.clone(),
+                        src_port: endpoints.src_port,
+                    };
+                    let (seq, http_data) = match self.reassemble(flow, seq, payload).await {
+                        Reassembled::Single => (seq, std::borrow::Cow::Borrowed(payload)),
+                        Reassembled::Complete { start_seq, data } => (start_seq, std::borrow::Cow::Owned(data)),
+                        Reassembled::Pending => continue,
+                    };
+                    let http_data: &[u8] = &http_data;
                     
                     // Try to parse as both request and response to determine actual direction
                     let parsed_request = self.parse_http_request(http_data);
                     let parsed_response = self.parse_http_response(http_data);
                     
-                    // Use actual HTTP parsing results to determine direction (more reliable than port heuristics)
+                    // The start line tells requests from responses; the container addresses tell
+                    // which end of the connection the message belongs to
                     let is_http_request = parsed_request.is_some();
                     let is_http_response = parsed_response.is_some();
+                    if is_http_request || is_http_response {
+                        CaptureCounters::add(&self.capture_counters.http_messages_parsed, 1);
+                    }
                     
-                    log::info!("Extracted HTTP data from packet: connection_id={}, port_heuristic_is_response={}, data_len={}, is_http_request={}, is_http_response={}", 
-                        connection_id, is_response, http_data.len(), is_http_request, is_http_response);
+                    let containers = self.containers_for_packet(&packet).await;
                     
-                    // Try to find container for this packet
-                    let container_match = self.find_container_for_packet(&packet).await;
+                    log::info!("Extracted HTTP data from packet: connection_id={}, direction={:?}, data_len={}, is_http_request={}, is_http_response={}", 
+                        connection_id, containers.direction(), http_data.len(), is_http_request, is_http_response);
                     
-                    // Use actual HTTP parsing to determine direction
                     if is_http_response {
                         // This is an HTTP response - try to match with pending request
                         if let Some(parsed) = parsed_response {
-                            if let Some(ref container_id) = container_match {
+                            let chunked_body = self.start_chunked_body(&parsed, &endpoints, seq, http_data);
+                            if let Some(container_id) = containers.response_owner() {
                                 let container_name = self.docker_service
                                     .list_containers()
                                     .await
@@ -763,9 +1432,11 @@ impl NetworkMonitorService {
                                 log::info!("Parsed HTTP response: status={:?} for container {}", parsed.status, container_id);
                                 self.handle_http_response(
                                     &connection_id,
+                                    ack,
                                     container_id,
                                     &container_name,
-                                    parsed.status,
+                                    parsed,
+                                    chunked_body,
                                 ).await;
                             } else {
                                 // Response arrived but no container match - try to match with pending request anyway
@@ -773,9 +1444,11 @@ impl NetworkMonitorService {
                                 log::debug!("HTTP response received but container not matched - trying to match with pending request (connection: {})", connection_id);
                                 self.handle_http_response(
                                     &connection_id,
+                                    ack,
                                     "unknown",
                                     "unknown",
-                                    parsed.status,
+                                    parsed,
+                                    chunked_body,
                                 ).await;
                             }
                         } else {
@@ -783,8 +1456,13 @@ impl NetworkMonitorService {
                         }
                     } else if is_http_request {
                         // This is an HTTP request - store as pending and wait for response
+                        // The response will acknowledge up to the end of this payload
+                        let pending_key = PendingKey {
+                            connection_id: connection_id.clone(),
+                            request_end_seq: seq.wrapping_add(http_data.len() as u32),
+                        };
                         if let Some(parsed) = parsed_request {
-                            if let Some(ref container_id) = container_match {
+                            if let Some(container_id) = containers.request_owner() {
                                 let container_name = self.docker_service
                                     .list_containers()
                                     .await
@@ -800,53 +1478,45 @@ impl NetworkMonitorService {
                                 log::debug!("Processing HTTP request for container {} (connection: {})", container_id, connection_id);
                                 log::info!("Parsed HTTP request: {} {} for container {}", parsed.method, parsed.path, container_id);
                                 self.handle_http_request(
-                                    &connection_id,
+                                    pending_key,
                                     container_id,
                                     &container_name,
-                                    parsed.method,
-                                    parsed.path,
+                                    containers.request_client().cloned(),
+                                    parsed,
+                                    endpoints,
                                 ).await;
                             } else {
                                 // Store request even if container not matched - we'll try to match when response arrives
                                 log::info!("Parsed HTTP request: {} {} but container not matched yet (connection: {}) - storing as pending", 
                                     parsed.method, parsed.path, connection_id);
                                 self.handle_http_request(
-                                    &connection_id,
+                                    pending_key,
                                     "unknown",
                                     "unknown",
-                                    parsed.method,
-                                    parsed.path,
+                                    None,
+                                    parsed,
+                                    endpoints,
                                 ).await;
                             }
                         } else {
                             log::debug!("Failed to parse HTTP request data (len={})", http_data.len());
                         }
+                    } else if self.track_chunked_responses {
+                        // Possibly the next piece of a chunked response body
+                        self.handle_response_continuation(&connection_id, &endpoints, seq, http_data).await;
                     } else {
                         log::debug!("Packet contains HTTP data but neither request nor response could be parsed (len={})", http_data.len());
                     }
                     
                     // Log container matching failure for debugging
-                    if container_match.is_none() && packet.data.len() >= 34 {
-                        let is_sll = packet.data[0] == 0x00 && packet.data[1] == 0x00;
-                        let (src_ip_offset, dst_ip_offset) = if is_sll { (28, 32) } else { (26, 30) };
-                        if packet.data.len() >= dst_ip_offset + 4 {
-                            let src_ip = format!("{}.{}.{}.{}", 
-                                packet.data[src_ip_offset], packet.data[src_ip_offset + 1], 
-                                packet.data[src_ip_offset + 2], packet.data[src_ip_offset + 3]);
-                            let dst_ip = format!("{}.{}.{}.{}", 
-                                packet.data[dst_ip_offset], packet.data[dst_ip_offset + 1], 
-                                packet.data[dst_ip_offset + 2], packet.data[dst_ip_offset + 3]);
-                            log::debug!("Could not match packet to container - Source IP: {}, Dest IP: {} (connection: {})", 
-                                src_ip, dst_ip, connection_id);
-                        }
+                    if containers.direction() == PacketDirection::Unknown {
+                        log::debug!("Could not match packet to container (connection: {})", connection_id);
                     }
                 } else {
                     // Packet didn't contain HTTP data or wasn't TCP
                     log::trace!("Packet did not contain HTTP data or was not TCP");
                 }
             }
-            
-            Ok(())
         }
         
         #[cfg(not(feature = "network-capture"))]
@@ -857,104 +1527,131 @@ impl NetworkMonitorService {
         }
     }
 
-    /// Extract HTTP payload from packet (simplified - real implementation needs TCP reassembly)
-    fn extract_http_payload<'a>(&self, packet_data: &'a [u8]) -> Option<&'a [u8]> {
-        // Skip Ethernet header (14 bytes) and IP header (variable, typically 20 bytes)
-        // Skip TCP header (variable, typically 20 bytes)
-        // This is simplified - real implementation needs proper IP/TCP parsing
-        if packet_data.len() > 54 {
-            Some(&packet_data[54..])
+    /// Apply the BPF filter for the given capture rules to an open capture
+    #[cfg(feature = "network-capture")]
+    fn apply_capture_filter(cap: &mut pcap::Capture<pcap::Active>, rules: &CaptureRules) {
+        let filter_str = rules.bpf_filter();
+        if let Err(e) = cap.filter(&filter_str, true) {
+            log::warn!("Failed to set packet filter '{}': {}", filter_str, e);
         } else {
-            None
+            log::info!("Packet filter applied: {} (capturing HTTP/HTTPS traffic on configured ports)", filter_str);
         }
     }
 
     /// Try to capture on a specific interface (helper method)
     async fn try_capture_on_interface(&self, interface: &str) -> Result<()> {
-        let monitor_clone = Arc::new(NetworkMonitorService {
-            docker_service: self.docker_service.clone(),
-            db: self.db.clone(),
-            captured_requests: Arc::clone(&self.captured_requests),
-            pending_requests: Arc::clone(&self.pending_requests),
-        });
-        
-        let interface_clone = interface.to_string();
-        tokio::spawn(async move {
-            if let Err(e) = monitor_clone.capture_packets(&interface_clone).await {
-                log::debug!("Failed to capture on {}: {}", interface_clone, e);
+        self.spawn_capture(interface.to_string());
+        Ok(())
+    }
+
+    /// Start a supervised capture task on an interface unless one is already running
+    fn spawn_capture(&self, interface: String) {
+        {
+            let mut health = self.capture_health.write().unwrap();
+            if health.active_interfaces.contains(&interface) {
+                return;
             }
+            health.active_interfaces.push(interface.clone());
+        }
+
+        let monitor = Arc::new(self.shared_handle());
+        tokio::spawn(async move {
+            monitor.supervise_capture(&interface).await;
+            monitor.capture_health.write().unwrap().active_interfaces.retain(|i| *i != interface);
         });
-        
-        Ok(())
     }
 
-    /// Find container ID for a packet by matching IP addresses
+    /// Run capture on an interface and, with the watchdog enabled, tear it down and re-initialize
+    /// it whenever it stalls or fails. Interface discovery is re-run before each restart so
+    /// networks recreated in the meantime (new bridge interfaces) get their own capture
+    async fn supervise_capture(&self, interface: &str) {
+        loop {
+            let reason = match self.capture_packets(interface).await {
+                Ok(CaptureEnd::Stalled(idle)) => format!("no packets for {}s", idle.as_secs()),
+                Ok(CaptureEnd::Failed(e)) => format!("capture error: {}", e),
+                Err(e) => {
+                    log::warn!("Failed to capture packets on {}: {}", interface, e);
+                    return;
+                }
+            };
+
+            if self.capture_stall_timeout.is_none() {
+                log::warn!("Capture on {} stopped ({}); capture watchdog is disabled", interface, reason);
+                return;
+            }
+
+            log::warn!("Capture on {} stopped ({}); re-initializing in {}s", interface, reason, CAPTURE_RESTART_DELAY_SECS);
+            tokio::time::sleep(std::time::Duration::from_secs(CAPTURE_RESTART_DELAY_SECS)).await;
+
+            // "any" covers every interface; named interfaces may have disappeared or been replaced
+            if interface != "any" {
+                let interfaces = self.get_docker_network_interfaces().await.unwrap_or_default();
+                for discovered in interfaces.iter().filter(|i| i.as_str() != interface) {
+                    self.spawn_capture(discovered.clone());
+                }
+                if !interfaces.iter().any(|i| i == interface) {
+                    log::warn!("Interface {} no longer exists; not restarting capture on it", interface);
+                    return;
+                }
+            }
+
+            let mut health = self.capture_health.write().unwrap();
+            health.restart_count += 1;
+            *health.restarts_by_interface.entry(interface.to_string()).or_default() += 1;
+            health.last_restart = Some(Utc::now());
+            health.last_restart_reason = Some(format!("{}: {}", interface, reason));
+            log::info!("Restarting capture on {} (restart #{})", interface, health.restart_count);
+        }
+    }
+
+    /// Containers owning a packet's source and destination IP addresses
     /// Works for both scenarios:
     /// 1. Running locally: matches packets to/from container IPs
     /// 2. Running in Docker: matches packets on Docker network
     #[cfg(feature = "network-capture")]
-    async fn find_container_for_packet(&self, packet: &pcap::Packet<'_>) -> Option<String> {
-        // Extract IP addresses from packet
-        // On Linux "any" interface, packets use SLL header (16 bytes) instead of Ethernet (14 bytes)
-        if packet.data.len() < 34 {
-            return None;
-        }
-        
-        // Detect packet type: SLL (Linux "any" interface) or Ethernet
-        let is_sll = packet.data[0] == 0x00 && packet.data[1] == 0x00;
-        let (src_ip_offset, dst_ip_offset) = if is_sll {
-            // SLL: Source IP at bytes 28-31, Dest IP at bytes 32-35
-            (28, 32)
-        } else {
-            // Ethernet: Source IP at bytes 26-29, Dest IP at bytes 30-33
-            (26, 30)
+    async fn containers_for_packet(&self, packet: &pcap::Packet<'_>) -> PacketContainers {
+        // Extract IP addresses from packet (IPv4 or IPv6, behind an SLL or Ethernet header)
+        let Some(ip) = parse_ip_layer(packet.data) else {
+            return PacketContainers::default();
         };
         
-        if packet.data.len() < dst_ip_offset + 4 {
-            return None;
-        }
-        
-        // Extract source and destination IP addresses from IP header
-        let src_ip = format!("{}.{}.{}.{}", 
-            packet.data[src_ip_offset], packet.data[src_ip_offset + 1], 
-            packet.data[src_ip_offset + 2], packet.data[src_ip_offset + 3]);
-        let dst_ip = format!("{}.{}.{}.{}", 
-            packet.data[dst_ip_offset], packet.data[dst_ip_offset + 1], 
-            packet.data[dst_ip_offset + 2], packet.data[dst_ip_offset + 3]);
+        // Addresses are compared as normalized strings (IpAddr formatting), matching
+        // the normalization applied to container IPs by get_container_network_info
+        let src_ip = ip.src_ip.to_string();
+        let dst_ip = ip.dst_ip.to_string();
         
         log::debug!("Packet IPs - Source: {}, Dest: {}", src_ip, dst_ip);
         
         // Skip localhost/loopback traffic (unless we're specifically looking for it)
-        if src_ip == "127.0.0.1" || dst_ip == "127.0.0.1" {
+        if ip.src_ip.is_loopback() || ip.dst_ip.is_loopback() {
             log::debug!("Skipping localhost traffic");
-            return None;
+            return PacketContainers::default();
         }
         
-        // Cache container IP mappings to avoid repeated Docker API calls
-        // This is a simple in-memory cache - in production you might want a more sophisticated cache
-        use std::sync::OnceLock;
-        static CONTAINER_IP_CACHE: OnceLock<tokio::sync::RwLock<std::collections::HashMap<String, Vec<String>>>> = OnceLock::new();
-        
-        let cache = CONTAINER_IP_CACHE.get_or_init(|| {
-            tokio::sync::RwLock::new(std::collections::HashMap::new())
-        });
-        
-        // Check cache first
+        // Container IPs are re-read from Docker at most once per TTL, however many are unknown
         {
-            let cache_read = cache.read().await;
-            for (container_id, ips) in cache_read.iter() {
-                if ips.contains(&src_ip) || ips.contains(&dst_ip) {
-                    log::info!("Matched packet to container {} from cache (IP: {})", container_id, 
-                        if ips.contains(&src_ip) { &src_ip } else { &dst_ip });
-                    return Some(container_id.clone());
-                }
+            let cache = self.container_ip_cache.read().await;
+            if cache.is_fresh(self.container_ip_cache_ttl) {
+                return cache.lookup(&src_ip, &dst_ip);
             }
         }
-        
-        // Cache miss - query Docker API
-        // Refresh cache periodically (every 30 seconds would be reasonable, but for now we'll refresh on miss)
-        log::info!("Cache miss - querying Docker API for container IPs matching {} or {}", src_ip, dst_ip);
-        
+
+        let mut cache = self.container_ip_cache.write().await;
+        // Another capture task may have refreshed the cache while this one waited for the lock
+        if !cache.is_fresh(self.container_ip_cache_ttl) {
+            // On failure the previous mappings stay in use until the next refresh is due,
+            // so an unreachable daemon isn't queried for every packet
+            if let Some(container_by_ip) = self.load_container_ips().await {
+                cache.container_by_ip = container_by_ip;
+            }
+            cache.refreshed_at = Some(std::time::Instant::now());
+        }
+        cache.lookup(&src_ip, &dst_ip)
+    }
+
+    /// IP to container ID for every container Docker lists; None if they can't be listed
+    #[cfg(feature = "network-capture")]
+    async fn load_container_ips(&self) -> Option<HashMap<String, String>> {
         // Add timeout to prevent hanging
         let containers_result = tokio::time::timeout(
             std::time::Duration::from_secs(5),
@@ -973,71 +1670,624 @@ impl NetworkMonitorService {
             }
         };
         
-        if !containers.is_empty() {
-            let mut cache_write = cache.write().await;
-            cache_write.clear(); // Simple refresh strategy - clear and rebuild
-            
-            let mut all_container_ips = Vec::new();
-            
-            let container_count = containers.len();
-            log::info!("Checking {} containers for IP match", container_count);
-            
-            for container in &containers {
-                // Get network info for this container
-                match self.docker_service.get_container_network_info(&container.id).await {
-                    Ok(network_info) => {
-                        let container_ips = network_info.ip_addresses.clone();
-                        log::info!("Container {} (name: {}) has IPs: {:?}", 
-                            &container.id[..12], container.name, container_ips);
-                        all_container_ips.extend(container_ips.iter().cloned());
-                        
-                        // Store in cache
-                        cache_write.insert(container.id.clone(), container_ips.clone());
-                        
-                        // Check if packet IP matches any container IP
-                        for container_ip in &container_ips {
-                            if container_ip == &src_ip || container_ip == &dst_ip {
-                                log::info!("✅ Matched packet to container {} (name: {}) with IP: {}", 
-                                    &container.id[..12], container.name, container_ip);
-                                return Some(container.id.clone());
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        log::debug!("Failed to get network info for container {}: {}", &container.id[..12], e);
+        let mut container_by_ip = HashMap::new();
+        for container in &containers {
+            match self.docker_service.get_container_network_info(&container.id).await {
+                Ok(network_info) => {
+                    log::debug!("Container {} (name: {}) has IPs: {:?}", 
+                        &container.id[..12], container.name, network_info.ip_addresses);
+                    for ip in network_info.ip_addresses {
+                        container_by_ip.insert(ip, container.id.clone());
                     }
                 }
+                Err(e) => {
+                    log::debug!("Failed to get network info for container {}: {}", &container.id[..12], e);
+                }
             }
-            
-            log::warn!("❌ No container IP matched. Packet IPs: {} / {}. Known container IPs ({} total): {:?}", 
-                src_ip, dst_ip, all_container_ips.len(), all_container_ips);
-            
-            // Also log which containers we checked
-            log::debug!("Checked {} containers for IP matching", container_count);
-        } else {
-            log::warn!("Failed to list containers for IP matching");
         }
         
-        None
+        log::info!("Refreshed container IP cache: {} IPs across {} containers", 
+            container_by_ip.len(), containers.len());
+        Some(container_by_ip)
     }
     
-    /// Find container ID for a packet by matching IP addresses (fallback when network-capture feature is disabled)
+    /// Containers owning a packet's addresses (fallback when network-capture feature is disabled)
     #[cfg(not(feature = "network-capture"))]
-    async fn find_container_for_packet(&self, _packet: &[u8]) -> Option<String> {
-        None
+    async fn containers_for_packet(&self, _packet: &[u8]) -> PacketContainers {
+        PacketContainers::default()
     }
 }
 
+/// Why a running capture loop ended
+#[derive(Debug)]
+enum CaptureEnd {
+    /// No packets for this long on an interface that previously had traffic
+    Stalled(std::time::Duration),
+    /// pcap returned an error, e.g. because the interface went away
+    Failed(String),
+}
+
 /// Parsed HTTP request from network packet
 struct ParsedHttpRequest {
     method: String,
     path: String,
-    status: Option<u16>,
-    response_time_ms: Option<f64>,
+    /// Allowlisted headers (see [`NetworkMonitorService::with_captured_headers`])
+    headers: BTreeMap<String, String>,
 }
 
 /// Parsed HTTP response from network packet
 struct ParsedHttpResponse {
     status: Option<u16>,
+    /// `Transfer-Encoding: chunked` body
+    chunked: bool,
+    /// Offset of the body within the packet payload
+    body_start: usize,
+    /// Allowlisted headers (see [`NetworkMonitorService::with_captured_headers`])
+    headers: BTreeMap<String, String>,
+}
+
+
+/// Add a message's allowlisted headers to those captured for its request. Headers already
+/// captured (the request's own) win, and any header that would take the total size of names
+/// and values past [`MAX_CAPTURED_HEADERS_BYTES`] is dropped
+fn merge_captured_headers(captured: &mut BTreeMap<String, String>, headers: BTreeMap<String, String>) {
+    let mut size: usize = captured.iter().map(|(name, value)| name.len() + value.len()).sum();
+    for (name, value) in headers {
+        if captured.contains_key(&name) {
+            continue;
+        }
+        let header_size = name.len() + value.len();
+        if size + header_size > MAX_CAPTURED_HEADERS_BYTES {
+            log::debug!("Dropping captured header {} ({} bytes): size cap reached", name, header_size);
+            continue;
+        }
+        size += header_size;
+        captured.insert(name, value);
+    }
+}
+
+/// IANA protocol number for TCP
+const IP_PROTOCOL_TCP: u8 = 6;
+
+/// IP-layer view of a captured frame
+struct IpLayer {
+    src_ip: IpAddr,
+    dst_ip: IpAddr,
+    /// Upper-layer protocol (IPv4 protocol field, or the last IPv6 next-header)
+    protocol: u8,
+    /// Offset of the transport (TCP/UDP) header within the frame
+    transport_start: usize,
+}
+
+/// Parse the IP header of a captured frame, detecting the IP version from its first nibble.
+/// Linux "any" captures carry a 16-byte SLL header (first two bytes zero) instead of the
+/// 14-byte Ethernet header. IPv6 extension headers are skipped to find the transport header.
+fn parse_ip_layer(data: &[u8]) -> Option<IpLayer> {
+    if data.len() < 2 {
+        return None;
+    }
+    let is_sll = data[0] == 0x00 && data[1] == 0x00;
+    let ip_start = if is_sll { 16 } else { 14 };
+    let header = data.get(ip_start..)?;
+
+    match header.first()? >> 4 {
+        4 => {
+            // IHL (low nibble) is the header length in 32-bit words; more than 5 means options
+            let header_len = (header[0] & 0x0F) as usize * 4;
+            if header_len < 20 || header.len() < header_len {
+                return None;
+            }
+            let src: [u8; 4] = header[12..16].try_into().ok()?;
+            let dst: [u8; 4] = header[16..20].try_into().ok()?;
+            Some(IpLayer {
+                src_ip: IpAddr::V4(Ipv4Addr::from(src)),
+                dst_ip: IpAddr::V4(Ipv4Addr::from(dst)),
+                protocol: header[9],
+                transport_start: ip_start + header_len,
+            })
+        }
+        6 => {
+            // 40-byte fixed header: next header at byte 6, addresses at 8..24 and 24..40
+            if header.len() < 40 {
+                return None;
+            }
+            let src: [u8; 16] = header[8..24].try_into().ok()?;
+            let dst: [u8; 16] = header[24..40].try_into().ok()?;
+            let (protocol, offset) = skip_ipv6_extension_headers(header, header[6], 40)?;
+            Some(IpLayer {
+                src_ip: IpAddr::V6(Ipv6Addr::from(src)),
+                dst_ip: IpAddr::V6(Ipv6Addr::from(dst)),
+                protocol,
+                transport_start: ip_start + offset,
+            })
+        }
+        _ => None,
+    }
 }
 
+/// Walk the IPv6 next-header chain from `offset`, returning the upper-layer protocol and its
+/// offset within `header`. Gives up on ESP / no-next-header and on truncated chains.
+fn skip_ipv6_extension_headers(header: &[u8], mut next_header: u8, mut offset: usize) -> Option<(u8, usize)> {
+    // Bound the walk so a malformed chain can't loop for long
+    for _ in 0..8 {
+        let len = match next_header {
+            // Hop-by-hop, routing, destination options: (hdr ext len + 1) * 8 bytes
+            0 | 43 | 60 => (*header.get(offset + 1)? as usize + 1) * 8,
+            // Fragment header is a fixed 8 bytes
+            44 => 8,
+            // Authentication header: (payload len + 2) * 4 bytes
+            51 => (*header.get(offset + 1)? as usize + 2) * 4,
+            // ESP (encrypted) or no next header: nothing we can parse
+            50 | 59 => return None,
+            protocol => return Some((protocol, offset)),
+        };
+        next_header = *header.get(offset)?;
+        offset += len;
+    }
+    None
+}
+
+/// TCP connection details and payload of a captured frame; None for non-TCP packets and
+/// packets without payload
+fn parse_tcp_packet(data: &[u8]) -> Option<PacketConnection<'_>> {
+    let ip = parse_ip_layer(data)?;
+    if ip.protocol != IP_PROTOCOL_TCP {
+        return None;
+    }
+
+    let src_ip = ip.src_ip.to_string();
+    let dst_ip = ip.dst_ip.to_string();
+
+    // Extract TCP ports
+    let tcp_start = ip.transport_start;
+    if data.len() < tcp_start + 4 {
+        return None;
+    }
+    let src_port = ((data[tcp_start] as u16) << 8) | (data[tcp_start + 1] as u16);
+    let dst_port = ((data[tcp_start + 2] as u16) << 8) | (data[tcp_start + 3] as u16);
+
+    // Create connection ID (bidirectional - same connection regardless of direction)
+    // SocketAddr formatting brackets IPv6 literals, e.g. "[fd00::2]:8080"
+    let src = std::net::SocketAddr::new(ip.src_ip, src_port);
+    let dst = std::net::SocketAddr::new(ip.dst_ip, dst_port);
+    let connection_id = if src_port < dst_port {
+        format!("{}-{}", src, dst)
+    } else {
+        format!("{}-{}", dst, src)
+    };
+
+    // Extract HTTP payload (skip link layer + IP + TCP headers)
+    // TCP header length is in the 4-bit header length field (byte 12 of TCP header, bits 4-7)
+    let tcp_header_len_offset = tcp_start + 12;
+    if data.len() < tcp_header_len_offset + 1 {
+        return None;
+    }
+    let tcp_header_len = ((data[tcp_header_len_offset] & 0xF0) >> 4) * 4;
+    let read_u32 = |offset: usize| {
+        u32::from_be_bytes([
+            data[offset],
+            data[offset + 1],
+            data[offset + 2],
+            data[offset + 3],
+        ])
+    };
+    let seq = read_u32(tcp_start + 4);
+    let ack = read_u32(tcp_start + 8);
+    let http_start = tcp_start + tcp_header_len as usize;
+    
+    if data.len() > http_start {
+        Some(PacketConnection {
+            connection_id,
+            endpoints: PacketEndpoints {
+                src_ip,
+                src_port,
+                dst_ip,
+                dst_port,
+            },
+            payload: &data[http_start..],
+            seq,
+            ack,
+        })
+    } else {
+        None
+    }
+}
+
+/// Whether a payload opens an HTTP request or response (method token or status line)
+/// Remove the chunked responses that have gone `IN_FLIGHT_RESPONSE_TIMEOUT_SECS` without a
+/// new segment, with the connection each was on
+fn take_stalled_responses(
+    in_flight: &mut HashMap<String, InFlightResponse>,
+    now: DateTime<Utc>,
+) -> Vec<(String, InFlightResponse)> {
+    let cutoff = now - chrono::Duration::seconds(IN_FLIGHT_RESPONSE_TIMEOUT_SECS);
+    let stalled: Vec<String> = in_flight
+        .iter()
+        .filter(|(_, entry)| entry.last_segment_at < cutoff)
+        .map(|(key, _)| key.clone())
+        .collect();
+    stalled
+        .into_iter()
+        .filter_map(|key| in_flight.remove(&key).map(|entry| (key, entry)))
+        .collect()
+}
+
+fn looks_like_http_start(payload: &[u8]) -> bool {
+    const STARTS: [&[u8]; 10] = [
+        b"HTTP/", b"GET ", b"POST ", b"PUT ", b"DELETE ", b"PATCH ", b"HEAD ", b"OPTIONS ", b"CONNECT ", b"TRACE ",
+    ];
+    STARTS.iter().any(|start| payload.starts_with(start))
+}
+
+/// Some(true) if `data` holds a complete request or response header block, Some(false)
+/// if it is a valid but incomplete prefix of one, None if it isn't HTTP
+fn http_headers_status(data: &[u8]) -> Option<bool> {
+    let mut headers = [httparse::EMPTY_HEADER; 64];
+    let status = if data.starts_with(b"HTTP/") {
+        httparse::Response::new(&mut headers).parse(data)
+    } else {
+        httparse::Request::new(&mut headers).parse(data)
+    };
+    match status {
+        Ok(httparse::Status::Complete(_)) => Some(true),
+        Ok(httparse::Status::Partial) => Some(false),
+        Err(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(endpoint: &str) -> PendingRequest {
+        PendingRequest {
+            container_id: "unknown".to_string(),
+            container_name: "unknown".to_string(),
+            client_container_id: None,
+            method: "GET".to_string(),
+            endpoint: endpoint.to_string(),
+            request_timestamp: Utc::now(),
+            endpoints: PacketEndpoints {
+                src_ip: "172.17.0.2".to_string(),
+                src_port: 40000,
+                dst_ip: "172.17.0.3".to_string(),
+                dst_port: 80,
+            },
+            headers: BTreeMap::new(),
+        }
+    }
+
+    fn key(connection_id: &str, request_end_seq: u32) -> PendingKey {
+        PendingKey {
+            connection_id: connection_id.to_string(),
+            request_end_seq,
+        }
+    }
+
+    #[test]
+    fn reused_tuple_does_not_pair_with_stale_request() {
+        let connection = "172.17.0.2:40000-172.17.0.3:80";
+        let mut pending_map = HashMap::new();
+        // Left over from an earlier connection on the same tuple, far away in sequence space
+        pending_map.insert(key(connection, 1_000), pending("/stale"));
+
+        // The new connection's response acks its own request, which is not pending (yet)
+        assert_eq!(NetworkMonitorService::find_pending_key(&pending_map, connection, 3_000_000_500), None);
+
+        pending_map.insert(key(connection, 3_000_000_000), pending("/fresh"));
+        let found = NetworkMonitorService::find_pending_key(&pending_map, connection, 3_000_000_500);
+        assert_eq!(found, Some(key(connection, 3_000_000_000)));
+        assert_eq!(pending_map[&found.unwrap()].endpoint, "/fresh");
+    }
+
+    #[test]
+    fn response_pairs_with_closest_preceding_request() {
+        let connection = "172.17.0.2:40000-172.17.0.3:80";
+        let mut pending_map = HashMap::new();
+        pending_map.insert(key(connection, 5_000), pending("/first"));
+        pending_map.insert(key(connection, 6_000), pending("/second"));
+        pending_map.insert(key("172.17.0.2:40001-172.17.0.3:80", 6_100), pending("/other"));
+
+        assert_eq!(NetworkMonitorService::find_pending_key(&pending_map, connection, 5_500), Some(key(connection, 5_000)));
+        assert_eq!(NetworkMonitorService::find_pending_key(&pending_map, connection, 6_200), Some(key(connection, 6_000)));
+        // An ack before every pending request's end acknowledges none of them
+        assert_eq!(NetworkMonitorService::find_pending_key(&pending_map, connection, 4_000), None);
+    }
+
+    #[test]
+    fn pairing_survives_sequence_wraparound() {
+        let connection = "172.17.0.2:40000-172.17.0.3:80";
+        let mut pending_map = HashMap::new();
+        pending_map.insert(key(connection, u32::MAX - 10), pending("/wrapped"));
+
+        assert_eq!(NetworkMonitorService::find_pending_key(&pending_map, connection, 20), Some(key(connection, u32::MAX - 10)));
+    }
+
+    /// Ethernet header carrying the given ethertype
+    fn ethernet(ethertype: u16) -> Vec<u8> {
+        let mut frame = vec![0x02, 0x42, 0xac, 0x11, 0x00, 0x03, 0x02, 0x42, 0xac, 0x11, 0x00, 0x02];
+        frame.extend_from_slice(&ethertype.to_be_bytes());
+        frame
+    }
+
+    /// 20-byte TCP header without options
+    fn tcp_header(src_port: u16, dst_port: u16, seq: u32, ack: u32) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend_from_slice(&src_port.to_be_bytes());
+        header.extend_from_slice(&dst_port.to_be_bytes());
+        header.extend_from_slice(&seq.to_be_bytes());
+        header.extend_from_slice(&ack.to_be_bytes());
+        // Data offset 5 words, PSH+ACK, window, checksum, urgent pointer
+        header.extend_from_slice(&[0x50, 0x18, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00]);
+        header
+    }
+
+    #[test]
+    fn parses_ipv4_header_with_options() {
+        let mut frame = ethernet(0x0800);
+        // IHL 6: a 24-byte header whose last word is four NOP options
+        frame.extend_from_slice(&[0x46, 0x00, 0x00, 0x00, 0x00, 0x01, 0x40, 0x00, 0x40, IP_PROTOCOL_TCP, 0x00, 0x00]);
+        frame.extend_from_slice(&[172, 17, 0, 2]);
+        frame.extend_from_slice(&[172, 17, 0, 3]);
+        frame.extend_from_slice(&[0x01, 0x01, 0x01, 0x01]);
+        frame.extend_from_slice(&tcp_header(40000, 80, 1_000, 2_000));
+        frame.extend_from_slice(b"GET / HTTP/1.1\r\n\r\n");
+
+        let ip = parse_ip_layer(&frame).unwrap();
+        assert_eq!(ip.transport_start, 14 + 24);
+
+        let connection = parse_tcp_packet(&frame).unwrap();
+        assert_eq!(connection.endpoints.src_ip, "172.17.0.2");
+        assert_eq!(connection.endpoints.dst_ip, "172.17.0.3");
+        assert_eq!(connection.endpoints.src_port, 40000);
+        assert_eq!(connection.endpoints.dst_port, 80);
+        assert_eq!((connection.seq, connection.ack), (1_000, 2_000));
+        assert_eq!(connection.payload, b"GET / HTTP/1.1\r\n\r\n");
+    }
+
+    #[test]
+    fn rejects_ipv4_header_shorter_than_its_ihl() {
+        let mut frame = ethernet(0x0800);
+        frame.extend_from_slice(&[0x4f, 0x00, 0x00, 0x00, 0x00, 0x01, 0x40, 0x00, 0x40, IP_PROTOCOL_TCP, 0x00, 0x00]);
+        frame.extend_from_slice(&[172, 17, 0, 2, 172, 17, 0, 3]);
+
+        assert!(parse_ip_layer(&frame).is_none());
+    }
+
+    /// 40-byte IPv6 fixed header from fd00::2 to fd00::3
+    fn ipv6_header(next_header: u8, payload_len: u16) -> Vec<u8> {
+        let mut header = vec![0x60, 0x00, 0x00, 0x00];
+        header.extend_from_slice(&payload_len.to_be_bytes());
+        header.extend_from_slice(&[next_header, 64]);
+        header.extend_from_slice(&"fd00::2".parse::<Ipv6Addr>().unwrap().octets());
+        header.extend_from_slice(&"fd00::3".parse::<Ipv6Addr>().unwrap().octets());
+        header
+    }
+
+    #[test]
+    fn parses_ipv6_tcp_packet() {
+        let payload = b"HTTP/1.1 204 No Content\r\n\r\n";
+        // Linux "any" capture: 16-byte SLL header starting with two zero bytes
+        let mut frame = vec![0x00, 0x00, 0x00, 0x01, 0x00, 0x06, 0x02, 0x42, 0xac, 0x11, 0x00, 0x03, 0x00, 0x00, 0x86, 0xdd];
+        frame.extend_from_slice(&ipv6_header(IP_PROTOCOL_TCP, (20 + payload.len()) as u16));
+        frame.extend_from_slice(&tcp_header(8080, 51000, 7, 9));
+        frame.extend_from_slice(payload);
+
+        let connection = parse_tcp_packet(&frame).unwrap();
+        assert_eq!(connection.endpoints.src_ip, "fd00::2");
+        assert_eq!(connection.endpoints.dst_ip, "fd00::3");
+        assert_eq!(connection.endpoints.src_port, 8080);
+        assert_eq!(connection.endpoints.dst_port, 51000);
+        assert_eq!(connection.connection_id, "[fd00::2]:8080-[fd00::3]:51000");
+        assert_eq!((connection.seq, connection.ack), (7, 9));
+        assert_eq!(connection.payload, payload);
+    }
+
+    #[test]
+    fn skips_ipv6_extension_headers() {
+        let mut frame = ethernet(0x86dd);
+        // Hop-by-hop options (8 bytes) in front of the TCP header
+        frame.extend_from_slice(&ipv6_header(0, 8 + 20 + 4));
+        frame.extend_from_slice(&[IP_PROTOCOL_TCP, 0, 0x01, 0x04, 0, 0, 0, 0]);
+        frame.extend_from_slice(&tcp_header(51000, 80, 1, 0));
+        frame.extend_from_slice(b"GET ");
+
+        let ip = parse_ip_layer(&frame).unwrap();
+        assert_eq!(ip.protocol, IP_PROTOCOL_TCP);
+        assert_eq!(ip.transport_start, 14 + 40 + 8);
+
+        let connection = parse_tcp_packet(&frame).unwrap();
+        assert_eq!(connection.endpoints.src_port, 51000);
+        assert_eq!(connection.endpoints.dst_port, 80);
+        assert_eq!(connection.payload, b"GET ");
+    }
+
+    fn containers(src: Option<&str>, dst: Option<&str>) -> PacketContainers {
+        PacketContainers {
+            src: src.map(str::to_string),
+            dst: dst.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn request_between_containers_keeps_both_ends() {
+        let request = containers(Some("caller"), Some("callee"));
+        assert_eq!(request.direction(), PacketDirection::ToContainer);
+        assert_eq!(request.request_owner().map(String::as_str), Some("callee"));
+        assert_eq!(request.request_client().map(String::as_str), Some("caller"));
+
+        // Its response travels the other way and is recorded for the same container
+        let response = containers(Some("callee"), Some("caller"));
+        assert_eq!(response.response_owner().map(String::as_str), Some("callee"));
+    }
+
+    #[test]
+    fn outbound_call_is_recorded_for_the_sender() {
+        let request = containers(Some("caller"), None);
+        assert_eq!(request.direction(), PacketDirection::FromContainer);
+        assert_eq!(request.request_owner().map(String::as_str), Some("caller"));
+        assert_eq!(request.request_client().map(String::as_str), Some("caller"));
+
+        let response = containers(None, Some("caller"));
+        assert_eq!(response.direction(), PacketDirection::ToContainer);
+        assert_eq!(response.response_owner().map(String::as_str), Some("caller"));
+    }
+
+    #[test]
+    fn request_from_outside_docker_has_no_client_container() {
+        let request = containers(None, Some("callee"));
+        assert_eq!(request.request_owner().map(String::as_str), Some("callee"));
+        assert_eq!(request.request_client(), None);
+
+        let unknown = containers(None, None);
+        assert_eq!(unknown.direction(), PacketDirection::Unknown);
+        assert_eq!(unknown.request_owner(), None);
+        assert_eq!(unknown.response_owner(), None);
+    }
+
+    fn in_flight(last_segment_at: DateTime<Utc>) -> InFlightResponse {
+        InFlightResponse {
+            pending: pending("/download"),
+            container_id: "unknown".to_string(),
+            container_name: "unknown".to_string(),
+            status_code: Some(200),
+            body: ChunkedBody {
+                decoder: ChunkedDecoder::new(),
+                next_seq: 0,
+                responder_ip: "172.17.0.3".to_string(),
+                responder_port: 80,
+            },
+            last_segment_at,
+        }
+    }
+
+    #[test]
+    fn sweep_takes_only_stalled_responses() {
+        let now = Utc::now();
+        let mut responses = HashMap::from([
+            ("stalled".to_string(), in_flight(now - chrono::Duration::seconds(IN_FLIGHT_RESPONSE_TIMEOUT_SECS + 1))),
+            ("active".to_string(), in_flight(now - chrono::Duration::seconds(1))),
+        ]);
+
+        let stalled = take_stalled_responses(&mut responses, now);
+        assert_eq!(stalled.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>(), ["stalled"]);
+        assert_eq!(responses.keys().collect::<Vec<_>>(), ["active"]);
+
+        // Later, with no new segment, the other one stalls as well
+        let later = now + chrono::Duration::seconds(IN_FLIGHT_RESPONSE_TIMEOUT_SECS);
+        assert_eq!(take_stalled_responses(&mut responses, later).len(), 1);
+        assert!(responses.is_empty());
+    }
+
+    fn ip_cache(refreshed_ago: Option<std::time::Duration>) -> ContainerIpCache {
+        ContainerIpCache {
+            container_by_ip: HashMap::from([
+                ("172.17.0.2".to_string(), "api".to_string()),
+                ("172.17.0.3".to_string(), "db".to_string()),
+            ]),
+            refreshed_at: refreshed_ago.map(|ago| std::time::Instant::now() - ago),
+        }
+    }
+
+    #[test]
+    fn ip_cache_is_fresh_only_within_the_ttl() {
+        let ttl = std::time::Duration::from_secs(30);
+        assert!(ip_cache(Some(std::time::Duration::from_secs(1))).is_fresh(ttl));
+        assert!(!ip_cache(Some(std::time::Duration::from_secs(31))).is_fresh(ttl));
+        assert!(!ip_cache(None).is_fresh(ttl));
+    }
+
+    #[test]
+    fn ip_cache_lookup_finds_both_ends() {
+        let cache = ip_cache(Some(std::time::Duration::ZERO));
+
+        let containers = cache.lookup("172.17.0.3", "172.17.0.2");
+        assert_eq!(containers.src.as_deref(), Some("db"));
+        assert_eq!(containers.dst.as_deref(), Some("api"));
+
+        let containers = cache.lookup("10.0.0.1", "172.17.0.2");
+        assert_eq!(containers.src, None);
+        assert_eq!(containers.dst.as_deref(), Some("api"));
+
+        let containers = cache.lookup("10.0.0.1", "10.0.0.2");
+        assert_eq!(containers.direction(), PacketDirection::Unknown);
+    }
+
+    #[test]
+    fn ip_cache_ttl_is_clamped() {
+        let docker = bollard::Docker::connect_with_http("http://127.0.0.1:9", 1, bollard::API_DEFAULT_VERSION).unwrap();
+        let service = NetworkMonitorService::new(Arc::new(DockerService::with_client(docker)));
+
+        let service = service.with_container_ip_cache_ttl(std::time::Duration::ZERO);
+        assert_eq!(service.container_ip_cache_ttl, MIN_CONTAINER_IP_CACHE_TTL);
+
+        let service = service.with_container_ip_cache_ttl(std::time::Duration::from_secs(5));
+        assert_eq!(service.container_ip_cache_ttl, std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn credential_headers_are_never_captured() {
+        let docker = bollard::Docker::connect_with_http("http://127.0.0.1:9", 1, bollard::API_DEFAULT_VERSION).unwrap();
+        let service = NetworkMonitorService::new(Arc::new(DockerService::with_client(docker)))
+            .with_captured_headers(
+                ["Host", "Authorization", "COOKIE", " set-cookie ", "Proxy-Authorization", "X-Request-Id"]
+                    .map(String::from)
+                    .to_vec(),
+            );
+        assert_eq!(service.captured_headers, vec!["host", "x-request-id"]);
+
+        let headers = [
+            httparse::Header { name: "Host", value: b"api:8080" },
+            httparse::Header { name: "Authorization", value: b"Bearer secret" },
+            httparse::Header { name: "Cookie", value: b"session=secret" },
+        ];
+        let extracted = service.extract_headers(&headers);
+        assert_eq!(extracted.len(), 1);
+        assert_eq!(extracted.get("host").map(String::as_str), Some("api:8080"));
+    }
+
+    #[tokio::test]
+    async fn capture_stats_count_matched_unmatched_and_expired_requests() {
+        let docker = bollard::Docker::connect_with_http("http://127.0.0.1:9", 1, bollard::API_DEFAULT_VERSION).unwrap();
+        let service = NetworkMonitorService::new(Arc::new(DockerService::with_client(docker)));
+        let connection = "172.17.0.2:40000-172.17.0.3:80";
+        let api = "0123456789abcdef";
+        let request = |path: &str| ParsedHttpRequest {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            headers: BTreeMap::new(),
+        };
+        let response = || ParsedHttpResponse {
+            status: Some(200),
+            chunked: false,
+            body_start: 0,
+            headers: BTreeMap::new(),
+        };
+        let endpoints = || pending("/").endpoints;
+
+        // Request to a known container answered on the same connection
+        service.handle_http_request(key(connection, 100), api, "api", None, request("/users"), endpoints()).await;
+        service.handle_http_response(connection, 100, api, "api", response(), None).await;
+        // Neither end belongs to a container
+        service.handle_http_request(key(connection, 200), "unknown", "unknown", None, request("/"), endpoints()).await;
+        service.handle_http_response(connection, 200, "unknown", "unknown", response(), None).await;
+        // A response nothing asked for
+        service.handle_http_response(connection, 300, api, "api", response(), None).await;
+        // Never answered
+        service.handle_http_request(key(connection, 400), api, "api", None, request("/slow"), endpoints()).await;
+        {
+            let mut pending_map = service.pending_requests.write().await;
+            let later = Utc::now() + chrono::Duration::seconds(PENDING_REQUEST_TIMEOUT_SECS + 1);
+            service.expire_pending_requests(&mut pending_map, later);
+        }
+
+        let stats = service.get_capture_stats();
+        assert_eq!(stats.requests_matched, 1);
+        assert_eq!(stats.requests_unmatched, 1);
+        assert_eq!(stats.responses_without_request, 1);
+        assert_eq!(stats.requests_expired, 1);
+        let captured = service.captured_requests.read().await;
+        let endpoints: Vec<&str> = captured[api].iter().map(|request| request.endpoint.as_str()).collect();
+        assert_eq!(endpoints, ["/users"]);
+    }
+}
@@ -0,0 +1,97 @@
+use anyhow::{Result, bail};
+use eyes_devine_shared::Alert;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Waits between attempts of a failed delivery; the delivery is given up after the last one
+const DELIVERY_RETRY_DELAYS: [Duration; 3] = [
+    Duration::from_secs(1),
+    Duration::from_secs(5),
+    Duration::from_secs(15),
+];
+
+/// Time allowed for one webhook request
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Posts firing alerts to a generic JSON webhook and/or a Slack incoming webhook. Deliveries
+/// run in the background with retries, so a slow or failing endpoint never holds up alert
+/// evaluation. Without any URL configured every call is a no-op
+pub struct NotificationService {
+    client: reqwest::Client,
+    webhook_url: Option<String>,
+    slack_webhook_url: Option<String>,
+}
+
+impl NotificationService {
+    pub fn new(webhook_url: Option<String>, slack_webhook_url: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            webhook_url,
+            slack_webhook_url,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.webhook_url.is_some() || self.slack_webhook_url.is_some()
+    }
+
+    /// Deliver a newly firing alert to every configured webhook
+    pub fn notify(self: &Arc<Self>, alert: &Alert) {
+        if let Some(url) = &self.webhook_url {
+            let payload = serde_json::json!({
+                "event": "alert_firing",
+                "alert": alert,
+            });
+            self.spawn_delivery("webhook", url.clone(), payload);
+        }
+        if let Some(url) = &self.slack_webhook_url {
+            let payload = serde_json::json!({ "text": slack_message(alert) });
+            self.spawn_delivery("Slack", url.clone(), payload);
+        }
+    }
+
+    fn spawn_delivery(self: &Arc<Self>, target: &'static str, url: String, payload: serde_json::Value) {
+        let service = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut attempt = 0;
+            loop {
+                match service.post(&url, &payload).await {
+                    Ok(()) => return,
+                    Err(e) if attempt < DELIVERY_RETRY_DELAYS.len() => {
+                        log::warn!("{} notification failed (attempt {}): {}", target, attempt + 1, e);
+                        tokio::time::sleep(DELIVERY_RETRY_DELAYS[attempt]).await;
+                        attempt += 1;
+                    }
+                    Err(e) => {
+                        log::error!("Giving up on {} notification after {} attempts: {}", target, attempt + 1, e);
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    async fn post(&self, url: &str, payload: &serde_json::Value) -> Result<()> {
+        let response = self.client.post(url).json(payload).send().await?;
+        if !response.status().is_success() {
+            bail!("Webhook responded with {}", response.status());
+        }
+        Ok(())
+    }
+}
+
+fn slack_message(alert: &Alert) -> String {
+    format!(
+        ":rotating_light: *{}* is firing for `{}`: {} is {:.2} (threshold {}) at {}",
+        alert.rule_name,
+        alert.container_name,
+        alert.metric.as_str(),
+        alert.value,
+        alert.threshold,
+        alert.timestamp.to_rfc3339(),
+    )
+}
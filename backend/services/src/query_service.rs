@@ -1,25 +1,260 @@
-use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait, QueryOrder, QuerySelect};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait, FromQueryResult, QueryFilter, ColumnTrait, QueryOrder, QuerySelect, Statement, TransactionTrait};
 use chrono::{DateTime, Utc, FixedOffset};
 use anyhow::Result;
-use eyes_devine_shared::{ContainerStats, ContainerInfo, ImageInfo, HttpRequest};
-use crate::entity::{container_stats, container_info, docker_images, image_versions, http_requests};
+use eyes_devine_shared::{FleetStatsHistory, FleetStatsPoint, AggregatedContainerStats, GapfilledContainerStats, NetworkRate, ContainerLog, ContainerStats, ContainerInfo, ComposeProject, ContainerState, ContainerStatusChange, DiskUsage, ContainerRunState, ContainerHealth, ImageInfo, ImageSizePoint, ImageSizeTrend, ImageContainerUsage, StaleImage, StaleImageReport, HttpRequest, HttpStatusBreakdown, HttpRequestSummary, HttpLatencyPercentiles, HttpEndpointStats, ResourceRecommendation, ServiceMap, UsagePercentiles};
+use crate::entity::{container_disk_usage, container_stats, container_status_history, container_logs, container_info, docker_images, image_versions, http_requests, service_map_snapshots, total_stats};
+use std::collections::BTreeMap;
 
 pub struct QueryService {
     db: DatabaseConnection,
 }
 
+/// Server-side filters for [`QueryService::find_containers`]; all given filters must match
+#[derive(Debug, Clone, Default)]
+pub struct ContainerFilter {
+    /// Case-insensitive substring of the container name
+    pub name: Option<String>,
+    /// Case-insensitive substring of the image reference
+    pub image: Option<String>,
+    pub state: Option<ContainerRunState>,
+    /// Docker labels: `(key, Some(value))` must equal, `(key, None)` must be present
+    pub labels: Vec<(String, Option<String>)>,
+    /// Keep only the latest container per name instead of per id
+    pub latest_per_name: bool,
+}
+
+/// Captured HTTP requests grouped by the container that sent them, the container they were
+/// attributed to, the call and the `Host` header (when captured, see `CAPTURE_HTTP_HEADERS`)
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct ObservedHttpCall {
+    pub client_container_id: String,
+    pub container_id: String,
+    pub method: String,
+    pub endpoint: String,
+    pub http_status: i16,
+    pub host: Option<String>,
+    pub request_count: i64,
+}
+
+/// Which stored series a per-container query reads: one container id, or every container
+/// that has run under a name (short-lived job containers get a new id on each run)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContainerSelector {
+    Id(String),
+    Name(String),
+}
+
+impl ContainerSelector {
+    pub fn by_name(name: &str) -> Self {
+        Self::Name(name.trim_start_matches('/').to_string())
+    }
+
+    pub fn value(&self) -> &str {
+        match self {
+            Self::Id(value) | Self::Name(value) => value,
+        }
+    }
+
+    /// Column matched in tables that carry both `container_id` and `container_name`
+    pub fn column_name(&self) -> &'static str {
+        match self {
+            Self::Id(_) => "container_id",
+            Self::Name(_) => "container_name",
+        }
+    }
+
+    /// Cache key component, distinct for ids and names
+    pub fn cache_key(&self) -> String {
+        match self {
+            Self::Id(id) => id.clone(),
+            Self::Name(name) => format!("name={}", name),
+        }
+    }
+}
+
+/// Position in a newest-first listing. Rows strictly older than `timestamp` come after it, and
+/// with an `id` so do rows at the same timestamp with a lower id, so rows sharing a timestamp
+/// are neither repeated nor skipped across pages. Written as `{timestamp}_{id}`; a bare RFC 3339
+/// timestamp is accepted too (e.g. now, for the first page)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct KeysetCursor {
+    pub timestamp: DateTime<Utc>,
+    pub id: Option<i64>,
+}
+
+impl KeysetCursor {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (timestamp, id) = match value.rsplit_once('_') {
+            Some((timestamp, id)) => (timestamp, Some(id.parse().ok()?)),
+            None => (value, None),
+        };
+        let timestamp = DateTime::parse_from_rfc3339(timestamp).ok()?.with_timezone(&Utc);
+        Some(Self { timestamp, id })
+    }
+
+    /// `WHERE` condition selecting the rows after the cursor, with the timestamp bound to
+    /// `$timestamp_param` and the id (if any) to the next parameter
+    fn condition(&self, timestamp_param: usize) -> String {
+        match self.id {
+            Some(_) => format!("(timestamp, id) < (${}, ${})", timestamp_param, timestamp_param + 1),
+            None => format!("timestamp < ${}", timestamp_param),
+        }
+    }
+
+    fn values(&self) -> Vec<sea_orm::Value> {
+        let mut values = vec![self.timestamp.with_timezone(&FixedOffset::east_opt(0).unwrap()).into()];
+        values.extend(self.id.map(sea_orm::Value::from));
+        values
+    }
+}
+
+impl std::fmt::Display for KeysetCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let timestamp = self.timestamp.to_rfc3339_opts(chrono::SecondsFormat::Micros, true);
+        match self.id {
+            Some(id) => write!(f, "{}_{}", timestamp, id),
+            None => f.write_str(&timestamp),
+        }
+    }
+}
+
+/// What [`QueryService::get_top_containers`] ranks containers by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopMetric {
+    Cpu,
+    Memory,
+    /// Received plus sent bytes per second over [`TOP_NETWORK_RATE_WINDOW`]
+    Network,
+}
+
+impl TopMetric {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "cpu" => Some(Self::Cpu),
+            "memory" => Some(Self::Memory),
+            "network" => Some(Self::Network),
+            _ => None,
+        }
+    }
+
+    fn order_expression(self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu_usage_percent",
+            Self::Memory => "memory_usage_bytes",
+            Self::Network => TOP_NETWORK_RATE_EXPRESSION,
+        }
+    }
+}
+
+/// How far before a container's latest sample [`TopMetric::Network`] looks for the sample
+/// its throughput is measured against
+const TOP_NETWORK_RATE_WINDOW: chrono::TimeDelta = chrono::TimeDelta::minutes(5);
+
+/// Earliest sample of each `latest` row's container within `$3` (the rate window) before it
+const TOP_NETWORK_BASELINE_JOIN: &str = "LEFT JOIN LATERAL (\
+    SELECT timestamp, network_rx_bytes, network_tx_bytes FROM container_stats \
+    WHERE container_id = latest.container_id \
+    AND timestamp >= latest.timestamp - $3::interval AND timestamp < latest.timestamp \
+    ORDER BY timestamp LIMIT 1) baseline ON true";
+
+/// Bytes/sec between the baseline and the latest sample. The counters are cumulative, so a
+/// drop (the container restarted) counts as no traffic, like a container without a baseline
+const TOP_NETWORK_RATE_EXPRESSION: &str = "COALESCE(GREATEST(\
+    (latest.network_rx_bytes + latest.network_tx_bytes) - (baseline.network_rx_bytes + baseline.network_tx_bytes), 0) \
+    / NULLIF(EXTRACT(EPOCH FROM latest.timestamp - baseline.timestamp), 0), 0)";
+
+/// Latest `container_stats` row of every container known in `container_info`, in one query.
+/// The time bound (`$1`, [`LATEST_STATS_WINDOW`] ago) lets TimescaleDB skip all but the newest
+/// chunks (containers with no sample in the window have no latest stats); within them the
+/// DISTINCT ON becomes a skip scan over the (container_id, timestamp DESC) index
+const LATEST_STATS_PER_CONTAINER_SQL: &str = "SELECT DISTINCT ON (container_id) * FROM container_stats \
+    WHERE timestamp > $1 \
+    AND container_id IN (SELECT container_id FROM container_info) \
+    ORDER BY container_id, timestamp DESC";
+
+/// How far back [`LATEST_STATS_PER_CONTAINER_SQL`] looks for a container's latest sample
+const LATEST_STATS_WINDOW: chrono::TimeDelta = chrono::TimeDelta::days(1);
+
+/// Hypertables whose retention policy can be changed at runtime with
+/// [`QueryService::set_retention_policy`]
+pub const RETENTION_HYPERTABLES: &[&str] = &[
+    "container_stats",
+    "container_logs",
+    "image_versions",
+    "http_requests",
+    "total_stats",
+    "container_disk_usage",
+    "container_status_history",
+];
+
+/// Units accepted in a retention interval such as `14 days`
+const RETENTION_INTERVAL_UNITS: &[&str] = &[
+    "hour", "hours", "day", "days", "week", "weeks", "month", "months", "year", "years",
+];
+
 impl QueryService {
     pub fn new(db: DatabaseConnection) -> Self {
         Self { db }
     }
 
+    /// Reject tables outside [`RETENTION_HYPERTABLES`] and intervals other than
+    /// `<positive whole number> <unit>`; the message is meant for the client
+    pub fn validate_retention(table: &str, interval: Option<&str>) -> std::result::Result<(), String> {
+        if !RETENTION_HYPERTABLES.contains(&table) {
+            return Err(format!(
+                "Unknown table '{}' (expected one of: {})",
+                table,
+                RETENTION_HYPERTABLES.join(", ")
+            ));
+        }
+        if let Some(interval) = interval {
+            let valid = match interval.split_whitespace().collect::<Vec<_>>().as_slice() {
+                [amount, unit] => {
+                    amount.parse::<u32>().is_ok_and(|n| n > 0)
+                        && RETENTION_INTERVAL_UNITS.contains(&unit.to_lowercase().as_str())
+                }
+                _ => false,
+            };
+            if !valid {
+                return Err(format!(
+                    "Invalid interval '{}' (expected e.g. '12 hours', '14 days' or '6 months')",
+                    interval
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the TimescaleDB retention policy of a hypertable: chunks older than `interval`
+    /// are dropped from then on, or never when `interval` is None. Check the arguments with
+    /// [`Self::validate_retention`] first
+    pub async fn set_retention_policy(&self, table: &str, interval: Option<&str>) -> Result<()> {
+        let txn = self.db.begin().await?;
+        txn.execute_raw(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT remove_retention_policy($1::regclass, if_exists => TRUE)",
+            [table.into()],
+        ))
+        .await?;
+        if let Some(interval) = interval {
+            txn.execute_raw(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "SELECT add_retention_policy($1::regclass, $2::interval)",
+                [table.into(), interval.into()],
+            ))
+            .await?;
+        }
+        txn.commit().await?;
+        Ok(())
+    }
+
     /// Get latest stats for a specific container
     pub async fn get_latest_container_stats(
         &self,
-        container_id: &str,
+        selector: &ContainerSelector,
     ) -> Result<Option<ContainerStats>> {
         let stats = container_stats::Entity::find()
-            .filter(container_stats::Column::ContainerId.eq(container_id))
+            .filter(Self::stats_filter(selector))
             .order_by_desc(container_stats::Column::Timestamp)
             .limit(1)
             .one(&self.db)
@@ -28,36 +263,128 @@ impl QueryService {
         Ok(stats.map(|s| Self::entity_to_container_stats(&s)))
     }
 
+    /// Most recent disk usage sample of a container
+    pub async fn get_latest_disk_usage(&self, selector: &ContainerSelector) -> Result<Option<DiskUsage>> {
+        let filter = match selector {
+            ContainerSelector::Id(id) => container_disk_usage::Column::ContainerId.eq(id.as_str()),
+            ContainerSelector::Name(name) => container_disk_usage::Column::ContainerName.eq(name.as_str()),
+        };
+        let usage = container_disk_usage::Entity::find()
+            .filter(filter)
+            .order_by_desc(container_disk_usage::Column::Timestamp)
+            .one(&self.db)
+            .await?;
+
+        Ok(usage.map(|usage| DiskUsage {
+            container_id: usage.container_id,
+            container_name: usage.container_name,
+            size_rw_bytes: usage.size_rw_bytes.map(|size| size as u64),
+            size_root_fs_bytes: usage.size_root_fs_bytes.map(|size| size as u64),
+            timestamp: usage.timestamp.with_timezone(&Utc),
+        }))
+    }
+
+    /// Recorded state changes of a container over `from`..`to`, newest first
+    pub async fn get_container_status_history(
+        &self,
+        selector: &ContainerSelector,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: Option<u64>,
+    ) -> Result<Vec<ContainerStatusChange>> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+        let filter = match selector {
+            ContainerSelector::Id(id) => container_status_history::Column::ContainerId.eq(id.as_str()),
+            ContainerSelector::Name(name) => container_status_history::Column::ContainerName.eq(name.as_str()),
+        };
+        let mut query = container_status_history::Entity::find().filter(filter);
+
+        if let Some(from_dt) = from {
+            query = query.filter(container_status_history::Column::Timestamp.gte(from_dt.with_timezone(&fixed_offset)));
+        }
+        if let Some(to_dt) = to {
+            query = query.filter(container_status_history::Column::Timestamp.lte(to_dt.with_timezone(&fixed_offset)));
+        }
+
+        query = query.order_by_desc(container_status_history::Column::Timestamp);
+        if let Some(limit_val) = limit {
+            query = query.limit(limit_val);
+        }
+
+        let rows = query.all(&self.db).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ContainerStatusChange {
+                container_id: row.container_id,
+                container_name: row.container_name,
+                status: row.status,
+                state: row.state.as_deref().and_then(ContainerRunState::parse).map(|state| ContainerState {
+                    state,
+                    exit_code: row.exit_code,
+                    health: row.health.as_deref().and_then(ContainerHealth::parse),
+                }),
+                timestamp: row.timestamp.with_timezone(&Utc),
+            })
+            .collect())
+    }
+
     /// Get latest stats for all containers
     pub async fn get_latest_all_container_stats(&self) -> Result<Vec<ContainerStats>> {
-        // Get distinct container IDs first
-        let containers = container_info::Entity::find()
-            .order_by_desc(container_info::Column::CollectedAt)
+        // One round trip for every container, rather than one query per container
+        let sql = format!("SELECT * FROM ({}) latest ORDER BY container_name", LATEST_STATS_PER_CONTAINER_SQL);
+        let stats = container_stats::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(DbBackend::Postgres, sql, [Self::latest_stats_since()]))
             .all(&self.db)
             .await?;
 
-        let mut latest_stats = Vec::new();
-        for container in containers {
-            if let Ok(Some(stats)) = self.get_latest_container_stats(&container.container_id).await {
-                latest_stats.push(stats);
+        Ok(stats.iter().map(Self::entity_to_container_stats).collect())
+    }
+
+    /// Lower time bound (`$1`) of [`LATEST_STATS_PER_CONTAINER_SQL`]
+    fn latest_stats_since() -> sea_orm::Value {
+        (Utc::now() - LATEST_STATS_WINDOW).with_timezone(&FixedOffset::east_opt(0).unwrap()).into()
+    }
+
+    /// The `n` containers with the highest latest `metric`, highest first. Network is ranked by
+    /// throughput over the [`TOP_NETWORK_RATE_WINDOW`] up to each container's latest sample
+    pub async fn get_top_containers(&self, metric: TopMetric, n: u64) -> Result<Vec<ContainerStats>> {
+        let mut values = vec![Self::latest_stats_since(), (n as i64).into()];
+        let join = match metric {
+            TopMetric::Network => {
+                values.push(format!("{} seconds", TOP_NETWORK_RATE_WINDOW.num_seconds()).into());
+                TOP_NETWORK_BASELINE_JOIN
             }
-        }
+            TopMetric::Cpu | TopMetric::Memory => "",
+        };
+        let sql = format!(
+            "SELECT latest.* FROM ({}) latest {} ORDER BY {} DESC, latest.container_name LIMIT $2",
+            LATEST_STATS_PER_CONTAINER_SQL,
+            join,
+            metric.order_expression()
+        );
+        let stats = container_stats::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(DbBackend::Postgres, sql, values))
+            .all(&self.db)
+            .await?;
 
-        Ok(latest_stats)
+        Ok(stats.iter().map(Self::entity_to_container_stats).collect())
     }
 
-    /// Get historical stats for a container within a time range
+    /// Get historical stats for a container (or every container run under a name) within a time
+    /// range, newest first. `before` is an exclusive cursor for paging back through the range
     pub async fn get_container_stats_history(
         &self,
-        container_id: &str,
+        selector: &ContainerSelector,
         from: Option<DateTime<Utc>>,
         to: Option<DateTime<Utc>>,
+        before: Option<KeysetCursor>,
         limit: Option<u64>,
-    ) -> Result<Vec<ContainerStats>> {
+    ) -> Result<(Vec<ContainerStats>, Option<KeysetCursor>)> {
         let fixed_offset = FixedOffset::east_opt(0).unwrap();
         
         let mut query = container_stats::Entity::find()
-            .filter(container_stats::Column::ContainerId.eq(container_id));
+            .filter(Self::stats_filter(selector));
 
         if let Some(from_dt) = from {
             let from_tz = from_dt.with_timezone(&fixed_offset);
@@ -69,25 +396,265 @@ impl QueryService {
             query = query.filter(container_stats::Column::Timestamp.lte(to_tz));
         }
 
-        query = query.order_by_desc(container_stats::Column::Timestamp);
+        if let Some(before) = before {
+            query = query.filter(sea_orm::sea_query::Expr::cust_with_values(before.condition(1), before.values()));
+        }
+
+        // The id breaks ties between samples sharing a timestamp, so cursors are stable
+        query = query
+            .order_by_desc(container_stats::Column::Timestamp)
+            .order_by_desc(container_stats::Column::Id);
 
         if let Some(limit_val) = limit {
             query = query.limit(limit_val);
         }
 
         let stats = query.all(&self.db).await?;
+        let last = stats.last().map(|row| KeysetCursor {
+            timestamp: row.timestamp.with_timezone(&Utc),
+            id: Some(i64::from(row.id)),
+        });
+
+        Ok((stats.iter().map(Self::entity_to_container_stats).collect(), last))
+    }
+
+    /// Get a container's stats history averaged/maxed per time bucket. Buckets of an hour or
+    /// more read the `container_stats_hourly` continuous aggregate; shorter buckets are
+    /// computed from raw `container_stats` with `time_bucket`
+    pub async fn get_container_stats_history_bucketed(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket_seconds: i64,
+    ) -> Result<Vec<AggregatedContainerStats>> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
 
-        Ok(stats.iter().map(|s| Self::entity_to_container_stats(s)).collect())
+        let sql = if bucket_seconds >= 3600 {
+            format!(r#"
+            SELECT time_bucket($1::interval, bucket) AS bucket,
+                   AVG(avg_cpu_usage_percent) AS avg_cpu,
+                   MAX(max_cpu_usage_percent) AS max_cpu,
+                   AVG(avg_memory_usage_bytes)::DOUBLE PRECISION AS avg_mem,
+                   MAX(max_memory_usage_bytes)::DOUBLE PRECISION AS max_mem,
+                   AVG(avg_memory_usage_percent) AS avg_mem_pct,
+                   MAX(max_memory_usage_percent) AS max_mem_pct
+            FROM container_stats_hourly
+            WHERE {} = $2 AND bucket >= time_bucket('1 hour', $3::timestamptz) AND bucket <= $4
+            GROUP BY 1
+            ORDER BY 1
+            "#, selector.column_name())
+        } else {
+            format!(r#"
+            SELECT time_bucket($1::interval, timestamp) AS bucket,
+                   AVG(cpu_usage_percent) AS avg_cpu,
+                   MAX(cpu_usage_percent) AS max_cpu,
+                   AVG(memory_usage_bytes)::DOUBLE PRECISION AS avg_mem,
+                   MAX(memory_usage_bytes)::DOUBLE PRECISION AS max_mem,
+                   AVG(memory_usage_percent) AS avg_mem_pct,
+                   MAX(memory_usage_percent) AS max_mem_pct
+            FROM container_stats
+            WHERE {} = $2 AND timestamp >= $3 AND timestamp <= $4
+            GROUP BY 1
+            ORDER BY 1
+            "#, selector.column_name())
+        };
+
+        let rows = BucketedStatsRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            [
+                format!("{} seconds", bucket_seconds).into(),
+                selector.value().into(),
+                from.with_timezone(&fixed_offset).into(),
+                to.with_timezone(&fixed_offset).into(),
+            ],
+        ))
+        .all(&self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| AggregatedContainerStats {
+                timestamp: row.bucket.with_timezone(&Utc),
+                avg_cpu_usage_percent: row.avg_cpu.unwrap_or(0.0),
+                max_cpu_usage_percent: row.max_cpu.unwrap_or(0.0),
+                avg_memory_usage_bytes: row.avg_mem.unwrap_or(0.0) as u64,
+                max_memory_usage_bytes: row.max_mem.unwrap_or(0.0) as u64,
+                avg_memory_usage_percent: row.avg_mem_pct.unwrap_or(0.0),
+                max_memory_usage_percent: row.max_mem_pct.unwrap_or(0.0),
+            })
+            .collect())
     }
 
-    /// Get aggregated total stats from latest container stats
+    /// Like [`Self::get_container_stats_history_bucketed`], but with every bucket between `from`
+    /// and `to` present (TimescaleDB `time_bucket_gapfill`). Buckets without samples hold
+    /// nulls, or the previous bucket's values when `carry_forward` (`locf`)
+    pub async fn get_container_stats_history_gapfilled(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket_seconds: i64,
+        carry_forward: bool,
+    ) -> Result<Vec<GapfilledContainerStats>> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+        let fill = |aggregate: &str| {
+            if carry_forward {
+                format!("locf({})", aggregate)
+            } else {
+                aggregate.to_string()
+            }
+        };
+
+        // Gapfill needs explicit bounds; the hourly range starts on the hour like the bucketed query
+        let (time_column, start, source, columns) = if bucket_seconds >= 3600 {
+            (
+                "bucket",
+                "time_bucket('1 hour', $3::timestamptz)",
+                "container_stats_hourly",
+                [
+                    "AVG(avg_cpu_usage_percent)",
+                    "MAX(max_cpu_usage_percent)",
+                    "AVG(avg_memory_usage_bytes)::DOUBLE PRECISION",
+                    "MAX(max_memory_usage_bytes)::DOUBLE PRECISION",
+                    "AVG(avg_memory_usage_percent)",
+                    "MAX(max_memory_usage_percent)",
+                ],
+            )
+        } else {
+            (
+                "timestamp",
+                "$3::timestamptz",
+                "container_stats",
+                [
+                    "AVG(cpu_usage_percent)",
+                    "MAX(cpu_usage_percent)",
+                    "AVG(memory_usage_bytes)::DOUBLE PRECISION",
+                    "MAX(memory_usage_bytes)::DOUBLE PRECISION",
+                    "AVG(memory_usage_percent)",
+                    "MAX(memory_usage_percent)",
+                ],
+            )
+        };
+        let sql = format!(
+            r#"
+            SELECT time_bucket_gapfill($1::interval, {time_column}, {start}, $4::timestamptz) AS bucket,
+                   {} AS avg_cpu,
+                   {} AS max_cpu,
+                   {} AS avg_mem,
+                   {} AS max_mem,
+                   {} AS avg_mem_pct,
+                   {} AS max_mem_pct
+            FROM {source}
+            WHERE {} = $2 AND {time_column} >= {start} AND {time_column} <= $4
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+            fill(columns[0]),
+            fill(columns[1]),
+            fill(columns[2]),
+            fill(columns[3]),
+            fill(columns[4]),
+            fill(columns[5]),
+            selector.column_name(),
+        );
+
+        let rows = BucketedStatsRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            [
+                format!("{} seconds", bucket_seconds).into(),
+                selector.value().into(),
+                from.with_timezone(&fixed_offset).into(),
+                to.with_timezone(&fixed_offset).into(),
+            ],
+        ))
+        .all(&self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| GapfilledContainerStats {
+                timestamp: row.bucket.with_timezone(&Utc),
+                avg_cpu_usage_percent: row.avg_cpu,
+                max_cpu_usage_percent: row.max_cpu,
+                avg_memory_usage_bytes: row.avg_mem.map(|m| m as u64),
+                max_memory_usage_bytes: row.max_mem.map(|m| m as u64),
+                avg_memory_usage_percent: row.avg_mem_pct,
+                max_memory_usage_percent: row.max_mem_pct,
+            })
+            .collect())
+    }
+
+    /// Network throughput per `bucket_seconds` bucket in bytes/sec. The byte counters are
+    /// cumulative, so each bucket's rate is the growth since the previous bucket's last sample
+    /// divided by the time between the two samples. A counter that went down (the container
+    /// restarted) counts as no traffic. With `by=name`, each run's rate is computed on its
+    /// own and the runs are summed
+    pub async fn get_container_network_rate(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket_seconds: i64,
+    ) -> Result<Vec<NetworkRate>> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        // One extra bucket before `from` gives the first bucket of the range its baseline
+        let sql = format!(r#"
+            SELECT
+                time_bucket($1::interval, timestamp) AS bucket,
+                container_id,
+                MAX(timestamp) AS sampled_at,
+                last(network_rx_bytes, timestamp) AS rx_bytes,
+                last(network_tx_bytes, timestamp) AS tx_bytes,
+                time_bucket($1::interval, timestamp) >= time_bucket($1::interval, $3::timestamptz) AS in_range
+            FROM container_stats
+            WHERE {} = $2 AND timestamp >= $3::timestamptz - $1::interval AND timestamp <= $4
+            GROUP BY 1, 2
+            ORDER BY container_id, bucket
+            "#, selector.column_name());
+
+        let rows = NetworkSampleRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            [
+                format!("{} seconds", bucket_seconds).into(),
+                selector.value().into(),
+                from.with_timezone(&fixed_offset).into(),
+                to.with_timezone(&fixed_offset).into(),
+            ],
+        ))
+        .all(&self.db)
+        .await?;
+
+        Ok(network_rates(&rows))
+    }
+
+    /// Get aggregated total stats: the worker's latest `total_stats` snapshot if it is recent,
+    /// otherwise aggregated from the latest stats of every container
     pub async fn get_total_stats(&self) -> Result<eyes_devine_shared::TotalStats> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+        let fresh_since = Utc::now() - chrono::Duration::seconds(TOTAL_STATS_MAX_AGE_SECS);
+
+        let snapshot = total_stats::Entity::find()
+            .filter(total_stats::Column::Timestamp.gte(fresh_since.with_timezone(&fixed_offset)))
+            .order_by_desc(total_stats::Column::Timestamp)
+            .one(&self.db)
+            .await?;
+        if let Some(snapshot) = snapshot {
+            return Ok(Self::entity_to_total_stats(&snapshot));
+        }
+
         let all_stats = self.get_latest_all_container_stats().await?;
 
+        // Without a snapshot only containers that reported stats are known
         let total_containers = all_stats.len();
         if total_containers == 0 {
             return Ok(eyes_devine_shared::TotalStats {
                 total_containers: 0,
+                running_containers: 0,
                 total_cpu_usage_percent: 0.0,
                 total_memory_usage_bytes: 0,
                 total_memory_limit_bytes: 0,
@@ -115,6 +682,7 @@ impl QueryService {
 
         Ok(eyes_devine_shared::TotalStats {
             total_containers,
+            running_containers: total_containers,
             total_cpu_usage_percent: total_cpu,
             total_memory_usage_bytes: total_memory_usage,
             total_memory_limit_bytes: total_memory_limit,
@@ -127,32 +695,91 @@ impl QueryService {
         })
     }
 
-    /// Get latest container info for all containers
+    /// Get latest container info for all containers (the worker keeps one row per container)
     pub async fn get_all_containers(&self) -> Result<Vec<ContainerInfo>> {
-        // Get the most recent info for each container
-        // This is a simplified version - in production, you might want to use a window function
         let containers = container_info::Entity::find()
             .order_by_desc(container_info::Column::CollectedAt)
             .all(&self.db)
             .await?;
 
-        // Deduplicate by container_id, keeping the most recent
-        let mut seen = std::collections::HashSet::new();
-        let mut result = Vec::new();
-
-        for container in containers {
-            if seen.insert(container.container_id.clone()) {
-                result.push(ContainerInfo {
-                    id: container.container_id,
-                    name: container.container_name,
-                    image: container.image,
-                    status: container.status,
-                    created: container.created.map(|dt| dt.with_timezone(&Utc)),
-                });
+        Ok(containers.into_iter().map(Self::entity_to_container_info).collect())
+    }
+
+    /// Latest info for all containers, grouped by Docker Compose project
+    pub async fn get_containers_grouped_by_project(&self) -> Result<Vec<ComposeProject>> {
+        Ok(ComposeProject::group(self.get_all_containers().await?))
+    }
+
+    /// Latest info per container (or per name) matching `filter`, most recently collected
+    /// first, one page at a time; also returns the total number of matches
+    pub async fn find_containers(
+        &self,
+        filter: &ContainerFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<ContainerInfo>, u64)> {
+        let like = |text: &str| format!("%{}%", text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+
+        let mut conditions = vec!["TRUE".to_string()];
+        let mut values: Vec<sea_orm::Value> = Vec::new();
+        if let Some(name) = &filter.name {
+            values.push(like(name).into());
+            conditions.push(format!("container_name ILIKE ${}", values.len()));
+        }
+        if let Some(image) = &filter.image {
+            values.push(like(image).into());
+            conditions.push(format!("image ILIKE ${}", values.len()));
+        }
+        if let Some(state) = filter.state {
+            values.push(state.as_str().into());
+            conditions.push(format!("state = ${}", values.len()));
+        }
+        for (key, value) in &filter.labels {
+            values.push(key.as_str().into());
+            let key_param = values.len();
+            match value {
+                Some(value) => {
+                    values.push(value.as_str().into());
+                    conditions.push(format!("labels ->> ${} = ${}", key_param, values.len()));
+                }
+                None => conditions.push(format!("labels ->> ${} IS NOT NULL", key_param)),
             }
         }
 
-        Ok(result)
+        // container_info already holds one row per container id
+        let latest = if filter.latest_per_name {
+            "WITH latest AS (SELECT DISTINCT ON (container_name) * FROM container_info ORDER BY container_name, collected_at DESC)"
+        } else {
+            "WITH latest AS (SELECT * FROM container_info)"
+        };
+        let where_clause = conditions.join(" AND ");
+
+        let total = CountRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!("{} SELECT COUNT(*)::BIGINT AS total FROM latest WHERE {}", latest, where_clause),
+            values.clone(),
+        ))
+        .one(&self.db)
+        .await?
+        .map(|row| row.total as u64)
+        .unwrap_or(0);
+
+        values.push((limit as i64).into());
+        values.push((offset as i64).into());
+        let sql = format!(
+            "{} SELECT * FROM latest WHERE {} ORDER BY collected_at DESC, container_name LIMIT ${} OFFSET ${}",
+            latest,
+            where_clause,
+            values.len() - 1,
+            values.len()
+        );
+
+        let containers = container_info::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(DbBackend::Postgres, sql, values))
+            .all(&self.db)
+            .await?;
+
+        Ok((containers.into_iter().map(Self::entity_to_container_info).collect(), total))
     }
 
     /// Get all images
@@ -161,7 +788,7 @@ impl QueryService {
             .all(&self.db)
             .await?;
 
-        Ok(images.iter().map(|img| Self::entity_to_image_info(img)).collect())
+        Ok(images.iter().map(Self::entity_to_image_info).collect())
     }
 
     /// Get image by ID
@@ -174,6 +801,54 @@ impl QueryService {
         Ok(image.map(|img| Self::entity_to_image_info(&img)))
     }
 
+    /// Images built more than `older_than_days` ago (oldest first), each with the running
+    /// containers that use it. Images without a known build date are listed separately
+    pub async fn get_stale_images(&self, older_than_days: u32) -> Result<StaleImageReport> {
+        let now = Utc::now();
+        let cutoff = now - chrono::Duration::days(older_than_days as i64);
+        let images = self.get_all_images().await?;
+        let running: Vec<ContainerInfo> = self
+            .get_all_containers()
+            .await?
+            .into_iter()
+            .filter(ContainerInfo::is_running)
+            .collect();
+
+        let mut stale = Vec::new();
+        let mut unknown_created = Vec::new();
+        for image in images {
+            let containers: Vec<ImageContainerUsage> = running
+                .iter()
+                .filter(|container| image_matches_reference(&image, &container.image))
+                .map(|container| ImageContainerUsage {
+                    container_id: container.id.clone(),
+                    container_name: container.name.clone(),
+                })
+                .collect();
+
+            match image.created {
+                Some(created) if created < cutoff => stale.push(StaleImage {
+                    age_days: Some((now - created).num_days()),
+                    image,
+                    containers,
+                }),
+                Some(_) => {}
+                None => unknown_created.push(StaleImage {
+                    age_days: None,
+                    image,
+                    containers,
+                }),
+            }
+        }
+        stale.sort_by_key(|entry| entry.image.created);
+
+        Ok(StaleImageReport {
+            older_than_days,
+            images: stale,
+            unknown_created,
+        })
+    }
+
     /// Get image version history
     pub async fn get_image_history(
         &self,
@@ -205,7 +880,258 @@ impl QueryService {
 
         let versions = query.all(&self.db).await?;
 
-        Ok(versions.iter().map(|v| Self::entity_version_to_image_info(v)).collect())
+        Ok(versions.iter().map(Self::entity_version_to_image_info).collect())
+    }
+
+    /// Size of an image between `from` and `to`, following rebuilds under its current tags:
+    /// one point per build seen in `image_versions` (when it was first seen), oldest first.
+    /// An image id never changes size, so without tags the trend is a single point
+    pub async fn get_image_size_trend(
+        &self,
+        image_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<ImageSizeTrend> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+        let repo_tags: Vec<String> = self
+            .get_image(image_id)
+            .await?
+            .map(|image| image.repo_tags)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|tag| tag != "<none>:<none>")
+            .collect();
+
+        let mut values: Vec<sea_orm::Value> = vec![
+            image_id.into(),
+            from.with_timezone(&fixed_offset).into(),
+            to.with_timezone(&fixed_offset).into(),
+        ];
+        let mut matches = "image_id = $1".to_string();
+        if !repo_tags.is_empty() {
+            let placeholders: Vec<String> = repo_tags
+                .iter()
+                .map(|tag| {
+                    values.push(tag.as_str().into());
+                    format!("${}", values.len())
+                })
+                .collect();
+            matches = format!(
+                "(image_id = $1 OR jsonb_exists_any(repo_tags::jsonb, ARRAY[{}]))",
+                placeholders.join(", ")
+            );
+        }
+
+        // Snapshots repeat every image collection, interleaved when several builds under the
+        // tags are still present; keep the first snapshot of each build
+        let sql = format!(r#"
+            SELECT timestamp, image_id, size_bytes FROM (
+                SELECT
+                    timestamp,
+                    image_id,
+                    size_bytes,
+                    ROW_NUMBER() OVER (PARTITION BY image_id ORDER BY timestamp) AS build_row
+                FROM image_versions
+                WHERE {} AND timestamp >= $2 AND timestamp <= $3
+            ) versions
+            WHERE build_row = 1
+            ORDER BY timestamp
+            "#, matches);
+
+        let rows = ImageSizeRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            values,
+        ))
+        .all(&self.db)
+        .await?;
+
+        let points: Vec<ImageSizePoint> = rows
+            .into_iter()
+            .map(|row| ImageSizePoint {
+                timestamp: row.timestamp.with_timezone(&Utc),
+                image_id: row.image_id,
+                size_bytes: row.size_bytes.max(0) as u64,
+            })
+            .collect();
+        let delta_bytes = match (points.first(), points.last()) {
+            (Some(first), Some(last)) => last.size_bytes as i64 - first.size_bytes as i64,
+            _ => 0,
+        };
+
+        Ok(ImageSizeTrend {
+            image_id: image_id.to_string(),
+            repo_tags,
+            points,
+            delta_bytes,
+        })
+    }
+
+    /// Fleet-wide CPU/memory per time bucket. Buckets with worker `total_stats` snapshots are
+    /// served from them; the others (e.g. before the worker started taking snapshots) fall back
+    /// to per-container stats: each container is averaged within the bucket, then CPU is
+    /// averaged and memory summed across containers. Ranges longer than
+    /// `RAW_STATS_MAX_RANGE_HOURS` read the hourly aggregate and use buckets of at least an hour
+    pub async fn get_fleet_stats_history(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket_seconds: i64,
+    ) -> Result<FleetStatsHistory> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+        let use_hourly = to - from > chrono::Duration::hours(RAW_STATS_MAX_RANGE_HOURS);
+
+        let (fallback_source, bucket_seconds, per_container) = if use_hourly {
+            (
+                "hourly",
+                bucket_seconds.max(3600),
+                Self::fleet_from_per_container(r#"
+                SELECT time_bucket($1::interval, bucket) AS bucket, container_id,
+                       AVG(avg_cpu_usage_percent) AS cpu,
+                       AVG(avg_memory_usage_bytes)::DOUBLE PRECISION AS mem,
+                       AVG(avg_memory_usage_percent) AS mem_pct
+                FROM container_stats_hourly
+                WHERE bucket >= time_bucket('1 hour', $2::timestamptz) AND bucket <= $3
+                GROUP BY 1, container_id
+                "#),
+            )
+        } else {
+            (
+                "raw",
+                bucket_seconds,
+                Self::fleet_from_per_container(r#"
+                SELECT time_bucket($1::interval, timestamp) AS bucket, container_id,
+                       AVG(cpu_usage_percent) AS cpu,
+                       AVG(memory_usage_bytes)::DOUBLE PRECISION AS mem,
+                       AVG(memory_usage_percent) AS mem_pct
+                FROM container_stats
+                WHERE timestamp >= $2 AND timestamp <= $3
+                GROUP BY 1, container_id
+                "#),
+            )
+        };
+
+        // Every bucket in the range is returned; buckets without samples come back as gaps
+        let sql = format!(
+            r#"
+            WITH buckets AS (
+                SELECT generate_series(time_bucket($1::interval, $2::timestamptz), $3::timestamptz, $1::interval) AS bucket
+            ),
+            snapshots AS (
+                SELECT time_bucket($1::interval, timestamp) AS bucket,
+                       MAX(running_containers)::BIGINT AS containers,
+                       MAX(total_containers)::BIGINT AS all_containers,
+                       AVG(total_cpu_usage_percent) AS cpu,
+                       AVG(total_memory_usage_bytes)::DOUBLE PRECISION AS mem,
+                       AVG(total_memory_usage_percent) AS mem_pct
+                FROM total_stats
+                WHERE timestamp >= $2 AND timestamp <= $3
+                GROUP BY 1
+            ),
+            per_container AS ({per_container})
+            SELECT b.bucket, s.bucket IS NOT NULL AS from_snapshots,
+                   COALESCE(s.containers, p.containers, 0)::BIGINT AS containers, s.all_containers,
+                   CASE WHEN s.bucket IS NOT NULL THEN s.cpu ELSE p.cpu END AS cpu,
+                   CASE WHEN s.bucket IS NOT NULL THEN s.mem ELSE p.mem END AS mem,
+                   CASE WHEN s.bucket IS NOT NULL THEN s.mem_pct ELSE p.mem_pct END AS mem_pct
+            FROM buckets b
+            LEFT JOIN snapshots s ON s.bucket = b.bucket
+            LEFT JOIN per_container p ON p.bucket = b.bucket
+            ORDER BY b.bucket
+            "#
+        );
+
+        let rows = FleetStatsRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            [
+                format!("{} seconds", bucket_seconds).into(),
+                from.with_timezone(&fixed_offset).into(),
+                to.with_timezone(&fixed_offset).into(),
+            ],
+        ))
+        .all(&self.db)
+        .await?;
+
+        let snapshot_buckets = rows.iter().filter(|row| row.from_snapshots).count();
+        let source = if snapshot_buckets == 0 {
+            fallback_source.to_string()
+        } else if snapshot_buckets == rows.len() {
+            "total_stats".to_string()
+        } else {
+            format!("total_stats+{}", fallback_source)
+        };
+
+        let points = rows
+            .into_iter()
+            .map(|row| FleetStatsPoint {
+                timestamp: row.bucket.with_timezone(&Utc),
+                total_containers: row.containers as usize,
+                all_containers: row.all_containers.map(|n| n as usize),
+                total_cpu_usage_percent: row.cpu,
+                total_memory_usage_bytes: row.mem.map(|m| m as u64),
+                avg_memory_usage_percent: row.mem_pct,
+            })
+            .collect();
+
+        Ok(FleetStatsHistory {
+            bucket_seconds,
+            source,
+            points,
+        })
+    }
+
+    /// Roll per-container bucket averages up into fleet rows: CPU averaged, memory summed
+    fn fleet_from_per_container(per_container: &str) -> String {
+        format!(
+            r#"
+            SELECT bucket, COUNT(*)::BIGINT AS containers, NULL::BIGINT AS all_containers,
+                   AVG(cpu) AS cpu, SUM(mem) AS mem, AVG(mem_pct) AS mem_pct
+            FROM ({per_container}) per_container
+            GROUP BY bucket
+            "#
+        )
+    }
+
+    fn entity_to_total_stats(entity: &total_stats::Model) -> eyes_devine_shared::TotalStats {
+        eyes_devine_shared::TotalStats {
+            total_containers: entity.total_containers as usize,
+            running_containers: entity.running_containers as usize,
+            total_cpu_usage_percent: entity.total_cpu_usage_percent,
+            total_memory_usage_bytes: entity.total_memory_usage_bytes as u64,
+            total_memory_limit_bytes: entity.total_memory_limit_bytes as u64,
+            total_memory_usage_percent: entity.total_memory_usage_percent,
+            total_network_rx_bytes: entity.total_network_rx_bytes as u64,
+            total_network_tx_bytes: entity.total_network_tx_bytes as u64,
+            total_block_read_bytes: entity.total_block_read_bytes as u64,
+            total_block_write_bytes: entity.total_block_write_bytes as u64,
+            timestamp: entity.timestamp.with_timezone(&Utc),
+        }
+    }
+
+    fn entity_to_container_info(container: container_info::Model) -> ContainerInfo {
+        let labels: BTreeMap<String, String> =
+            container.labels.and_then(|labels| serde_json::from_value(labels).ok()).unwrap_or_default();
+        let (compose_project, compose_service) = ContainerInfo::compose_labels(&labels);
+        ContainerInfo {
+            id: container.container_id,
+            name: container.container_name,
+            image: container.image,
+            status: container.status,
+            created: container.created.map(|dt| dt.with_timezone(&Utc)),
+            started_at: container.started_at.map(|dt| dt.with_timezone(&Utc)),
+            restart_count: container.restart_count,
+            state: container.state.as_deref().and_then(ContainerRunState::parse).map(|state| ContainerState {
+                state,
+                exit_code: container.exit_code,
+                health: container.health.as_deref().and_then(ContainerHealth::parse),
+            }),
+            health: container.health,
+            labels,
+            compose_project,
+            compose_service,
+            collected_at: Some(container.collected_at.with_timezone(&Utc)),
+        }
     }
 
     // Helper: Convert entity to ContainerStats
@@ -217,11 +1143,31 @@ impl QueryService {
             memory_usage_bytes: entity.memory_usage_bytes as u64,
             memory_limit_bytes: entity.memory_limit_bytes as u64,
             memory_usage_percent: entity.memory_usage_percent,
+            memory_cache_bytes: entity.memory_cache_bytes.unwrap_or(0) as u64,
+            memory_swap_bytes: entity.memory_swap_bytes.unwrap_or(0) as u64,
             network_rx_bytes: entity.network_rx_bytes as u64,
             network_tx_bytes: entity.network_tx_bytes as u64,
             block_read_bytes: entity.block_read_bytes as u64,
             block_write_bytes: entity.block_write_bytes as u64,
+            per_cpu_usage_percent: entity
+                .per_cpu_usage_percent
+                .clone()
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or_default(),
+            pids_current: entity.pids_current.unwrap_or(0) as u64,
+            pids_limit: entity.pids_limit.unwrap_or(0) as u64,
+            timestamp: entity.timestamp.with_timezone(&Utc),
+        }
+    }
+
+    // Helper: Convert entity to ContainerLog
+    fn entity_to_container_log(entity: &container_logs::Model) -> ContainerLog {
+        ContainerLog {
+            container_id: entity.container_id.clone(),
+            container_name: entity.container_name.clone(),
+            log_line: entity.log_line.clone(),
             timestamp: entity.timestamp.with_timezone(&Utc),
+            stream: entity.stream.clone(),
         }
     }
 
@@ -269,18 +1215,24 @@ impl QueryService {
         }
     }
 
-    /// Get HTTP requests for a specific container
+    /// Get HTTP requests for a specific container, optionally only those with one `method`
+    /// (case-insensitive) and/or an `endpoint` starting with the given prefix (case-insensitive)
     pub async fn get_container_http_requests(
         &self,
-        container_id: &str,
+        selector: &ContainerSelector,
         from: Option<DateTime<Utc>>,
         to: Option<DateTime<Utc>>,
+        method: Option<&str>,
+        endpoint: Option<&str>,
         limit: Option<u64>,
     ) -> Result<Vec<HttpRequest>> {
         let fixed_offset = FixedOffset::east_opt(0).unwrap();
         
         let mut query = http_requests::Entity::find()
-            .filter(http_requests::Column::ContainerId.eq(container_id));
+            .filter(match selector {
+                ContainerSelector::Id(id) => http_requests::Column::ContainerId.eq(id.as_str()),
+                ContainerSelector::Name(name) => http_requests::Column::ContainerName.eq(name.as_str()),
+            });
 
         if let Some(from_dt) = from {
             let from_tz = from_dt.with_timezone(&fixed_offset);
@@ -292,6 +1244,20 @@ impl QueryService {
             query = query.filter(http_requests::Column::Timestamp.lte(to_tz));
         }
 
+        // Methods are stored upper-case as captured
+        if let Some(method) = method {
+            query = query.filter(http_requests::Column::Method.eq(method.to_uppercase()));
+        }
+
+        // Prefix match; LIKE wildcards in the prefix are escaped (`\` is the default escape)
+        if let Some(endpoint) = endpoint {
+            let pattern = format!(
+                "{}%",
+                endpoint.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+            );
+            query = query.filter(sea_orm::sea_query::Expr::cust_with_values("endpoint ILIKE $1", [pattern]));
+        }
+
         query = query.order_by_desc(http_requests::Column::Timestamp);
 
         if let Some(limit_val) = limit {
@@ -300,7 +1266,485 @@ impl QueryService {
 
         let requests = query.all(&self.db).await?;
 
-        Ok(requests.iter().map(|r| Self::entity_to_http_request(r)).collect())
+        Ok(requests.iter().map(Self::entity_to_http_request).collect())
+    }
+
+    /// Get a container's most recent requests to one endpoint, matched against the stored
+    /// (normalized) endpoint template such as `/api/users/:id`
+    pub async fn get_endpoint_http_requests(
+        &self,
+        selector: &ContainerSelector,
+        endpoint: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: u64,
+    ) -> Result<Vec<HttpRequest>> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        let requests = http_requests::Entity::find()
+            .filter(match selector {
+                ContainerSelector::Id(id) => http_requests::Column::ContainerId.eq(id.as_str()),
+                ContainerSelector::Name(name) => http_requests::Column::ContainerName.eq(name.as_str()),
+            })
+            .filter(http_requests::Column::Endpoint.eq(endpoint))
+            .filter(http_requests::Column::Timestamp.gte(from.with_timezone(&fixed_offset)))
+            .filter(http_requests::Column::Timestamp.lte(to.with_timezone(&fixed_offset)))
+            .order_by_desc(http_requests::Column::Timestamp)
+            .limit(limit)
+            .all(&self.db)
+            .await?;
+
+        Ok(requests.iter().map(Self::entity_to_http_request).collect())
+    }
+
+    /// Stored log lines of one container, newest first (fallback when Docker can't be reached)
+    pub async fn get_container_logs(
+        &self,
+        container_id: &str,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        limit: u64,
+    ) -> Result<Vec<ContainerLog>> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        let mut query = container_logs::Entity::find()
+            .filter(container_logs::Column::ContainerId.eq(container_id));
+        if let Some(since) = since {
+            query = query.filter(container_logs::Column::Timestamp.gte(since.with_timezone(&fixed_offset)));
+        }
+        if let Some(until) = until {
+            query = query.filter(container_logs::Column::Timestamp.lte(until.with_timezone(&fixed_offset)));
+        }
+
+        let logs = query
+            .order_by_desc(container_logs::Column::Timestamp)
+            .limit(limit)
+            .all(&self.db)
+            .await?;
+
+        Ok(logs.iter().map(Self::entity_to_container_log).collect())
+    }
+
+    /// Full-text search over stored log lines of every container: lines containing all words
+    /// of `text` (case-insensitive, whole words). Ordered by `ts_rank` (ties newest first)
+    /// when `by_relevance`, otherwise newest first with `before` as an exclusive cursor for
+    /// paging back through the range. Uses the GIN index on `to_tsvector('simple', log_line)`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search_logs(
+        &self,
+        text: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        container_id: Option<&str>,
+        stream: Option<&str>,
+        before: Option<KeysetCursor>,
+        by_relevance: bool,
+        limit: u64,
+    ) -> Result<(Vec<ContainerLog>, Option<KeysetCursor>)> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        // Same expression as the index, so the planner can use it
+        let document = "to_tsvector('simple', log_line)";
+        let search = "plainto_tsquery('simple', $1)";
+
+        let mut conditions = vec![
+            format!("{} @@ {}", document, search),
+            "timestamp >= $2".to_string(),
+            "timestamp <= $3".to_string(),
+        ];
+        let mut values: Vec<sea_orm::Value> = vec![
+            text.into(),
+            from.with_timezone(&fixed_offset).into(),
+            to.with_timezone(&fixed_offset).into(),
+        ];
+        if let Some(container_id) = container_id {
+            values.push(container_id.into());
+            conditions.push(format!("container_id = ${}", values.len()));
+        }
+        if let Some(stream) = stream {
+            values.push(stream.into());
+            conditions.push(format!("stream = ${}", values.len()));
+        }
+        if let Some(before) = before {
+            conditions.push(before.condition(values.len() + 1));
+            values.extend(before.values());
+        }
+        values.push((limit as i64).into());
+
+        let order = if by_relevance {
+            format!("ts_rank({}, {}) DESC, timestamp DESC, id DESC", document, search)
+        } else {
+            "timestamp DESC, id DESC".to_string()
+        };
+        let sql = format!(
+            "SELECT * FROM container_logs WHERE {} ORDER BY {} LIMIT ${}",
+            conditions.join(" AND "),
+            order,
+            values.len()
+        );
+
+        let logs = container_logs::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(DbBackend::Postgres, sql, values))
+            .all(&self.db)
+            .await?;
+        let last = logs.last().map(|row| KeysetCursor {
+            timestamp: row.timestamp.with_timezone(&Utc),
+            id: Some(i64::from(row.id)),
+        });
+
+        Ok((logs.iter().map(Self::entity_to_container_log).collect(), last))
+    }
+
+    /// Get the stored service-map snapshot closest in time to `at`
+    pub async fn get_nearest_service_map_snapshot(&self, at: DateTime<Utc>) -> Result<Option<ServiceMap>> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+        let at_fixed = at.with_timezone(&fixed_offset);
+
+        let before = service_map_snapshots::Entity::find()
+            .filter(service_map_snapshots::Column::Timestamp.lte(at_fixed))
+            .order_by_desc(service_map_snapshots::Column::Timestamp)
+            .one(&self.db)
+            .await?;
+        let after = service_map_snapshots::Entity::find()
+            .filter(service_map_snapshots::Column::Timestamp.gt(at_fixed))
+            .order_by_asc(service_map_snapshots::Column::Timestamp)
+            .one(&self.db)
+            .await?;
+
+        let nearest = match (before, after) {
+            (Some(b), Some(a)) => {
+                if at_fixed - b.timestamp <= a.timestamp - at_fixed { Some(b) } else { Some(a) }
+            }
+            (b, a) => b.or(a),
+        };
+
+        match nearest {
+            Some(model) => Ok(Some(serde_json::from_value(model.snapshot)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Captured HTTP calls made by containers since `since`, most frequent first
+    pub async fn get_observed_http_calls(&self, since: DateTime<Utc>, limit: u64) -> Result<Vec<ObservedHttpCall>> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        let calls = ObservedHttpCall::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                client_container_id,
+                container_id,
+                method,
+                endpoint,
+                http_status,
+                headers->>'host' AS host,
+                COUNT(*)::BIGINT AS request_count
+            FROM http_requests
+            WHERE timestamp >= $1 AND client_container_id IS NOT NULL
+            GROUP BY client_container_id, container_id, method, endpoint, http_status, headers->>'host'
+            ORDER BY request_count DESC
+            LIMIT $2
+            "#,
+            [
+                since.with_timezone(&fixed_offset).into(),
+                (limit as i64).into(),
+            ],
+        ))
+        .all(&self.db)
+        .await?;
+
+        Ok(calls)
+    }
+
+    /// Count HTTP requests per status class (2xx/3xx/4xx/5xx) for a container
+    pub async fn get_http_status_breakdown(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<HttpStatusBreakdown> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        let row = StatusBreakdownRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!(r#"
+            SELECT
+                COALESCE(SUM(CASE WHEN http_status BETWEEN 200 AND 299 THEN 1 ELSE 0 END), 0)::BIGINT AS status_2xx,
+                COALESCE(SUM(CASE WHEN http_status BETWEEN 300 AND 399 THEN 1 ELSE 0 END), 0)::BIGINT AS status_3xx,
+                COALESCE(SUM(CASE WHEN http_status BETWEEN 400 AND 499 THEN 1 ELSE 0 END), 0)::BIGINT AS status_4xx,
+                COALESCE(SUM(CASE WHEN http_status BETWEEN 500 AND 599 THEN 1 ELSE 0 END), 0)::BIGINT AS status_5xx,
+                COALESCE(SUM(CASE WHEN http_status < 200 OR http_status > 599 THEN 1 ELSE 0 END), 0)::BIGINT AS other
+            FROM http_requests
+            WHERE {} = $1 AND timestamp >= $2 AND timestamp <= $3
+            "#, selector.column_name()),
+            [
+                selector.value().into(),
+                from.with_timezone(&fixed_offset).into(),
+                to.with_timezone(&fixed_offset).into(),
+            ],
+        ))
+        .one(&self.db)
+        .await?
+        .unwrap_or_default();
+
+        Ok(HttpStatusBreakdown {
+            status_2xx: row.status_2xx,
+            status_3xx: row.status_3xx,
+            status_4xx: row.status_4xx,
+            status_5xx: row.status_5xx,
+            other: row.other,
+        })
+    }
+
+    /// Total requests, counts per status class, 5xx error rate and request rate for a container
+    pub async fn get_http_request_summary(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<HttpRequestSummary> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        let rows = StatusClassCountRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!(r#"
+            SELECT
+                (CASE WHEN http_status BETWEEN 200 AND 599 THEN http_status / 100 ELSE 0 END)::INT AS status_class,
+                COUNT(*) AS requests
+            FROM http_requests
+            WHERE {} = $1 AND timestamp >= $2 AND timestamp <= $3
+            GROUP BY 1
+            "#, selector.column_name()),
+            [
+                selector.value().into(),
+                from.with_timezone(&fixed_offset).into(),
+                to.with_timezone(&fixed_offset).into(),
+            ],
+        ))
+        .all(&self.db)
+        .await?;
+
+        let mut status_classes = HttpStatusBreakdown::default();
+        for row in &rows {
+            match row.status_class {
+                2 => status_classes.status_2xx = row.requests,
+                3 => status_classes.status_3xx = row.requests,
+                4 => status_classes.status_4xx = row.requests,
+                5 => status_classes.status_5xx = row.requests,
+                _ => status_classes.other += row.requests,
+            }
+        }
+        let total: i64 = rows.iter().map(|row| row.requests).sum();
+
+        let error_rate = if total > 0 {
+            status_classes.status_5xx as f64 / total as f64
+        } else {
+            0.0
+        };
+        let minutes = (to - from).num_milliseconds() as f64 / 60_000.0;
+        let requests_per_minute = if minutes > 0.0 { total as f64 / minutes } else { 0.0 };
+
+        Ok(HttpRequestSummary {
+            from,
+            to,
+            total,
+            status_classes,
+            error_rate,
+            requests_per_minute,
+        })
+    }
+
+    /// Response-time percentiles and average for a container's HTTP requests
+    pub async fn get_http_latency_percentiles(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<HttpLatencyPercentiles> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        let row = LatencyPercentilesRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!(r#"
+            SELECT
+                COUNT(*) AS request_count,
+                AVG(response_time_ms) AS avg_ms,
+                percentile_cont(0.5) WITHIN GROUP (ORDER BY response_time_ms) AS p50_ms,
+                percentile_cont(0.95) WITHIN GROUP (ORDER BY response_time_ms) AS p95_ms,
+                percentile_cont(0.99) WITHIN GROUP (ORDER BY response_time_ms) AS p99_ms
+            FROM http_requests
+            WHERE {} = $1 AND timestamp >= $2 AND timestamp <= $3
+            "#, selector.column_name()),
+            [
+                selector.value().into(),
+                from.with_timezone(&fixed_offset).into(),
+                to.with_timezone(&fixed_offset).into(),
+            ],
+        ))
+        .one(&self.db)
+        .await?
+        .unwrap_or_default();
+
+        // Aggregates are NULL over an empty range
+        Ok(HttpLatencyPercentiles {
+            request_count: row.request_count,
+            avg_ms: row.avg_ms.unwrap_or(0.0),
+            p50_ms: row.p50_ms.unwrap_or(0.0),
+            p95_ms: row.p95_ms.unwrap_or(0.0),
+            p99_ms: row.p99_ms.unwrap_or(0.0),
+        })
+    }
+
+    /// Every (method, endpoint) a container served in the range with its call count, average
+    /// latency and 5xx count, most called first, at most `limit` of them
+    pub async fn get_container_endpoints(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: u64,
+    ) -> Result<Vec<HttpEndpointStats>> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        let rows = EndpointStatsRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!(r#"
+            SELECT
+                method,
+                endpoint,
+                COUNT(*) AS request_count,
+                AVG(response_time_ms) AS avg_ms,
+                COUNT(*) FILTER (WHERE http_status >= 500) AS error_count
+            FROM http_requests
+            WHERE {} = $1 AND timestamp >= $2 AND timestamp <= $3
+            GROUP BY method, endpoint
+            ORDER BY request_count DESC, endpoint, method
+            LIMIT $4
+            "#, selector.column_name()),
+            [
+                selector.value().into(),
+                from.with_timezone(&fixed_offset).into(),
+                to.with_timezone(&fixed_offset).into(),
+                (limit as i64).into(),
+            ],
+        ))
+        .all(&self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| HttpEndpointStats {
+                method: row.method,
+                endpoint: row.endpoint,
+                request_count: row.request_count,
+                avg_response_time_ms: row.avg_ms.unwrap_or(0.0),
+                error_count: row.error_count,
+            })
+            .collect())
+    }
+
+    /// Suggest CPU/memory limits for a container from the p95 (plus headroom) of its usage
+    /// over the window; no suggestion is made with fewer than `min_samples` samples
+    pub async fn get_resource_recommendation(
+        &self,
+        selector: &ContainerSelector,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        min_samples: u64,
+    ) -> Result<ResourceRecommendation> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        let row = UsagePercentilesRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!(r#"
+            SELECT
+                COUNT(*)::BIGINT AS samples,
+                percentile_cont(0.5) WITHIN GROUP (ORDER BY cpu_usage_percent) AS cpu_p50,
+                percentile_cont(0.95) WITHIN GROUP (ORDER BY cpu_usage_percent) AS cpu_p95,
+                percentile_cont(0.99) WITHIN GROUP (ORDER BY cpu_usage_percent) AS cpu_p99,
+                MAX(cpu_usage_percent) AS cpu_max,
+                percentile_cont(0.5) WITHIN GROUP (ORDER BY memory_usage_bytes) AS mem_p50,
+                percentile_cont(0.95) WITHIN GROUP (ORDER BY memory_usage_bytes) AS mem_p95,
+                percentile_cont(0.99) WITHIN GROUP (ORDER BY memory_usage_bytes) AS mem_p99,
+                MAX(memory_usage_bytes)::DOUBLE PRECISION AS mem_max,
+                (SELECT memory_limit_bytes FROM container_stats
+                 WHERE {column} = $1 ORDER BY timestamp DESC LIMIT 1) AS memory_limit
+            FROM container_stats
+            WHERE {column} = $1 AND timestamp >= $2 AND timestamp <= $3
+            "#, column = selector.column_name()),
+            [
+                selector.value().into(),
+                from.with_timezone(&fixed_offset).into(),
+                to.with_timezone(&fixed_offset).into(),
+            ],
+        ))
+        .one(&self.db)
+        .await?
+        .unwrap_or_default();
+
+        let sample_count = row.samples.max(0) as u64;
+        let percentiles = |p50: Option<f64>, p95: Option<f64>, p99: Option<f64>, max: Option<f64>| {
+            Some(UsagePercentiles { p50: p50?, p95: p95?, p99: p99?, max: max? })
+        };
+        let cpu = percentiles(row.cpu_p50, row.cpu_p95, row.cpu_p99, row.cpu_max);
+        let memory = percentiles(row.mem_p50, row.mem_p95, row.mem_p99, row.mem_max);
+        let current_memory_limit_bytes = row.memory_limit.filter(|limit| *limit > 0).map(|limit| limit as u64);
+
+        let mut recommendation = ResourceRecommendation {
+            container_id: selector.value().to_string(),
+            from,
+            to,
+            sample_count,
+            min_samples,
+            insufficient_data: sample_count < min_samples,
+            cpu_percent: cpu.clone(),
+            memory_bytes: memory.clone(),
+            current_memory_limit_bytes,
+            headroom: RECOMMENDATION_HEADROOM,
+            suggested_cpu_limit_cores: None,
+            suggested_memory_limit_bytes: None,
+            reasons: Vec::new(),
+        };
+
+        if recommendation.insufficient_data {
+            recommendation.reasons.push(format!(
+                "Insufficient data: {} samples in the window, need at least {}",
+                sample_count, min_samples
+            ));
+            return Ok(recommendation);
+        }
+
+        let headroom_pct = RECOMMENDATION_HEADROOM * 100.0;
+        if let Some(cpu) = &cpu {
+            // 100% = one fully used core; round up to a tenth of a core
+            let cores = ((cpu.p95 / 100.0 * (1.0 + RECOMMENDATION_HEADROOM)) * 10.0).ceil().max(1.0) / 10.0;
+            recommendation.suggested_cpu_limit_cores = Some(cores);
+            recommendation.reasons.push(format!(
+                "Set CPU limit to {:.1} cores based on p95 of {:.1}% of a core + {:.0}% headroom (max observed {:.1}%)",
+                cores, cpu.p95, headroom_pct, cpu.max
+            ));
+        }
+        if let Some(memory) = &memory {
+            // Never below the observed peak, rounded up to a 64 MiB step
+            let wanted = (memory.p95 * (1.0 + RECOMMENDATION_HEADROOM)).max(memory.max);
+            let bytes = ((wanted / MEMORY_LIMIT_STEP_BYTES as f64).ceil().max(1.0) as u64) * MEMORY_LIMIT_STEP_BYTES;
+            recommendation.suggested_memory_limit_bytes = Some(bytes);
+            let mib = |value: f64| value / (1024.0 * 1024.0);
+            recommendation.reasons.push(format!(
+                "Set memory limit to {:.0}MiB based on p95 of {:.0}MiB + {:.0}% headroom (max observed {:.0}MiB)",
+                mib(bytes as f64), mib(memory.p95), headroom_pct, mib(memory.max)
+            ));
+        }
+
+        Ok(recommendation)
+    }
+
+    // Helper: Filter container_stats rows by id or name
+    fn stats_filter(selector: &ContainerSelector) -> sea_orm::sea_query::SimpleExpr {
+        match selector {
+            ContainerSelector::Id(id) => container_stats::Column::ContainerId.eq(id.as_str()),
+            ContainerSelector::Name(name) => container_stats::Column::ContainerName.eq(name.as_str()),
+        }
     }
 
     // Helper: Convert entity to HttpRequest
@@ -313,7 +1757,718 @@ impl QueryService {
             http_status: entity.http_status as u16,
             response_time_ms: entity.response_time_ms,
             timestamp: entity.timestamp.with_timezone(&Utc),
+            headers: entity.headers.clone().and_then(|headers| serde_json::from_value(headers).ok()),
+            client_container_id: entity.client_container_id.clone(),
         }
     }
 }
 
+#[derive(Debug, FromQueryResult)]
+struct BucketedStatsRow {
+    bucket: DateTime<FixedOffset>,
+    avg_cpu: Option<f64>,
+    max_cpu: Option<f64>,
+    avg_mem: Option<f64>,
+    max_mem: Option<f64>,
+    avg_mem_pct: Option<f64>,
+    max_mem_pct: Option<f64>,
+}
+
+/// Oldest worker `total_stats` snapshot still served as the current fleet totals
+const TOTAL_STATS_MAX_AGE_SECS: i64 = 300;
+
+/// Longest range served from raw `container_stats`; longer ranges use the hourly aggregate
+pub const RAW_STATS_MAX_RANGE_HOURS: i64 = 48;
+
+#[derive(Debug, FromQueryResult)]
+struct FleetStatsRow {
+    bucket: DateTime<FixedOffset>,
+    from_snapshots: bool,
+    containers: i64,
+    all_containers: Option<i64>,
+    cpu: Option<f64>,
+    mem: Option<f64>,
+    mem_pct: Option<f64>,
+}
+
+/// Fraction added on top of observed p95 usage when suggesting limits
+const RECOMMENDATION_HEADROOM: f64 = 0.2;
+
+/// Suggested memory limits are rounded up to a multiple of this
+const MEMORY_LIMIT_STEP_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Default, FromQueryResult)]
+struct UsagePercentilesRow {
+    samples: i64,
+    cpu_p50: Option<f64>,
+    cpu_p95: Option<f64>,
+    cpu_p99: Option<f64>,
+    cpu_max: Option<f64>,
+    mem_p50: Option<f64>,
+    mem_p95: Option<f64>,
+    mem_p99: Option<f64>,
+    mem_max: Option<f64>,
+    memory_limit: Option<i64>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct StatusClassCountRow {
+    status_class: i32,
+    requests: i64,
+}
+
+/// Last network counters of one container in one bucket
+#[derive(Debug, FromQueryResult)]
+struct NetworkSampleRow {
+    bucket: DateTime<FixedOffset>,
+    container_id: String,
+    sampled_at: DateTime<FixedOffset>,
+    rx_bytes: i64,
+    tx_bytes: i64,
+    /// False for the baseline bucket before the requested range
+    in_range: bool,
+}
+
+/// Per-bucket rates from samples ordered by container, then bucket: each sample's growth
+/// over the same container's previous one, summed across containers. A counter that went
+/// down (the container restarted) counts as no traffic
+fn network_rates(samples: &[NetworkSampleRow]) -> Vec<NetworkRate> {
+    let mut rates: BTreeMap<DateTime<FixedOffset>, (f64, f64)> = BTreeMap::new();
+    for pair in samples.windows(2) {
+        let (previous, sample) = (&pair[0], &pair[1]);
+        if previous.container_id != sample.container_id || !sample.in_range {
+            continue;
+        }
+        let elapsed = (sample.sampled_at - previous.sampled_at).num_milliseconds() as f64 / 1000.0;
+        if elapsed <= 0.0 {
+            continue;
+        }
+        let rate = rates.entry(sample.bucket).or_default();
+        rate.0 += (sample.rx_bytes - previous.rx_bytes).max(0) as f64 / elapsed;
+        rate.1 += (sample.tx_bytes - previous.tx_bytes).max(0) as f64 / elapsed;
+    }
+
+    rates
+        .into_iter()
+        .map(|(bucket, (rx, tx))| NetworkRate {
+            timestamp: bucket.with_timezone(&Utc),
+            rx_bytes_per_sec: rx,
+            tx_bytes_per_sec: tx,
+        })
+        .collect()
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ImageSizeRow {
+    timestamp: DateTime<FixedOffset>,
+    image_id: String,
+    size_bytes: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct EndpointStatsRow {
+    method: String,
+    endpoint: String,
+    request_count: i64,
+    avg_ms: Option<f64>,
+    error_count: i64,
+}
+
+#[derive(Debug, Default, FromQueryResult)]
+struct LatencyPercentilesRow {
+    request_count: i64,
+    avg_ms: Option<f64>,
+    p50_ms: Option<f64>,
+    p95_ms: Option<f64>,
+    p99_ms: Option<f64>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct CountRow {
+    total: i64,
+}
+
+#[derive(Debug, Default, FromQueryResult)]
+struct StatusBreakdownRow {
+    status_2xx: i64,
+    status_3xx: i64,
+    status_4xx: i64,
+    status_5xx: i64,
+    other: i64,
+}
+
+/// Whether a container's image reference (`nginx`, `nginx:1.25`, `registry:5000/app:v2`,
+/// or an image id, possibly shortened) names this image. Untagged references mean `:latest`
+fn image_matches_reference(image: &ImageInfo, reference: &str) -> bool {
+    let id = image.id.strip_prefix("sha256:").unwrap_or(&image.id);
+    let reference_id = reference.strip_prefix("sha256:").unwrap_or(reference);
+    if reference_id.len() >= 12 && id.starts_with(reference_id) {
+        return true;
+    }
+
+    let last_segment = reference.rsplit('/').next().unwrap_or(reference);
+    let tagged = if last_segment.contains(':') || reference.contains('@') {
+        reference.to_string()
+    } else {
+        format!("{}:latest", reference)
+    };
+    image.repo_tags.contains(&tagged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::MockDatabase;
+
+    fn stats_row(id: i32, container_id: &str, container_name: &str) -> container_stats::Model {
+        let timestamp = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        container_stats::Model {
+            id,
+            container_id: container_id.to_string(),
+            container_name: container_name.to_string(),
+            cpu_usage_percent: 1.5,
+            memory_usage_bytes: 1024,
+            memory_limit_bytes: 4096,
+            memory_usage_percent: 25.0,
+            memory_cache_bytes: None,
+            memory_swap_bytes: None,
+            network_rx_bytes: 10,
+            network_tx_bytes: 20,
+            block_read_bytes: 0,
+            block_write_bytes: 0,
+            per_cpu_usage_percent: None,
+            pids_current: Some(3),
+            pids_limit: None,
+            timestamp,
+            created_at: timestamp,
+        }
+    }
+
+    #[tokio::test]
+    async fn latest_stats_for_all_containers_is_one_query() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![
+                stats_row(1, "aaa", "api"),
+                stats_row(2, "bbb", "db"),
+                stats_row(3, "ccc", "web"),
+            ]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let stats = service.get_latest_all_container_stats().await.unwrap();
+        assert_eq!(stats.len(), 3);
+
+        let QueryService { db } = service;
+        let log = db.into_transaction_log();
+        assert_eq!(log.len(), 1);
+        let statements = log[0].statements();
+        assert_eq!(statements.len(), 1);
+        let expected_sql = format!("SELECT * FROM ({}) latest ORDER BY container_name", LATEST_STATS_PER_CONTAINER_SQL);
+        assert_eq!(statements[0].sql, expected_sql);
+    }
+
+    #[tokio::test]
+    async fn latest_stats_query_is_time_bounded() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![stats_row(1, "aaa", "api")]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let before = Utc::now();
+        service.get_top_containers(TopMetric::Cpu, 5).await.unwrap();
+        let after = Utc::now();
+
+        let QueryService { db } = service;
+        let log = db.into_transaction_log();
+        let statement = &log[0].statements()[0];
+        assert!(statement.sql.contains("DISTINCT ON (container_id)"));
+        assert!(statement.sql.contains("WHERE timestamp > $1"));
+        let values = &statement.values.as_ref().unwrap().0;
+        let sea_orm::Value::ChronoDateTimeWithTimeZone(Some(since)) = &values[0] else {
+            panic!("expected a timestamp bound, got {:?}", values[0]);
+        };
+        let since = since.with_timezone(&Utc);
+        assert!(since >= before - LATEST_STATS_WINDOW && since <= after - LATEST_STATS_WINDOW);
+        assert_eq!(values[1], 5i64.into());
+    }
+
+    fn network_sample(container_id: &str, minute: i64, rx_bytes: i64, tx_bytes: i64, in_range: bool) -> BTreeMap<&'static str, sea_orm::Value> {
+        let at = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap() + chrono::Duration::minutes(minute);
+        BTreeMap::from([
+            ("bucket", at.into()),
+            ("container_id", container_id.into()),
+            ("sampled_at", (at + chrono::Duration::seconds(59)).into()),
+            ("rx_bytes", rx_bytes.into()),
+            ("tx_bytes", tx_bytes.into()),
+            ("in_range", in_range.into()),
+        ])
+    }
+
+    #[tokio::test]
+    async fn network_rate_treats_a_counter_reset_as_no_traffic() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![
+                // The bucket before the range is only a baseline
+                network_sample("aaa", 0, 1_000, 100, false),
+                network_sample("aaa", 1, 7_000, 700, true),
+                // Restarted: the counters start over
+                network_sample("aaa", 2, 500, 50, true),
+                network_sample("aaa", 3, 3_500, 350, true),
+                network_sample("bbb", 0, 0, 0, false),
+                network_sample("bbb", 1, 600, 60, true),
+            ]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let from = DateTime::parse_from_rfc3339("2026-01-01T00:01:00Z").unwrap().with_timezone(&Utc);
+        let rates = service
+            .get_container_network_rate(&ContainerSelector::Name("api".to_string()), from, from + chrono::Duration::minutes(3), 60)
+            .await
+            .unwrap();
+
+        let minutes: Vec<(u32, f64, f64)> = rates
+            .iter()
+            .map(|rate| (chrono::Timelike::minute(&rate.timestamp), rate.rx_bytes_per_sec, rate.tx_bytes_per_sec))
+            .collect();
+        assert_eq!(minutes, [(1, 110.0, 11.0), (2, 0.0, 0.0), (3, 50.0, 5.0)]);
+    }
+
+    #[tokio::test]
+    async fn status_history_maps_the_recorded_state() {
+        let at = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap();
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![container_status_history::Model {
+                id: 2,
+                container_id: "aaa".to_string(),
+                container_name: "api".to_string(),
+                status: "Exited (137) 3 seconds ago".to_string(),
+                state: Some("exited".to_string()),
+                exit_code: Some(137),
+                health: None,
+                timestamp: at,
+                created_at: at,
+            }]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let history = service
+            .get_container_status_history(&ContainerSelector::Id("aaa".to_string()), None, None, Some(10))
+            .await
+            .unwrap();
+
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            history[0].state,
+            Some(ContainerState { state: ContainerRunState::Exited, exit_code: Some(137), health: None })
+        );
+        assert_eq!(history[0].timestamp, at.with_timezone(&Utc));
+    }
+
+    fn fleet_row(minute: i64, from_snapshots: bool, containers: i64, cpu: Option<f64>) -> BTreeMap<&'static str, sea_orm::Value> {
+        let at = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap() + chrono::Duration::minutes(minute);
+        BTreeMap::from([
+            ("bucket", at.into()),
+            ("from_snapshots", from_snapshots.into()),
+            ("containers", containers.into()),
+            ("all_containers", (if from_snapshots { Some(containers + 1) } else { None }).into()),
+            ("cpu", cpu.into()),
+            ("mem", cpu.map(|cpu| cpu * 1_000.0).into()),
+            ("mem_pct", cpu.into()),
+        ])
+    }
+
+    #[tokio::test]
+    async fn fleet_history_falls_back_per_bucket_before_the_snapshots_start() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![
+                fleet_row(0, false, 3, Some(10.0)),
+                fleet_row(1, false, 0, None),
+                fleet_row(2, true, 4, Some(20.0)),
+            ]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let from = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let history = service
+            .get_fleet_stats_history(from, from + chrono::Duration::minutes(3), 60)
+            .await
+            .unwrap();
+
+        assert_eq!(history.source, "total_stats+raw");
+        assert_eq!(history.bucket_seconds, 60);
+        let points: Vec<_> = history
+            .points
+            .iter()
+            .map(|point| (point.total_containers, point.all_containers, point.total_cpu_usage_percent))
+            .collect();
+        assert_eq!(points, [(3, None, Some(10.0)), (0, None, None), (4, Some(5), Some(20.0))]);
+    }
+
+    #[tokio::test]
+    async fn fleet_history_uses_hourly_buckets_on_long_ranges() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![fleet_row(0, true, 2, Some(5.0))]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let from = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let history = service
+            .get_fleet_stats_history(from, from + chrono::Duration::days(7), 60)
+            .await
+            .unwrap();
+
+        assert_eq!(history.bucket_seconds, 3600);
+        assert_eq!(history.source, "total_stats");
+
+        let QueryService { db } = service;
+        let log = db.into_transaction_log();
+        let statement = &log[0].statements()[0];
+        assert!(statement.sql.contains("FROM container_stats_hourly"));
+        let bucket: sea_orm::Value = "3600 seconds".into();
+        assert_eq!(statement.values.as_ref().map(|values| &values.0[0]), Some(&bucket));
+    }
+
+    #[tokio::test]
+    async fn image_size_trend_keeps_the_first_snapshot_of_each_build() {
+        let at = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap();
+        let image = docker_images::Model {
+            id: 1,
+            image_id: "sha256:new".to_string(),
+            repo_tags: serde_json::json!(["app:latest"]),
+            size_bytes: 300,
+            architecture: None,
+            os: None,
+            created_at: None,
+            first_seen: at,
+            last_seen: at,
+        };
+        let point = |hours: i64, image_id: &str, size_bytes: i64| -> BTreeMap<&'static str, sea_orm::Value> {
+            BTreeMap::from([
+                ("timestamp", (at + chrono::Duration::hours(hours)).into()),
+                ("image_id", image_id.into()),
+                ("size_bytes", size_bytes.into()),
+            ])
+        };
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![image]])
+            .append_query_results([vec![point(0, "sha256:old", 200), point(5, "sha256:new", 300)]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let trend = service
+            .get_image_size_trend("sha256:new", at.with_timezone(&Utc), (at + chrono::Duration::days(1)).with_timezone(&Utc))
+            .await
+            .unwrap();
+        assert_eq!(trend.points.len(), 2);
+        assert_eq!(trend.delta_bytes, 100);
+
+        let QueryService { db } = service;
+        let log = db.into_transaction_log();
+        let statement = &log[1].statements()[0];
+        assert!(statement.sql.contains("ROW_NUMBER() OVER (PARTITION BY image_id ORDER BY timestamp) AS build_row"));
+        assert!(statement.sql.contains("WHERE build_row = 1"));
+        assert!(statement.sql.contains("jsonb_exists_any(repo_tags::jsonb, ARRAY[$4])"));
+        let tag: sea_orm::Value = "app:latest".into();
+        assert_eq!(statement.values.as_ref().map(|values| &values.0[3]), Some(&tag));
+    }
+
+    #[test]
+    fn keyset_cursor_round_trips() {
+        let cursor = KeysetCursor::parse("2026-01-01T00:00:00.250Z_42").unwrap();
+        assert_eq!(cursor.id, Some(42));
+        assert_eq!(cursor.to_string(), "2026-01-01T00:00:00.250000Z_42");
+        assert_eq!(KeysetCursor::parse(&cursor.to_string()), Some(cursor));
+
+        let bare = KeysetCursor::parse("2026-01-01T00:00:00+00:00").unwrap();
+        assert_eq!(bare.id, None);
+        assert_eq!(KeysetCursor::parse("2026-01-01T00:00:00Z_x"), None);
+        assert_eq!(KeysetCursor::parse("yesterday"), None);
+    }
+
+    #[tokio::test]
+    async fn stats_history_pages_by_timestamp_and_id() {
+        let first = stats_row(7, "aaa", "api");
+        let second = stats_row(6, "aaa", "api");
+        let last_timestamp = second.timestamp.with_timezone(&Utc);
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![first, second]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let before = KeysetCursor::parse("2026-01-01T00:00:00Z_9").unwrap();
+        let (stats, last) = service
+            .get_container_stats_history(&ContainerSelector::Id("aaa".to_string()), None, None, Some(before), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(last, Some(KeysetCursor { timestamp: last_timestamp, id: Some(6) }));
+
+        let QueryService { db } = service;
+        let log = db.into_transaction_log();
+        let statement = &log[0].statements()[0];
+        assert!(statement.sql.contains(r#"(timestamp, id) < ($2, $3)"#), "{}", statement.sql);
+        assert!(statement.sql.ends_with(r#"ORDER BY "container_stats"."timestamp" DESC, "container_stats"."id" DESC LIMIT $4"#), "{}", statement.sql);
+    }
+
+    #[test]
+    fn retention_accepts_known_tables_and_whole_intervals() {
+        for table in RETENTION_HYPERTABLES {
+            assert_eq!(QueryService::validate_retention(table, None), Ok(()));
+        }
+        for interval in ["1 hour", "14 days", "1 DAY", "  6   months ", "4294967295 years"] {
+            assert_eq!(QueryService::validate_retention("container_logs", Some(interval)), Ok(()), "{}", interval);
+        }
+    }
+
+    #[test]
+    fn retention_rejects_unknown_tables_and_bad_intervals() {
+        let error = QueryService::validate_retention("container_info", Some("14 days")).unwrap_err();
+        assert!(error.starts_with("Unknown table 'container_info'"), "{}", error);
+        assert!(QueryService::validate_retention("pg_catalog.pg_class", None).is_err());
+
+        for interval in [
+            "",
+            "14",
+            "days",
+            "0 days",
+            "-1 days",
+            "1.5 days",
+            "14 fortnights",
+            "14 days; DROP TABLE x",
+            "4294967296 years",
+        ] {
+            let error = QueryService::validate_retention("container_logs", Some(interval)).unwrap_err();
+            assert!(error.starts_with("Invalid interval"), "{}: {}", interval, error);
+        }
+    }
+
+    fn bucket_row(minute: i64, cpu: Option<f64>, memory: Option<f64>) -> BTreeMap<&'static str, sea_orm::Value> {
+        let bucket = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap() + chrono::Duration::minutes(minute);
+        BTreeMap::from([
+            ("bucket", bucket.into()),
+            ("avg_cpu", cpu.into()),
+            ("max_cpu", cpu.into()),
+            ("avg_mem", memory.into()),
+            ("max_mem", memory.into()),
+            ("avg_mem_pct", Option::<f64>::None.into()),
+            ("max_mem_pct", Option::<f64>::None.into()),
+        ])
+    }
+
+    #[tokio::test]
+    async fn gapfilled_history_keeps_empty_buckets_as_nulls() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![
+                bucket_row(0, Some(12.5), Some(2048.0)),
+                bucket_row(1, None, None),
+                bucket_row(2, Some(7.0), Some(1024.0)),
+            ]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let from = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let selector = ContainerSelector::Id("abc".to_string());
+        let history = service
+            .get_container_stats_history_gapfilled(&selector, from, from + chrono::Duration::minutes(3), 60, false)
+            .await
+            .unwrap();
+
+        let points: Vec<_> = history
+            .iter()
+            .map(|point| (point.avg_cpu_usage_percent, point.max_memory_usage_bytes))
+            .collect();
+        assert_eq!(points, [(Some(12.5), Some(2048)), (None, None), (Some(7.0), Some(1024))]);
+
+        let QueryService { db } = service;
+        let log = db.into_transaction_log();
+        let statement = &log[0].statements()[0];
+        assert!(statement.sql.contains("time_bucket_gapfill($1::interval, timestamp, $3::timestamptz, $4::timestamptz)"));
+        assert!(statement.sql.contains("FROM container_stats\n"));
+        assert!(!statement.sql.contains("locf("));
+        let values = &statement.values.as_ref().unwrap().0;
+        assert_eq!(values[0], "60 seconds".into());
+        assert_eq!(values[1], "abc".into());
+    }
+
+    #[tokio::test]
+    async fn gapfilled_history_carries_forward_from_the_hourly_aggregate() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([Vec::<BTreeMap<&'static str, sea_orm::Value>>::new()])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let from = DateTime::parse_from_rfc3339("2026-01-01T00:30:00Z").unwrap().with_timezone(&Utc);
+        let selector = ContainerSelector::Name("web".to_string());
+        service
+            .get_container_stats_history_gapfilled(&selector, from, from + chrono::Duration::days(2), 3600, true)
+            .await
+            .unwrap();
+
+        let QueryService { db } = service;
+        let log = db.into_transaction_log();
+        let statement = &log[0].statements()[0];
+        assert!(statement.sql.contains("FROM container_stats_hourly"));
+        assert!(statement.sql.contains("time_bucket('1 hour', $3::timestamptz)"));
+        assert!(statement.sql.contains("locf(AVG(avg_cpu_usage_percent)) AS avg_cpu"));
+        assert!(statement.sql.contains("WHERE container_name = $2"));
+        assert_eq!(statement.values.as_ref().unwrap().0[0], "3600 seconds".into());
+    }
+
+    #[tokio::test]
+    async fn http_requests_filter_by_method_and_escaped_endpoint_prefix() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([Vec::<http_requests::Model>::new()])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let selector = ContainerSelector::Id("abc".to_string());
+        service
+            .get_container_http_requests(&selector, None, None, Some("post"), Some("/api/100%_off"), Some(50))
+            .await
+            .unwrap();
+
+        let QueryService { db } = service;
+        let log = db.into_transaction_log();
+        let statement = &log[0].statements()[0];
+        assert!(statement.sql.contains(r#""http_requests"."method" = $2 AND (endpoint ILIKE $3)"#));
+        let values = &statement.values.as_ref().unwrap().0;
+        assert_eq!(values[1], "POST".into());
+        // `%` and `_` in the prefix match literally
+        assert_eq!(values[2], r"/api/100\%\_off%".into());
+    }
+
+    #[tokio::test]
+    async fn container_endpoints_map_counts_and_average_latency() {
+        let endpoint_row = |method: &str, endpoint: &str, requests: i64, avg_ms: Option<f64>, errors: i64| {
+            BTreeMap::from([
+                ("method", sea_orm::Value::from(method)),
+                ("endpoint", endpoint.into()),
+                ("request_count", requests.into()),
+                ("avg_ms", avg_ms.into()),
+                ("error_count", errors.into()),
+            ])
+        };
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![
+                endpoint_row("GET", "/api/users/:id", 120, Some(8.5), 3),
+                endpoint_row("POST", "/api/orders", 4, None, 0),
+            ]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let to = DateTime::parse_from_rfc3339("2026-01-01T01:00:00Z").unwrap().with_timezone(&Utc);
+        let selector = ContainerSelector::Name("api".to_string());
+        let endpoints = service
+            .get_container_endpoints(&selector, to - chrono::Duration::hours(1), to, 20)
+            .await
+            .unwrap();
+
+        let rows: Vec<_> = endpoints
+            .iter()
+            .map(|e| (e.method.as_str(), e.endpoint.as_str(), e.request_count, e.avg_response_time_ms, e.error_count))
+            .collect();
+        assert_eq!(rows, [("GET", "/api/users/:id", 120, 8.5, 3), ("POST", "/api/orders", 4, 0.0, 0)]);
+
+        let QueryService { db } = service;
+        let log = db.into_transaction_log();
+        let statement = &log[0].statements()[0];
+        assert!(statement.sql.contains("WHERE container_name = $1"));
+        assert!(statement.sql.contains("GROUP BY method, endpoint"));
+        let values = &statement.values.as_ref().unwrap().0;
+        assert_eq!((&values[0], &values[3]), (&"api".into(), &20i64.into()));
+    }
+
+    fn log_row(id: i32, log_line: &str) -> container_logs::Model {
+        let timestamp = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap() + chrono::Duration::seconds(i64::from(id));
+        container_logs::Model {
+            id,
+            container_id: "aaa".to_string(),
+            container_name: "api".to_string(),
+            log_line: log_line.to_string(),
+            stream: "stderr".to_string(),
+            timestamp,
+            created_at: timestamp,
+        }
+    }
+
+    #[tokio::test]
+    async fn log_search_matches_the_index_expression_and_pages_by_time() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![log_row(9, "connection refused"), log_row(4, "connection refused again")]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        let from = Utc::now() - chrono::Duration::hours(1);
+        let to = Utc::now();
+        let before = KeysetCursor::parse("2026-01-02T00:00:00Z_12").unwrap();
+        let (logs, last) = service
+            .search_logs("connection refused", from, to, Some("aaa"), Some("stderr"), Some(before), false, 2)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(last, Some(KeysetCursor { timestamp: log_row(4, "").timestamp.with_timezone(&Utc), id: Some(4) }));
+
+        let QueryService { db } = service;
+        let log = db.into_transaction_log();
+        let statement = &log[0].statements()[0];
+        assert_eq!(
+            statement.sql,
+            "SELECT * FROM container_logs WHERE to_tsvector('simple', log_line) @@ plainto_tsquery('simple', $1) \
+             AND timestamp >= $2 AND timestamp <= $3 AND container_id = $4 AND stream = $5 \
+             AND (timestamp, id) < ($6, $7) ORDER BY timestamp DESC, id DESC LIMIT $8"
+        );
+        let values = &statement.values.as_ref().unwrap().0;
+        assert_eq!(values[0], "connection refused".into());
+        assert_eq!(values[3], "aaa".into());
+        assert_eq!(values[4], "stderr".into());
+        assert_eq!(values[6], 12i64.into());
+        assert_eq!(values[7], 2i64.into());
+    }
+
+    #[tokio::test]
+    async fn log_search_by_relevance_orders_by_rank() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![log_row(1, "timeout")]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        service
+            .search_logs("timeout", Utc::now() - chrono::Duration::hours(1), Utc::now(), None, None, None, true, 10)
+            .await
+            .unwrap();
+
+        let QueryService { db } = service;
+        let log = db.into_transaction_log();
+        let statement = &log[0].statements()[0];
+        assert!(
+            statement.sql.ends_with(
+                "ORDER BY ts_rank(to_tsvector('simple', log_line), plainto_tsquery('simple', $1)) DESC, timestamp DESC, id DESC LIMIT $4"
+            ),
+            "{}",
+            statement.sql
+        );
+    }
+
+    #[tokio::test]
+    async fn top_containers_by_network_rank_by_recent_throughput() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![stats_row(1, "aaa", "api")]])
+            .into_connection();
+        let service = QueryService::new(db);
+
+        service.get_top_containers(TopMetric::Network, 3).await.unwrap();
+
+        let QueryService { db } = service;
+        let log = db.into_transaction_log();
+        let statement = &log[0].statements()[0];
+        assert!(statement.sql.contains(TOP_NETWORK_BASELINE_JOIN), "{}", statement.sql);
+        assert!(
+            statement.sql.ends_with(&format!("ORDER BY {} DESC, latest.container_name LIMIT $2", TOP_NETWORK_RATE_EXPRESSION)),
+            "{}",
+            statement.sql
+        );
+        let values = &statement.values.as_ref().unwrap().0;
+        assert_eq!(values[1], 3i64.into());
+        assert_eq!(values[2], "300 seconds".into());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::DockerService;
+use eyes_devine_shared::{ComponentState, ComponentStatus, Readiness};
+use sea_orm::DatabaseConnection;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Time allowed for each dependency check; a slower answer counts as down
+pub const READINESS_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Check that Docker answers a container listing and the database a ping, concurrently.
+/// Without a connection the database is down when `database_required` (it was configured
+/// but couldn't be reached), otherwise disabled
+pub async fn check(
+    docker_service: &DockerService,
+    db: Option<&DatabaseConnection>,
+    database_required: bool,
+) -> Readiness {
+    let docker = timed(async { docker_service.list_containers().await.map(|_| ()) });
+    let database = async {
+        match db {
+            Some(db) => timed(async { db.ping().await.map_err(anyhow::Error::from) }).await,
+            None if database_required => ComponentStatus {
+                state: ComponentState::Down,
+                latency_ms: None,
+                error: Some("Not connected".to_string()),
+            },
+            None => ComponentStatus {
+                state: ComponentState::Disabled,
+                latency_ms: None,
+                error: None,
+            },
+        }
+    };
+    let (docker, database) = tokio::join!(docker, database);
+
+    Readiness {
+        ready: docker.state != ComponentState::Down && database.state != ComponentState::Down,
+        docker,
+        database,
+    }
+}
+
+async fn timed(check: impl Future<Output = anyhow::Result<()>>) -> ComponentStatus {
+    let started = Instant::now();
+    let result = tokio::time::timeout(READINESS_CHECK_TIMEOUT, check).await;
+    let latency_ms = Some(started.elapsed().as_millis() as u64);
+
+    match result {
+        Ok(Ok(())) => ComponentStatus {
+            state: ComponentState::Up,
+            latency_ms,
+            error: None,
+        },
+        Ok(Err(e)) => ComponentStatus {
+            state: ComponentState::Down,
+            latency_ms,
+            error: Some(e.to_string()),
+        },
+        Err(_) => ComponentStatus {
+            state: ComponentState::Down,
+            latency_ms,
+            error: Some(format!("No answer within {}s", READINESS_CHECK_TIMEOUT.as_secs())),
+        },
+    }
+}
@@ -1,9 +1,14 @@
 pub mod models;
 
 pub use models::{
-    ContainerInfo, ContainerLog, ContainerStats, TotalStats, LogFilter,
-    ContainerDetails, ContainerEnvironment, ImageInfo, ComprehensiveStats,
+    FleetStatsHistory, FleetStatsPoint, AggregatedContainerStats, GapfilledContainerStats, NetworkRate,
+    ContainerInfo, ComposeProject, COMPOSE_PROJECT_LABEL, COMPOSE_SERVICE_LABEL, ContainerState, ContainerRunState, ContainerHealth, ContainerLog, ContainerStats, TotalStats, LogFilter,
+    ContainerDetails, ContainerEnvironment, ImageInfo, ImageSizePoint, ImageSizeTrend, ImageContainerUsage, StaleImage, StaleImageReport, ComprehensiveStats,
     ConnectionType, NetworkInfo, PortMapping, ContainerNetworkInfo,
-    ServiceConnection, ServiceNode, ServiceEdge, ServiceMap, HttpRequest,
+    ContainerFullDetails, ContainerMount, ContainerRestartPolicy,
+    ServiceConnection, ServiceNode, ServiceEdge, ServiceMap, ServiceMapDiff, ServiceEdgeChange, HttpRequest,
+    DockerConnectionHealth, ComponentState, ComponentStatus, Readiness, CaptureHealth, CaptureStats, ContainerEvent, DockerContainerEvent, HttpStatusBreakdown, HttpRequestSummary, HttpLatencyPercentiles, HttpEndpointStats, MemoryPressure,
+    ResourceRecommendation, UsagePercentiles,
+    DiskUsage, ContainerStatusChange, ContainerProcess, Alert, AlertComparator, AlertMetric, AlertRule, AlertRuleInput, AlertState,
 };
 
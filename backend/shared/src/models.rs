@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContainerStats {
@@ -9,16 +10,52 @@ pub struct ContainerStats {
     pub memory_usage_bytes: u64,
     pub memory_limit_bytes: u64,
     pub memory_usage_percent: f64,
+    #[serde(default)]
+    pub memory_cache_bytes: u64, // Page cache (`cache` on cgroup v1, `file` on v2); 0 when not reported
+    #[serde(default)]
+    pub memory_swap_bytes: u64, // Swap in use (cgroup v1 with swap accounting only); 0 when not reported
     pub network_rx_bytes: u64,
     pub network_tx_bytes: u64,
     pub block_read_bytes: u64,
     pub block_write_bytes: u64,
+    #[serde(default)]
+    pub per_cpu_usage_percent: Vec<f64>, // One entry per core; empty when Docker doesn't report per-core usage (cgroup v2)
+    #[serde(default)]
+    pub pids_current: u64, // Processes/threads in the container's cgroup; 0 when not reported
+    #[serde(default)]
+    pub pids_limit: u64, // 0 when there is no limit (or it isn't reported)
     pub timestamp: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ContainerStats {
+    /// All-zero stats, for containers that are stopped or whose stats can't be read
+    pub fn empty(container_id: String, container_name: String, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            container_id,
+            container_name,
+            cpu_usage_percent: 0.0,
+            memory_usage_bytes: 0,
+            memory_limit_bytes: 0,
+            memory_usage_percent: 0.0,
+            memory_cache_bytes: 0,
+            memory_swap_bytes: 0,
+            network_rx_bytes: 0,
+            network_tx_bytes: 0,
+            block_read_bytes: 0,
+            block_write_bytes: 0,
+            per_cpu_usage_percent: Vec::new(),
+            pids_current: 0,
+            pids_limit: 0,
+            timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TotalStats {
-    pub total_containers: usize,
+    pub total_containers: usize, // All containers, including stopped ones
+    #[serde(default)]
+    pub running_containers: usize, // Containers the resource totals are taken over
     pub total_cpu_usage_percent: f64,
     pub total_memory_usage_bytes: u64,
     pub total_memory_limit_bytes: u64,
@@ -30,13 +67,216 @@ pub struct TotalStats {
     pub timestamp: DateTime<Utc>,
 }
 
+/// Fleet-wide stats rolled up per time bucket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetStatsHistory {
+    pub bucket_seconds: i64,
+    pub source: String, // "total_stats" (worker snapshots), "raw" (container_stats), "hourly" (container_stats_hourly aggregate), or "total_stats+raw"/"total_stats+hourly" when only some buckets have snapshots
+    pub points: Vec<FleetStatsPoint>,
+}
+
+/// One bucket of the fleet series; value fields are None for buckets without data (gaps)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetStatsPoint {
+    pub timestamp: DateTime<Utc>, // Bucket start
+    pub total_containers: usize, // Containers reporting in the bucket
+    #[serde(default)]
+    pub all_containers: Option<usize>, // Including stopped ones; only known for buckets served from total_stats
+    pub total_cpu_usage_percent: Option<f64>, // Averaged across containers, like TotalStats
+    pub total_memory_usage_bytes: Option<u64>,
+    pub avg_memory_usage_percent: Option<f64>,
+}
+
+/// One time bucket of a container's stats history (from the hourly aggregate or raw samples)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregatedContainerStats {
+    pub timestamp: DateTime<Utc>, // Bucket start
+    pub avg_cpu_usage_percent: f64,
+    pub max_cpu_usage_percent: f64,
+    pub avg_memory_usage_bytes: u64,
+    pub max_memory_usage_bytes: u64,
+    pub avg_memory_usage_percent: f64,
+    pub max_memory_usage_percent: f64,
+}
+
+/// One bucket of a gap-filled stats history. Every bucket of the range is present; the
+/// values are null where there were no samples, or the last known values when carried forward
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GapfilledContainerStats {
+    pub timestamp: DateTime<Utc>, // Bucket start
+    pub avg_cpu_usage_percent: Option<f64>,
+    pub max_cpu_usage_percent: Option<f64>,
+    pub avg_memory_usage_bytes: Option<u64>,
+    pub max_memory_usage_bytes: Option<u64>,
+    pub avg_memory_usage_percent: Option<f64>,
+    pub max_memory_usage_percent: Option<f64>,
+}
+
+/// Network throughput of a container over one time bucket, from its cumulative byte counters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkRate {
+    pub timestamp: DateTime<Utc>, // Bucket start
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContainerInfo {
     pub id: String,
     pub name: String,
     pub image: String,
     pub status: String,
-    pub created: Option<DateTime<Utc>>,
+    pub created: Option<DateTime<Utc>>, // When the container object was created
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>, // Last start (State.StartedAt); None if never started or not inspected
+    #[serde(default)]
+    pub restart_count: Option<i64>, // Restarts by the restart policy (RestartCount); None if not inspected
+    #[serde(default)]
+    pub state: Option<ContainerState>, // Structured form of `status` from inspect; None if not inspected
+    #[serde(default)]
+    pub health: Option<String>, // "starting", "healthy" or "unhealthy"; None without a health check
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>, // e.g. com.docker.compose.project
+    #[serde(default)]
+    pub compose_project: Option<String>, // From COMPOSE_PROJECT_LABEL; None for standalone containers
+    #[serde(default)]
+    pub compose_service: Option<String>, // From COMPOSE_SERVICE_LABEL
+    #[serde(default)]
+    pub collected_at: Option<DateTime<Utc>>, // When the container list was read; None if unknown
+}
+
+/// Labels Docker Compose sets on the containers of a stack
+pub const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+pub const COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
+
+impl ContainerInfo {
+    /// Whether the container is running (paused or restarting containers are not).
+    /// Uses the structured state when inspected, otherwise Docker's status string
+    /// ("Up 3 hours (healthy)", "Up 2 minutes (Paused)", "Restarting (1) 5 seconds ago", ...)
+    pub fn is_running(&self) -> bool {
+        match self.state {
+            Some(state) => state.state == ContainerRunState::Running,
+            None => {
+                let status = self.status.to_lowercase();
+                (status.starts_with("up") && !status.contains("(paused)")) || status == "running"
+            }
+        }
+    }
+
+    /// Compose project and service from the container's labels
+    pub fn compose_labels(labels: &BTreeMap<String, String>) -> (Option<String>, Option<String>) {
+        (
+            labels.get(COMPOSE_PROJECT_LABEL).cloned(),
+            labels.get(COMPOSE_SERVICE_LABEL).cloned(),
+        )
+    }
+}
+
+/// Containers of one Docker Compose project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComposeProject {
+    pub project: Option<String>, // None groups the standalone containers
+    pub containers: Vec<ContainerInfo>,
+}
+
+impl ComposeProject {
+    /// Group containers by compose project, projects by name with the standalone group
+    /// last; containers within a project are ordered by service, then name
+    pub fn group(containers: Vec<ContainerInfo>) -> Vec<ComposeProject> {
+        let mut projects: BTreeMap<Option<String>, Vec<ContainerInfo>> = BTreeMap::new();
+        for container in containers {
+            projects.entry(container.compose_project.clone()).or_default().push(container);
+        }
+
+        // None sorts first in the map; move the standalone group to the end
+        let standalone = projects.remove(&None);
+        projects
+            .into_iter()
+            .chain(standalone.map(|containers| (None, containers)))
+            .map(|(project, mut containers)| {
+                containers.sort_by(|a, b| {
+                    a.compose_service.cmp(&b.compose_service).then_with(|| a.name.cmp(&b.name))
+                });
+                ComposeProject { project, containers }
+            })
+            .collect()
+    }
+}
+
+/// Structured container state, derived from inspect's `State` rather than the `status` string
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContainerState {
+    pub state: ContainerRunState,
+    pub exit_code: Option<i32>, // Only set for exited/dead containers
+    pub health: Option<ContainerHealth>, // None when the container has no healthcheck
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRunState {
+    Created,
+    Running,
+    Paused,
+    Restarting,
+    Removing,
+    Exited,
+    Dead,
+}
+
+impl ContainerRunState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Running => "running",
+            Self::Paused => "paused",
+            Self::Restarting => "restarting",
+            Self::Removing => "removing",
+            Self::Exited => "exited",
+            Self::Dead => "dead",
+        }
+    }
+
+    /// Parse Docker's state name (as in inspect's `State.Status`)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "created" => Some(Self::Created),
+            "running" => Some(Self::Running),
+            "paused" => Some(Self::Paused),
+            "restarting" => Some(Self::Restarting),
+            "removing" => Some(Self::Removing),
+            "exited" => Some(Self::Exited),
+            "dead" => Some(Self::Dead),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerHealth {
+    Starting,
+    Healthy,
+    Unhealthy,
+}
+
+impl ContainerHealth {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Starting => "starting",
+            Self::Healthy => "healthy",
+            Self::Unhealthy => "unhealthy",
+        }
+    }
+
+    /// Parse Docker's health status; "none"/empty (no healthcheck) yields None
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "starting" => Some(Self::Starting),
+            "healthy" => Some(Self::Healthy),
+            "unhealthy" => Some(Self::Unhealthy),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +320,46 @@ pub struct ImageInfo {
     pub os: Option<String>,
 }
 
+/// A running container that uses an image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageContainerUsage {
+    pub container_id: String,
+    pub container_name: String,
+}
+
+/// An image in the staleness report with the running containers using it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleImage {
+    pub image: ImageInfo,
+    pub age_days: Option<i64>, // Days since the image was built; None if the build date is unknown
+    pub containers: Vec<ImageContainerUsage>,
+}
+
+/// Images built more than `older_than_days` ago, a lightweight patch-hygiene report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleImageReport {
+    pub older_than_days: u32,
+    pub images: Vec<StaleImage>, // Oldest first
+    pub unknown_created: Vec<StaleImage>, // Build date unknown, so their age can't be judged
+}
+
+/// Size of an image build at one image snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageSizePoint {
+    pub timestamp: DateTime<Utc>,
+    pub image_id: String, // Changes when the tag was rebuilt
+    pub size_bytes: u64,
+}
+
+/// How an image's size changed across rebuilds under its tags
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageSizeTrend {
+    pub image_id: String,
+    pub repo_tags: Vec<String>, // Tags whose rebuilds are followed
+    pub points: Vec<ImageSizePoint>, // Oldest first, one per build plus the latest snapshot
+    pub delta_bytes: i64, // Last point minus first; 0 with fewer than two points
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContainerDetails {
     pub container_id: String,
@@ -93,7 +373,7 @@ pub struct ContainerDetails {
     pub created: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ComprehensiveStats {
     pub total_containers: usize,
     pub containers_up: usize,
@@ -109,6 +389,8 @@ pub struct ComprehensiveStats {
 pub enum ConnectionType {
     #[serde(rename = "environment_variable")]
     EnvironmentVariable,
+    #[serde(rename = "database_url")]
+    DatabaseUrl,
     #[serde(rename = "same_network")]
     SameNetwork,
     #[serde(rename = "port_mapping")]
@@ -132,6 +414,8 @@ pub struct PortMapping {
     pub container_port: u16,
     pub host_port: Option<u16>,
     pub protocol: String, // "tcp", "udp"
+    pub host_ip: Option<String>,
+    pub published: bool, // reachable from the host (bound port or host network mode)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,6 +425,47 @@ pub struct ContainerNetworkInfo {
     pub networks: Vec<NetworkInfo>,
     pub ports: Vec<PortMapping>,
     pub ip_addresses: Vec<String>,
+    pub network_mode: String, // "bridge", "host", "none", "container:<id>" or a network name
+}
+
+/// Everything worth showing about one container, assembled from a single inspect call.
+/// The environment is left out on purpose (it often holds secrets); see the `/env` endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerFullDetails {
+    pub id: String,
+    pub name: String,
+    pub image: String, // As configured, e.g. "nginx:latest"
+    pub image_id: String, // Resolved image ("sha256:...")
+    pub created: Option<DateTime<Utc>>,
+    pub state: Option<ContainerState>,
+    pub restart_count: Option<i64>,
+    pub command: Vec<String>, // Config.Cmd
+    pub entrypoint: Vec<String>, // Config.Entrypoint
+    pub working_dir: Option<String>,
+    pub user: Option<String>,
+    pub labels: BTreeMap<String, String>,
+    pub mounts: Vec<ContainerMount>,
+    pub restart_policy: ContainerRestartPolicy,
+    pub network_mode: String,
+    pub networks: Vec<NetworkInfo>, // With their aliases
+    pub ports: Vec<PortMapping>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerMount {
+    #[serde(rename = "type")]
+    pub mount_type: String, // "bind", "volume", "tmpfs", ...
+    pub name: Option<String>, // Volume name; None for bind mounts
+    pub source: String, // Host path (or the volume's mountpoint)
+    pub destination: String, // Path inside the container
+    pub mode: String, // Mode string as given, e.g. "ro", "rw,z"; often empty
+    pub read_write: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerRestartPolicy {
+    pub name: String, // "no", "always", "unless-stopped" or "on-failure"
+    pub maximum_retry_count: Option<i64>, // Only meaningful for "on-failure"
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -182,6 +507,25 @@ pub struct ServiceMap {
     pub timestamp: DateTime<Utc>,
 }
 
+/// Difference between two stored service-map snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceMapDiff {
+    pub from_timestamp: DateTime<Utc>, // Timestamp of the snapshot nearest to `from`
+    pub to_timestamp: DateTime<Utc>,   // Timestamp of the snapshot nearest to `to`
+    pub added_nodes: Vec<ServiceNode>,
+    pub removed_nodes: Vec<ServiceNode>,
+    pub added_edges: Vec<ServiceEdge>,
+    pub removed_edges: Vec<ServiceEdge>,
+    pub changed_edges: Vec<ServiceEdgeChange>,
+}
+
+/// An edge present in both snapshots whose confidence or evidence changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceEdgeChange {
+    pub before: ServiceEdge,
+    pub after: ServiceEdge,
+}
+
 // HTTP Request Tracking Models
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -193,5 +537,439 @@ pub struct HttpRequest {
     pub http_status: u16,        // e.g., 200, 404, 500
     pub response_time_ms: f64,   // Response time in milliseconds
     pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub headers: Option<BTreeMap<String, String>>, // Allowlisted headers (lowercase names); None when none matched
+    #[serde(default)]
+    pub client_container_id: Option<String>, // Container that sent the request (container_id is the one it was sent to); None for clients outside Docker
+}
+
+
+// Docker Connection Health
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockerConnectionHealth {
+    pub connected: bool,
+    pub last_checked: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub reconnect_count: u64,
+}
+
+// Readiness
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentState {
+    Up,
+    Down,
+    Disabled, // Not configured for this process; doesn't affect readiness
+}
+
+/// Result of checking one dependency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentStatus {
+    pub state: ComponentState,
+    pub latency_ms: Option<u64>, // Time the check took; None when it wasn't run
+    pub error: Option<String>,
+}
+
+/// Body of `GET /ready`: ready when no dependency is down
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub docker: ComponentStatus,
+    pub database: ComponentStatus,
+}
+
+// Packet Capture Health
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaptureHealth {
+    /// Silence after which a capture that had traffic is re-initialized; None if the watchdog is off
+    pub stall_timeout_secs: Option<u64>,
+    pub active_interfaces: Vec<String>,
+    pub restart_count: u64,
+    pub restarts_by_interface: BTreeMap<String, u64>,
+    pub last_restart: Option<DateTime<Utc>>,
+    pub last_restart_reason: Option<String>,
+}
+
+/// Packet capture counters since the worker started, summed over all interfaces
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaptureStats {
+    pub packets_seen: u64,
+    pub tcp_packets: u64,
+    /// Packets that carried HTTP data on a capture port
+    pub http_packets: u64,
+    /// HTTP requests and responses whose headers parsed
+    pub http_messages_parsed: u64,
+    /// Request/response pairs stored against a container
+    pub requests_matched: u64,
+    /// Request/response pairs no container could be found for (not stored unless configured)
+    pub requests_unmatched: u64,
+    /// Responses that arrived without a pending request to pair with
+    pub responses_without_request: u64,
+    /// Requests dropped after waiting too long for their response
+    pub requests_expired: u64,
+}
+
+// Container Lifecycle Events
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerEvent {
+    pub container_id: String,
+    pub container_name: String,
+    pub event_type: String,       // e.g., "died"
+    pub exit_code: Option<i64>,   // e.g., 0 (clean), 137 (SIGKILL/OOM)
+    pub oom_killed: Option<bool>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A container lifecycle event as reported live by the Docker events API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockerContainerEvent {
+    pub action: String,           // "create", "start", "stop" or "die"
+    pub container_id: String,
+    pub container_name: String,
+    pub exit_code: Option<i64>,   // only on "die"
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Request counts per HTTP status class for a container over a time range
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HttpStatusBreakdown {
+    #[serde(rename = "2xx")]
+    pub status_2xx: i64,
+    #[serde(rename = "3xx")]
+    pub status_3xx: i64,
+    #[serde(rename = "4xx")]
+    pub status_4xx: i64,
+    #[serde(rename = "5xx")]
+    pub status_5xx: i64,
+    pub other: i64, // 1xx and anything unparseable
+}
+
+/// Request volume and error rate for a container over a time range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpRequestSummary {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub total: i64,
+    pub status_classes: HttpStatusBreakdown,
+    pub error_rate: f64,          // 5xx / total, 0 without requests
+    pub requests_per_minute: f64, // total over the length of the range
+}
+
+/// HTTP response-time distribution for a container over a time range (all zero without data)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HttpLatencyPercentiles {
+    pub request_count: i64,
+    pub avg_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+/// One (method, endpoint) pair seen in a container's captured traffic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpEndpointStats {
+    pub method: String,
+    pub endpoint: String, // Normalized template, e.g. /api/users/:id
+    pub request_count: i64,
+    pub avg_response_time_ms: f64,
+    pub error_count: i64, // 5xx responses, like the summary's error rate
+}
+
+/// Observed distribution of one resource over a recommendation window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsagePercentiles {
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// CPU/memory limit suggestions for a container, derived from its stats history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceRecommendation {
+    pub container_id: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub sample_count: u64,
+    pub min_samples: u64,
+    /// True when there are fewer than `min_samples` samples; no suggestions are made then
+    pub insufficient_data: bool,
+    /// CPU usage in percent of one core
+    pub cpu_percent: Option<UsagePercentiles>,
+    pub memory_bytes: Option<UsagePercentiles>,
+    /// Memory limit reported by Docker in the latest sample (host memory when unlimited)
+    pub current_memory_limit_bytes: Option<u64>,
+    /// Fraction added on top of the observed p95 (0.2 = 20%)
+    pub headroom: f64,
+    pub suggested_cpu_limit_cores: Option<f64>,
+    pub suggested_memory_limit_bytes: Option<u64>,
+    /// Human-readable explanation of each suggestion
+    pub reasons: Vec<String>,
+}
+
+/// Fleet-wide memory usage against configured limits and host memory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryPressure {
+    pub total_containers: usize,
+    pub total_memory_usage_bytes: u64,
+    /// Sum of the configured limits of containers that have one
+    pub total_memory_limit_bytes: u64,
+    /// Usage of containers with a limit, as a percentage of those limits
+    pub usage_percent_of_limits: f64,
+    pub host_memory_bytes: Option<u64>,
+    /// Usage of all containers (including unlimited ones) as a percentage of host memory
+    pub usage_percent_of_host: Option<f64>,
+    pub unlimited_containers: usize,
+    pub threshold_percent: f64,
+    /// Containers whose memory usage percent is at or above `threshold_percent`
+    pub containers_over_threshold: usize,
+    pub containers_over_threshold_names: Vec<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Container stat an alert rule watches
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    CpuPercent,
+    MemoryPercent,
+    MemoryBytes,
+}
+
+impl AlertMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CpuPercent => "cpu_percent",
+            Self::MemoryPercent => "memory_percent",
+            Self::MemoryBytes => "memory_bytes",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "cpu_percent" => Some(Self::CpuPercent),
+            "memory_percent" => Some(Self::MemoryPercent),
+            "memory_bytes" => Some(Self::MemoryBytes),
+            _ => None,
+        }
+    }
+
+    /// The watched value in one stats sample
+    pub fn value(&self, stats: &ContainerStats) -> f64 {
+        match self {
+            Self::CpuPercent => stats.cpu_usage_percent,
+            Self::MemoryPercent => stats.memory_usage_percent,
+            Self::MemoryBytes => stats.memory_usage_bytes as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertComparator {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl AlertComparator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gt => "gt",
+            Self::Gte => "gte",
+            Self::Lt => "lt",
+            Self::Lte => "lte",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "gt" => Some(Self::Gt),
+            "gte" => Some(Self::Gte),
+            "lt" => Some(Self::Lt),
+            "lte" => Some(Self::Lte),
+            _ => None,
+        }
+    }
+
+    /// Whether `value` breaches `threshold`
+    pub fn breached(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Gt => value > threshold,
+            Self::Gte => value >= threshold,
+            Self::Lt => value < threshold,
+            Self::Lte => value <= threshold,
+        }
+    }
+}
+
+/// Threshold on one metric for the containers whose id or name matches `container_pattern`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub id: i64,
+    pub name: String,
+    /// Glob (`*` matches any run of characters) against the container id or name
+    pub container_pattern: String,
+    pub metric: AlertMetric,
+    pub comparator: AlertComparator,
+    pub threshold: f64,
+    /// How long the threshold must stay breached before the rule fires (0 = on the first sample)
+    pub duration_secs: u32,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body of the create and update rule endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRuleInput {
+    pub name: String,
+    #[serde(default = "default_alert_container_pattern")]
+    pub container_pattern: String,
+    pub metric: AlertMetric,
+    pub comparator: AlertComparator,
+    pub threshold: f64,
+    #[serde(default)]
+    pub duration_secs: u32,
+    #[serde(default = "default_alert_enabled")]
+    pub enabled: bool,
+}
+
+fn default_alert_container_pattern() -> String {
+    "*".to_string()
+}
+
+fn default_alert_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertState {
+    Firing,
+    Resolved,
+}
+
+impl AlertState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Firing => "firing",
+            Self::Resolved => "resolved",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "firing" => Some(Self::Firing),
+            "resolved" => Some(Self::Resolved),
+            _ => None,
+        }
+    }
+}
+
+/// One firing or resolved transition of a rule for a container
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub id: i64,
+    pub rule_id: i64,
+    pub rule_name: String,
+    pub container_id: String,
+    pub container_name: String,
+    pub metric: AlertMetric,
+    /// The sample value that caused the transition
+    pub value: f64,
+    pub threshold: f64,
+    pub state: AlertState,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A change of a container's run state, exit code or health, recorded by the worker when it
+/// sees the container for the first time or its state differs from the previous listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerStatusChange {
+    pub container_id: String,
+    pub container_name: String,
+    pub status: String, // Docker's status text at the time, e.g. "Exited (1) 2 seconds ago"
+    pub state: Option<ContainerState>,
+    pub timestamp: DateTime<Utc>,
 }
 
+/// Disk used by one container, from Docker's size accounting (expensive to compute, so sampled rarely)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsage {
+    pub container_id: String,
+    pub container_name: String,
+    /// Writable layer: files the container created or changed (`SizeRw`)
+    pub size_rw_bytes: Option<u64>,
+    /// Whole root filesystem including the image layers (`SizeRootFs`)
+    pub size_root_fs_bytes: Option<u64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// One row of `docker top` for a container
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerProcess {
+    pub pid: u32, // Host PID
+    pub user: String,
+    pub cpu_percent: Option<f64>, // None when ps did not report it (e.g. Windows containers)
+    pub memory_percent: Option<f64>,
+    pub command: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(status: &str, state: Option<ContainerRunState>) -> ContainerInfo {
+        ContainerInfo {
+            id: "abc".to_string(),
+            name: "web".to_string(),
+            image: "nginx".to_string(),
+            status: status.to_string(),
+            created: None,
+            started_at: None,
+            restart_count: None,
+            state: state.map(|state| ContainerState { state, exit_code: None, health: None }),
+            health: None,
+            labels: BTreeMap::new(),
+            compose_project: None,
+            compose_service: None,
+            collected_at: None,
+        }
+    }
+
+    #[test]
+    fn is_running_from_docker_status_strings() {
+        let cases = [
+            ("Up 3 hours", true),
+            ("Up 3 hours (healthy)", true),
+            ("Up 10 seconds (health: starting)", true),
+            ("Up About a minute (unhealthy)", true),
+            ("running", true),
+            ("Up 2 minutes (Paused)", false),
+            ("Restarting (1) 5 seconds ago", false),
+            ("Exited (0) 2 hours ago", false),
+            ("Exited (137) About a minute ago", false),
+            ("Created", false),
+            ("Removal In Progress", false),
+            ("Dead", false),
+            ("", false),
+        ];
+        for (status, running) in cases {
+            assert_eq!(container(status, None).is_running(), running, "status {:?}", status);
+        }
+    }
+
+    #[test]
+    fn is_running_prefers_inspected_state() {
+        assert!(!container("Up 3 hours", Some(ContainerRunState::Paused)).is_running());
+        assert!(!container("Up 3 hours", Some(ContainerRunState::Restarting)).is_running());
+        assert!(container("Exited (0) 2 hours ago", Some(ContainerRunState::Running)).is_running());
+    }
+}
@@ -25,6 +25,9 @@ export default defineConfig({
     entry: {
       index: './src/index.tsx',
     },
+    define: {
+      'import.meta.env.BACKEND_API_TOKEN': JSON.stringify(process.env.BACKEND_API_TOKEN ?? ''),
+    },
   },
 });
 
@@ -1,7 +1,7 @@
 import { useMemo } from 'react';
 import type { ContainerInfo, ContainerStats, DataPoint } from '../types';
 import MetricsChart from './MetricsChart';
-import { formatBytes, formatPercent } from '../utils/formatting';
+import { formatBytes, formatDate, formatPercent } from '../utils/formatting';
 import { Card, CardContent, CardHeader, CardTitle } from './ui/card';
 import { Badge } from './ui/badge';
 import { Separator } from './ui/separator';
@@ -10,10 +10,12 @@ interface ContainerCardProps {
   container: ContainerInfo;
   stats: ContainerStats | null;
   historicalData: DataPoint[];
+  // Left border color, used to group cards by a label value
+  accentColor?: string;
   onClick: () => void;
 }
 
-const ContainerCard = ({ container, stats, historicalData, onClick }: ContainerCardProps) => {
+const ContainerCard = ({ container, stats, historicalData, accentColor, onClick }: ContainerCardProps) => {
   const formattedStats = useMemo(() => {
     if (!stats) return null;
 
@@ -24,6 +26,9 @@ const ContainerCard = ({ container, stats, historicalData, onClick }: ContainerC
       memoryPercent: formatPercent(stats.memory_usage_percent),
       networkRx: formatBytes(stats.network_rx_bytes),
       networkTx: formatBytes(stats.network_tx_bytes),
+      pids: stats.pids_limit
+        ? `${stats.pids_current ?? 0} / ${stats.pids_limit}`
+        : `${stats.pids_current ?? 0} (no limit)`,
     };
   }, [stats]);
 
@@ -35,9 +40,18 @@ const ContainerCard = ({ container, stats, historicalData, onClick }: ContainerC
     return 'secondary';
   };
 
+  const healthVariant = {
+    healthy: 'success',
+    unhealthy: 'destructive',
+    starting: 'warning',
+  } as const;
+  const health = container.health;
+  const exitCode = container.state?.exit_code;
+
   return (
     <Card
       className="cursor-pointer transition-all duration-200 hover:-translate-y-0.5 hover:shadow-md"
+      style={accentColor ? { borderLeft: `4px solid ${accentColor}` } : undefined}
       onClick={onClick}
     >
       <CardHeader className="pb-3">
@@ -50,9 +64,36 @@ const ContainerCard = ({ container, stats, historicalData, onClick }: ContainerC
         <div className="text-sm text-gray-600 mb-2">
           <span className="font-medium text-gray-900">Image:</span> {container.image}
         </div>
+        {container.created && (
+          <div className="text-sm text-gray-600 mb-2">
+            <span className="font-medium text-gray-900">Created:</span> {formatDate(container.created)}
+          </div>
+        )}
+        <div className="text-sm text-gray-600 mb-2">
+          <span className="font-medium text-gray-900">Started:</span>{' '}
+          {container.started_at ? formatDate(container.started_at) : 'Never'}
+        </div>
+        {container.restart_count != null && (
+          <div className="text-sm text-gray-600 mb-2">
+            <span className="font-medium text-gray-900">Restarts:</span>{' '}
+            <span className={container.restart_count > 0 ? 'font-medium text-red-600' : undefined}>
+              {container.restart_count}
+            </span>
+          </div>
+        )}
         <div className="text-sm text-gray-600 mb-4">
           <span className="font-medium text-gray-900">Status:</span>{' '}
           <Badge variant={getStatusVariant(container.status)}>{container.status}</Badge>
+          {health && (
+            <Badge variant={healthVariant[health]} className="ml-2">
+              {health}
+            </Badge>
+          )}
+          {exitCode != null && (
+            <Badge variant={exitCode === 0 ? 'secondary' : 'destructive'} className="ml-2">
+              exit {exitCode}
+            </Badge>
+          )}
         </div>
 
         {stats && formattedStats && (
@@ -88,6 +129,9 @@ const ContainerCard = ({ container, stats, historicalData, onClick }: ContainerC
             <div className="mb-2 text-gray-900">
               <strong className="text-gray-600 mr-1">Network TX:</strong> {formattedStats.networkTx}
             </div>
+            <div className="mb-2 text-gray-900">
+              <strong className="text-gray-600 mr-1">PIDs:</strong> {formattedStats.pids}
+            </div>
           </div>
           </>
         )}
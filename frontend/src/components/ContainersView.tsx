@@ -1,6 +1,39 @@
-import { useMemo } from 'react';
+import { useEffect, useMemo, useState } from 'react';
 import type { ContainerInfo, ContainerStats, DataPoint } from '../types';
 import ContainerCard from './ContainerCard';
+import { isRunning, labelColor } from '../utils/containers';
+import { fetchContainerNetworkRate, fetchContainerStatsHistoryGapfilled } from '../services/api';
+import { Input } from './ui/input';
+
+const STATUS_FILTER_KEY = 'containers.statusFilter';
+// Replaced by STATUS_FILTER_KEY; still read so an earlier "hide stopped" choice carries over
+const HIDE_STOPPED_KEY = 'containers.hideStopped';
+const COLOR_BY_LABEL_KEY = 'containers.colorByLabel';
+const SORT_BY_KEY = 'containers.sortBy';
+
+type StatusFilter = 'all' | 'running' | 'stopped';
+
+// Metric sorts put the highest usage first, status puts running containers first; ties go by name
+const SORT_OPTIONS = [
+  { value: 'cpu', label: 'CPU' },
+  { value: 'memory', label: 'Memory' },
+  { value: 'name', label: 'Name' },
+  { value: 'status', label: 'Status' },
+] as const;
+
+type SortBy = (typeof SORT_OPTIONS)[number]['value'];
+
+// Chart ranges: `live` shows the in-memory rolling buffer, the others bucketed stats history
+// (bucket sizes keep each chart at roughly 60-170 points)
+const CHART_RANGES = [
+  { value: 'live', label: 'Live', seconds: 0, bucket: 0 },
+  { value: '1h', label: '1h', seconds: 60 * 60, bucket: 60 },
+  { value: '6h', label: '6h', seconds: 6 * 60 * 60, bucket: 300 },
+  { value: '24h', label: '24h', seconds: 24 * 60 * 60, bucket: 900 },
+  { value: '7d', label: '7d', seconds: 7 * 24 * 60 * 60, bucket: 3600 },
+] as const;
+
+type ChartRange = (typeof CHART_RANGES)[number]['value'];
 
 interface ContainersViewProps {
   containers: ContainerInfo[];
@@ -23,13 +56,231 @@ const ContainersView = ({
     return map;
   }, [containerStats]);
 
+  const [statusFilter, setStatusFilter] = useState<StatusFilter>(() => {
+    const stored = localStorage.getItem(STATUS_FILTER_KEY);
+    if (stored === 'all' || stored === 'running' || stored === 'stopped') {
+      return stored;
+    }
+    return localStorage.getItem(HIDE_STOPPED_KEY) === 'true' ? 'running' : 'all';
+  });
+
+  useEffect(() => {
+    localStorage.setItem(STATUS_FILTER_KEY, statusFilter);
+  }, [statusFilter]);
+
+  // Case-insensitive substring match on container name or image
+  const [searchQuery, setSearchQuery] = useState('');
+
+  const [sortBy, setSortBy] = useState<SortBy>(() => {
+    const stored = localStorage.getItem(SORT_BY_KEY);
+    return SORT_OPTIONS.find((option) => option.value === stored)?.value ?? 'cpu';
+  });
+
+  useEffect(() => {
+    localStorage.setItem(SORT_BY_KEY, sortBy);
+  }, [sortBy]);
+
+  // Label key whose value picks each card's accent color, e.g. com.docker.compose.project
+  const [colorByLabel, setColorByLabel] = useState(
+    () => localStorage.getItem(COLOR_BY_LABEL_KEY) || ''
+  );
+
+  useEffect(() => {
+    localStorage.setItem(COLOR_BY_LABEL_KEY, colorByLabel);
+  }, [colorByLabel]);
+
+  const labelKeys = useMemo(() => {
+    const keys = new Set<string>();
+    containers.forEach((c) => Object.keys(c.labels ?? {}).forEach((key) => keys.add(key)));
+    return Array.from(keys).sort();
+  }, [containers]);
+
+  const statusFilteredContainers = useMemo(() => {
+    switch (statusFilter) {
+      case 'running':
+        return containers.filter(isRunning);
+      case 'stopped':
+        return containers.filter((c) => !isRunning(c));
+      default:
+        return containers;
+    }
+  }, [containers, statusFilter]);
+
+  const visibleContainers = useMemo(() => {
+    const query = searchQuery.trim().toLowerCase();
+    const matching = query
+      ? statusFilteredContainers.filter(
+          (c) => c.name.toLowerCase().includes(query) || c.image.toLowerCase().includes(query)
+        )
+      : statusFilteredContainers;
+
+    const metric = (c: ContainerInfo): number | undefined => {
+      const stats = statsMap.get(c.id);
+      if (!stats) return undefined;
+      return sortBy === 'cpu' ? stats.cpu_usage_percent : stats.memory_usage_bytes;
+    };
+
+    return [...matching].sort((a, b) => {
+      if (sortBy === 'cpu' || sortBy === 'memory') {
+        const left = metric(a);
+        const right = metric(b);
+        // Containers without stats go last
+        if (left === undefined || right === undefined) {
+          if (left !== right) return left === undefined ? 1 : -1;
+        } else if (left !== right) {
+          return right - left;
+        }
+      } else if (sortBy === 'status') {
+        // Running containers first
+        const byStatus = Number(isRunning(b)) - Number(isRunning(a));
+        if (byStatus !== 0) return byStatus;
+      }
+      return a.name.localeCompare(b.name);
+    });
+  }, [statusFilteredContainers, searchQuery, sortBy, statsMap]);
+
+  const [chartRange, setChartRange] = useState<ChartRange>('live');
+  const [rangeData, setRangeData] = useState<Map<string, DataPoint[]>>(new Map());
+  const [rangeError, setRangeError] = useState<string | null>(null);
+
+  // Refetch only when the range or the set of containers changes, not on every refresh or
+  // keystroke in the search box
+  const visibleIds = statusFilteredContainers.map((c) => c.id).join(',');
+
+  useEffect(() => {
+    const range = CHART_RANGES.find((r) => r.value === chartRange);
+    if (!range || range.value === 'live' || !visibleIds) {
+      setRangeData(new Map());
+      setRangeError(null);
+      return;
+    }
+
+    let cancelled = false;
+    const now = Date.now();
+    const from = new Date(now - range.seconds * 1000).toISOString();
+    const to = new Date(now).toISOString();
+    Promise.all(
+      visibleIds.split(',').map(async (id) => {
+        // Gap-filled so stopped periods show as breaks instead of lines drawn across them
+        const [buckets, rates] = await Promise.all([
+          fetchContainerStatsHistoryGapfilled(id, { from, to, bucket: range.bucket }),
+          fetchContainerNetworkRate(id, { from, to, bucket: range.bucket }),
+        ]);
+        const networkKbByBucket = new Map(
+          rates.map((r) => [
+            new Date(r.timestamp).getTime(),
+            (r.rx_bytes_per_sec + r.tx_bytes_per_sec) / 1024,
+          ])
+        );
+        const points: DataPoint[] = buckets.map((b) => ({
+          timestamp: new Date(b.timestamp).getTime() / 1000,
+          cpu: b.avg_cpu_usage_percent,
+          memory: b.avg_memory_usage_percent,
+          network: networkKbByBucket.get(new Date(b.timestamp).getTime()) ?? null,
+        }));
+        return [id, points] as const;
+      })
+    )
+      .then((entries) => {
+        if (!cancelled) {
+          setRangeData(new Map(entries));
+          setRangeError(null);
+        }
+      })
+      .catch((err) => {
+        if (!cancelled) {
+          setRangeError(err instanceof Error ? err.message : 'Failed to load stats history');
+        }
+      });
+
+    return () => {
+      cancelled = true;
+    };
+  }, [chartRange, visibleIds]);
+
   return (
     <div className="mb-8">
-      <h2 className="text-2xl mb-5 text-gray-900">Containers</h2>
+      <div className="flex items-center justify-between mb-5">
+        <h2 className="text-2xl text-gray-900">Containers</h2>
+        <div className="flex items-center gap-4">
+          <label className="flex items-center gap-2 text-sm text-gray-600">
+            Charts
+            <select
+              className="text-sm border border-gray-300 rounded px-2 py-1"
+              value={chartRange}
+              onChange={(e) => setChartRange(e.target.value as ChartRange)}
+              title="Live shows the last two minutes; longer ranges show averages from the stats history"
+            >
+              {CHART_RANGES.map((range) => (
+                <option key={range.value} value={range.value}>
+                  {range.label}
+                </option>
+              ))}
+            </select>
+          </label>
+          <label className="flex items-center gap-2 text-sm text-gray-600">
+            Color by label
+            <select
+              className="text-sm border border-gray-300 rounded px-2 py-1"
+              value={colorByLabel}
+              onChange={(e) => setColorByLabel(e.target.value)}
+            >
+              <option value="">None</option>
+              {colorByLabel && !labelKeys.includes(colorByLabel) && (
+                <option value={colorByLabel}>{colorByLabel}</option>
+              )}
+              {labelKeys.map((key) => (
+                <option key={key} value={key}>
+                  {key}
+                </option>
+              ))}
+            </select>
+          </label>
+          <label className="flex items-center gap-2 text-sm text-gray-600">
+            Sort by
+            <select
+              className="text-sm border border-gray-300 rounded px-2 py-1"
+              value={sortBy}
+              onChange={(e) => setSortBy(e.target.value as SortBy)}
+            >
+              {SORT_OPTIONS.map((option) => (
+                <option key={option.value} value={option.value}>
+                  {option.label}
+                </option>
+              ))}
+            </select>
+          </label>
+          <select
+            className="text-sm border border-gray-300 rounded px-2 py-1"
+            value={statusFilter}
+            onChange={(e) => setStatusFilter(e.target.value as StatusFilter)}
+          >
+            <option value="all">All</option>
+            <option value="running">Running</option>
+            <option value="stopped">Stopped</option>
+          </select>
+        </div>
+      </div>
+      <div className="mb-5">
+        <Input
+          type="text"
+          placeholder="Search by name or image..."
+          value={searchQuery}
+          onChange={(e) => setSearchQuery(e.target.value)}
+          className="w-full max-w-md"
+        />
+      </div>
+      {rangeError && (
+        <div className="bg-red-50 text-red-800 p-3 rounded mb-4 text-sm">
+          Failed to load stats history: {rangeError}
+        </div>
+      )}
       <div className="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-5">
-        {containers.map((container) => {
+        {visibleContainers.map((container) => {
           const stats = statsMap.get(container.id) || null;
-          const history = historicalData.get(container.id) || [];
+          const history =
+            (chartRange === 'live' ? historicalData : rangeData).get(container.id) || [];
+          const labelValue = colorByLabel ? container.labels?.[colorByLabel] : undefined;
 
           return (
             <ContainerCard
@@ -37,14 +288,19 @@ const ContainersView = ({
               container={container}
               stats={stats}
               historicalData={history}
+              accentColor={labelValue ? labelColor(labelValue) : undefined}
               onClick={() => onSelect(container.id)}
             />
           );
         })}
       </div>
-      {containers.length === 0 && (
+      {visibleContainers.length === 0 && (
         <div className="p-10 text-center text-gray-500 bg-white rounded-lg">
-          No containers found
+          {containers.length === 0
+            ? 'No containers found'
+            : searchQuery.trim()
+              ? 'No containers match the search'
+              : `No ${statusFilter} containers`}
         </div>
       )}
     </div>
@@ -0,0 +1,230 @@
+import { useState, useEffect, useMemo, useCallback } from 'react';
+import type { ContainerInfo, HttpRequest, HttpRequestSummary, HttpLatencyPercentiles } from '../types';
+import {
+  fetchContainerHttpRequests,
+  fetchContainerHttpSummary,
+  fetchContainerHttpLatency,
+} from '../services/api';
+import { formatDate, formatPercent } from '../utils/formatting';
+import { Badge } from './ui/badge';
+import { Button } from './ui/button';
+import { Card, CardContent } from './ui/card';
+
+// Window covered by the summary bar
+const SUMMARY_WINDOW_MS = 60 * 60 * 1000;
+
+type SortKey = 'timestamp' | 'response_time_ms' | 'http_status';
+
+interface HttpRequestsViewProps {
+  containers: ContainerInfo[];
+  selectedContainer: string | null;
+  onContainerChange: (containerId: string) => void;
+}
+
+const getStatusVariant = (status: number): 'success' | 'warning' | 'destructive' | 'secondary' => {
+  if (status >= 200 && status < 300) return 'success';
+  if (status >= 300 && status < 400) return 'warning';
+  if (status >= 400) return 'destructive';
+  return 'secondary';
+};
+
+const getResponseTimeColor = (ms: number): string => {
+  if (ms < 100) return 'text-green-600';
+  if (ms < 500) return 'text-yellow-600';
+  return 'text-red-600';
+};
+
+const HttpRequestsView = ({
+  containers,
+  selectedContainer,
+  onContainerChange,
+}: HttpRequestsViewProps) => {
+  const [requests, setRequests] = useState<HttpRequest[]>([]);
+  const [summary, setSummary] = useState<HttpRequestSummary | null>(null);
+  const [latency, setLatency] = useState<HttpLatencyPercentiles | null>(null);
+  const [limit, setLimit] = useState(100);
+  const [sortKey, setSortKey] = useState<SortKey>('timestamp');
+  const [sortDescending, setSortDescending] = useState(true);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadRequests = useCallback(async () => {
+    if (!selectedContainer) return;
+
+    try {
+      setLoading(true);
+      setError(null);
+      const since = new Date(Date.now() - SUMMARY_WINDOW_MS);
+      const [newRequests, newSummary, newLatency] = await Promise.all([
+        fetchContainerHttpRequests(selectedContainer, limit),
+        fetchContainerHttpSummary(selectedContainer, since),
+        fetchContainerHttpLatency(selectedContainer, since),
+      ]);
+      setRequests(newRequests);
+      setSummary(newSummary);
+      setLatency(newLatency);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to fetch HTTP requests');
+      console.error('Error fetching HTTP requests:', err);
+    } finally {
+      setLoading(false);
+    }
+  }, [selectedContainer, limit]);
+
+  useEffect(() => {
+    if (selectedContainer) {
+      loadRequests();
+    } else {
+      setRequests([]);
+      setSummary(null);
+      setLatency(null);
+    }
+  }, [selectedContainer, loadRequests]);
+
+  const sortedRequests = useMemo(() => {
+    const direction = sortDescending ? -1 : 1;
+    return [...requests].sort((a, b) => {
+      const left = sortKey === 'timestamp' ? new Date(a.timestamp).getTime() : a[sortKey];
+      const right = sortKey === 'timestamp' ? new Date(b.timestamp).getTime() : b[sortKey];
+      return (left - right) * direction;
+    });
+  }, [requests, sortKey, sortDescending]);
+
+  // Clicking the active column flips the direction; a new column starts with the largest first
+  const handleSort = (key: SortKey) => {
+    if (key === sortKey) {
+      setSortDescending(!sortDescending);
+    } else {
+      setSortKey(key);
+      setSortDescending(true);
+    }
+  };
+
+  const sortIndicator = (key: SortKey) => (key === sortKey ? (sortDescending ? ' ↓' : ' ↑') : '');
+
+  return (
+    <div className="mb-8">
+      <h2 className="text-2xl mb-5 text-gray-900">HTTP Requests</h2>
+      <div className="flex gap-2.5 mb-5 flex-wrap items-center">
+        <select
+          value={selectedContainer || ''}
+          onChange={(e) => onContainerChange(e.target.value)}
+          className="px-3 py-2 border border-gray-300 rounded text-sm min-w-[250px]"
+        >
+          <option value="">Select a container...</option>
+          {containers.map((container) => (
+            <option key={container.id} value={container.id}>
+              {container.name} ({container.status})
+            </option>
+          ))}
+        </select>
+        <select
+          value={limit}
+          onChange={(e) => setLimit(Number(e.target.value))}
+          className="px-3 py-2 border border-gray-300 rounded text-sm"
+        >
+          <option value={50}>Last 50</option>
+          <option value={100}>Last 100</option>
+          <option value={500}>Last 500</option>
+        </select>
+        <Button onClick={loadRequests} disabled={!selectedContainer || loading} size="sm">
+          Refresh
+        </Button>
+      </div>
+
+      {error && (
+        <div className="bg-red-50 text-red-800 p-4 rounded mb-4 border-l-4 border-red-800 shadow-sm">
+          <strong>Error:</strong> {error}
+        </div>
+      )}
+
+      {summary && latency && (
+        <div className="flex gap-6 mb-4 text-sm text-gray-700 flex-wrap">
+          <span>
+            Last hour: <strong>{summary.total}</strong> requests
+          </span>
+          <span>
+            p95 latency: <strong className={getResponseTimeColor(latency.p95_ms)}>{latency.p95_ms.toFixed(2)} ms</strong>
+          </span>
+          <span>
+            Error rate:{' '}
+            <strong className={summary.error_rate > 0 ? 'text-red-600' : 'text-green-600'}>
+              {formatPercent(summary.error_rate * 100)}
+            </strong>
+          </span>
+        </div>
+      )}
+
+      <Card className="max-h-[600px] overflow-y-auto">
+        <CardContent className="p-5">
+          {sortedRequests.length > 0 ? (
+            <div className="overflow-x-auto">
+              <table className="w-full text-sm">
+                <thead>
+                  <tr className="border-b border-gray-200">
+                    <th
+                      className="text-left py-2 px-3 font-semibold text-gray-700 cursor-pointer select-none"
+                      onClick={() => handleSort('timestamp')}
+                    >
+                      Time{sortIndicator('timestamp')}
+                    </th>
+                    <th className="text-left py-2 px-3 font-semibold text-gray-700">Method</th>
+                    <th className="text-left py-2 px-3 font-semibold text-gray-700">Endpoint</th>
+                    <th
+                      className="text-left py-2 px-3 font-semibold text-gray-700 cursor-pointer select-none"
+                      onClick={() => handleSort('http_status')}
+                    >
+                      Status{sortIndicator('http_status')}
+                    </th>
+                    <th
+                      className="text-right py-2 px-3 font-semibold text-gray-700 cursor-pointer select-none"
+                      onClick={() => handleSort('response_time_ms')}
+                    >
+                      Response Time{sortIndicator('response_time_ms')}
+                    </th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {sortedRequests.map((request, idx) => (
+                    <tr
+                      key={`${request.container_id}-${request.timestamp}-${idx}`}
+                      className="border-b border-gray-100 hover:bg-gray-50 transition-colors"
+                    >
+                      <td className="py-2 px-3 text-gray-600">{formatDate(request.timestamp)}</td>
+                      <td className="py-2 px-3">
+                        <Badge variant="secondary" className="text-xs font-mono">
+                          {request.method}
+                        </Badge>
+                      </td>
+                      <td className="py-2 px-3 font-mono text-gray-900">{request.endpoint}</td>
+                      <td className="py-2 px-3">
+                        <Badge variant={getStatusVariant(request.http_status)} className="text-xs">
+                          {request.http_status}
+                        </Badge>
+                      </td>
+                      <td
+                        className={`py-2 px-3 text-right font-semibold ${getResponseTimeColor(request.response_time_ms)}`}
+                      >
+                        {request.response_time_ms.toFixed(2)} ms
+                      </td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            </div>
+          ) : (
+            <div className="p-10 text-center text-gray-500">
+              {selectedContainer
+                ? loading
+                  ? 'Loading HTTP requests...'
+                  : 'No HTTP requests captured for this container.'
+                : 'Select a container to view its HTTP requests.'}
+            </div>
+          )}
+        </CardContent>
+      </Card>
+    </div>
+  );
+};
+
+export default HttpRequestsView;
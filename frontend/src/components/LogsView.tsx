@@ -10,10 +10,12 @@ interface LogsViewProps {
   selectedContainer: string | null;
   logs: ContainerLog[];
   logLimit: number;
+  liveTail: boolean;
   onContainerChange: (containerId: string) => void;
   onLimitChange: (limit: number) => void;
   onLoad: () => void;
   onClear: () => void;
+  onLiveTailChange: (liveTail: boolean) => void;
 }
 
 const LogsView = ({
@@ -21,10 +23,12 @@ const LogsView = ({
   selectedContainer,
   logs,
   logLimit,
+  liveTail,
   onContainerChange,
   onLimitChange,
   onLoad,
   onClear,
+  onLiveTailChange,
 }: LogsViewProps) => {
   const [limitInput, setLimitInput] = useState(logLimit.toString());
   const [searchQuery, setSearchQuery] = useState('');
@@ -83,6 +87,14 @@ const LogsView = ({
         <Button onClick={onClear} variant="outline" size="sm">
           Clear
         </Button>
+        <Button
+          onClick={() => onLiveTailChange(!liveTail)}
+          disabled={!selectedContainer}
+          variant={liveTail ? 'default' : 'outline'}
+          size="sm"
+        >
+          {liveTail ? 'Stop Live Tail' : 'Live Tail'}
+        </Button>
       </div>
 
       {/* Search/Filter Input */}
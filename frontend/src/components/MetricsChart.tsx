@@ -1,4 +1,4 @@
-import { useMemo } from 'react';
+import { useMemo, useState } from 'react';
 import {
   LineChart,
   Line,
@@ -11,23 +11,44 @@ import {
 } from 'recharts';
 import type { DataPoint } from '../types';
 
+export type AxisScale = 'linear' | 'log';
+
+// Log axes can't show 0, so values are floored at this (KB/s) before plotting
+const LOG_SCALE_EPSILON = 0.01;
+
 interface MetricsChartProps {
   data: DataPoint[];
   width?: number;
   height?: number;
+  // Initial scale of the network axis; can be toggled from the chart
+  networkScale?: AxisScale;
 }
 
-const MetricsChart = ({ data, width = 300, height = 120 }: MetricsChartProps) => {
+const MetricsChart = ({ data, width = 300, height = 120, networkScale = 'linear' }: MetricsChartProps) => {
+  const [scale, setScale] = useState<AxisScale>(networkScale);
+  const isLogScale = scale === 'log';
+
   // Memoize chart data transformation to prevent unnecessary recalculations
   // Use data length as dependency to ensure we recalculate when new points are added
   const chartData = useMemo(() => {
-    return data.map((point) => ({
-      time: new Date(point.timestamp * 1000).toLocaleTimeString(),
-      cpu: point.cpu,
-      memory: point.memory,
-      network: point.network,
-    }));
-  }, [data]);
+    // Ranges over a day need the date on the axis, not just the time
+    const spansDays =
+      data.length > 1 && data[data.length - 1].timestamp - data[0].timestamp > 24 * 60 * 60;
+    return data.map((point) => {
+      const date = new Date(point.timestamp * 1000);
+      return {
+        time: spansDays
+          ? date.toLocaleString([], { month: 'short', day: 'numeric', hour: '2-digit', minute: '2-digit' })
+          : date.toLocaleTimeString(),
+        cpu: point.cpu,
+        memory: point.memory,
+        network:
+          point.network !== null && isLogScale
+            ? Math.max(point.network, LOG_SCALE_EPSILON)
+            : point.network,
+      };
+    });
+  }, [data, isLogScale]);
   
   // Track if this is the initial render (no animation) or update (with animation)
   const isInitialRender = useMemo(() => data.length <= 1, [data.length]);
@@ -41,90 +62,105 @@ const MetricsChart = ({ data, width = 300, height = 120 }: MetricsChartProps) =>
   }
 
   return (
-    <div className="bg-white rounded p-2.5" style={{ width, height }}>
-      <ResponsiveContainer width="100%" height="100%">
-        <LineChart data={chartData} margin={{ top: 5, right: 30, left: 5, bottom: 5 }}>
-          <CartesianGrid strokeDasharray="3 3" stroke="#e0e0e0" />
-          <XAxis
-            dataKey="time"
-            stroke="#666"
-            fontSize={10}
-            tick={{ fill: '#666' }}
-            interval="preserveStartEnd"
-          />
-          {/* Left Y-axis for CPU and Memory (percentages 0-100) */}
-          <YAxis
-            yAxisId="left"
-            stroke="#666"
-            fontSize={10}
-            tick={{ fill: '#666' }}
-            domain={[0, 100]}
-            label={{ value: '%', angle: -90, position: 'insideLeft', style: { textAnchor: 'middle' } }}
-          />
-          {/* Right Y-axis for Network (KB/s) */}
-          <YAxis
-            yAxisId="right"
-            orientation="right"
-            stroke="#4CAF50"
-            fontSize={10}
-            tick={{ fill: '#4CAF50' }}
-            label={{ value: 'KB/s', angle: 90, position: 'insideRight', style: { textAnchor: 'middle' } }}
-          />
-          <Tooltip
-            contentStyle={{
-              backgroundColor: 'rgba(255, 255, 255, 0.95)',
-              border: '1px solid #ccc',
-              borderRadius: '4px',
-            }}
-          />
-          <Legend
-            wrapperStyle={{ fontSize: '12px', paddingTop: '10px' }}
-            iconType="line"
-          />
-          <Line
-            yAxisId="left"
-            type="monotone"
-            dataKey="cpu"
-            stroke="#FFC107"
-            strokeWidth={2}
-            dot={false}
-            name="CPU %"
-            isAnimationActive={!isInitialRender}
-            animationDuration={200}
-            animationEasing="ease-out"
-            connectNulls={false}
-            activeDot={{ r: 4 }}
-          />
-          <Line
-            yAxisId="left"
-            type="monotone"
-            dataKey="memory"
-            stroke="#2196F3"
-            strokeWidth={2}
-            dot={false}
-            name="Memory %"
-            isAnimationActive={!isInitialRender}
-            animationDuration={200}
-            animationEasing="ease-out"
-            connectNulls={false}
-            activeDot={{ r: 4 }}
-          />
-          <Line
-            yAxisId="right"
-            type="monotone"
-            dataKey="network"
-            stroke="#4CAF50"
-            strokeWidth={2}
-            dot={false}
-            name="Network KB/s"
-            isAnimationActive={!isInitialRender}
-            animationDuration={200}
-            animationEasing="ease-out"
-            connectNulls={false}
-            activeDot={{ r: 4 }}
-          />
-        </LineChart>
-      </ResponsiveContainer>
+    <div className="bg-white rounded p-2.5 flex flex-col" style={{ width, height }}>
+      <div className="flex justify-end">
+        <button
+          type="button"
+          className="text-[10px] text-gray-500 hover:text-gray-800"
+          onClick={() => setScale(isLogScale ? 'linear' : 'log')}
+          title="Toggle log scale for the network axis"
+        >
+          Network: {isLogScale ? 'log' : 'linear'}
+        </button>
+      </div>
+      <div className="flex-1 min-h-0">
+        <ResponsiveContainer width="100%" height="100%">
+          <LineChart data={chartData} margin={{ top: 5, right: 30, left: 5, bottom: 5 }}>
+            <CartesianGrid strokeDasharray="3 3" stroke="#e0e0e0" />
+            <XAxis
+              dataKey="time"
+              stroke="#666"
+              fontSize={10}
+              tick={{ fill: '#666' }}
+              interval="preserveStartEnd"
+            />
+            {/* Left Y-axis for CPU and Memory (percentages 0-100) */}
+            <YAxis
+              yAxisId="left"
+              stroke="#666"
+              fontSize={10}
+              tick={{ fill: '#666' }}
+              domain={[0, 100]}
+              label={{ value: '%', angle: -90, position: 'insideLeft', style: { textAnchor: 'middle' } }}
+            />
+            {/* Right Y-axis for Network (KB/s) */}
+            <YAxis
+              yAxisId="right"
+              orientation="right"
+              stroke="#4CAF50"
+              fontSize={10}
+              tick={{ fill: '#4CAF50' }}
+              scale={scale}
+              domain={isLogScale ? [LOG_SCALE_EPSILON, 'auto'] : [0, 'auto']}
+              allowDataOverflow={isLogScale}
+              label={{ value: 'KB/s', angle: 90, position: 'insideRight', style: { textAnchor: 'middle' } }}
+            />
+            <Tooltip
+              contentStyle={{
+                backgroundColor: 'rgba(255, 255, 255, 0.95)',
+                border: '1px solid #ccc',
+                borderRadius: '4px',
+              }}
+            />
+            <Legend
+              wrapperStyle={{ fontSize: '12px', paddingTop: '10px' }}
+              iconType="line"
+            />
+            <Line
+              yAxisId="left"
+              type="monotone"
+              dataKey="cpu"
+              stroke="#FFC107"
+              strokeWidth={2}
+              dot={false}
+              name="CPU %"
+              isAnimationActive={!isInitialRender}
+              animationDuration={200}
+              animationEasing="ease-out"
+              connectNulls={false}
+              activeDot={{ r: 4 }}
+            />
+            <Line
+              yAxisId="left"
+              type="monotone"
+              dataKey="memory"
+              stroke="#2196F3"
+              strokeWidth={2}
+              dot={false}
+              name="Memory %"
+              isAnimationActive={!isInitialRender}
+              animationDuration={200}
+              animationEasing="ease-out"
+              connectNulls={false}
+              activeDot={{ r: 4 }}
+            />
+            <Line
+              yAxisId="right"
+              type="monotone"
+              dataKey="network"
+              stroke="#4CAF50"
+              strokeWidth={2}
+              dot={false}
+              name="Network KB/s"
+              isAnimationActive={!isInitialRender}
+              animationDuration={200}
+              animationEasing="ease-out"
+              connectNulls={false}
+              activeDot={{ r: 4 }}
+            />
+          </LineChart>
+        </ResponsiveContainer>
+      </div>
     </div>
   );
 };
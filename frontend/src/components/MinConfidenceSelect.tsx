@@ -0,0 +1,26 @@
+interface MinConfidenceSelectProps {
+  value: number;
+  onChange: (value: number) => void;
+}
+
+const CONFIDENCE_THRESHOLDS = [0.5, 0.7, 0.9];
+
+const MinConfidenceSelect = ({ value, onChange }: MinConfidenceSelectProps) => {
+  return (
+    <select
+      value={value}
+      onChange={(e) => onChange(Number(e.target.value))}
+      className="px-3 py-2 border border-gray-300 rounded-md text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+      title="Minimum edge confidence"
+    >
+      <option value={0}>Any confidence</option>
+      {CONFIDENCE_THRESHOLDS.map((threshold) => (
+        <option key={threshold} value={threshold}>
+          Confidence ≥ {Math.round(threshold * 100)}%
+        </option>
+      ))}
+    </select>
+  );
+};
+
+export default MinConfidenceSelect;
@@ -21,6 +21,7 @@ import { Input } from '../components/ui/input';
 import { Separator } from '../components/ui/separator';
 import MetricsChart from '../components/MetricsChart';
 import { formatBytes, formatPercent, formatDate } from '../utils/formatting';
+import { networkRateKb } from '../utils/containers';
 import { Search, X } from 'lucide-react';
 
 const MAX_HISTORY = 60;
@@ -39,6 +40,8 @@ const APM = () => {
   const [logLimit, setLogLimit] = useState(100);
 
   const intervalRef = useRef<number | null>(null);
+  // Previous sample of the selected service, to turn its network counters into a rate
+  const lastStatsRef = useRef<ContainerStats | undefined>(undefined);
 
   let isLoaded = false;
 
@@ -90,9 +93,10 @@ const APM = () => {
 
       // Update historical data
       const timestamp = new Date(stats.timestamp).getTime() / 1000;
+      const networkKb = networkRateKb(lastStatsRef.current, stats);
+      lastStatsRef.current = stats;
       setHistoricalData((prev) => {
         const newData = [...prev];
-        const networkKb = (stats.network_rx_bytes + stats.network_tx_bytes) / 1024;
         const dataPoint: DataPoint = {
           timestamp,
           cpu: stats.cpu_usage_percent,
@@ -135,6 +139,7 @@ const APM = () => {
   const handleServiceSelect = (serviceId: string) => {
     setSelectedServiceId(serviceId);
     setHistoricalData([]); // Reset history when switching services
+    lastStatsRef.current = undefined;
   };
 
   const selectedService = containers.find((c) => c.id === selectedServiceId);
@@ -1,6 +1,7 @@
 import { useState, useEffect, useRef } from 'react';
 import type { TotalStats } from '../types';
-import { fetchTotalStats, connectSSEStats } from '../services/api';
+import { fetchTotalStats, connectStats } from '../services/api';
+import type { StatsConnection } from '../services/api';
 import { Card, CardContent, CardHeader, CardTitle } from '../components/ui/card';
 import { formatBytes, formatPercent } from '../utils/formatting';
 import { Cpu, HardDrive, Network, Activity } from 'lucide-react';
@@ -10,11 +11,11 @@ const Dashboard = () => {
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [sseConnected, setSseConnected] = useState(false);
-  const sseRef = useRef<EventSource | null>(null);
+  const sseRef = useRef<StatsConnection | null>(null);
 
-  // Set up SSE connection for real-time stats
+  // Set up the live stats connection (SSE or WebSocket, per runtime config)
   useEffect(() => {
-    const eventSource = connectSSEStats(
+    const connection = connectStats(
       (stats) => {
         setTotalStats(stats);
         setSseConnected(true);
@@ -26,7 +27,7 @@ const Dashboard = () => {
       }
     );
 
-    sseRef.current = eventSource;
+    sseRef.current = connection;
 
     // Also fetch initial data
     const loadStats = async () => {
@@ -44,7 +45,7 @@ const Dashboard = () => {
     loadStats();
 
     return () => {
-      eventSource.close();
+      connection.close();
     };
   }, []);
 
@@ -10,11 +10,16 @@ import {
   fetchContainers,
   fetchAllContainerStats,
   fetchContainerLogs,
-  connectSSEStats,
+  connectStats,
+  connectContainerEvents,
+  connectContainerLogs,
 } from '../services/api';
+import type { StatsConnection } from '../services/api';
 import TotalStatsView from '../components/TotalStatsView';
 import ContainersView from '../components/ContainersView';
 import LogsView from '../components/LogsView';
+import HttpRequestsView from '../components/HttpRequestsView';
+import { networkRateKb } from '../utils/containers';
 
 const MAX_HISTORY = 60; // Keep last 60 data points
 
@@ -27,6 +32,7 @@ const DockerMonitor = () => {
   const [selectedContainer, setSelectedContainer] = useState<string | null>(null);
   const [logs, setLogs] = useState<ContainerLog[]>([]);
   const [logLimit, setLogLimit] = useState(100);
+  const [liveTail, setLiveTail] = useState(false);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [sseConnected, setSseConnected] = useState(false);
@@ -34,11 +40,22 @@ const DockerMonitor = () => {
     new Map()
   );
 
-  const sseRef = useRef<EventSource | null>(null);
+  const sseRef = useRef<StatsConnection | null>(null);
   const intervalRef = useRef<number | null>(null);
+  // Previous sample per container, to turn the cumulative network counters into a rate
+  const lastStatsRef = useRef<Map<string, ContainerStats>>(new Map());
 
   // Update historical data for charts - append new points smoothly
   const updateHistoricalData = useCallback((stats: ContainerStats[]) => {
+    // Worked out outside the state updater, which may run more than once per update
+    const networkKb = new Map<string, number | null>();
+    stats.forEach((stat) => {
+      const previous = lastStatsRef.current.get(stat.container_id);
+      if (previous?.timestamp === stat.timestamp) return;
+      networkKb.set(stat.container_id, networkRateKb(previous, stat));
+      lastStatsRef.current.set(stat.container_id, stat);
+    });
+
     setHistoricalData((prev) => {
       const newData = new Map(prev);
       let hasChanges = false;
@@ -55,14 +72,11 @@ const DockerMonitor = () => {
           Math.abs(entry[entry.length - 1].timestamp - timestamp) > 0.1;
 
         if (isNew) {
-          const networkKb =
-            (stat.network_rx_bytes + stat.network_tx_bytes) / 1024;
-
           const dataPoint: DataPoint = {
             timestamp,
             cpu: stat.cpu_usage_percent,
             memory: stat.memory_usage_percent,
-            network: networkKb,
+            network: networkKb.get(stat.container_id) ?? null,
           };
 
           // Append new point to the end (create new array to trigger React update)
@@ -129,9 +143,9 @@ const DockerMonitor = () => {
     }
   }, [selectedContainer, logLimit]);
 
-  // Set up SSE connection for total stats
+  // Set up the live stats connection for total stats (SSE or WebSocket, per runtime config)
   useEffect(() => {
-    const eventSource = connectSSEStats(
+    const connection = connectStats(
       (stats) => {
         setTotalStats(stats);
         setSseConnected(true);
@@ -143,13 +157,25 @@ const DockerMonitor = () => {
       }
     );
 
-    sseRef.current = eventSource;
+    sseRef.current = connection;
 
     return () => {
-      eventSource.close();
+      connection.close();
     };
   }, []);
 
+  // Refresh the container list as soon as a container is created, started or stopped
+  // instead of waiting for the next periodic refresh
+  useEffect(() => {
+    const eventSource = connectContainerEvents(() => {
+      refreshData();
+    });
+
+    return () => {
+      eventSource.close();
+    };
+  }, [refreshData]);
+
   // Initial data fetch and periodic refresh
   useEffect(() => {
     refreshData();
@@ -173,6 +199,19 @@ const DockerMonitor = () => {
     }
   }, [selectedContainer, logLimit, loadLogs]);
 
+  // While live tail is on, append new lines to the loaded ones, keeping the last `logLimit`
+  useEffect(() => {
+    if (!liveTail || !selectedContainer) return;
+
+    const eventSource = connectContainerLogs(selectedContainer, (log) => {
+      setLogs((prev) => [...prev, log].slice(-logLimit));
+    });
+
+    return () => {
+      eventSource.close();
+    };
+  }, [liveTail, selectedContainer, logLimit]);
+
   const handleContainerSelect = useCallback((containerId: string) => {
     setSelectedContainer(containerId);
   }, []);
@@ -225,10 +264,18 @@ const DockerMonitor = () => {
         selectedContainer={selectedContainer}
         logs={logs}
         logLimit={logLimit}
+        liveTail={liveTail}
         onContainerChange={handleContainerSelect}
         onLimitChange={handleLogLimitChange}
         onLoad={loadLogs}
         onClear={() => setLogs([])}
+        onLiveTailChange={setLiveTail}
+      />
+
+      <HttpRequestsView
+        containers={containers}
+        selectedContainer={selectedContainer}
+        onContainerChange={handleContainerSelect}
       />
     </div>
   );
@@ -6,11 +6,13 @@ import { Network, Server, Database } from 'lucide-react';
 import { Card, CardContent, CardHeader, CardTitle } from '../components/ui/card';
 import { Badge } from '../components/ui/badge';
 import { Button } from '../components/ui/button';
+import MinConfidenceSelect from '../components/MinConfidenceSelect';
 
 const ServiceMap = () => {
   const [serviceMap, setServiceMap] = useState<ServiceMap | null>(null);
   const [containers, setContainers] = useState<ContainerInfo[]>([]);
   const [selectedServiceId, setSelectedServiceId] = useState<string>('');
+  const [minConfidence, setMinConfidence] = useState<number>(0);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [selectedNode, setSelectedNode] = useState<ServiceNode | null>(null);
@@ -35,7 +37,10 @@ const ServiceMap = () => {
     const loadServiceMap = async () => {
       try {
         setLoading(true);
-        const data = await fetchServiceMap(selectedServiceId || undefined);
+        const data = await fetchServiceMap(
+          selectedServiceId || undefined,
+          minConfidence > 0 ? minConfidence : undefined
+        );
         setServiceMap(data);
         setError(null);
       } catch (err) {
@@ -46,7 +51,7 @@ const ServiceMap = () => {
     };
 
     loadServiceMap();
-  }, [selectedServiceId]);
+  }, [selectedServiceId, minConfidence]);
 
   const getServiceIcon = (imageFamily: string) => {
     const family = imageFamily.toLowerCase();
@@ -63,6 +68,8 @@ const ServiceMap = () => {
     switch (type) {
       case 'environment_variable':
         return '#10b981'; // green
+      case 'database_url':
+        return '#059669'; // dark green
       case 'same_network':
         return '#3b82f6'; // blue
       case 'port_mapping':
@@ -80,6 +87,8 @@ const ServiceMap = () => {
     switch (type) {
       case 'environment_variable':
         return 'Env Var';
+      case 'database_url':
+        return 'DSN';
       case 'same_network':
         return 'Network';
       case 'port_mapping':
@@ -93,6 +102,20 @@ const ServiceMap = () => {
     }
   };
 
+  // Native SVG tooltip shown while hovering an edge
+  const getEdgeTooltip = (edge: ServiceEdge): string =>
+    [
+      `${getConnectionTypeLabel(edge.connection_type)} (${(edge.confidence * 100).toFixed(0)}% confidence)`,
+      ...edge.evidence,
+    ].join('\n');
+
+  // Focus the map on a node: the backend returns only it and the services connected to it
+  const focusNode = (node: ServiceNode) => {
+    setSelectedNode(node);
+    setSelectedEdge(null);
+    setSelectedServiceId(node.container_id);
+  };
+
   const getStatusVariant = (status: string): 'success' | 'destructive' | 'warning' | 'secondary' => {
     const statusLower = status.toLowerCase();
     if (statusLower.includes('running') || statusLower.includes('up')) return 'success';
@@ -161,6 +184,7 @@ const ServiceMap = () => {
                   </option>
                 ))}
               </select>
+              <MinConfidenceSelect value={minConfidence} onChange={setMinConfidence} />
               {selectedServiceId && (
                 <Button
                   variant="outline"
@@ -213,6 +237,7 @@ const ServiceMap = () => {
                 </option>
               ))}
             </select>
+            <MinConfidenceSelect value={minConfidence} onChange={setMinConfidence} />
             {selectedServiceId && (
               <Button
                 variant="outline"
@@ -252,19 +277,30 @@ const ServiceMap = () => {
                     const opacity = Math.max(0.3, edge.confidence);
 
                     return (
-                      <line
-                        key={`edge-${idx}`}
-                        x1={fromNode.x}
-                        y1={fromNode.y}
-                        x2={toNode.x}
-                        y2={toNode.y}
-                        stroke={color}
-                        strokeWidth={2 * edge.confidence}
-                        opacity={opacity}
-                        markerEnd="url(#arrowhead)"
-                        className="hover:stroke-width-4 transition-all"
-                        onClick={() => setSelectedEdge(edge)}
-                      />
+                      <g key={`edge-${idx}`} onClick={() => setSelectedEdge(edge)}>
+                        <line
+                          x1={fromNode.x}
+                          y1={fromNode.y}
+                          x2={toNode.x}
+                          y2={toNode.y}
+                          stroke={color}
+                          strokeWidth={2 * edge.confidence}
+                          opacity={opacity}
+                          markerEnd="url(#arrowhead)"
+                          className="hover:stroke-width-4 transition-all"
+                        />
+                        {/* Wider invisible line so thin edges are easy to hover and click */}
+                        <line
+                          x1={fromNode.x}
+                          y1={fromNode.y}
+                          x2={toNode.x}
+                          y2={toNode.y}
+                          stroke="transparent"
+                          strokeWidth={12}
+                        >
+                          <title>{getEdgeTooltip(edge)}</title>
+                        </line>
+                      </g>
                     );
                   })}
 
@@ -292,8 +328,9 @@ const ServiceMap = () => {
                         key={node.container_id}
                         transform={`translate(${node.x}, ${node.y})`}
                         className="cursor-pointer"
-                        onClick={() => setSelectedNode(node)}
+                        onClick={() => focusNode(node)}
                       >
+                        <title>{`${node.container_name}: click to show only its connections`}</title>
                         {/* Node circle */}
                         <circle
                           r="30"
@@ -1,19 +1,85 @@
 import type {
   ContainerInfo,
+  ComposeProject,
   ContainerStats,
+  ContainerProcess,
+  AggregatedContainerStats,
+  GapfilledContainerStats,
+  NetworkRate,
+  PaginatedResponse,
   TotalStats,
+  ComprehensiveStats,
   ContainerLog,
+  DockerContainerEvent,
   ImageInfo,
+  ImageSizeTrend,
   ServiceMap,
   HttpRequest,
+  HttpRequestSummary,
+  HttpLatencyPercentiles,
 } from '../types';
 
-const API_BASE =
+// Build-time fallback; can be overridden per deployment at runtime (see loadRuntimeConfig)
+const BUILD_TIME_API_BASE =
   (import.meta as { env?: { VITE_API_URL?: string } }).env?.VITE_API_URL ||
   'http://127.0.0.1:8080';
 
+// Build-time token (BACKEND_API_TOKEN, see rsbuild.config.ts); a runtime `apiToken` wins
+const BUILD_TIME_API_TOKEN = import.meta.env.BACKEND_API_TOKEN || undefined;
+
+let API_BASE = window.__CONFIG__?.apiBaseUrl || BUILD_TIME_API_BASE;
+let API_TOKEN = window.__CONFIG__?.apiToken ?? BUILD_TIME_API_TOKEN;
+let STATS_TRANSPORT = window.__CONFIG__?.statsTransport ?? 'sse';
+
+/**
+ * Resolve the backend URL at runtime so one build can serve every environment.
+ * Order: `window.__CONFIG__.apiBaseUrl` injected by the host page, then `/config.json`
+ * served next to the bundle, then the build-time `VITE_API_URL`.
+ * Call once before rendering.
+ */
+export async function loadRuntimeConfig(): Promise<void> {
+  if (window.__CONFIG__?.apiBaseUrl) {
+    API_BASE = window.__CONFIG__.apiBaseUrl;
+    API_TOKEN = window.__CONFIG__.apiToken ?? BUILD_TIME_API_TOKEN;
+    STATS_TRANSPORT = window.__CONFIG__.statsTransport ?? 'sse';
+    return;
+  }
+
+  try {
+    const response = await fetch('/config.json', { cache: 'no-store' });
+    if (response.ok) {
+      const config: RuntimeConfig = await response.json();
+      if (config.apiBaseUrl) {
+        API_BASE = config.apiBaseUrl;
+      }
+      if (config.apiToken) {
+        API_TOKEN = config.apiToken;
+      }
+      if (config.statsTransport) {
+        STATS_TRANSPORT = config.statsTransport;
+      }
+    }
+  } catch {
+    // No runtime config - keep the build-time value
+  }
+}
+
+/**
+ * URL of a streaming endpoint. EventSource can't send an Authorization header,
+ * so the token (if configured) goes in the `access_token` query parameter.
+ */
+function streamUrl(path: string): string {
+  const url = `${API_BASE}${path}`;
+  if (!API_TOKEN) {
+    return url;
+  }
+  const separator = url.includes('?') ? '&' : '?';
+  return `${url}${separator}access_token=${encodeURIComponent(API_TOKEN)}`;
+}
+
 async function fetchJson<T>(url: string): Promise<T> {
-  const response = await fetch(url);
+  const headers: HeadersInit = API_TOKEN ? { Authorization: `Bearer ${API_TOKEN}` } : {};
+  const response = await fetch(url, { headers });
   if (!response.ok) {
     throw new Error(`HTTP error! status: ${response.status}`);
   }
@@ -33,6 +99,11 @@ export async function fetchContainers(): Promise<ContainerInfo[]> {
   return fetchJson<ContainerInfo[]>(`${API_BASE}/api/containers`);
 }
 
+// Containers grouped by compose project, standalone containers last
+export async function fetchProjects(): Promise<ComposeProject[]> {
+  return fetchJson<ComposeProject[]>(`${API_BASE}/api/projects`);
+}
+
 // Container Stats
 export async function fetchContainerStats(
   containerId: string
@@ -65,6 +136,85 @@ export async function fetchContainerStatsHistory(
   return fetchJson<ContainerStats[]>(url);
 }
 
+// Page back through raw stats, newest first; start with `before` = now and continue
+// with `pagination.next_cursor` until it is absent
+export async function fetchContainerStatsHistoryPage(
+  containerId: string,
+  before: string,
+  limit?: number
+): Promise<PaginatedResponse<ContainerStats>> {
+  const searchParams = new URLSearchParams({ before });
+  if (limit) searchParams.set('limit', limit.toString());
+
+  return fetchJson<PaginatedResponse<ContainerStats>>(
+    `${API_BASE}/api/containers/${containerId}/stats/history?${searchParams.toString()}`
+  );
+}
+
+export async function fetchContainerStatsHistoryBucketed(
+  containerId: string,
+  params: {
+    from?: string;
+    to?: string;
+    bucket: number; // seconds; an hour or more reads the hourly aggregate
+  }
+): Promise<AggregatedContainerStats[]> {
+  const searchParams = new URLSearchParams();
+  if (params.from) searchParams.set('from', params.from);
+  if (params.to) searchParams.set('to', params.to);
+  searchParams.set('bucket', params.bucket.toString());
+
+  return fetchJson<AggregatedContainerStats[]>(
+    `${API_BASE}/api/containers/${containerId}/stats/history?${searchParams.toString()}`
+  );
+}
+
+// Every bucket between `from` and `to`, with nulls where there were no samples
+export async function fetchContainerStatsHistoryGapfilled(
+  containerId: string,
+  params: {
+    from: string;
+    to: string;
+    bucket: number; // seconds; an hour or more reads the hourly aggregate
+  }
+): Promise<GapfilledContainerStats[]> {
+  const searchParams = new URLSearchParams({
+    from: params.from,
+    to: params.to,
+    bucket: params.bucket.toString(),
+    gapfill: 'true',
+  });
+
+  return fetchJson<GapfilledContainerStats[]>(
+    `${API_BASE}/api/containers/${containerId}/stats/history?${searchParams.toString()}`
+  );
+}
+
+// Network throughput per bucket; buckets without samples are left out
+export async function fetchContainerNetworkRate(
+  containerId: string,
+  params: {
+    from: string;
+    to: string;
+    bucket: number; // seconds
+  }
+): Promise<NetworkRate[]> {
+  const searchParams = new URLSearchParams({
+    from: params.from,
+    to: params.to,
+    bucket: params.bucket.toString(),
+  });
+
+  return fetchJson<NetworkRate[]>(
+    `${API_BASE}/api/containers/${containerId}/stats/network-rate?${searchParams.toString()}`
+  );
+}
+
+// Processes running in a container (docker top); empty when it is stopped
+export async function fetchContainerProcesses(containerId: string): Promise<ContainerProcess[]> {
+  return fetchJson<ContainerProcess[]>(`${API_BASE}/api/containers/${containerId}/top`);
+}
+
 // Container Logs
 export async function fetchContainerLogs(
   containerId: string,
@@ -84,12 +234,29 @@ export async function fetchImage(imageId: string): Promise<ImageInfo> {
   return fetchJson<ImageInfo>(`${API_BASE}/api/images/${imageId}`);
 }
 
+export async function fetchImageSizeTrend(
+  imageId: string,
+  params?: {
+    from?: string;
+    to?: string;
+  }
+): Promise<ImageSizeTrend> {
+  const searchParams = new URLSearchParams();
+  if (params?.from) searchParams.set('from', params.from);
+  if (params?.to) searchParams.set('to', params.to);
+
+  const url = `${API_BASE}/api/images/${imageId}/size-trend${
+    searchParams.toString() ? `?${searchParams.toString()}` : ''
+  }`;
+  return fetchJson<ImageSizeTrend>(url);
+}
+
 // SSE Connection for real-time stats
 export function connectSSEStats(
   onMessage: (stats: TotalStats) => void,
   onError?: (error: Event) => void
 ): EventSource {
-  const eventSource = new EventSource(`${API_BASE}/api/stats/total/sse`);
+  const eventSource = new EventSource(streamUrl('/api/stats/total/sse'));
 
   eventSource.onmessage = (event) => {
     try {
@@ -110,12 +277,118 @@ export function connectSSEStats(
   return eventSource;
 }
 
+// WebSocket connection for real-time stats (same data as the SSE stream)
+export function connectStatsWebSocket(
+  onMessage: (stats: TotalStats) => void,
+  onError?: (error: Event) => void
+): WebSocket {
+  const socket = new WebSocket(streamUrl('/api/stats/ws').replace(/^http/, 'ws'));
+
+  socket.onmessage = (event) => {
+    try {
+      const message = JSON.parse(event.data);
+      if (message.type === 'comprehensive_stats') {
+        onMessage((message.data as ComprehensiveStats).total_stats);
+      } else if (message.type === 'error') {
+        console.error('Stats WebSocket error:', message.error);
+      }
+    } catch (error) {
+      console.error('Failed to parse WebSocket data:', error);
+    }
+  };
+
+  socket.onerror = (error) => {
+    console.error('WebSocket connection error:', error);
+    if (onError) {
+      onError(error);
+    }
+  };
+
+  return socket;
+}
+
+/** An open live-stats connection, whichever transport it uses */
+export interface StatsConnection {
+  close(): void;
+}
+
+// Real-time stats over the configured transport (`statsTransport` in the runtime config)
+export function connectStats(
+  onMessage: (stats: TotalStats) => void,
+  onError?: (error: Event) => void
+): StatsConnection {
+  return STATS_TRANSPORT === 'websocket'
+    ? connectStatsWebSocket(onMessage, onError)
+    : connectSSEStats(onMessage, onError);
+}
+
+// SSE Connection for container lifecycle events (create/start/stop/die)
+export function connectContainerEvents(
+  onEvent: (event: DockerContainerEvent) => void,
+  onError?: (error: Event) => void
+): EventSource {
+  const eventSource = new EventSource(streamUrl('/api/events/sse'));
+
+  eventSource.addEventListener('container', (event) => {
+    try {
+      const containerEvent: DockerContainerEvent = JSON.parse((event as MessageEvent).data);
+      onEvent(containerEvent);
+    } catch (error) {
+      console.error('Failed to parse container event:', error);
+    }
+  });
+
+  eventSource.onerror = (error) => {
+    console.error('Container events connection error:', error);
+    if (onError) {
+      onError(error);
+    }
+  };
+
+  return eventSource;
+}
+
+// SSE Connection following one container's logs; only lines written after connecting are sent
+export function connectContainerLogs(
+  containerId: string,
+  onLog: (log: ContainerLog) => void,
+  onError?: (error: Event) => void
+): EventSource {
+  const eventSource = new EventSource(streamUrl(`/api/containers/${containerId}/logs/stream`));
+
+  eventSource.onmessage = (event) => {
+    try {
+      const data = JSON.parse(event.data);
+      if (data.error) {
+        console.error('Container logs stream error:', data.error);
+        return;
+      }
+      onLog(data as ContainerLog);
+    } catch (error) {
+      console.error('Failed to parse log line:', error);
+    }
+  };
+
+  eventSource.onerror = (error) => {
+    console.error('Container logs connection error:', error);
+    if (onError) {
+      onError(error);
+    }
+  };
+
+  return eventSource;
+}
+
 // Service Map
-export async function fetchServiceMap(serviceId?: string): Promise<ServiceMap> {
-  const url = serviceId
-    ? `${API_BASE}/api/services/map?service_id=${encodeURIComponent(serviceId)}`
-    : `${API_BASE}/api/services/map`;
-  return fetchJson<ServiceMap>(url);
+export async function fetchServiceMap(
+  serviceId?: string,
+  minConfidence?: number
+): Promise<ServiceMap> {
+  const params = new URLSearchParams();
+  if (serviceId) params.set('service_id', serviceId);
+  if (minConfidence !== undefined) params.set('min_confidence', String(minConfidence));
+  const query = params.toString();
+  return fetchJson<ServiceMap>(`${API_BASE}/api/services/map${query ? `?${query}` : ''}`);
 }
 
 // HTTP Requests
@@ -128,3 +401,24 @@ export async function fetchContainerHttpRequests(
   );
 }
 
+// Without `from`, the backend covers its maximum query range (MAX_QUERY_RANGE_DAYS)
+export async function fetchContainerHttpSummary(
+  containerId: string,
+  from?: Date
+): Promise<HttpRequestSummary> {
+  const query = from ? `?from=${encodeURIComponent(from.toISOString())}` : '';
+  return fetchJson<HttpRequestSummary>(
+    `${API_BASE}/api/containers/${containerId}/http/summary${query}`
+  );
+}
+
+export async function fetchContainerHttpLatency(
+  containerId: string,
+  from?: Date
+): Promise<HttpLatencyPercentiles> {
+  const query = from ? `?from=${encodeURIComponent(from.toISOString())}` : '';
+  return fetchJson<HttpLatencyPercentiles>(
+    `${API_BASE}/api/containers/${containerId}/http/latency${query}`
+  );
+}
+
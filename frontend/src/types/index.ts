@@ -7,15 +7,21 @@ export interface ContainerStats {
   memory_usage_bytes: number;
   memory_limit_bytes: number;
   memory_usage_percent: number;
+  memory_cache_bytes?: number; // Page cache; 0 when not reported
+  memory_swap_bytes?: number; // Swap in use; 0 when not reported (e.g. cgroup v2)
   network_rx_bytes: number;
   network_tx_bytes: number;
   block_read_bytes: number;
   block_write_bytes: number;
+  per_cpu_usage_percent?: number[]; // Empty when Docker doesn't report per-core usage
+  pids_current?: number; // 0 when not reported
+  pids_limit?: number; // 0 when there is no limit
   timestamp: string; // ISO 8601 string
 }
 
 export interface TotalStats {
-  total_containers: number;
+  total_containers: number; // Including stopped containers
+  running_containers: number;
   total_cpu_usage_percent: number;
   total_memory_usage_bytes: number;
   total_memory_limit_bytes: number;
@@ -27,12 +33,114 @@ export interface TotalStats {
   timestamp: string;
 }
 
+export interface ContainerDetails {
+  container_id: string;
+  container_name: string;
+  image: string;
+  status: string;
+  is_running: boolean;
+  environment: { key: string; value: string }[]; // Empty on the live stats stream
+  image_info: ImageInfo | null;
+  stats: ContainerStats; // All zeros for stopped containers
+  created: string | null;
+}
+
+// Snapshot pushed on the stats WebSocket
+export interface ComprehensiveStats {
+  total_containers: number;
+  containers_up: number;
+  containers_down: number;
+  total_stats: TotalStats;
+  containers: ContainerDetails[];
+  timestamp: string;
+}
+
+export interface PaginationInfo {
+  page?: number;
+  page_size: number;
+  total?: number;
+  total_pages?: number;
+  has_next: boolean;
+  has_prev: boolean;
+  next_cursor?: string; // Cursor pagination: pass as `before` for the next (older) page
+}
+
+export interface PaginatedResponse<T> {
+  data: T[];
+  pagination: PaginationInfo;
+}
+
+// One bucket of a container's stats history (long ranges or `bucket` requests)
+export interface AggregatedContainerStats {
+  timestamp: string; // Bucket start
+  avg_cpu_usage_percent: number;
+  max_cpu_usage_percent: number;
+  avg_memory_usage_bytes: number;
+  max_memory_usage_bytes: number;
+  avg_memory_usage_percent: number;
+  max_memory_usage_percent: number;
+}
+
+// Bucket of gap-filled stats history; values are null for buckets without samples
+export interface GapfilledContainerStats {
+  timestamp: string; // Bucket start
+  avg_cpu_usage_percent: number | null;
+  max_cpu_usage_percent: number | null;
+  avg_memory_usage_bytes: number | null;
+  max_memory_usage_bytes: number | null;
+  avg_memory_usage_percent: number | null;
+  max_memory_usage_percent: number | null;
+}
+
+// Network throughput over one bucket, derived from the cumulative byte counters
+export interface NetworkRate {
+  timestamp: string; // Bucket start
+  rx_bytes_per_sec: number;
+  tx_bytes_per_sec: number;
+}
+
 export interface ContainerInfo {
   id: string;
   name: string;
   image: string;
   status: string;
   created?: string;
+  started_at?: string; // Last start; absent if never started
+  restart_count?: number; // Restarts by the restart policy; absent if not inspected
+  state?: ContainerState; // Structured form of `status`
+  health?: 'starting' | 'healthy' | 'unhealthy' | null; // Absent/null for containers without a health check
+  labels?: Record<string, string>;
+  compose_project?: string; // com.docker.compose.project label; absent for standalone containers
+  compose_service?: string; // com.docker.compose.service label
+  collected_at?: string; // When the worker read the container list
+}
+
+// A change of a container's run state, exit code or health, as recorded by the worker
+export interface ContainerStatusChange {
+  container_id: string;
+  container_name: string;
+  status: string;
+  state?: ContainerState;
+  timestamp: string;
+}
+
+export interface ComposeProject {
+  project: string | null; // null groups the standalone containers
+  containers: ContainerInfo[];
+}
+
+export interface ContainerProcess {
+  pid: number;
+  user: string;
+  cpu_percent?: number;
+  memory_percent?: number;
+  command: string;
+}
+
+export interface ContainerState {
+  state: 'created' | 'running' | 'paused' | 'restarting' | 'removing' | 'exited' | 'dead';
+  exit_code?: number;
+  health?: 'starting' | 'healthy' | 'unhealthy'; // Absent for containers without a health check
 }
 
 export interface ContainerLog {
@@ -43,6 +151,15 @@ export interface ContainerLog {
   stream: string; // "stdout" or "stderr"
 }
 
+// Live container lifecycle event from /api/events/sse
+export interface DockerContainerEvent {
+  action: 'create' | 'start' | 'stop' | 'die';
+  container_id: string;
+  container_name: string;
+  exit_code: number | null; // only on "die"
+  timestamp: string;
+}
+
 export interface ImageInfo {
   id: string;
   repo_tags: string[];
@@ -52,17 +169,32 @@ export interface ImageInfo {
   os?: string;
 }
 
+export interface ImageSizePoint {
+  timestamp: string;
+  image_id: string;
+  size_bytes: number;
+}
+
+// Size of an image across rebuilds under its tags
+export interface ImageSizeTrend {
+  image_id: string;
+  repo_tags: string[];
+  points: ImageSizePoint[];
+  delta_bytes: number;
+}
+
 // Chart data point
 export interface DataPoint {
   timestamp: number; // Unix timestamp in seconds
-  cpu: number;
-  memory: number;
-  network: number; // KB/s
+  cpu: number | null; // null where there were no samples, so charts break the line
+  memory: number | null;
+  network: number | null; // KB/s; null where unknown
 }
 
 // Service Communication Detection Types
 export type ConnectionType =
   | 'environment_variable'
+  | 'database_url'
   | 'same_network'
   | 'port_mapping'
   | 'network_traffic'
@@ -100,5 +232,32 @@ export interface HttpRequest {
   http_status: number;
   response_time_ms: number;
   timestamp: string;
+  headers?: Record<string, string> | null; // allowlisted via the worker's CAPTURE_HTTP_HEADERS
+  client_container_id?: string | null; // Container that sent the request; null for clients outside Docker
+}
+
+export interface HttpStatusBreakdown {
+  '2xx': number;
+  '3xx': number;
+  '4xx': number;
+  '5xx': number;
+  other: number; // 1xx and anything unparseable
+}
+
+export interface HttpRequestSummary {
+  from: string;
+  to: string;
+  total: number;
+  status_classes: HttpStatusBreakdown;
+  error_rate: number; // 5xx / total, 0 without requests
+  requests_per_minute: number;
+}
+
+export interface HttpLatencyPercentiles {
+  request_count: number;
+  avg_ms: number;
+  p50_ms: number;
+  p95_ms: number;
+  p99_ms: number;
 }
 
@@ -0,0 +1,61 @@
+import type { ContainerInfo, ContainerStats } from '../types';
+
+/**
+ * Whether a container is running (paused or restarting containers are not).
+ * Mirrors the backend's `ContainerInfo::is_running`: the structured state when present,
+ * otherwise the Docker status string ("Up 5 minutes", "Up 2 minutes (Paused)", "Exited (0) ...").
+ */
+export function isRunning(container: ContainerInfo): boolean {
+  if (container.state) {
+    return container.state.state === 'running';
+  }
+  const statusLower = container.status.toLowerCase();
+  return (statusLower.startsWith('up') && !statusLower.includes('(paused)')) || statusLower === 'running';
+}
+
+// Accent colors for label-based grouping; order is fixed so colors are stable
+const LABEL_PALETTE = [
+  '#2563eb',
+  '#16a34a',
+  '#dc2626',
+  '#9333ea',
+  '#ea580c',
+  '#0891b2',
+  '#ca8a04',
+  '#db2777',
+  '#4f46e5',
+  '#059669',
+];
+
+/**
+ * Stable accent color for a label value (FNV-1a hash into a fixed palette),
+ * so a given project/team keeps its color across renders and reloads.
+ */
+export function labelColor(value: string): string {
+  let hash = 0x811c9dc5;
+  for (let i = 0; i < value.length; i++) {
+    hash ^= value.charCodeAt(i);
+    hash = Math.imul(hash, 0x01000193);
+  }
+  return LABEL_PALETTE[(hash >>> 0) % LABEL_PALETTE.length];
+}
+
+/**
+ * Network throughput in KB/s (rx + tx) between two samples of a container's cumulative byte
+ * counters. A counter that went down (the container restarted) counts as no traffic; null
+ * without an earlier sample to compare with.
+ */
+export function networkRateKb(
+  previous: ContainerStats | undefined,
+  current: ContainerStats
+): number | null {
+  if (!previous) return null;
+  const elapsedSeconds =
+    (new Date(current.timestamp).getTime() - new Date(previous.timestamp).getTime()) / 1000;
+  if (elapsedSeconds <= 0) return null;
+  const deltaBytes =
+    current.network_rx_bytes +
+    current.network_tx_bytes -
+    (previous.network_rx_bytes + previous.network_tx_bytes);
+  return Math.max(deltaBytes, 0) / 1024 / elapsedSeconds;
+}
@@ -2,9 +2,22 @@
 
 interface ImportMetaEnv {
   readonly VITE_API_URL?: string;
+  readonly BACKEND_API_TOKEN?: string;
 }
 
 interface ImportMeta {
   readonly env: ImportMetaEnv;
 }
 
+
+interface RuntimeConfig {
+  readonly apiBaseUrl?: string;
+  /** Token for a backend with API_TOKEN set; sent as a Bearer header, and as `?access_token=` on streams */
+  readonly apiToken?: string;
+  /** Live stats transport; use 'websocket' where a proxy buffers SSE (default 'sse') */
+  readonly statsTransport?: 'sse' | 'websocket';
+}
+
+interface Window {
+  __CONFIG__?: RuntimeConfig;
+}
@@ -10,6 +10,23 @@ pub mod m20241201_000007_create_image_versions;
 pub mod m20241201_000008_create_indexes_and_aggregates;
 pub mod m20241201_000009_add_retention_policies;
 pub mod m20241201_000010_create_http_requests;
+pub mod m20241201_000011_create_container_events;
+pub mod m20241201_000012_create_unmatched_http_requests;
+pub mod m20241201_000013_create_service_map_snapshots;
+pub mod m20241201_000014_add_container_info_started_at;
+pub mod m20241201_000015_add_container_info_state;
+pub mod m20241201_000016_add_container_info_labels;
+pub mod m20241201_000017_add_container_stats_per_cpu;
+pub mod m20241201_000018_create_total_stats;
+pub mod m20241201_000019_add_http_requests_headers;
+pub mod m20241201_000020_add_container_stats_memory_breakdown;
+pub mod m20241201_000021_create_alert_rules;
+pub mod m20241201_000022_add_container_info_restart_count;
+pub mod m20241201_000023_create_container_disk_usage;
+pub mod m20241201_000024_add_container_logs_search_index;
+pub mod m20241201_000025_add_container_stats_pids;
+pub mod m20241201_000026_upsert_container_info;
+pub mod m20241201_000027_add_http_requests_client_container;
 
 pub struct Migrator;
 
@@ -27,6 +44,23 @@ impl MigratorTrait for Migrator {
             Box::new(m20241201_000008_create_indexes_and_aggregates::Migration),
             Box::new(m20241201_000009_add_retention_policies::Migration),
             Box::new(m20241201_000010_create_http_requests::Migration),
+            Box::new(m20241201_000011_create_container_events::Migration),
+            Box::new(m20241201_000012_create_unmatched_http_requests::Migration),
+            Box::new(m20241201_000013_create_service_map_snapshots::Migration),
+            Box::new(m20241201_000014_add_container_info_started_at::Migration),
+            Box::new(m20241201_000015_add_container_info_state::Migration),
+            Box::new(m20241201_000016_add_container_info_labels::Migration),
+            Box::new(m20241201_000017_add_container_stats_per_cpu::Migration),
+            Box::new(m20241201_000018_create_total_stats::Migration),
+            Box::new(m20241201_000019_add_http_requests_headers::Migration),
+            Box::new(m20241201_000020_add_container_stats_memory_breakdown::Migration),
+            Box::new(m20241201_000021_create_alert_rules::Migration),
+            Box::new(m20241201_000022_add_container_info_restart_count::Migration),
+            Box::new(m20241201_000023_create_container_disk_usage::Migration),
+            Box::new(m20241201_000024_add_container_logs_search_index::Migration),
+            Box::new(m20241201_000025_add_container_stats_pids::Migration),
+            Box::new(m20241201_000026_upsert_container_info::Migration),
+            Box::new(m20241201_000027_add_http_requests_client_container::Migration),
         ]
     }
 }
@@ -0,0 +1,116 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UnmatchedHttpRequests::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UnmatchedHttpRequests::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(UnmatchedHttpRequests::SrcIp)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnmatchedHttpRequests::SrcPort)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnmatchedHttpRequests::DstIp)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnmatchedHttpRequests::DstPort)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnmatchedHttpRequests::Method)
+                            .string_len(10)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnmatchedHttpRequests::Endpoint)
+                            .string_len(500)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnmatchedHttpRequests::HttpStatus)
+                            .small_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnmatchedHttpRequests::ResponseTimeMs)
+                            .double()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnmatchedHttpRequests::Reason)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnmatchedHttpRequests::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnmatchedHttpRequests::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_unmatched_http_requests_timestamp")
+                    .table(UnmatchedHttpRequests::Table)
+                    .col(UnmatchedHttpRequests::Timestamp)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UnmatchedHttpRequests::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UnmatchedHttpRequests {
+    Table,
+    Id,
+    SrcIp,
+    SrcPort,
+    DstIp,
+    DstPort,
+    Method,
+    Endpoint,
+    HttpStatus,
+    ResponseTimeMs,
+    Reason,
+    Timestamp,
+    CreatedAt,
+}
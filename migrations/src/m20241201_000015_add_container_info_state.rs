@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContainerInfo::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(ContainerInfo::State)
+                            .string_len(20)
+                            .null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(ContainerInfo::ExitCode)
+                            .integer()
+                            .null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(ContainerInfo::Health)
+                            .string_len(20)
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContainerInfo::Table)
+                    .drop_column(ContainerInfo::State)
+                    .drop_column(ContainerInfo::ExitCode)
+                    .drop_column(ContainerInfo::Health)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ContainerInfo {
+    Table,
+    State,
+    ExitCode,
+    Health,
+}
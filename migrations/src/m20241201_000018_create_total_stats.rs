@@ -0,0 +1,158 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TotalStats::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TotalStats::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(TotalStats::TotalContainers)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TotalStats::RunningContainers)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TotalStats::TotalCpuUsagePercent)
+                            .double()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TotalStats::TotalMemoryUsageBytes)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TotalStats::TotalMemoryLimitBytes)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TotalStats::TotalMemoryUsagePercent)
+                            .double()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TotalStats::TotalNetworkRxBytes)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TotalStats::TotalNetworkTxBytes)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TotalStats::TotalBlockReadBytes)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TotalStats::TotalBlockWriteBytes)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TotalStats::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TotalStats::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Convert to hypertable after creating the table
+        let conn = manager.get_connection();
+        conn.execute_unprepared(
+            r#"
+            DO $$
+            BEGIN
+                IF NOT EXISTS (
+                    SELECT 1 FROM timescaledb_information.hypertables 
+                    WHERE hypertable_name = 'total_stats'
+                ) THEN
+                    -- Drop the primary key constraint to convert to hypertable
+                    IF EXISTS (
+                        SELECT 1 FROM pg_constraint 
+                        WHERE conname = 'total_stats_pkey'
+                    ) THEN
+                        ALTER TABLE total_stats DROP CONSTRAINT total_stats_pkey;
+                    END IF;
+                    
+                    -- One row per snapshot keeps this series small; use weekly chunks
+                    PERFORM create_hypertable('total_stats', 'timestamp', 
+                        chunk_time_interval => INTERVAL '7 days',
+                        if_not_exists => TRUE);
+                    
+                    -- Recreate primary key as composite (id, timestamp) to satisfy TimescaleDB
+                    ALTER TABLE total_stats ADD CONSTRAINT total_stats_pkey 
+                        PRIMARY KEY (id, timestamp);
+                END IF;
+            END $$;
+            "#,
+        )
+        .await
+        .map_err(|e| DbErr::Custom(format!("Failed to convert total_stats to hypertable: {}", e)))?;
+
+        // Keep the fleet series for 30 days, like image_versions
+        conn.execute_unprepared(
+            r#"
+            SELECT add_retention_policy('total_stats', 
+                INTERVAL '30 days',
+                if_not_exists => TRUE);
+            "#,
+        )
+        .await
+        .ok();
+
+        log::info!("Created total_stats table, converted to hypertable, and added retention policy");
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TotalStats::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TotalStats {
+    Table,
+    Id,
+    TotalContainers,
+    RunningContainers,
+    TotalCpuUsagePercent,
+    TotalMemoryUsageBytes,
+    TotalMemoryLimitBytes,
+    TotalMemoryUsagePercent,
+    TotalNetworkRxBytes,
+    TotalNetworkTxBytes,
+    TotalBlockReadBytes,
+    TotalBlockWriteBytes,
+    Timestamp,
+    CreatedAt,
+}
@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Nullable like per_cpu_usage_percent, so existing (possibly compressed) chunks need no rewrite
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContainerStats::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(ContainerStats::MemoryCacheBytes)
+                            .big_integer()
+                            .null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(ContainerStats::MemorySwapBytes)
+                            .big_integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContainerStats::Table)
+                    .drop_column(ContainerStats::MemoryCacheBytes)
+                    .drop_column(ContainerStats::MemorySwapBytes)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ContainerStats {
+    Table,
+    MemoryCacheBytes,
+    MemorySwapBytes,
+}
@@ -0,0 +1,159 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AlertRules::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AlertRules::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AlertRules::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(AlertRules::ContainerPattern)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AlertRules::Metric).string().not_null())
+                    .col(ColumnDef::new(AlertRules::Comparator).string().not_null())
+                    .col(ColumnDef::new(AlertRules::Threshold).double().not_null())
+                    .col(
+                        ColumnDef::new(AlertRules::DurationSecs)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(AlertRules::Enabled)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(AlertRules::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AlertRules::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Firing/resolved transitions; a plain table (not a hypertable) since rows are rare
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alerts::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alerts::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Alerts::RuleId).big_integer().not_null())
+                    .col(ColumnDef::new(Alerts::RuleName).string().not_null())
+                    .col(ColumnDef::new(Alerts::ContainerId).string().not_null())
+                    .col(ColumnDef::new(Alerts::ContainerName).string().not_null())
+                    .col(ColumnDef::new(Alerts::Metric).string().not_null())
+                    .col(ColumnDef::new(Alerts::Value).double().not_null())
+                    .col(ColumnDef::new(Alerts::Threshold).double().not_null())
+                    .col(ColumnDef::new(Alerts::State).string().not_null())
+                    .col(
+                        ColumnDef::new(Alerts::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alerts::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_alerts_timestamp")
+                    .table(Alerts::Table)
+                    .col(Alerts::Timestamp)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Latest transition per rule and container, read when the worker starts
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_alerts_rule_container_timestamp")
+                    .table(Alerts::Table)
+                    .col(Alerts::RuleId)
+                    .col(Alerts::ContainerId)
+                    .col(Alerts::Timestamp)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alerts::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(AlertRules::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AlertRules {
+    Table,
+    Id,
+    Name,
+    ContainerPattern,
+    Metric,
+    Comparator,
+    Threshold,
+    DurationSecs,
+    Enabled,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Alerts {
+    Table,
+    Id,
+    RuleId,
+    RuleName,
+    ContainerId,
+    ContainerName,
+    Metric,
+    Value,
+    Threshold,
+    State,
+    Timestamp,
+    CreatedAt,
+}
@@ -0,0 +1,133 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ContainerDiskUsage::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ContainerDiskUsage::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerDiskUsage::ContainerId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerDiskUsage::ContainerName)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerDiskUsage::SizeRwBytes)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerDiskUsage::SizeRootFsBytes)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerDiskUsage::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerDiskUsage::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_container_disk_usage_container_timestamp")
+                    .table(ContainerDiskUsage::Table)
+                    .col(ContainerDiskUsage::ContainerId)
+                    .col(ContainerDiskUsage::Timestamp)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Convert to hypertable after creating the table
+        let conn = manager.get_connection();
+        conn.execute_unprepared(
+            r#"
+            DO $$
+            BEGIN
+                IF NOT EXISTS (
+                    SELECT 1 FROM timescaledb_information.hypertables 
+                    WHERE hypertable_name = 'container_disk_usage'
+                ) THEN
+                    -- Drop the primary key constraint to convert to hypertable
+                    IF EXISTS (
+                        SELECT 1 FROM pg_constraint 
+                        WHERE conname = 'container_disk_usage_pkey'
+                    ) THEN
+                        ALTER TABLE container_disk_usage DROP CONSTRAINT container_disk_usage_pkey;
+                    END IF;
+                    
+                    -- Sampled every few minutes, so weekly chunks stay small
+                    PERFORM create_hypertable('container_disk_usage', 'timestamp', 
+                        chunk_time_interval => INTERVAL '7 days',
+                        if_not_exists => TRUE);
+                    
+                    -- Recreate primary key as composite (id, timestamp) to satisfy TimescaleDB
+                    ALTER TABLE container_disk_usage ADD CONSTRAINT container_disk_usage_pkey 
+                        PRIMARY KEY (id, timestamp);
+                END IF;
+            END $$;
+            "#,
+        )
+        .await
+        .map_err(|e| DbErr::Custom(format!("Failed to convert container_disk_usage to hypertable: {}", e)))?;
+
+        // Keep disk usage for 30 days, like total_stats
+        conn.execute_unprepared(
+            r#"
+            SELECT add_retention_policy('container_disk_usage', 
+                INTERVAL '30 days',
+                if_not_exists => TRUE);
+            "#,
+        )
+        .await
+        .ok();
+
+        log::info!("Created container_disk_usage table, converted to hypertable, and added retention policy");
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ContainerDiskUsage::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ContainerDiskUsage {
+    Table,
+    Id,
+    ContainerId,
+    ContainerName,
+    SizeRwBytes,
+    SizeRootFsBytes,
+    Timestamp,
+    CreatedAt,
+}
@@ -0,0 +1,33 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        // Full-text index for the log search. The 'simple' configuration keeps every word as
+        // written (no stemming or stop words), which suits identifiers and error codes; the
+        // search query must use the same expression for the index to be picked
+        conn.execute_unprepared(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_container_logs_log_line_fts
+            ON container_logs USING GIN (to_tsvector('simple', log_line));
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        conn.execute_unprepared("DROP INDEX IF EXISTS idx_container_logs_log_line_fts;")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Nullable like the memory breakdown, so existing (possibly compressed) chunks need no rewrite
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContainerStats::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(ContainerStats::PidsCurrent)
+                            .big_integer()
+                            .null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(ContainerStats::PidsLimit)
+                            .big_integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContainerStats::Table)
+                    .drop_column(ContainerStats::PidsCurrent)
+                    .drop_column(ContainerStats::PidsLimit)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ContainerStats {
+    Table,
+    PidsCurrent,
+    PidsLimit,
+}
@@ -0,0 +1,208 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        // container_info becomes one row per container: keep only the latest snapshot of each
+        conn.execute_unprepared(
+            r#"
+            DELETE FROM container_info a
+            USING container_info b
+            WHERE a.container_id = b.container_id
+              AND (a.collected_at, a.id) < (b.collected_at, b.id);
+            "#,
+        )
+        .await
+        .map_err(|e| DbErr::Custom(format!("Failed to deduplicate container_info: {}", e)))?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_container_info_container_id")
+                    .table(ContainerInfo::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Conflict target of the worker's upsert
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_container_info_container_id_unique")
+                    .table(ContainerInfo::Table)
+                    .col(ContainerInfo::ContainerId)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ContainerStatusHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ContainerStatusHistory::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerStatusHistory::ContainerId)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerStatusHistory::ContainerName)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerStatusHistory::Status)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerStatusHistory::State)
+                            .string_len(32)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerStatusHistory::ExitCode)
+                            .integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerStatusHistory::Health)
+                            .string_len(32)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerStatusHistory::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ContainerStatusHistory::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_container_status_history_container_timestamp")
+                    .table(ContainerStatusHistory::Table)
+                    .col(ContainerStatusHistory::ContainerId)
+                    .col(ContainerStatusHistory::Timestamp)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Convert to hypertable after creating the table
+        conn.execute_unprepared(
+            r#"
+            DO $$
+            BEGIN
+                IF NOT EXISTS (
+                    SELECT 1 FROM timescaledb_information.hypertables
+                    WHERE hypertable_name = 'container_status_history'
+                ) THEN
+                    -- Drop the primary key constraint to convert to hypertable
+                    IF EXISTS (
+                        SELECT 1 FROM pg_constraint
+                        WHERE conname = 'container_status_history_pkey'
+                    ) THEN
+                        ALTER TABLE container_status_history DROP CONSTRAINT container_status_history_pkey;
+                    END IF;
+
+                    -- Only status changes are written, so monthly chunks stay small
+                    PERFORM create_hypertable('container_status_history', 'timestamp',
+                        chunk_time_interval => INTERVAL '30 days',
+                        if_not_exists => TRUE);
+
+                    -- Recreate primary key as composite (id, timestamp) to satisfy TimescaleDB
+                    ALTER TABLE container_status_history ADD CONSTRAINT container_status_history_pkey
+                        PRIMARY KEY (id, timestamp);
+                END IF;
+            END $$;
+            "#,
+        )
+        .await
+        .map_err(|e| DbErr::Custom(format!("Failed to convert container_status_history to hypertable: {}", e)))?;
+
+        // Keep status changes for 90 days
+        conn.execute_unprepared(
+            r#"
+            SELECT add_retention_policy('container_status_history',
+                INTERVAL '90 days',
+                if_not_exists => TRUE);
+            "#,
+        )
+        .await
+        .ok();
+
+        log::info!("Deduplicated container_info, created container_status_history hypertable, and added retention policy");
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ContainerStatusHistory::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_container_info_container_id_unique")
+                    .table(ContainerInfo::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_container_info_container_id")
+                    .table(ContainerInfo::Table)
+                    .col(ContainerInfo::ContainerId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ContainerInfo {
+    Table,
+    ContainerId,
+}
+
+#[derive(DeriveIden)]
+enum ContainerStatusHistory {
+    Table,
+    Id,
+    ContainerId,
+    ContainerName,
+    Status,
+    State,
+    ExitCode,
+    Health,
+    Timestamp,
+    CreatedAt,
+}
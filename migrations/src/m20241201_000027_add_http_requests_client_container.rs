@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The container that sent the request; container_id is the one it was sent to.
+        // Null for rows captured before this column existed and for clients outside Docker
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(HttpRequests::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(HttpRequests::ClientContainerId)
+                            .string_len(255)
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(HttpRequests::Table)
+                    .drop_column(HttpRequests::ClientContainerId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum HttpRequests {
+    Table,
+    ClientContainerId,
+}
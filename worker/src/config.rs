@@ -1,21 +1,98 @@
+use eyes_devine_services::capture_rules::DEFAULT_CAPTURE_PORTS;
+use eyes_devine_services::network_monitor_service::DEFAULT_CONTAINER_IP_CACHE_TTL;
 use std::env;
 use std::time::Duration;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsSinkKind {
+    Postgres,
+    File,
+    Stdout,
+}
+
 #[derive(Clone)]
 pub struct Config {
     pub server_host: String,
     pub server_port: u16,
-    pub database_url: String,
+    pub database_url: Option<String>,
+    
+    // Where collected batches are written
+    pub metrics_sink: MetricsSinkKind,
+    pub metrics_sink_file: String,
     
     // Collection intervals
     pub stats_collection_interval: Duration,
     pub status_collection_interval: Duration,
     pub image_collection_interval: Duration,
     pub http_requests_collection_interval: Duration,
+    pub service_map_snapshot_interval: Duration,
+    // Fleet totals snapshot into `total_stats` (None = off)
+    pub total_stats_interval: Option<Duration>,
+    // Per-container disk usage into `container_disk_usage` (None = off); Docker computes it
+    // by walking every writable layer, so keep this slow
+    pub disk_usage_interval: Option<Duration>,
+    
+    // Maximum number of image inspections in flight during image collection
+    pub image_inspect_concurrency: usize,
+    
+    // Stats sampling: containers that are neither labelled important nor above a resource
+    // threshold are only collected every `stats_sample_every` stats ticks (1 = no sampling)
+    pub stats_sample_every: u32,
+    pub stats_important_label: Option<String>,
+    pub stats_full_resolution_cpu_percent: Option<f64>,
+    pub stats_full_resolution_memory_percent: Option<f64>,
     
     // Batch settings
     pub batch_size: usize,
     pub batch_timeout: Duration,
+    // Per-stream overrides, already resolved against the global batch settings
+    pub stats_batch_size: usize,
+    pub stats_batch_timeout: Duration,
+    pub status_batch_size: usize,
+    pub status_batch_timeout: Duration,
+    pub image_batch_size: usize,
+    pub image_batch_timeout: Duration,
+    pub http_batch_size: usize,
+    pub http_batch_timeout: Duration,
+    // Rows kept per batch buffer while writes keep failing; the oldest are dropped past this
+    pub max_buffered_rows: usize,
+    
+    // Docker connection health probe
+    pub docker_health_probe_interval: Duration,
+    
+    // HTTP capture rules (ports + endpoint normalization), reloadable at runtime
+    pub capture_rules_file: Option<String>,
+    
+    // Ports captured unless the rules file lists its own `capture_ports`
+    pub http_ports: Vec<u16>,
+    
+    // Bearer token for the admin and capture-diagnostics routes (None = those routes are disabled)
+    pub admin_token: Option<String>,
+    
+    // Debug: persist captured requests that couldn't be attributed to a container
+    pub store_unmatched_requests: bool,
+    
+    // Time chunked HTTP responses to their terminating chunk instead of the first segment
+    pub track_chunked_responses: bool,
+    
+    // Re-initialize packet capture after this long without packets on a busy interface (None = off)
+    pub capture_stall_timeout: Option<Duration>,
+    
+    // How long container IPs used to attribute captured packets are cached
+    pub container_ip_cache_ttl: Duration,
+    
+    // Request/response headers stored with captured HTTP requests (e.g. Host, User-Agent)
+    pub captured_http_headers: Vec<String>,
+    
+    // Record exit code / finish time when a running container stops
+    pub record_exit_events: bool,
+    
+    // Keep container_info only for the latest container per name (job containers get new ids)
+    pub collapse_container_info_by_name: bool,
+    
+    // Where firing alerts are posted (generic JSON webhook / Slack incoming webhook); None = off
+    pub webhook_url: Option<String>,
+    pub slack_webhook_url: Option<String>,
 }
 
 impl Config {
@@ -28,8 +105,21 @@ impl Config {
             .and_then(|p| p.parse().ok())
             .unwrap_or(8081);
         
-        let database_url = env::var("DATABASE_URL")
-            .expect("DATABASE_URL environment variable must be set");
+        let metrics_sink = match env::var("METRICS_SINK").as_deref() {
+            Ok("file") => MetricsSinkKind::File,
+            Ok("stdout") => MetricsSinkKind::Stdout,
+            Ok("postgres") | Err(_) => MetricsSinkKind::Postgres,
+            Ok(other) => panic!("Unknown METRICS_SINK '{}' (expected postgres, file or stdout)", other),
+        };
+        
+        let metrics_sink_file = env::var("METRICS_SINK_FILE")
+            .unwrap_or_else(|_| "metrics.jsonl".to_string());
+        
+        // The database is only required by the Postgres sink
+        let database_url = env::var("DATABASE_URL").ok();
+        if metrics_sink == MetricsSinkKind::Postgres && database_url.is_none() {
+            panic!("DATABASE_URL environment variable must be set when METRICS_SINK is postgres");
+        }
 
         // Collection intervals (in seconds)
         let stats_interval_secs = env::var("STATS_COLLECTION_INTERVAL")
@@ -52,6 +142,35 @@ impl Config {
             .and_then(|s| s.parse().ok())
             .unwrap_or(30);
         
+        let service_map_snapshot_interval_secs = env::var("SERVICE_MAP_SNAPSHOT_INTERVAL")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(300);
+        
+        // 0 disables the fleet totals snapshots
+        let total_stats_interval_secs = env::var("TOTAL_STATS_INTERVAL")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(60);
+        
+        // 0 disables disk usage collection
+        let disk_usage_interval_secs = env::var("DISK_USAGE_INTERVAL")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(600);
+        
+        let image_inspect_concurrency = env::var("IMAGE_INSPECT_CONCURRENCY")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|n: &usize| *n > 0)
+            .unwrap_or(4);
+        
+        let stats_sample_every = env::var("STATS_SAMPLE_EVERY")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|n: &u32| *n > 0)
+            .unwrap_or(1);
+        
         // Batch settings
         let batch_size = env::var("BATCH_SIZE")
             .ok()
@@ -62,17 +181,122 @@ impl Config {
             .ok()
             .and_then(|s| s.parse().ok())
             .unwrap_or(1);
+        
+        // Per-stream overrides, e.g. HTTP_BATCH_SIZE / IMAGE_BATCH_TIMEOUT; unset falls back to the above
+        let stream_batch_size = |name: &str| {
+            env::var(format!("{}_BATCH_SIZE", name))
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|n: &usize| *n > 0)
+                .unwrap_or(batch_size)
+        };
+        let stream_batch_timeout = |name: &str| {
+            let secs = env::var(format!("{}_BATCH_TIMEOUT", name))
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(batch_timeout_secs);
+            Duration::from_secs(secs)
+        };
+        
+        // tokio's interval panics on a zero period, so 0 falls back to the default too
+        let docker_health_probe_interval_secs = env::var("DOCKER_HEALTH_PROBE_INTERVAL")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|secs: &u64| *secs > 0)
+            .unwrap_or(15);
+
+        // 0 disables the capture watchdog
+        let capture_stall_timeout_secs = env::var("CAPTURE_STALL_TIMEOUT")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(300);
+
+        // Comma-separated, e.g. "80,4000,8888"
+        let http_ports = match env::var("HTTP_CAPTURE_PORTS") {
+            Ok(ports) => ports
+                .split(',')
+                .map(str::trim)
+                .filter(|port| !port.is_empty())
+                .map(|port| match port.parse::<u16>() {
+                    Ok(port) if port > 0 => port,
+                    _ => panic!("Invalid port '{}' in HTTP_CAPTURE_PORTS", port),
+                })
+                .collect(),
+            Err(_) => DEFAULT_CAPTURE_PORTS.to_vec(),
+        };
 
         Self {
             server_host,
             server_port,
             database_url,
+            metrics_sink,
+            metrics_sink_file,
             stats_collection_interval: Duration::from_secs(stats_interval_secs),
             status_collection_interval: Duration::from_secs(status_interval_secs),
             image_collection_interval: Duration::from_secs(image_interval_secs),
             http_requests_collection_interval: Duration::from_secs(http_requests_interval_secs),
+            service_map_snapshot_interval: Duration::from_secs(service_map_snapshot_interval_secs),
+            total_stats_interval: (total_stats_interval_secs > 0)
+                .then(|| Duration::from_secs(total_stats_interval_secs)),
+            disk_usage_interval: (disk_usage_interval_secs > 0)
+                .then(|| Duration::from_secs(disk_usage_interval_secs)),
+            image_inspect_concurrency,
+            stats_sample_every,
+            stats_important_label: env::var("STATS_IMPORTANT_LABEL").ok().filter(|s| !s.is_empty()),
+            stats_full_resolution_cpu_percent: env::var("STATS_FULL_RESOLUTION_CPU_PERCENT")
+                .ok()
+                .and_then(|s| s.parse().ok()),
+            stats_full_resolution_memory_percent: env::var("STATS_FULL_RESOLUTION_MEMORY_PERCENT")
+                .ok()
+                .and_then(|s| s.parse().ok()),
             batch_size,
             batch_timeout: Duration::from_secs(batch_timeout_secs),
+            stats_batch_size: stream_batch_size("STATS"),
+            stats_batch_timeout: stream_batch_timeout("STATS"),
+            status_batch_size: stream_batch_size("STATUS"),
+            status_batch_timeout: stream_batch_timeout("STATUS"),
+            image_batch_size: stream_batch_size("IMAGE"),
+            image_batch_timeout: stream_batch_timeout("IMAGE"),
+            http_batch_size: stream_batch_size("HTTP"),
+            http_batch_timeout: stream_batch_timeout("HTTP"),
+            max_buffered_rows: env::var("MAX_BUFFERED_ROWS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|n: &usize| *n > 0)
+                .unwrap_or(100_000),
+            docker_health_probe_interval: Duration::from_secs(docker_health_probe_interval_secs),
+            capture_rules_file: env::var("CAPTURE_RULES_FILE").ok(),
+            http_ports,
+            admin_token: env::var("ADMIN_TOKEN").ok().filter(|s| !s.is_empty()),
+            store_unmatched_requests: env::var("STORE_UNMATCHED_HTTP_REQUESTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+            track_chunked_responses: env::var("TRACK_CHUNKED_RESPONSES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(true),
+            capture_stall_timeout: (capture_stall_timeout_secs > 0)
+                .then(|| Duration::from_secs(capture_stall_timeout_secs)),
+            container_ip_cache_ttl: env::var("CONTAINER_IP_CACHE_TTL")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_CONTAINER_IP_CACHE_TTL),
+            // Comma-separated, e.g. "Host,User-Agent,X-Request-Id"
+            captured_http_headers: env::var("CAPTURE_HTTP_HEADERS")
+                .map(|headers| headers.split(',').map(|h| h.trim().to_string()).filter(|h| !h.is_empty()).collect())
+                .unwrap_or_default(),
+            record_exit_events: env::var("RECORD_CONTAINER_EXIT_EVENTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(true),
+            collapse_container_info_by_name: env::var("COLLAPSE_CONTAINER_INFO_BY_NAME")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+            webhook_url: env::var("WEBHOOK_URL").ok().filter(|url| !url.is_empty()),
+            slack_webhook_url: env::var("SLACK_WEBHOOK_URL").ok().filter(|url| !url.is_empty()),
         }
     }
 }
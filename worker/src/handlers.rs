@@ -0,0 +1,177 @@
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use anyhow::Result;
+use eyes_devine_services::{readiness, CaptureRules, DockerService, NetworkMonitorService};
+use sea_orm::DatabaseConnection;
+use serde_json::json;
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use crate::schedule::CollectionSchedule;
+
+pub struct WorkerState {
+    pub docker_service: Arc<DockerService>,
+    /// Set when writing to the Postgres sink
+    pub db: Option<DatabaseConnection>,
+    pub network_monitor: Arc<NetworkMonitorService>,
+    pub schedule: Arc<CollectionSchedule>,
+    pub capture_rules_file: Option<String>,
+    pub http_ports: Vec<u16>,
+    pub admin_token: Option<String>,
+}
+
+/// Check the request's `Authorization: Bearer` token against `ADMIN_TOKEN`, like the server's
+/// admin endpoints. 403 when no admin token is configured, 401 when it is missing or wrong
+fn require_admin(req: &HttpRequest, admin_token: Option<&str>) -> Result<(), Box<HttpResponse>> {
+    let Some(expected) = admin_token else {
+        return Err(Box::new(HttpResponse::Forbidden().json(json!({
+            "error": "Admin endpoints are disabled; set ADMIN_TOKEN to enable them"
+        }))));
+    };
+
+    let token = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match token {
+        // Constant time, so response timing doesn't reveal how much of a guess was right
+        Some(token) if bool::from(token.as_bytes().ct_eq(expected.as_bytes())) => Ok(()),
+        _ => Err(Box::new(HttpResponse::Unauthorized().json(json!({
+            "error": "Missing or invalid admin token"
+        })))),
+    }
+}
+
+/// Readiness probe: 200 when Docker and, with the Postgres sink, the database answer;
+/// otherwise 503. `/health` stays a plain liveness probe
+pub async fn get_readiness(state: web::Data<WorkerState>) -> impl Responder {
+    let readiness = readiness::check(&state.docker_service, state.db.as_ref(), false).await;
+
+    if readiness.ready {
+        HttpResponse::Ok().json(readiness)
+    } else {
+        HttpResponse::ServiceUnavailable().json(readiness)
+    }
+}
+
+pub async fn get_status(state: web::Data<WorkerState>) -> impl Responder {
+    let docker = state.docker_service.health();
+    let status = if docker.connected { "ok" } else { "degraded" };
+
+    let capture = state.network_monitor.capture_health();
+
+    HttpResponse::Ok().json(json!({
+        "status": status,
+        "docker": docker,
+        "collectors": state.schedule.snapshot(),
+        "network_capture": {
+            // Built with `--features network-capture` and currently capturing on some interface
+            "feature_enabled": cfg!(feature = "network-capture"),
+            "capturing": !capture.active_interfaces.is_empty(),
+        },
+        "capture": capture,
+        "capture_stats": state.network_monitor.get_capture_stats(),
+    }))
+}
+
+/// Capture counters since the worker started, for checking why requests aren't showing up.
+/// Requires the `ADMIN_TOKEN` bearer token
+pub async fn get_capture_stats(req: HttpRequest, state: web::Data<WorkerState>) -> impl Responder {
+    if let Err(response) = require_admin(&req, state.admin_token.as_deref()) {
+        return *response;
+    }
+
+    HttpResponse::Ok().json(state.network_monitor.get_capture_stats())
+}
+
+/// Re-read the capture rules file and swap the rules into the running monitor.
+/// The current rules stay in place if the new ones fail to load or validate.
+pub fn reload_capture_rules(
+    network_monitor: &NetworkMonitorService,
+    rules_file: Option<&str>,
+    http_ports: &[u16],
+) -> Result<Arc<CaptureRules>> {
+    let rules_file = rules_file
+        .ok_or_else(|| anyhow::anyhow!("CAPTURE_RULES_FILE is not set - nothing to reload"))?;
+    let rules = CaptureRules::load(Some(rules_file), http_ports)?;
+    network_monitor.set_capture_rules(rules);
+    Ok(network_monitor.capture_rules())
+}
+
+/// Capture rules in effect and the file they were loaded from.
+/// Requires the `ADMIN_TOKEN` bearer token
+pub async fn get_capture_rules(req: HttpRequest, state: web::Data<WorkerState>) -> impl Responder {
+    if let Err(response) = require_admin(&req, state.admin_token.as_deref()) {
+        return *response;
+    }
+
+    let rules = state.network_monitor.capture_rules();
+    HttpResponse::Ok().json(json!({
+        "source": state.capture_rules_file,
+        "rules": rules.config(),
+    }))
+}
+
+/// Reload the capture rules file (see [`reload_capture_rules`]).
+/// Requires the `ADMIN_TOKEN` bearer token
+pub async fn reload_capture_rules_handler(req: HttpRequest, state: web::Data<WorkerState>) -> impl Responder {
+    if let Err(response) = require_admin(&req, state.admin_token.as_deref()) {
+        return *response;
+    }
+
+    match reload_capture_rules(&state.network_monitor, state.capture_rules_file.as_deref(), &state.http_ports) {
+        Ok(rules) => HttpResponse::Ok().json(json!({
+            "reloaded": true,
+            "rules": rules.config(),
+        })),
+        Err(e) => {
+            log::warn!("Capture rules reload failed, keeping current rules: {:#}", e);
+            HttpResponse::BadRequest().json(json!({
+                "error": format!("Failed to reload capture rules: {:#}", e)
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::test::TestRequest;
+
+    fn status(result: Result<(), Box<HttpResponse>>) -> Option<StatusCode> {
+        result.err().map(|response| response.status())
+    }
+
+    #[test]
+    fn admin_routes_are_disabled_without_admin_token() {
+        let req = TestRequest::get()
+            .insert_header((AUTHORIZATION, "Bearer secret"))
+            .to_http_request();
+        assert_eq!(status(require_admin(&req, None)), Some(StatusCode::FORBIDDEN));
+    }
+
+    #[test]
+    fn admin_routes_reject_missing_or_wrong_token() {
+        let req = TestRequest::get().to_http_request();
+        assert_eq!(status(require_admin(&req, Some("secret"))), Some(StatusCode::UNAUTHORIZED));
+
+        let req = TestRequest::get()
+            .insert_header((AUTHORIZATION, "Bearer wrong"))
+            .to_http_request();
+        assert_eq!(status(require_admin(&req, Some("secret"))), Some(StatusCode::UNAUTHORIZED));
+
+        let req = TestRequest::get()
+            .insert_header((AUTHORIZATION, "Basic c2VjcmV0"))
+            .to_http_request();
+        assert_eq!(status(require_admin(&req, Some("secret"))), Some(StatusCode::UNAUTHORIZED));
+    }
+
+    #[test]
+    fn admin_routes_accept_the_admin_token() {
+        let req = TestRequest::get()
+            .insert_header((AUTHORIZATION, "Bearer secret"))
+            .to_http_request();
+        assert_eq!(status(require_admin(&req, Some("secret"))), None);
+    }
+}
@@ -1,10 +1,14 @@
 mod config;
+mod handlers;
+mod schedule;
+mod sink;
 mod worker_service;
 mod entity;
 
 use actix_web::{web, App, HttpServer};
-use config::Config;
-use eyes_devine_services::{DockerService, create_connection};
+use config::{Config, MetricsSinkKind};
+use eyes_devine_services::{AlertService, CaptureRules, DockerService, NetworkMonitorService, NotificationService, QueryService, create_connection};
+use sink::{JsonLinesSink, MetricsSink, PostgresSink};
 use std::sync::Arc;
 use worker_service::WorkerService;
 
@@ -24,28 +28,167 @@ async fn main() -> std::io::Result<()> {
             .expect("Failed to initialize Docker service"),
     );
 
-    // Initialize database connection
-    let db = create_connection(&config.database_url)
-        .await
-        .expect("Failed to connect to database");
-    
-    log::info!("Database connection established");
+    // Re-create the Docker client if the daemon goes away (e.g. during a Docker upgrade)
+    docker_service.start_health_probe(config.docker_health_probe_interval);
+
+    // Initialize the metrics sink (and the database connection when writing to Postgres)
+    let (db, sink): (_, Arc<dyn MetricsSink>) = match config.metrics_sink {
+        MetricsSinkKind::Postgres => {
+            let database_url = config.database_url.as_deref().unwrap_or_default();
+            let db = create_connection(database_url)
+                .await
+                .expect("Failed to connect to database");
+            log::info!("Database connection established");
+            let sink = PostgresSink::new(db.clone())
+                .with_container_info_collapsed_by_name(config.collapse_container_info_by_name);
+            (Some(db), Arc::new(sink))
+        }
+        MetricsSinkKind::File => {
+            let sink = JsonLinesSink::file(&config.metrics_sink_file)
+                .await
+                .expect("Failed to open metrics sink file");
+            (None, Arc::new(sink))
+        }
+        MetricsSinkKind::Stdout => (None, Arc::new(JsonLinesSink::stdout())),
+    };
+
+    // Load HTTP capture rules (falls back to the default rules on HTTP_CAPTURE_PORTS if the file is invalid)
+    let capture_rules = CaptureRules::load(config.capture_rules_file.as_deref(), &config.http_ports)
+        .or_else(|e| {
+            log::warn!(
+                "Failed to load capture rules: {:#}. Using default rules on HTTP_CAPTURE_PORTS.",
+                e
+            );
+            CaptureRules::load(None, &config.http_ports)
+        })
+        .unwrap_or_else(|e| {
+            log::warn!("Invalid HTTP_CAPTURE_PORTS: {:#}. Using defaults.", e);
+            CaptureRules::default()
+        });
+
+    // Initialize network monitor for HTTP request capture. With the Postgres sink it inserts
+    // directly into the database; otherwise requests are buffered and written through the sink
+    let network_monitor = match &db {
+        Some(db) => NetworkMonitorService::with_database(docker_service.clone(), Arc::new(db.clone())),
+        None => NetworkMonitorService::new(docker_service.clone()),
+    };
+    let network_monitor = Arc::new(
+        network_monitor
+            .with_capture_rules(capture_rules)
+            .with_unmatched_request_storage(config.store_unmatched_requests)
+            .with_chunked_response_tracking(config.track_chunked_responses)
+            .with_capture_watchdog(config.capture_stall_timeout)
+            .with_container_ip_cache_ttl(config.container_ip_cache_ttl)
+            .with_captured_headers(config.captured_http_headers.clone()),
+    );
+
+    // Reload capture rules on SIGHUP without restarting the capture session
+    #[cfg(unix)]
+    {
+        let network_monitor = network_monitor.clone();
+        let rules_file = config.capture_rules_file.clone();
+        let http_ports = config.http_ports.clone();
+        tokio::spawn(async move {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut hangup = match signal(SignalKind::hangup()) {
+                Ok(s) => s,
+                Err(e) => {
+                    log::warn!("Failed to install SIGHUP handler: {}", e);
+                    return;
+                }
+            };
+            while hangup.recv().await.is_some() {
+                log::info!("Received SIGHUP, reloading capture rules");
+                if let Err(e) = handlers::reload_capture_rules(&network_monitor, rules_file.as_deref(), &http_ports) {
+                    log::warn!("Capture rules reload failed, keeping current rules: {:#}", e);
+                }
+            }
+        });
+    }
+
+    // Alerts are recorded in the database, so rules are only evaluated with the Postgres sink
+    let notifications = Arc::new(NotificationService::new(config.webhook_url.clone(), config.slack_webhook_url.clone()));
+    if notifications.is_enabled() {
+        log::info!("Alert notifications enabled");
+    }
+    let alert_service = db
+        .as_ref()
+        .map(|db| Arc::new(AlertService::new(db.clone()).with_notifications(notifications)));
 
     // Create and start worker service
-    let worker_service = WorkerService::new(docker_service, db, config.clone());
+    let worker_service = WorkerService::new(docker_service.clone(), sink, config.clone(), network_monitor.clone())
+        .with_query_service(db.as_ref().map(|db| Arc::new(QueryService::new(db.clone()))))
+        .with_alert_service(alert_service);
+    let schedule = worker_service.schedule();
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
     
     // Start the worker in a background task
-    tokio::spawn(async move {
-        worker_service.start().await;
+    let worker_task = tokio::spawn(async move {
+        worker_service.start(shutdown_rx).await;
+    });
+
+    let app_state = web::Data::new(handlers::WorkerState {
+        docker_service,
+        db: db.clone(),
+        network_monitor,
+        schedule,
+        capture_rules_file: config.capture_rules_file.clone(),
+        http_ports: config.http_ports.clone(),
+        admin_token: config.admin_token.clone(),
     });
 
     // Start a minimal HTTP server for health checks
-    HttpServer::new(move || {
+    let server = HttpServer::new(move || {
         App::new()
+            .app_data(app_state.clone())
             .route("/health", web::get().to(|| async { "OK" }))
+            .route("/ready", web::get().to(handlers::get_readiness))
+            .route("/status", web::get().to(handlers::get_status))
+            .route("/network/capture/stats", web::get().to(handlers::get_capture_stats))
+            .route("/admin/capture-rules", web::get().to(handlers::get_capture_rules))
+            .route("/admin/capture-rules/reload", web::post().to(handlers::reload_capture_rules_handler))
     })
     .bind(format!("{}:{}", config.server_host, config.server_port))?
-    .run()
-    .await
+    // Signals are handled below so the worker can flush its batches before the process exits
+    .disable_signals()
+    .run();
+
+    // On SIGTERM/SIGINT: stop collecting, flush batch buffers, then stop the HTTP server
+    let server_handle = server.handle();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        log::info!("Shutting down worker");
+        let _ = shutdown_tx.send(true);
+        if let Err(e) = worker_task.await {
+            log::error!("Worker task failed during shutdown: {}", e);
+        }
+        server_handle.stop(true).await;
+    });
+
+    server.await
+}
+
+/// Resolves on Ctrl+C (SIGINT) or, on Unix, SIGTERM (`docker stop`)
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(e) => {
+                log::warn!("Failed to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
 }
 
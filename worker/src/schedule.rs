@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Configured interval and recent run history of one periodic collector
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectorStatus {
+    /// None when the collector is disabled
+    pub interval_secs: Option<u64>,
+    pub runs: u64,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_duration_ms: Option<u64>,
+    /// The last cycle took longer than the interval (the next tick was skipped)
+    pub last_overran: bool,
+    pub overrun_count: u64,
+    pub last_error: Option<String>,
+}
+
+/// Start of one collection cycle, see [`CollectionSchedule::finish`]
+pub struct CollectionCycle {
+    started_at: DateTime<Utc>,
+    started: Instant,
+}
+
+impl CollectionCycle {
+    pub fn start() -> Self {
+        Self {
+            started_at: Utc::now(),
+            started: Instant::now(),
+        }
+    }
+}
+
+/// Per-collector schedule and last-run state, updated by the collection loops and
+/// reported on the worker's `/status` endpoint
+#[derive(Default)]
+pub struct CollectionSchedule {
+    collectors: RwLock<BTreeMap<&'static str, CollectorStatus>>,
+}
+
+impl CollectionSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a collector with its configured interval (None = disabled)
+    pub fn register(&self, name: &'static str, interval: Option<Duration>) {
+        self.collectors.write().unwrap().insert(name, CollectorStatus {
+            interval_secs: interval.map(|i| i.as_secs()),
+            runs: 0,
+            last_run_at: None,
+            last_success_at: None,
+            last_duration_ms: None,
+            last_overran: false,
+            overrun_count: 0,
+            last_error: None,
+        });
+    }
+
+    /// Record a finished cycle; `error` is None when it succeeded
+    pub fn finish(&self, name: &'static str, cycle: CollectionCycle, error: Option<String>) {
+        let elapsed = cycle.started.elapsed();
+        let mut collectors = self.collectors.write().unwrap();
+        let Some(status) = collectors.get_mut(name) else {
+            return;
+        };
+
+        let overran = status.interval_secs.is_some_and(|secs| elapsed > Duration::from_secs(secs));
+        status.runs += 1;
+        status.last_run_at = Some(cycle.started_at);
+        status.last_duration_ms = Some(elapsed.as_millis() as u64);
+        status.last_overran = overran;
+        if overran {
+            status.overrun_count += 1;
+        }
+        match error {
+            Some(error) => status.last_error = Some(error),
+            None => {
+                status.last_success_at = Some(cycle.started_at);
+                status.last_error = None;
+            }
+        }
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<&'static str, CollectorStatus> {
+        self.collectors.read().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cycle_lasting(elapsed: Duration) -> CollectionCycle {
+        CollectionCycle {
+            started_at: Utc::now(),
+            started: Instant::now() - elapsed,
+        }
+    }
+
+    #[test]
+    fn finish_ignores_unregistered_collectors() {
+        let schedule = CollectionSchedule::new();
+        schedule.finish("stats", CollectionCycle::start(), None);
+        assert!(schedule.snapshot().is_empty());
+    }
+
+    #[test]
+    fn failed_run_keeps_the_last_success() {
+        let schedule = CollectionSchedule::new();
+        schedule.register("stats", Some(Duration::from_secs(60)));
+
+        let cycle = CollectionCycle::start();
+        let succeeded_at = cycle.started_at;
+        schedule.finish("stats", cycle, None);
+        schedule.finish("stats", CollectionCycle::start(), Some("docker unreachable".to_string()));
+
+        let status = &schedule.snapshot()["stats"];
+        assert_eq!(status.runs, 2);
+        assert_eq!(status.last_success_at, Some(succeeded_at));
+        assert_eq!(status.last_error.as_deref(), Some("docker unreachable"));
+
+        schedule.finish("stats", CollectionCycle::start(), None);
+        let status = &schedule.snapshot()["stats"];
+        assert_eq!(status.runs, 3);
+        assert!(status.last_success_at >= Some(succeeded_at));
+        assert_eq!(status.last_error, None);
+    }
+
+    #[test]
+    fn cycles_longer_than_the_interval_count_as_overruns() {
+        let schedule = CollectionSchedule::new();
+        schedule.register("stats", Some(Duration::from_secs(1)));
+        schedule.register("images", None);
+
+        schedule.finish("stats", cycle_lasting(Duration::from_secs(2)), None);
+        schedule.finish("images", cycle_lasting(Duration::from_secs(2)), None);
+
+        let snapshot = schedule.snapshot();
+        assert!(snapshot["stats"].last_overran);
+        assert_eq!(snapshot["stats"].overrun_count, 1);
+        assert!(snapshot["stats"].last_duration_ms >= Some(2000));
+        assert!(!snapshot["images"].last_overran);
+        assert_eq!(snapshot["images"].overrun_count, 0);
+
+        schedule.finish("stats", CollectionCycle::start(), None);
+        let snapshot = schedule.snapshot();
+        assert!(!snapshot["stats"].last_overran);
+        assert_eq!(snapshot["stats"].overrun_count, 1);
+    }
+}
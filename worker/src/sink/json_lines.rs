@@ -0,0 +1,97 @@
+use super::MetricsSink;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use eyes_devine_shared::{ContainerEvent, ContainerInfo, ContainerStats, DiskUsage, HttpRequest, ImageInfo, ServiceMap, TotalStats};
+use serde::Serialize;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+/// Serializes each record as one JSON object per line: `{"type": "stats", "data": {...}}`
+/// Used for both the stdout sink and the file sink
+pub struct JsonLinesSink {
+    name: &'static str,
+    writer: Mutex<Box<dyn AsyncWrite + Send + Unpin>>,
+}
+
+#[derive(Serialize)]
+struct Record<'a, T> {
+    #[serde(rename = "type")]
+    record_type: &'a str,
+    data: &'a T,
+}
+
+impl JsonLinesSink {
+    pub fn stdout() -> Self {
+        Self {
+            name: "stdout",
+            writer: Mutex::new(Box::new(tokio::io::stdout())),
+        }
+    }
+
+    /// Append to the given file, creating it if needed
+    pub async fn file(path: &str) -> Result<Self> {
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .with_context(|| format!("Failed to open metrics sink file: {}", path))?;
+
+        Ok(Self {
+            name: "file",
+            writer: Mutex::new(Box::new(file)),
+        })
+    }
+
+    async fn write_records<T: Serialize>(&self, record_type: &str, items: &[T]) -> Result<()> {
+        let mut buf = Vec::new();
+        for item in items {
+            serde_json::to_writer(&mut buf, &Record { record_type, data: item })?;
+            buf.push(b'\n');
+        }
+
+        let mut writer = self.writer.lock().await;
+        writer.write_all(&buf).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MetricsSink for JsonLinesSink {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn write_stats(&self, stats: &[ContainerStats]) -> Result<()> {
+        self.write_records("stats", stats).await
+    }
+
+    async fn write_container_info(&self, containers: &[ContainerInfo]) -> Result<()> {
+        self.write_records("container_info", containers).await
+    }
+
+    async fn write_images(&self, images: &[ImageInfo]) -> Result<()> {
+        self.write_records("image", images).await
+    }
+
+    async fn write_http_requests(&self, requests: &[HttpRequest]) -> Result<()> {
+        self.write_records("http_request", requests).await
+    }
+
+    async fn write_container_events(&self, events: &[ContainerEvent]) -> Result<()> {
+        self.write_records("container_event", events).await
+    }
+
+    async fn write_service_map_snapshot(&self, service_map: &ServiceMap) -> Result<()> {
+        self.write_records("service_map", std::slice::from_ref(service_map)).await
+    }
+
+    async fn write_total_stats(&self, total_stats: &TotalStats) -> Result<()> {
+        self.write_records("total_stats", std::slice::from_ref(total_stats)).await
+    }
+
+    async fn write_disk_usage(&self, usage: &[DiskUsage]) -> Result<()> {
+        self.write_records("disk_usage", usage).await
+    }
+}
@@ -0,0 +1,32 @@
+mod json_lines;
+mod postgres;
+
+pub use json_lines::JsonLinesSink;
+pub use postgres::PostgresSink;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use eyes_devine_shared::{ContainerEvent, ContainerInfo, ContainerStats, DiskUsage, HttpRequest, ImageInfo, ServiceMap, TotalStats};
+
+/// Destination for the batches produced by the worker's collectors
+#[async_trait]
+pub trait MetricsSink: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    async fn write_stats(&self, stats: &[ContainerStats]) -> Result<()>;
+
+    async fn write_container_info(&self, containers: &[ContainerInfo]) -> Result<()>;
+
+    async fn write_images(&self, images: &[ImageInfo]) -> Result<()>;
+
+    async fn write_http_requests(&self, requests: &[HttpRequest]) -> Result<()>;
+
+    async fn write_container_events(&self, events: &[ContainerEvent]) -> Result<()>;
+
+    async fn write_service_map_snapshot(&self, service_map: &ServiceMap) -> Result<()>;
+
+    async fn write_total_stats(&self, total_stats: &TotalStats) -> Result<()>;
+
+    async fn write_disk_usage(&self, usage: &[DiskUsage]) -> Result<()>;
+}
@@ -0,0 +1,493 @@
+use super::MetricsSink;
+use crate::entity::container_info;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Utc};
+use eyes_devine_services::{container_disk_usage, container_events, container_stats, container_status_history, docker_images, http_requests, image_versions, service_map_snapshots, total_stats};
+use eyes_devine_shared::{ContainerEvent, ContainerInfo, ContainerStats, DiskUsage, HttpRequest, ImageInfo, ServiceMap, TotalStats};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// What a status history row is recorded on: run state, exit code and health. The status
+/// text itself ("Up 5 minutes") changes on every tick
+type StatusKey = (Option<String>, Option<i32>, Option<String>);
+
+/// Writes batches into the TimescaleDB/Postgres tables read by the server
+pub struct PostgresSink {
+    db: DatabaseConnection,
+    /// Drop container_info rows of earlier containers that ran under the same name
+    collapse_container_info_by_name: bool,
+}
+
+impl PostgresSink {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            collapse_container_info_by_name: false,
+        }
+    }
+
+    /// Keep container_info only for the latest container per name, so job containers that
+    /// get a new id on every run don't accumulate rows. Stats and events keep every id
+    pub fn with_container_info_collapsed_by_name(mut self, enabled: bool) -> Self {
+        self.collapse_container_info_by_name = enabled;
+        self
+    }
+
+    fn status_key(container: &ContainerInfo) -> StatusKey {
+        (
+            container.state.map(|s| s.state.as_str().to_string()),
+            container.state.and_then(|s| s.exit_code),
+            container.state.and_then(|s| s.health).map(|h| h.as_str().to_string()),
+        )
+    }
+
+    /// Split a batch of listings into the latest listing per container (a batch can span
+    /// several status ticks; the upsert may touch each container only once) and the status
+    /// history rows to add. `last_status` holds the stored state per container; a container
+    /// gets a history row when it first appears or its state differs from the previous
+    /// listing, stamped with the time it was listed (`now` if unknown)
+    fn split_status_batch(
+        containers: &[ContainerInfo],
+        mut last_status: HashMap<String, StatusKey>,
+        now: DateTime<Utc>,
+    ) -> (Vec<&ContainerInfo>, Vec<container_status_history::ActiveModel>) {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+        let mut latest: Vec<&ContainerInfo> = Vec::new();
+        let mut latest_index: HashMap<&str, usize> = HashMap::new();
+        let mut history: Vec<container_status_history::ActiveModel> = Vec::new();
+        for container in containers {
+            let status = Self::status_key(container);
+            if last_status.get(&container.id) != Some(&status) {
+                let observed_at = container.collected_at.unwrap_or(now);
+                history.push(container_status_history::ActiveModel {
+                    container_id: Set(container.id.clone()),
+                    container_name: Set(container.name.clone()),
+                    status: Set(container.status.clone()),
+                    state: Set(status.0.clone()),
+                    exit_code: Set(status.1),
+                    health: Set(status.2.clone()),
+                    timestamp: Set(observed_at.with_timezone(&fixed_offset)),
+                    ..Default::default()
+                });
+                last_status.insert(container.id.clone(), status);
+            }
+
+            match latest_index.get(container.id.as_str()) {
+                Some(&i) => latest[i] = container,
+                None => {
+                    latest_index.insert(&container.id, latest.len());
+                    latest.push(container);
+                }
+            }
+        }
+
+        (latest, history)
+    }
+}
+
+#[async_trait]
+impl MetricsSink for PostgresSink {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn write_stats(&self, stats: &[ContainerStats]) -> Result<()> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        let active_models: Vec<container_stats::ActiveModel> = stats
+            .iter()
+            .map(|stat| {
+                let timestamp = stat.timestamp.with_timezone(&fixed_offset);
+                container_stats::ActiveModel {
+                    container_id: Set(stat.container_id.clone()),
+                    container_name: Set(stat.container_name.clone()),
+                    cpu_usage_percent: Set(stat.cpu_usage_percent),
+                    memory_usage_bytes: Set(stat.memory_usage_bytes as i64),
+                    memory_limit_bytes: Set(stat.memory_limit_bytes as i64),
+                    memory_usage_percent: Set(stat.memory_usage_percent),
+                    memory_cache_bytes: Set(Some(stat.memory_cache_bytes as i64)),
+                    memory_swap_bytes: Set(Some(stat.memory_swap_bytes as i64)),
+                    network_rx_bytes: Set(stat.network_rx_bytes as i64),
+                    network_tx_bytes: Set(stat.network_tx_bytes as i64),
+                    block_read_bytes: Set(stat.block_read_bytes as i64),
+                    block_write_bytes: Set(stat.block_write_bytes as i64),
+                    per_cpu_usage_percent: Set(Some(json!(stat.per_cpu_usage_percent))),
+                    pids_current: Set(Some(stat.pids_current as i64)),
+                    pids_limit: Set(Some(stat.pids_limit as i64)),
+                    timestamp: Set(timestamp),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        container_stats::Entity::insert_many(active_models)
+            .exec(&self.db)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to batch insert stats: {}", e))?;
+
+        Ok(())
+    }
+
+    async fn write_container_info(&self, containers: &[ContainerInfo]) -> Result<()> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+        let now = Utc::now();
+
+        let ids: Vec<&str> = containers.iter().map(|c| c.id.as_str()).collect();
+
+        let txn = self.db.begin().await?;
+
+        // Compare against the stored row (or the previous tick in this batch) so history
+        // only gets a row when a container appears or its state changes
+        let last_status: HashMap<String, StatusKey> = container_info::Entity::find()
+            .filter(container_info::Column::ContainerId.is_in(ids.clone()))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|row| (row.container_id, (row.state, row.exit_code, row.health)))
+            .collect();
+        let (latest, history) = Self::split_status_batch(containers, last_status, now);
+
+        let active_models: Vec<container_info::ActiveModel> = latest
+            .into_iter()
+            .map(|container| {
+                let created = container.created.map(|dt| dt.with_timezone(&fixed_offset));
+                container_info::ActiveModel {
+                    container_id: Set(container.id.clone()),
+                    container_name: Set(container.name.clone()),
+                    image: Set(container.image.clone()),
+                    status: Set(container.status.clone()),
+                    created: Set(created),
+                    started_at: Set(container.started_at.map(|dt| dt.with_timezone(&fixed_offset))),
+                    restart_count: Set(container.restart_count),
+                    state: Set(container.state.map(|s| s.state.as_str().to_string())),
+                    exit_code: Set(container.state.and_then(|s| s.exit_code)),
+                    health: Set(container.health.clone()),
+                    labels: Set(Some(json!(container.labels))),
+                    collected_at: Set(container.collected_at.unwrap_or(now).with_timezone(&fixed_offset)),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        container_info::Entity::insert_many(active_models)
+            .on_conflict(
+                OnConflict::column(container_info::Column::ContainerId)
+                    .update_columns([
+                        container_info::Column::ContainerName,
+                        container_info::Column::Image,
+                        container_info::Column::Status,
+                        container_info::Column::Created,
+                        container_info::Column::StartedAt,
+                        container_info::Column::RestartCount,
+                        container_info::Column::State,
+                        container_info::Column::ExitCode,
+                        container_info::Column::Health,
+                        container_info::Column::Labels,
+                        container_info::Column::CollectedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&txn)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to upsert container info: {}", e))?;
+
+        if !history.is_empty() {
+            container_status_history::Entity::insert_many(history)
+                .exec(&txn)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to insert container status history: {}", e))?;
+        }
+
+        if self.collapse_container_info_by_name {
+            let names: Vec<&str> = containers.iter().map(|c| c.name.as_str()).collect();
+            container_info::Entity::delete_many()
+                .filter(container_info::Column::ContainerName.is_in(names))
+                .filter(container_info::Column::ContainerId.is_not_in(ids))
+                .exec(&txn)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to collapse container info by name: {}", e))?;
+        }
+
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn write_images(&self, images: &[ImageInfo]) -> Result<()> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+        let now = Utc::now().with_timezone(&fixed_offset);
+
+        for image in images {
+            // First, try to update existing image
+            let existing = docker_images::Entity::find()
+                .filter(docker_images::Column::ImageId.eq(&image.id))
+                .one(&self.db)
+                .await?;
+
+            if let Some(existing_model) = existing {
+                // Update existing image
+                let mut active_model: docker_images::ActiveModel = existing_model.into();
+                active_model.repo_tags = Set(json!(image.repo_tags));
+                active_model.size_bytes = Set(image.size as i64);
+                active_model.architecture = Set(image.architecture.clone());
+                active_model.os = Set(image.os.clone());
+                active_model.created_at = Set(image.created.map(|dt| dt.with_timezone(&fixed_offset)));
+                active_model.last_seen = Set(now);
+                active_model.update(&self.db).await?;
+
+                // Also insert into image_versions for history
+                let version_model = image_versions::ActiveModel {
+                    image_id: Set(image.id.clone()),
+                    repo_tags: Set(json!(image.repo_tags)),
+                    size_bytes: Set(image.size as i64),
+                    timestamp: Set(now),
+                    ..Default::default()
+                };
+                image_versions::Entity::insert(version_model).exec(&self.db).await?;
+            } else {
+                // Insert new image
+                let image_model = docker_images::ActiveModel {
+                    image_id: Set(image.id.clone()),
+                    repo_tags: Set(json!(image.repo_tags)),
+                    size_bytes: Set(image.size as i64),
+                    architecture: Set(image.architecture.clone()),
+                    os: Set(image.os.clone()),
+                    created_at: Set(image.created.map(|dt| dt.with_timezone(&fixed_offset))),
+                    first_seen: Set(now),
+                    last_seen: Set(now),
+                    ..Default::default()
+                };
+                docker_images::Entity::insert(image_model).exec(&self.db).await?;
+
+                // Insert into image_versions
+                let version_model = image_versions::ActiveModel {
+                    image_id: Set(image.id.clone()),
+                    repo_tags: Set(json!(image.repo_tags)),
+                    size_bytes: Set(image.size as i64),
+                    timestamp: Set(now),
+                    ..Default::default()
+                };
+                image_versions::Entity::insert(version_model).exec(&self.db).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn write_http_requests(&self, requests: &[HttpRequest]) -> Result<()> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        log::debug!("🔍 Preparing {} HTTP requests for database insertion", requests.len());
+
+        let active_models: Vec<http_requests::ActiveModel> = requests
+            .iter()
+            .map(|req| {
+                let timestamp = req.timestamp.with_timezone(&fixed_offset);
+                http_requests::ActiveModel {
+                    container_id: Set(req.container_id.clone()),
+                    container_name: Set(req.container_name.clone()),
+                    endpoint: Set(req.endpoint.clone()),
+                    method: Set(req.method.clone()),
+                    http_status: Set(req.http_status as i16),
+                    response_time_ms: Set(req.response_time_ms),
+                    headers: Set(req.headers.as_ref().map(|headers| json!(headers))),
+                    client_container_id: Set(req.client_container_id.clone()),
+                    timestamp: Set(timestamp),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        log::debug!("📝 Executing database insert for {} HTTP request records", active_models.len());
+        
+        http_requests::Entity::insert_many(active_models)
+            .exec(&self.db)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to batch insert HTTP requests: {}", e))?;
+
+        log::debug!("✓ Database insert completed successfully for {} HTTP requests", requests.len());
+        Ok(())
+    }
+
+    async fn write_container_events(&self, events: &[ContainerEvent]) -> Result<()> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        let active_models: Vec<container_events::ActiveModel> = events
+            .iter()
+            .map(|event| container_events::ActiveModel {
+                container_id: Set(event.container_id.clone()),
+                container_name: Set(event.container_name.clone()),
+                event_type: Set(event.event_type.clone()),
+                exit_code: Set(event.exit_code),
+                oom_killed: Set(event.oom_killed),
+                finished_at: Set(event.finished_at.map(|dt| dt.with_timezone(&fixed_offset))),
+                timestamp: Set(event.timestamp.with_timezone(&fixed_offset)),
+                ..Default::default()
+            })
+            .collect();
+
+        container_events::Entity::insert_many(active_models)
+            .exec(&self.db)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to insert container events: {}", e))?;
+
+        Ok(())
+    }
+
+    async fn write_service_map_snapshot(&self, service_map: &ServiceMap) -> Result<()> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        service_map_snapshots::ActiveModel {
+            snapshot: Set(serde_json::to_value(service_map)?),
+            timestamp: Set(service_map.timestamp.with_timezone(&fixed_offset)),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to insert service map snapshot: {}", e))?;
+
+        Ok(())
+    }
+
+    async fn write_total_stats(&self, stats: &TotalStats) -> Result<()> {
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        total_stats::ActiveModel {
+            total_containers: Set(stats.total_containers as i32),
+            running_containers: Set(stats.running_containers as i32),
+            total_cpu_usage_percent: Set(stats.total_cpu_usage_percent),
+            total_memory_usage_bytes: Set(stats.total_memory_usage_bytes as i64),
+            total_memory_limit_bytes: Set(stats.total_memory_limit_bytes as i64),
+            total_memory_usage_percent: Set(stats.total_memory_usage_percent),
+            total_network_rx_bytes: Set(stats.total_network_rx_bytes as i64),
+            total_network_tx_bytes: Set(stats.total_network_tx_bytes as i64),
+            total_block_read_bytes: Set(stats.total_block_read_bytes as i64),
+            total_block_write_bytes: Set(stats.total_block_write_bytes as i64),
+            timestamp: Set(stats.timestamp.with_timezone(&fixed_offset)),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to insert total stats: {}", e))?;
+
+        Ok(())
+    }
+
+    async fn write_disk_usage(&self, usage: &[DiskUsage]) -> Result<()> {
+        if usage.is_empty() {
+            return Ok(());
+        }
+        let fixed_offset = FixedOffset::east_opt(0).unwrap();
+
+        let active_models: Vec<container_disk_usage::ActiveModel> = usage
+            .iter()
+            .map(|usage| container_disk_usage::ActiveModel {
+                container_id: Set(usage.container_id.clone()),
+                container_name: Set(usage.container_name.clone()),
+                size_rw_bytes: Set(usage.size_rw_bytes.map(|size| size as i64)),
+                size_root_fs_bytes: Set(usage.size_root_fs_bytes.map(|size| size as i64)),
+                timestamp: Set(usage.timestamp.with_timezone(&fixed_offset)),
+                ..Default::default()
+            })
+            .collect();
+
+        container_disk_usage::Entity::insert_many(active_models)
+            .exec(&self.db)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to insert disk usage: {}", e))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use eyes_devine_shared::{ContainerRunState, ContainerState};
+    use std::collections::BTreeMap;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    fn listing(id: &str, state: ContainerRunState, exit_code: Option<i32>, listed_at: i64) -> ContainerInfo {
+        ContainerInfo {
+            id: id.to_string(),
+            name: format!("{}-name", id),
+            image: "nginx".to_string(),
+            status: format!("{:?}", state),
+            created: None,
+            started_at: None,
+            restart_count: None,
+            state: Some(ContainerState { state, exit_code, health: None }),
+            health: None,
+            labels: BTreeMap::new(),
+            compose_project: None,
+            compose_service: None,
+            collected_at: Some(at(listed_at)),
+        }
+    }
+
+    fn history_rows(history: &[container_status_history::ActiveModel]) -> Vec<(String, Option<String>, DateTime<Utc>)> {
+        history
+            .iter()
+            .map(|row| {
+                (
+                    row.container_id.clone().unwrap(),
+                    row.state.clone().unwrap(),
+                    row.timestamp.clone().unwrap().with_timezone(&Utc),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn first_seen_container_gets_a_history_row() {
+        let containers = [listing("a", ContainerRunState::Running, None, 5)];
+
+        let (latest, history) = PostgresSink::split_status_batch(&containers, HashMap::new(), at(60));
+
+        assert_eq!(latest.len(), 1);
+        assert_eq!(history_rows(&history), vec![("a".to_string(), Some("running".to_string()), at(5))]);
+    }
+
+    #[test]
+    fn unchanged_container_gets_no_history_row() {
+        let containers = [listing("a", ContainerRunState::Running, None, 5)];
+        let stored = HashMap::from([("a".to_string(), PostgresSink::status_key(&containers[0]))]);
+
+        let (latest, history) = PostgresSink::split_status_batch(&containers, stored, at(60));
+
+        assert_eq!(latest.len(), 1);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn several_changes_in_one_batch_keep_their_own_times() {
+        let containers = [
+            listing("a", ContainerRunState::Running, None, 0),
+            listing("a", ContainerRunState::Exited, Some(1), 10),
+            listing("a", ContainerRunState::Exited, Some(1), 20),
+            listing("a", ContainerRunState::Running, None, 30),
+        ];
+        let stored = HashMap::from([("a".to_string(), PostgresSink::status_key(&containers[0]))]);
+
+        let (latest, history) = PostgresSink::split_status_batch(&containers, stored, at(60));
+
+        assert_eq!(
+            history_rows(&history),
+            vec![
+                ("a".to_string(), Some("exited".to_string()), at(10)),
+                ("a".to_string(), Some("running".to_string()), at(30)),
+            ]
+        );
+        assert_eq!(history[0].exit_code.clone().unwrap(), Some(1));
+        // The upsert gets the last listing only
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].collected_at, Some(at(30)));
+    }
+}
@@ -1,18 +1,32 @@
-use eyes_devine_services::{DockerService, NetworkMonitorService};
+use eyes_devine_services::{AlertService, DockerService, NetworkMonitorService, QueryService, ServiceMapService};
 use eyes_devine_shared::{ContainerInfo, ContainerStats, ImageInfo, HttpRequest};
-use sea_orm::{DatabaseConnection, EntityTrait, ColumnTrait, QueryFilter, ActiveModelTrait};
 use std::sync::Arc;
-use std::collections::HashMap;
-use tokio::sync::Mutex;
-use chrono::{Utc, FixedOffset};
+use std::collections::{HashMap, HashSet};
+use futures::future::BoxFuture;
+use futures::stream::{self, StreamExt};
+use std::time::Duration;
+use tokio::sync::{Mutex, watch};
 use crate::config::Config;
-use crate::entity::container_info;
-use eyes_devine_services::{container_stats, docker_images, image_versions, http_requests};
+use crate::schedule::{CollectionCycle, CollectionSchedule};
+use crate::sink::MetricsSink;
+
+/// Waits between attempts of a failed batch write before the rows go back to the buffer
+const BATCH_WRITE_RETRY_DELAYS: [Duration; 3] = [
+    Duration::from_millis(100),
+    Duration::from_millis(400),
+    Duration::from_millis(1600),
+];
 
 pub struct WorkerService {
     docker_service: Arc<DockerService>,
-    db: DatabaseConnection,
+    sink: Arc<dyn MetricsSink>,
     config: Config,
+    network_monitor: Arc<NetworkMonitorService>,
+    schedule: Arc<CollectionSchedule>,
+    /// Lets service map snapshots include edges from captured HTTP requests; None without a database
+    query_service: Option<Arc<QueryService>>,
+    /// Evaluates alert rules against each stats tick; None without a database
+    alert_service: Option<Arc<AlertService>>,
 }
 
 // Batch buffers for collecting data before inserting
@@ -26,31 +40,70 @@ struct BatchBuffers {
 impl WorkerService {
     pub fn new(
         docker_service: Arc<DockerService>,
-        db: DatabaseConnection,
+        sink: Arc<dyn MetricsSink>,
         config: Config,
+        network_monitor: Arc<NetworkMonitorService>,
     ) -> Self {
+        let schedule = CollectionSchedule::new();
+        schedule.register("stats", Some(config.stats_collection_interval));
+        schedule.register("status", Some(config.status_collection_interval));
+        schedule.register("images", Some(config.image_collection_interval));
+        schedule.register("http_requests", Some(config.http_requests_collection_interval));
+        schedule.register("service_map", Some(config.service_map_snapshot_interval));
+        schedule.register("total_stats", config.total_stats_interval);
+        schedule.register("disk_usage", config.disk_usage_interval);
+
         Self {
             docker_service,
-            db,
+            sink,
             config,
+            network_monitor,
+            schedule: Arc::new(schedule),
+            query_service: None,
+            alert_service: None,
         }
     }
 
-    pub async fn start(&self) {
+    /// Read captured HTTP requests back when building service map snapshots
+    pub fn with_query_service(mut self, query_service: Option<Arc<QueryService>>) -> Self {
+        self.query_service = query_service;
+        self
+    }
+
+    /// Evaluate alert rules on every stats tick
+    pub fn with_alert_service(mut self, alert_service: Option<Arc<AlertService>>) -> Self {
+        self.alert_service = alert_service;
+        self
+    }
+
+    /// Collector intervals and last-run times, shared with the status endpoint
+    pub fn schedule(&self) -> Arc<CollectionSchedule> {
+        Arc::clone(&self.schedule)
+    }
+
+    /// Run the collectors until `shutdown` turns true (or its sender is dropped), then
+    /// flush what is left in the batch buffers to the sink and return
+    pub async fn start(&self, shutdown: watch::Receiver<bool>) {
         log::info!("Worker service starting with configuration:");
+        log::info!("  - Metrics sink: {}", self.sink.name());
         log::info!("  - Stats collection interval: {:?}", self.config.stats_collection_interval);
         log::info!("  - Status collection interval: {:?}", self.config.status_collection_interval);
         log::info!("  - Image collection interval: {:?}", self.config.image_collection_interval);
         log::info!("  - HTTP requests collection interval: {:?}", self.config.http_requests_collection_interval);
+        log::info!("  - Service map snapshot interval: {:?}", self.config.service_map_snapshot_interval);
+        log::info!("  - Total stats snapshot interval: {:?}", self.config.total_stats_interval);
+        log::info!("  - Disk usage collection interval: {:?}", self.config.disk_usage_interval);
+        log::info!("  - Image inspect concurrency: {}", self.config.image_inspect_concurrency);
+        log::info!("  - Alert rules: {}", if self.alert_service.is_some() { "evaluated every stats tick" } else { "disabled (no database)" });
         log::info!("  - Batch size: {}", self.config.batch_size);
         log::info!("  - Batch timeout: {:?}", self.config.batch_timeout);
+        log::info!("  - Batch size/timeout per stream: stats {}/{:?}, status {}/{:?}, images {}/{:?}, HTTP {}/{:?}",
+            self.config.stats_batch_size, self.config.stats_batch_timeout,
+            self.config.status_batch_size, self.config.status_batch_timeout,
+            self.config.image_batch_size, self.config.image_batch_timeout,
+            self.config.http_batch_size, self.config.http_batch_timeout);
 
-        // Initialize network monitor for HTTP request capture with database connection for direct insertion
-        let db_for_monitor = Arc::new(self.db.clone());
-        let network_monitor = Arc::new(NetworkMonitorService::with_database(
-            self.docker_service.clone(),
-            db_for_monitor,
-        ));
+        let network_monitor = Arc::clone(&self.network_monitor);
         let network_monitor_for_start = Arc::clone(&network_monitor);
         
         // Start network monitoring in background (non-blocking)
@@ -98,30 +151,60 @@ impl WorkerService {
         let status_task = self.start_status_collection(buffers.container_info.clone());
         let image_task = self.start_image_collection(buffers.images.clone());
         let http_requests_task = self.start_http_requests_collection(buffers.http_requests.clone(), network_monitor.clone());
+        let service_map_task = self.start_service_map_snapshots();
+        let total_stats_task = self.start_total_stats_snapshots();
+        let disk_usage_task = self.start_disk_usage_collection();
+
+        // Spawn batch insertion tasks; each flushes its buffer and returns on shutdown
+        let stats_insert_task = self.start_batch_insert_stats(buffers.stats.clone(), shutdown.clone());
+        let status_insert_task = self.start_batch_insert_container_info(buffers.container_info.clone(), shutdown.clone());
+        let image_insert_task = self.start_batch_insert_images(buffers.images.clone(), shutdown.clone());
+        let http_requests_insert_task = self.start_batch_insert_http_requests(buffers.http_requests.clone(), shutdown.clone());
+
+        // Collection tasks run until shutdown (they never exit on their own)
+        let mut collection_shutdown = shutdown;
+        let collection = async {
+            tokio::select! {
+                _ = stats_task => log::error!("Stats collection task exited"),
+                _ = status_task => log::error!("Status collection task exited"),
+                _ = image_task => log::error!("Image collection task exited"),
+                _ = http_requests_task => log::error!("HTTP requests collection task exited"),
+                _ = service_map_task => log::error!("Service map snapshot task exited"),
+                _ = total_stats_task => log::error!("Total stats snapshot task exited"),
+                _ = disk_usage_task => log::error!("Disk usage collection task exited"),
+                _ = collection_shutdown.wait_for(|stop| *stop) => log::info!("Shutdown requested, stopping collection and flushing batch buffers"),
+            }
+        };
 
-        // Spawn batch insertion tasks
-        let stats_insert_task = self.start_batch_insert_stats(buffers.stats.clone());
-        let status_insert_task = self.start_batch_insert_container_info(buffers.container_info.clone());
-        let image_insert_task = self.start_batch_insert_images(buffers.images.clone());
-        let http_requests_insert_task = self.start_batch_insert_http_requests(buffers.http_requests.clone());
+        tokio::join!(
+            collection,
+            stats_insert_task,
+            status_insert_task,
+            image_insert_task,
+            http_requests_insert_task,
+        );
+        log::info!("Worker service stopped");
+    }
 
-        // Wait for all tasks (they run forever)
+    /// Sleep for one batch timeout. Returns true as soon as shutdown is requested
+    /// (or the shutdown sender is gone) so the caller can flush and stop
+    async fn wait_for_batch(batch_timeout: Duration, shutdown: &mut watch::Receiver<bool>) -> bool {
         tokio::select! {
-            _ = stats_task => log::error!("Stats collection task exited"),
-            _ = status_task => log::error!("Status collection task exited"),
-            _ = image_task => log::error!("Image collection task exited"),
-            _ = http_requests_task => log::error!("HTTP requests collection task exited"),
-            _ = stats_insert_task => log::error!("Stats batch insert task exited"),
-            _ = status_insert_task => log::error!("Status batch insert task exited"),
-            _ = image_insert_task => log::error!("Image batch insert task exited"),
-            _ = http_requests_insert_task => log::error!("HTTP requests batch insert task exited"),
+            _ = tokio::time::sleep(batch_timeout) => *shutdown.borrow(),
+            changed = shutdown.changed() => changed.is_err() || *shutdown.borrow(),
         }
     }
 
     // Stats collection task - collects container stats periodically
+    // With STATS_SAMPLE_EVERY > 1, only important/busy containers are collected on every tick
     async fn start_stats_collection(&self, buffer: Arc<Mutex<Vec<ContainerStats>>>) {
         let docker_service = self.docker_service.clone();
         let interval = self.config.stats_collection_interval;
+        let mut sampler = StatsSampler::new(&self.config);
+
+        if self.config.stats_sample_every > 1 {
+            log::info!("  - Stats sampling: every {} ticks for non-important containers", self.config.stats_sample_every);
+        }
 
         let mut ticker = tokio::time::interval(interval);
         ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
@@ -129,24 +212,39 @@ impl WorkerService {
         loop {
             ticker.tick().await;
 
-            match Self::collect_container_stats(&docker_service).await {
-                Ok(stats) => {
+            let cycle = CollectionCycle::start();
+            let error = match Self::collect_container_stats(&docker_service, &mut sampler).await {
+                Ok((stats, running)) => {
+                    if let Some(alert_service) = &self.alert_service
+                        && let Err(e) = alert_service.evaluate(&stats, &running).await
+                    {
+                        log::warn!("Failed to evaluate alert rules: {}", e);
+                    }
                     let stats_len = stats.len();
                     let mut buf = buffer.lock().await;
                     buf.extend(stats);
                     log::debug!("Collected {} stats, buffer size: {}", stats_len, buf.len());
+                    None
                 }
                 Err(e) => {
                     log::warn!("Failed to collect container stats: {}", e);
+                    Some(e.to_string())
                 }
-            }
+            };
+            self.schedule.finish("stats", cycle, error);
         }
     }
 
     // Status collection task - collects container status periodically
+    // Also records a "died" event with the exit code when a running container stops
     async fn start_status_collection(&self, buffer: Arc<Mutex<Vec<ContainerInfo>>>) {
         let docker_service = self.docker_service.clone();
+        let sink = self.sink.clone();
         let interval = self.config.status_collection_interval;
+        let record_exit_events = self.config.record_exit_events;
+
+        // Running state per container as of the previous tick
+        let mut was_running: HashMap<String, bool> = HashMap::new();
 
         let mut ticker = tokio::time::interval(interval);
         ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
@@ -154,16 +252,56 @@ impl WorkerService {
         loop {
             ticker.tick().await;
 
-            match docker_service.list_containers().await {
-                Ok(containers) => {
+            let cycle = CollectionCycle::start();
+            let error = match docker_service.list_containers().await {
+                Ok(mut containers) => {
+                    // The container list doesn't carry start times or structured state; inspect for them
+                    for container in &mut containers {
+                        if let Err(e) = docker_service.fill_inspect_state(container).await {
+                            log::debug!("Failed to inspect state of {}: {}", container.name, e);
+                        }
+                    }
+
+                    if record_exit_events {
+                        let mut events = Vec::new();
+                        for container in &containers {
+                            let is_running = container.is_running();
+                            let previously_running = was_running.insert(container.id.clone(), is_running);
+
+                            if previously_running == Some(true) && !is_running {
+                                events.push(
+                                    docker_service
+                                        .get_container_exit_event(&container.id, &container.name, &container.status)
+                                        .await,
+                                );
+                            }
+                        }
+                        // Forget containers that no longer exist
+                        was_running.retain(|id, _| containers.iter().any(|c| &c.id == id));
+
+                        if !events.is_empty() {
+                            for event in &events {
+                                log::info!("Container {} ({}) died with exit code {:?} (oom_killed: {:?})",
+                                    event.container_name, &event.container_id[..12.min(event.container_id.len())],
+                                    event.exit_code, event.oom_killed);
+                            }
+                            if let Err(e) = sink.write_container_events(&events).await {
+                                log::error!("Failed to insert container events: {}", e);
+                            }
+                        }
+                    }
+
                     let mut buf = buffer.lock().await;
                     buf.extend(containers);
                     log::debug!("Collected container status, buffer size: {}", buf.len());
+                    None
                 }
                 Err(e) => {
                     log::warn!("Failed to collect container status: {}", e);
+                    Some(e.to_string())
                 }
-            }
+            };
+            self.schedule.finish("status", cycle, error);
         }
     }
 
@@ -171,6 +309,7 @@ impl WorkerService {
     async fn start_image_collection(&self, buffer: Arc<Mutex<Vec<ImageInfo>>>) {
         let docker_service = self.docker_service.clone();
         let interval = self.config.image_collection_interval;
+        let concurrency = self.config.image_inspect_concurrency;
 
         let mut ticker = tokio::time::interval(interval);
         ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
@@ -178,133 +317,270 @@ impl WorkerService {
         loop {
             ticker.tick().await;
 
-            match Self::collect_images(&docker_service).await {
+            let cycle = CollectionCycle::start();
+            let started = std::time::Instant::now();
+            let result = Self::collect_images(&docker_service, concurrency).await;
+            let elapsed = started.elapsed();
+            if elapsed > interval {
+                log::warn!("Image collection took {:?}, longer than its {:?} interval; consider raising IMAGE_INSPECT_CONCURRENCY",
+                    elapsed, interval);
+            }
+
+            let error = match result {
                 Ok(images) => {
                     let images_len = images.len();
                     let mut buf = buffer.lock().await;
                     buf.extend(images);
                     log::debug!("Collected {} images, buffer size: {}", images_len, buf.len());
+                    None
                 }
                 Err(e) => {
                     log::warn!("Failed to collect images: {}", e);
+                    Some(e.to_string())
                 }
-            }
+            };
+            self.schedule.finish("images", cycle, error);
         }
     }
 
-    // Batch insert task for stats
-    async fn start_batch_insert_stats(&self, buffer: Arc<Mutex<Vec<ContainerStats>>>) {
-        let db = self.db.clone();
-        let batch_size = self.config.batch_size;
-        let batch_timeout = self.config.batch_timeout;
+    // Service map snapshot task - stores the detected service map periodically so it can be diffed over time
+    async fn start_service_map_snapshots(&self) {
+        let service_map_service = ServiceMapService::new(self.docker_service.clone(), self.query_service.clone());
+        let sink = self.sink.clone();
+        let interval = self.config.service_map_snapshot_interval;
 
-        loop {
-            tokio::time::sleep(batch_timeout).await;
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
-            let mut buf = buffer.lock().await;
-            if buf.is_empty() {
-                continue;
-            }
+        loop {
+            ticker.tick().await;
 
-            // Take up to batch_size items
-            let to_insert: Vec<ContainerStats> = if buf.len() > batch_size {
-                buf.drain(..batch_size).collect()
-            } else {
-                buf.drain(..).collect()
+            let cycle = CollectionCycle::start();
+            let error = match service_map_service.generate_service_map().await {
+                Ok(service_map) => {
+                    if let Err(e) = sink.write_service_map_snapshot(&service_map).await {
+                        log::error!("Failed to store service map snapshot: {}", e);
+                        Some(e.to_string())
+                    } else {
+                        log::debug!("Stored service map snapshot ({} nodes, {} edges)",
+                            service_map.nodes.len(), service_map.edges.len());
+                        None
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Failed to generate service map snapshot: {}", e);
+                    Some(e.to_string())
+                }
             };
+            self.schedule.finish("service_map", cycle, error);
+        }
+    }
 
-            drop(buf); // Release lock before DB operation
+    // Total stats snapshot task - stores fleet totals as their own series so the fleet history
+    // and current totals don't have to be aggregated across containers on every query
+    async fn start_total_stats_snapshots(&self) {
+        let Some(interval) = self.config.total_stats_interval else {
+            // Disabled: park forever so the select! in `start` doesn't treat it as exited
+            return std::future::pending().await;
+        };
+        let docker_service = self.docker_service.clone();
+        let sink = self.sink.clone();
 
-            if !to_insert.is_empty() {
-                if let Err(e) = Self::batch_insert_stats(&db, &to_insert).await {
-                    log::error!("Failed to batch insert stats: {}", e);
-                    // Optionally: re-add to buffer or queue for retry
-                } else {
-                    log::info!("Successfully inserted {} stats", to_insert.len());
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+
+            let cycle = CollectionCycle::start();
+            let error = match docker_service.get_total_stats().await {
+                Ok(total_stats) => {
+                    if let Err(e) = sink.write_total_stats(&total_stats).await {
+                        log::error!("Failed to store total stats snapshot: {}", e);
+                        Some(e.to_string())
+                    } else {
+                        log::debug!("Stored total stats snapshot ({} running / {} containers)",
+                            total_stats.running_containers, total_stats.total_containers);
+                        None
+                    }
                 }
-            }
+                Err(e) => {
+                    log::warn!("Failed to compute total stats: {}", e);
+                    Some(e.to_string())
+                }
+            };
+            self.schedule.finish("total_stats", cycle, error);
         }
     }
 
-    // Batch insert task for container info
-    async fn start_batch_insert_container_info(&self, buffer: Arc<Mutex<Vec<ContainerInfo>>>) {
-        let db = self.db.clone();
-        let batch_size = self.config.batch_size;
-        let batch_timeout = self.config.batch_timeout;
+    // Disk usage task - samples writable-layer and root filesystem sizes on its own slow interval,
+    // written straight to the sink (one row per container every few minutes needs no batching)
+    async fn start_disk_usage_collection(&self) {
+        let Some(interval) = self.config.disk_usage_interval else {
+            // Disabled: park forever so the select! in `start` doesn't treat it as exited
+            return std::future::pending().await;
+        };
+        let docker_service = self.docker_service.clone();
+        let sink = self.sink.clone();
 
-        loop {
-            tokio::time::sleep(batch_timeout).await;
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
-            let mut buf = buffer.lock().await;
-            if buf.is_empty() {
-                continue;
-            }
+        loop {
+            ticker.tick().await;
 
-            let to_insert: Vec<ContainerInfo> = if buf.len() > batch_size {
-                buf.drain(..batch_size).collect()
-            } else {
-                buf.drain(..).collect()
+            let cycle = CollectionCycle::start();
+            let error = match docker_service.get_disk_usage().await {
+                Ok(usage) => {
+                    if let Err(e) = sink.write_disk_usage(&usage).await {
+                        log::error!("Failed to store disk usage: {}", e);
+                        Some(e.to_string())
+                    } else {
+                        log::debug!("Stored disk usage for {} containers", usage.len());
+                        None
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Failed to collect disk usage: {}", e);
+                    Some(e.to_string())
+                }
             };
+            self.schedule.finish("disk_usage", cycle, error);
+        }
+    }
 
-            drop(buf);
+    // Batch insert task for stats
+    async fn start_batch_insert_stats(&self, buffer: Arc<Mutex<Vec<ContainerStats>>>, shutdown: watch::Receiver<bool>) {
+        let (batch_size, batch_timeout) = (self.config.stats_batch_size, self.config.stats_batch_timeout);
+        self.run_batch_writer(buffer, batch_size, batch_timeout, shutdown, "stats", |sink, rows| sink.write_stats(rows)).await;
+    }
 
-            if !to_insert.is_empty() {
-                if let Err(e) = Self::batch_insert_container_info(&db, &to_insert).await {
-                    log::error!("Failed to batch insert container info: {}", e);
-                } else {
-                    log::info!("Successfully inserted {} container info records", to_insert.len());
-                }
-            }
-        }
+    // Batch insert task for container info
+    async fn start_batch_insert_container_info(&self, buffer: Arc<Mutex<Vec<ContainerInfo>>>, shutdown: watch::Receiver<bool>) {
+        let (batch_size, batch_timeout) = (self.config.status_batch_size, self.config.status_batch_timeout);
+        self.run_batch_writer(buffer, batch_size, batch_timeout, shutdown, "container info", |sink, rows| sink.write_container_info(rows)).await;
     }
 
     // Batch insert task for images
-    async fn start_batch_insert_images(&self, buffer: Arc<Mutex<Vec<ImageInfo>>>) {
-        let db = self.db.clone();
-        let batch_size = self.config.batch_size;
-        let batch_timeout = self.config.batch_timeout;
+    async fn start_batch_insert_images(&self, buffer: Arc<Mutex<Vec<ImageInfo>>>, shutdown: watch::Receiver<bool>) {
+        let (batch_size, batch_timeout) = (self.config.image_batch_size, self.config.image_batch_timeout);
+        self.run_batch_writer(buffer, batch_size, batch_timeout, shutdown, "image", |sink, rows| sink.write_images(rows)).await;
+    }
+
+    // Helper: Every batch timeout, write up to batch_size rows from the buffer to the sink,
+    // putting them back if the write keeps failing. Once shutdown is requested, batches are
+    // written back to back until the buffer is empty, then this returns
+    async fn run_batch_writer<T, F>(
+        &self,
+        buffer: Arc<Mutex<Vec<T>>>,
+        batch_size: usize,
+        batch_timeout: Duration,
+        mut shutdown: watch::Receiver<bool>,
+        kind: &str,
+        write: F,
+    ) where
+        F: for<'r> Fn(&'r dyn MetricsSink, &'r [T]) -> BoxFuture<'r, anyhow::Result<()>>,
+    {
+        let sink = self.sink.as_ref();
+        let max_buffered = self.config.max_buffered_rows;
+        let mut stopping = false;
 
         loop {
-            tokio::time::sleep(batch_timeout).await;
+            if !stopping {
+                stopping = Self::wait_for_batch(batch_timeout, &mut shutdown).await;
+            }
 
             let mut buf = buffer.lock().await;
             if buf.is_empty() {
+                if stopping {
+                    return;
+                }
                 continue;
             }
 
-            let to_insert: Vec<ImageInfo> = if buf.len() > batch_size {
+            // Take up to batch_size items
+            let to_insert: Vec<T> = if buf.len() > batch_size {
                 buf.drain(..batch_size).collect()
             } else {
                 buf.drain(..).collect()
             };
 
-            drop(buf);
+            drop(buf); // Release lock before DB operation
 
-            if !to_insert.is_empty() {
-                if let Err(e) = Self::batch_insert_images(&db, &to_insert).await {
-                    log::error!("Failed to batch insert images: {}", e);
-                } else {
-                    log::info!("Successfully inserted {} image records", to_insert.len());
-                }
+            if let Err(e) = Self::write_with_retry(&to_insert, |rows| write(sink, rows)).await {
+                log::error!("Failed to batch insert {} rows: {}", kind, e);
+                Self::requeue(&buffer, to_insert, max_buffered, stopping, kind).await;
+            } else {
+                log::info!("Successfully wrote {} {} rows to {} sink", to_insert.len(), kind, sink.name());
             }
         }
     }
 
-    // Helper: Collect container stats for all running containers
+    // Helper: Write one batch, retrying with backoff (BATCH_WRITE_RETRY_DELAYS) on failure
+    async fn write_with_retry<'a, T, F, Fut>(rows: &'a [T], write: F) -> anyhow::Result<()>
+    where
+        F: Fn(&'a [T]) -> Fut,
+        Fut: std::future::Future<Output = anyhow::Result<()>>,
+    {
+        let mut delays = BATCH_WRITE_RETRY_DELAYS.iter();
+        loop {
+            match write(rows).await {
+                Ok(()) => return Ok(()),
+                Err(e) => match delays.next() {
+                    Some(delay) => {
+                        log::warn!("Batch write of {} rows failed: {}. Retrying in {:?}", rows.len(), e, delay);
+                        tokio::time::sleep(*delay).await;
+                    }
+                    None => return Err(e),
+                },
+            }
+        }
+    }
+
+    // Helper: Put a batch that could not be written back at the front of its buffer for the
+    // next cycle, dropping the oldest rows past MAX_BUFFERED_ROWS. During shutdown the rows
+    // are dropped instead, since nothing would retry them
+    async fn requeue<T>(buffer: &Mutex<Vec<T>>, rows: Vec<T>, max_buffered: usize, stopping: bool, kind: &str) {
+        if stopping {
+            log::error!("Dropping {} {} rows that could not be written before shutdown", rows.len(), kind);
+            return;
+        }
+
+        let mut buf = buffer.lock().await;
+        buf.splice(0..0, rows);
+        if buf.len() > max_buffered {
+            let excess = buf.len() - max_buffered;
+            buf.drain(..excess);
+            log::warn!("{} buffer exceeds {} rows; dropped the {} oldest", kind, max_buffered, excess);
+        }
+    }
+
+    // Helper: Collect container stats for the running containers the sampler selects this tick
+    /// Stats of the running containers sampled this tick, and the ids of every running
+    /// container (sampled or not)
     async fn collect_container_stats(
         docker_service: &DockerService,
-    ) -> anyhow::Result<Vec<ContainerStats>> {
+        sampler: &mut StatsSampler,
+    ) -> anyhow::Result<(Vec<ContainerStats>, HashSet<String>)> {
         let containers = docker_service.list_containers().await?;
         let mut stats = Vec::new();
+        let mut running = HashSet::new();
+
+        sampler.advance(&containers);
 
         for container in containers {
             // Only collect stats for running containers
-            let is_running = container.status.to_lowercase().contains("up")
-                || container.status.to_lowercase().contains("running");
-
+            let is_running = container.is_running();
             if is_running {
+                running.insert(container.id.clone());
+            }
+
+            if is_running && sampler.should_collect(&container) {
                 match docker_service.get_container_stats(&container.id).await {
-                    Ok(stat) => stats.push(stat),
+                    Ok(stat) => {
+                        sampler.observe(&stat);
+                        stats.push(stat);
+                    }
                     Err(e) => {
                         log::debug!("Failed to get stats for container {}: {}", container.id, e);
                     }
@@ -312,98 +588,28 @@ impl WorkerService {
             }
         }
 
-        Ok(stats)
+        Ok((stats, running))
     }
 
     // Helper: Collect all images
-    async fn collect_images(docker_service: &DockerService) -> anyhow::Result<Vec<ImageInfo>> {
-        // List containers and get unique images
+    async fn collect_images(docker_service: &DockerService, concurrency: usize) -> anyhow::Result<Vec<ImageInfo>> {
+        // List containers and get unique images, so each image is inspected once per cycle
         let containers = docker_service.list_containers().await?;
-        let mut image_ids = std::collections::HashSet::new();
-        let mut images = Vec::new();
-
-        for container in containers {
-            if !image_ids.contains(&container.image) {
-                image_ids.insert(container.image.clone());
-                if let Ok(Some(image_info)) = docker_service.get_image_info(&container.image).await {
-                    images.push(image_info);
-                }
-            }
-        }
-
-        Ok(images)
-    }
-
-    // Helper: Batch insert stats
-    async fn batch_insert_stats(
-        db: &DatabaseConnection,
-        stats: &[ContainerStats],
-    ) -> anyhow::Result<()> {
-        use sea_orm::ActiveValue::Set;
-
-        let fixed_offset = FixedOffset::east_opt(0).unwrap();
-
-        let active_models: Vec<container_stats::ActiveModel> = stats
-            .iter()
-            .map(|stat| {
-                let timestamp = stat.timestamp.with_timezone(&fixed_offset);
-                container_stats::ActiveModel {
-                    container_id: Set(stat.container_id.clone()),
-                    container_name: Set(stat.container_name.clone()),
-                    cpu_usage_percent: Set(stat.cpu_usage_percent),
-                    memory_usage_bytes: Set(stat.memory_usage_bytes as i64),
-                    memory_limit_bytes: Set(stat.memory_limit_bytes as i64),
-                    memory_usage_percent: Set(stat.memory_usage_percent),
-                    network_rx_bytes: Set(stat.network_rx_bytes as i64),
-                    network_tx_bytes: Set(stat.network_tx_bytes as i64),
-                    block_read_bytes: Set(stat.block_read_bytes as i64),
-                    block_write_bytes: Set(stat.block_write_bytes as i64),
-                    timestamp: Set(timestamp),
-                    ..Default::default()
-                }
-            })
-            .collect();
-
-        container_stats::Entity::insert_many(active_models)
-            .exec(db)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to batch insert stats: {}", e))?;
-
-        Ok(())
-    }
-
-    // Helper: Batch insert container info
-    async fn batch_insert_container_info(
-        db: &DatabaseConnection,
-        containers: &[ContainerInfo],
-    ) -> anyhow::Result<()> {
-        use sea_orm::ActiveValue::Set;
-
-        let fixed_offset = FixedOffset::east_opt(0).unwrap();
-        let collected_at = Utc::now().with_timezone(&fixed_offset);
-
-        let active_models: Vec<container_info::ActiveModel> = containers
-            .iter()
-            .map(|container| {
-                let created = container.created.map(|dt| dt.with_timezone(&fixed_offset));
-                container_info::ActiveModel {
-                    container_id: Set(container.id.clone()),
-                    container_name: Set(container.name.clone()),
-                    image: Set(container.image.clone()),
-                    status: Set(container.status.clone()),
-                    created: Set(created),
-                    collected_at: Set(collected_at),
-                    ..Default::default()
-                }
-            })
-            .collect();
-
-        container_info::Entity::insert_many(active_models)
-            .exec(db)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to batch insert container info: {}", e))?;
-
-        Ok(())
+        let image_refs: HashSet<String> = containers.into_iter().map(|c| c.image).collect();
+
+        let inspected: Vec<Option<ImageInfo>> = stream::iter(image_refs)
+            .map(|image| async move { docker_service.get_image_info(&image).await.ok().flatten() })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+
+        // Different tags can resolve to the same image
+        let mut image_ids = HashSet::new();
+        Ok(inspected
+            .into_iter()
+            .flatten()
+            .filter(|image| image_ids.insert(image.id.clone()))
+            .collect())
     }
 
     // HTTP requests collection task - collects HTTP requests periodically
@@ -423,7 +629,8 @@ impl WorkerService {
         loop {
             ticker.tick().await;
 
-            match Self::collect_http_requests(&docker_service, &network_monitor).await {
+            let cycle = CollectionCycle::start();
+            let error = match Self::collect_http_requests(&docker_service, &network_monitor).await {
                 Ok(requests) => {
                     let requests_len = requests.len();
                     if requests_len > 0 {
@@ -433,70 +640,53 @@ impl WorkerService {
                     } else {
                         log::debug!("No HTTP requests collected (network capture may not be working)");
                     }
+                    None
                 }
                 Err(e) => {
                     log::warn!("Failed to collect HTTP requests: {}", e);
+                    Some(e.to_string())
                 }
-            }
+            };
+            self.schedule.finish("http_requests", cycle, error);
         }
     }
 
     // Batch insert task for HTTP requests
-    async fn start_batch_insert_http_requests(&self, buffer: Arc<Mutex<Vec<HttpRequest>>>) {
-        let db = self.db.clone();
-        let batch_size = self.config.batch_size;
-        let batch_timeout = self.config.batch_timeout;
-
-        loop {
-            tokio::time::sleep(batch_timeout).await;
-
-            let mut buf = buffer.lock().await;
-            if buf.is_empty() {
-                continue;
-            }
-
-            let to_insert: Vec<HttpRequest> = if buf.len() > batch_size {
-                buf.drain(..batch_size).collect()
-            } else {
-                buf.drain(..).collect()
-            };
+    async fn start_batch_insert_http_requests(&self, buffer: Arc<Mutex<Vec<HttpRequest>>>, shutdown: watch::Receiver<bool>) {
+        let (batch_size, batch_timeout) = (self.config.http_batch_size, self.config.http_batch_timeout);
+        self.run_batch_writer(buffer, batch_size, batch_timeout, shutdown, "HTTP request", |sink, rows| {
+            Self::log_http_batch(rows, sink.name());
+            sink.write_http_requests(rows)
+        })
+        .await;
+    }
 
-            drop(buf);
+    // Helper: Log a summary of the HTTP requests about to be written
+    fn log_http_batch(requests: &[HttpRequest], sink_name: &str) {
+        let mut container_counts: HashMap<String, usize> = HashMap::new();
+        let mut method_counts: HashMap<String, usize> = HashMap::new();
+        for req in requests {
+            *container_counts.entry(req.container_name.clone()).or_insert(0) += 1;
+            *method_counts.entry(req.method.clone()).or_insert(0) += 1;
+        }
 
-            if !to_insert.is_empty() {
-                // Log summary of requests being inserted
-                let mut container_counts: HashMap<String, usize> = HashMap::new();
-                let mut method_counts: HashMap<String, usize> = HashMap::new();
-                for req in &to_insert {
-                    *container_counts.entry(req.container_name.clone()).or_insert(0) += 1;
-                    *method_counts.entry(req.method.clone()).or_insert(0) += 1;
-                }
-                
-                log::info!("💾 Attempting to insert {} HTTP request records into database", to_insert.len());
-                log::info!("   📊 Summary: {} unique containers, {} unique methods", 
-                    container_counts.len(), method_counts.len());
-                log::debug!("   📋 Container breakdown: {:?}", container_counts);
-                log::debug!("   📋 Method breakdown: {:?}", method_counts);
-                
-                // Log first few requests for traceability
-                let sample_size = std::cmp::min(5, to_insert.len());
-                for (idx, req) in to_insert.iter().take(sample_size).enumerate() {
-                    log::info!("   [{}/{}] {} {} {} ({}ms) - container: {} ({})", 
-                        idx + 1, sample_size,
-                        req.method, req.endpoint, req.http_status, 
-                        req.response_time_ms as i64,
-                        &req.container_id[..12], req.container_name);
-                }
-                if to_insert.len() > sample_size {
-                    log::info!("   ... and {} more requests", to_insert.len() - sample_size);
-                }
-                
-                if let Err(e) = Self::batch_insert_http_requests(&db, &to_insert).await {
-                    log::error!("❌ Failed to batch insert HTTP requests: {}", e);
-                } else {
-                    log::info!("✅ Successfully inserted {} HTTP request records into database", to_insert.len());
-                }
-            }
+        log::info!("💾 Attempting to write {} HTTP request records to {} sink", requests.len(), sink_name);
+        log::info!("   📊 Summary: {} unique containers, {} unique methods",
+            container_counts.len(), method_counts.len());
+        log::debug!("   📋 Container breakdown: {:?}", container_counts);
+        log::debug!("   📋 Method breakdown: {:?}", method_counts);
+
+        // Log first few requests for traceability
+        let sample_size = std::cmp::min(5, requests.len());
+        for (idx, req) in requests.iter().take(sample_size).enumerate() {
+            log::info!("   [{}/{}] {} {} {} ({}ms) - container: {} ({})",
+                idx + 1, sample_size,
+                req.method, req.endpoint, req.http_status,
+                req.response_time_ms as i64,
+                &req.container_id[..12], req.container_name);
+        }
+        if requests.len() > sample_size {
+            log::info!("   ... and {} more requests", requests.len() - sample_size);
         }
     }
 
@@ -512,8 +702,7 @@ impl WorkerService {
 
         for container in containers {
             // Only collect for running containers
-            let is_running = container.status.to_lowercase().contains("up")
-                || container.status.to_lowercase().contains("running");
+            let is_running = container.is_running();
 
             if !is_running {
                 continue;
@@ -562,112 +751,111 @@ impl WorkerService {
         log::info!("Collected total {} HTTP requests from all containers", all_requests.len());
         Ok(all_requests)
     }
+}
 
+/// Decides which containers get full-resolution stats and which are only sampled
+struct StatsSampler {
+    sample_every: u32,
+    important_label: Option<String>,
+    cpu_threshold: Option<f64>,
+    memory_threshold: Option<f64>,
+    tick: u64,
+    // Containers whose last collected sample was above a resource threshold
+    busy: HashSet<String>,
+}
 
-    // Helper: Batch insert images
-    async fn batch_insert_images(
-        db: &DatabaseConnection,
-        images: &[ImageInfo],
-    ) -> anyhow::Result<()> {
-        use sea_orm::ActiveValue::Set;
-        use serde_json::json;
-
-        let fixed_offset = FixedOffset::east_opt(0).unwrap();
-        let now = Utc::now().with_timezone(&fixed_offset);
-
-        for image in images {
-            // First, try to update existing image
-            let existing = docker_images::Entity::find()
-                .filter(docker_images::Column::ImageId.eq(&image.id))
-                .one(db)
-                .await?;
-
-            if let Some(existing_model) = existing {
-                // Update existing image
-                let mut active_model: docker_images::ActiveModel = existing_model.into();
-                active_model.repo_tags = Set(json!(image.repo_tags));
-                active_model.size_bytes = Set(image.size as i64);
-                active_model.architecture = Set(image.architecture.clone());
-                active_model.os = Set(image.os.clone());
-                active_model.created_at = Set(image.created.map(|dt| dt.with_timezone(&fixed_offset)));
-                active_model.last_seen = Set(now);
-                active_model.update(db).await?;
-
-                // Also insert into image_versions for history
-                let version_model = image_versions::ActiveModel {
-                    image_id: Set(image.id.clone()),
-                    repo_tags: Set(json!(image.repo_tags)),
-                    size_bytes: Set(image.size as i64),
-                    timestamp: Set(now),
-                    ..Default::default()
-                };
-                image_versions::Entity::insert(version_model).exec(db).await?;
-            } else {
-                // Insert new image
-                let image_model = docker_images::ActiveModel {
-                    image_id: Set(image.id.clone()),
-                    repo_tags: Set(json!(image.repo_tags)),
-                    size_bytes: Set(image.size as i64),
-                    architecture: Set(image.architecture.clone()),
-                    os: Set(image.os.clone()),
-                    created_at: Set(image.created.map(|dt| dt.with_timezone(&fixed_offset))),
-                    first_seen: Set(now),
-                    last_seen: Set(now),
-                    ..Default::default()
-                };
-                docker_images::Entity::insert(image_model).exec(db).await?;
-
-                // Insert into image_versions
-                let version_model = image_versions::ActiveModel {
-                    image_id: Set(image.id.clone()),
-                    repo_tags: Set(json!(image.repo_tags)),
-                    size_bytes: Set(image.size as i64),
-                    timestamp: Set(now),
-                    ..Default::default()
-                };
-                image_versions::Entity::insert(version_model).exec(db).await?;
-            }
+impl StatsSampler {
+    fn new(config: &Config) -> Self {
+        Self {
+            sample_every: config.stats_sample_every.max(1),
+            important_label: config.stats_important_label.clone(),
+            cpu_threshold: config.stats_full_resolution_cpu_percent,
+            memory_threshold: config.stats_full_resolution_memory_percent,
+            tick: 0,
+            busy: HashSet::new(),
         }
+    }
 
-        Ok(())
-    }
-
-    // Helper: Batch insert HTTP requests
-    async fn batch_insert_http_requests(
-        db: &DatabaseConnection,
-        requests: &[HttpRequest],
-    ) -> anyhow::Result<()> {
-        use sea_orm::ActiveValue::Set;
-
-        let fixed_offset = FixedOffset::east_opt(0).unwrap();
-
-        log::debug!("🔍 Preparing {} HTTP requests for database insertion", requests.len());
-
-        let active_models: Vec<http_requests::ActiveModel> = requests
-            .iter()
-            .map(|req| {
-                let timestamp = req.timestamp.with_timezone(&fixed_offset);
-                http_requests::ActiveModel {
-                    container_id: Set(req.container_id.clone()),
-                    container_name: Set(req.container_name.clone()),
-                    endpoint: Set(req.endpoint.clone()),
-                    method: Set(req.method.clone()),
-                    http_status: Set(req.http_status as i16),
-                    response_time_ms: Set(req.response_time_ms),
-                    timestamp: Set(timestamp),
-                    ..Default::default()
-                }
-            })
-            .collect();
+    /// Start a new collection tick, forgetting containers that no longer exist
+    fn advance(&mut self, containers: &[ContainerInfo]) {
+        self.tick += 1;
+        let ids: HashSet<&str> = containers.iter().map(|c| c.id.as_str()).collect();
+        self.busy.retain(|id| ids.contains(id.as_str()));
+    }
 
-        log::debug!("📝 Executing database insert for {} HTTP request records", active_models.len());
-        
-        http_requests::Entity::insert_many(active_models)
-            .exec(db)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to batch insert HTTP requests: {}", e))?;
+    fn should_collect(&self, container: &ContainerInfo) -> bool {
+        if self.sample_every <= 1 || self.tick % self.sample_every as u64 == 1 {
+            return true;
+        }
+
+        let important = self.important_label.as_ref().is_some_and(|label| {
+            container
+                .labels
+                .get(label)
+                .is_some_and(|value| !value.eq_ignore_ascii_case("false"))
+        });
+
+        important || self.busy.contains(&container.id)
+    }
+
+    /// Record whether a fresh sample puts the container above a full-resolution threshold
+    fn observe(&mut self, stats: &ContainerStats) {
+        let over_cpu = self.cpu_threshold.is_some_and(|t| stats.cpu_usage_percent >= t);
+        let over_memory = self.memory_threshold.is_some_and(|t| stats.memory_usage_percent >= t);
+
+        if over_cpu || over_memory {
+            self.busy.insert(stats.container_id.clone());
+        } else {
+            self.busy.remove(&stats.container_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn requeue_puts_rows_back_in_front_and_caps_the_buffer() {
+        let buffer = Mutex::new(vec![4, 5]);
+        WorkerService::requeue(&buffer, vec![1, 2, 3], 10, false, "test").await;
+        assert_eq!(*buffer.lock().await, vec![1, 2, 3, 4, 5]);
+
+        WorkerService::requeue(&buffer, vec![0], 4, false, "test").await;
+        assert_eq!(*buffer.lock().await, vec![2, 3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn requeue_drops_rows_when_stopping() {
+        let buffer = Mutex::new(vec![4, 5]);
+        WorkerService::requeue(&buffer, vec![1, 2, 3], 10, true, "test").await;
+        assert_eq!(*buffer.lock().await, vec![4, 5]);
+    }
+
+    #[tokio::test]
+    async fn write_with_retry_retries_until_a_write_succeeds() {
+        let attempts = AtomicUsize::new(0);
+        let result = WorkerService::write_with_retry(&[1, 2], |_| async {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                anyhow::bail!("connection reset");
+            }
+            Ok(())
+        })
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
 
-        log::debug!("✓ Database insert completed successfully for {} HTTP requests", requests.len());
-        Ok(())
+    #[tokio::test]
+    async fn write_with_retry_gives_up_after_the_last_delay() {
+        let attempts = AtomicUsize::new(0);
+        let result = WorkerService::write_with_retry(&[1, 2], |_| async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            anyhow::bail!("connection refused")
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), BATCH_WRITE_RETRY_DELAYS.len() + 1);
     }
 }